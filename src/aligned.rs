@@ -0,0 +1,266 @@
+use std::{
+    fmt,
+    ops::{Deref, DerefMut},
+};
+
+use crate::{error::Error, runtime_array::ArrayCStyle};
+
+/// The cache-line size assumed by [`CacheAligned`] and
+/// [`ArrayCStyle::new_cache_aligned`].
+pub const CACHE_LINE_SIZE: usize = 64;
+
+/// Pads and aligns a value to a full cache line.
+///
+/// An `ArrayCStyle<CacheAligned<T>>` places every element on its own cache
+/// line, so per-thread counters and similar slots written from different
+/// cores do not false-share.
+///
+/// # Example
+///
+/// ```rust ignore
+/// use std::sync::atomic::{AtomicU64, Ordering};
+/// use runnarr::{aligned::CacheAligned, runtime_array::ArrayCStyle};
+///
+/// let counters: ArrayCStyle<CacheAligned<AtomicU64>> =
+///     (0..8).map(|_| CacheAligned::new(AtomicU64::new(0))).collect();
+/// counters[3].fetch_add(1, Ordering::Relaxed);
+/// ```
+#[derive(Debug, Clone, Copy, Default, Hash, PartialEq, Eq, PartialOrd, Ord)]
+#[repr(C, align(64))]
+pub struct CacheAligned<T>(pub T);
+
+impl<T> CacheAligned<T> {
+    /// Wraps `value` so it occupies a full cache line.
+    #[inline(always)]
+    pub const fn new(value: T) -> Self {
+        Self(value)
+    }
+
+    /// Unwraps the padded value.
+    #[inline(always)]
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> Deref for CacheAligned<T> {
+    type Target = T;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for CacheAligned<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<T> From<T> for CacheAligned<T> {
+    fn from(value: T) -> Self {
+        Self(value)
+    }
+}
+
+impl<T> ArrayCStyle<T> {
+    /// Creates a new uninitialized `Array` whose buffer starts on a cache-line
+    /// boundary.
+    ///
+    /// Elements are still packed; use `ArrayCStyle<CacheAligned<T>>` when
+    /// each element needs a cache line of its own.
+    #[track_caller]
+    pub fn new_cache_aligned(size: usize) -> Result<Self, Error> {
+        Self::new_aligned(size, CACHE_LINE_SIZE)
+    }
+
+    /// Creates a new zeroed `Array` whose buffer starts on a cache-line
+    /// boundary.
+    ///
+    /// See [`ArrayCStyle::new_cache_aligned`].
+    #[track_caller]
+    pub fn zeroed_cache_aligned(size: usize) -> Result<Self, Error> {
+        Self::zeroed_aligned(size, CACHE_LINE_SIZE)
+    }
+}
+
+/// A runtime sized array whose buffer alignment is part of its type.
+///
+/// APIs can ask for an `AlignedArray<f32, 64>` in their signatures instead of
+/// checking `ptr() as usize % 64` at runtime; the compiler then rejects
+/// buffers that were not allocated with the right alignment. `ALIGN` must be
+/// a power of two, which is checked at compile time.
+///
+/// # Example
+///
+/// ```rust ignore
+/// use runnarr::aligned::AlignedArray;
+///
+/// fn dot_avx512(a: &AlignedArray<f32, 64>, b: &AlignedArray<f32, 64>) -> f32 {
+///     // aligned loads are always valid here
+/// }
+///
+/// let a = AlignedArray::<f32, 64>::zeroed(1024).unwrap();
+/// ```
+pub struct AlignedArray<T, const ALIGN: usize> {
+    array: ArrayCStyle<T>,
+}
+
+impl<T, const ALIGN: usize> AlignedArray<T, ALIGN> {
+    const VALID_ALIGN: () =
+        assert!(ALIGN.is_power_of_two(), "ALIGN must be a power of two");
+
+    /// Creates a new uninitialized `AlignedArray` with the specified size.
+    ///
+    /// See [`ArrayCStyle::new`] for the initialization contract.
+    #[track_caller]
+    pub fn new(size: usize) -> Result<Self, Error> {
+        let () = Self::VALID_ALIGN;
+        Ok(Self {
+            array: ArrayCStyle::new_aligned(size, ALIGN)?,
+        })
+    }
+
+    /// Creates a new `AlignedArray` with all elements initialized to zero.
+    #[track_caller]
+    pub fn zeroed(size: usize) -> Result<Self, Error> {
+        let () = Self::VALID_ALIGN;
+        Ok(Self {
+            array: ArrayCStyle::zeroed_aligned(size, ALIGN)?,
+        })
+    }
+
+    /// Wraps an existing array after checking that its buffer is aligned to
+    /// `ALIGN`.
+    ///
+    /// # Errors
+    ///
+    /// Gives the array back if its buffer is not sufficiently aligned.
+    pub fn from_array(array: ArrayCStyle<T>) -> Result<Self, ArrayCStyle<T>> {
+        let () = Self::VALID_ALIGN;
+        if (array.ptr() as usize).is_multiple_of(ALIGN) {
+            Ok(Self { array })
+        } else {
+            Err(array)
+        }
+    }
+
+    /// Returns the alignment guaranteed by the type.
+    #[inline(always)]
+    pub const fn alignment() -> usize {
+        ALIGN
+    }
+
+    /// Unwraps the underlying array.
+    #[inline(always)]
+    pub fn into_array(self) -> ArrayCStyle<T> {
+        self.array
+    }
+
+    /// Returns a reference to the underlying array.
+    #[inline(always)]
+    pub fn as_array(&self) -> &ArrayCStyle<T> {
+        &self.array
+    }
+
+    /// Returns the length of the array.
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.array.len()
+    }
+
+    /// Returns `true` if the array holds no elements.
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.array.is_empty()
+    }
+
+    /// Returns a raw pointer to the elements, aligned to `ALIGN`.
+    #[inline(always)]
+    pub fn ptr(&self) -> *const T {
+        self.array.ptr()
+    }
+
+    /// Returns a mutable raw pointer to the elements, aligned to `ALIGN`.
+    #[inline(always)]
+    pub fn ptr_mut(&mut self) -> *mut T {
+        self.array.ptr_mut()
+    }
+
+    /// Gets a reference to the element at `index`, or `None` if it is out of
+    /// bounds.
+    #[inline(always)]
+    pub fn get(&self, index: usize) -> Option<&T> {
+        self.array.get(index)
+    }
+
+    /// Gets a mutable reference to the element at `index`, or `None` if it is
+    /// out of bounds.
+    #[inline(always)]
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        self.array.get_mut(index)
+    }
+
+    /// Returns the elements as a slice.
+    #[inline(always)]
+    pub fn as_slice(&self) -> &[T] {
+        self.array.as_slice()
+    }
+
+    /// Returns the elements as a mutable slice.
+    #[inline(always)]
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        self.array.as_mut_slice()
+    }
+}
+
+#[cfg(not(feature = "no-panic"))]
+impl<T, const ALIGN: usize> std::ops::Index<usize> for AlignedArray<T, ALIGN> {
+    type Output = T;
+    fn index(&self, index: usize) -> &Self::Output {
+        self.get(index).expect("Index out of bounds")
+    }
+}
+
+#[cfg(not(feature = "no-panic"))]
+impl<T, const ALIGN: usize> std::ops::IndexMut<usize>
+    for AlignedArray<T, ALIGN>
+{
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        self.get_mut(index).expect("Index out of bounds")
+    }
+}
+
+impl<T: Clone, const ALIGN: usize> AlignedArray<T, ALIGN> {
+    /// Creates a deep copy of the array with the same alignment.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the copy cannot be allocated.
+    #[track_caller]
+    pub fn try_clone(&self) -> Result<Self, Error> {
+        // `from_array` may have wrapped a buffer that is only aligned by
+        // chance, so the copy asks for `ALIGN` explicitly
+        let mut copy = Self::new(self.len())?;
+        for (index, value) in self.as_slice().iter().enumerate() {
+            unsafe { copy.ptr_mut().add(index).write(value.clone()) };
+        }
+        Ok(copy)
+    }
+}
+
+#[cfg(not(feature = "no-panic"))]
+impl<T: Clone, const ALIGN: usize> Clone for AlignedArray<T, ALIGN> {
+    #[track_caller]
+    fn clone(&self) -> Self {
+        self.try_clone().expect("Failed to allocate array clone")
+    }
+}
+
+impl<T: fmt::Debug, const ALIGN: usize> fmt::Debug for AlignedArray<T, ALIGN> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AlignedArray")
+            .field("align", &ALIGN)
+            .field("array", &self.array)
+            .finish()
+    }
+}
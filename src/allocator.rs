@@ -0,0 +1,521 @@
+use std::{
+    alloc::Layout,
+    ffi::c_void,
+    fmt, ptr,
+    ptr::NonNull,
+    sync::{Arc, RwLock},
+    time::Duration,
+};
+
+use crate::{
+    error::{AllocError, Error},
+    runtime_array::ArrayCStyle,
+};
+
+/// A minimal allocator interface usable on stable Rust.
+///
+/// This mirrors the shape of the unstable `std::alloc::Allocator` trait closely
+/// enough that arrays can be backed by arenas, pools, or custom allocators
+/// instead of only the global allocator.
+///
+/// `ArrayCStyle` never asks an allocator for a zero-sized block; empty arrays
+/// and zero-sized element types use a dangling pointer and skip the allocator
+/// entirely.
+///
+/// # Safety
+///
+/// Implementors must return blocks that are valid for reads and writes of
+/// `layout.size()` bytes and aligned to at least `layout.align()`. A block
+/// returned by `allocate`, `allocate_zeroed` or `reallocate` must remain valid
+/// until it is passed back to `deallocate` or `reallocate` of the same
+/// allocator with the same layout.
+pub unsafe trait RawAllocator {
+    /// Allocates a block of memory described by `layout`.
+    fn allocate(&self, layout: Layout) -> Result<NonNull<u8>, Error>;
+
+    /// Allocates a block of memory described by `layout` with every byte set
+    /// to zero.
+    fn allocate_zeroed(&self, layout: Layout) -> Result<NonNull<u8>, Error> {
+        let ptr = self.allocate(layout)?;
+        unsafe {
+            ptr.as_ptr().write_bytes(0, layout.size());
+        }
+        Ok(ptr)
+    }
+
+    /// Releases a block previously handed out by this allocator.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must have been returned by this allocator for exactly `layout`
+    /// and must not be used afterwards.
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout);
+
+    /// Resizes a block previously handed out by this allocator.
+    ///
+    /// On success the old block must be considered freed. On failure the old
+    /// block is left untouched and is still owned by the caller.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must have been returned by this allocator for exactly
+    /// `old_layout`, and `new_layout` must have the same alignment.
+    unsafe fn reallocate(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<u8>, Error> {
+        let new_ptr = self.allocate(new_layout)?;
+        unsafe {
+            ptr::copy_nonoverlapping(
+                ptr.as_ptr(),
+                new_ptr.as_ptr(),
+                old_layout.size().min(new_layout.size()),
+            );
+            self.deallocate(ptr, old_layout);
+        }
+        Ok(new_ptr)
+    }
+}
+
+unsafe impl<A: RawAllocator + ?Sized> RawAllocator for &A {
+    #[inline(always)]
+    fn allocate(&self, layout: Layout) -> Result<NonNull<u8>, Error> {
+        (**self).allocate(layout)
+    }
+
+    #[inline(always)]
+    fn allocate_zeroed(&self, layout: Layout) -> Result<NonNull<u8>, Error> {
+        (**self).allocate_zeroed(layout)
+    }
+
+    #[inline(always)]
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        unsafe { (**self).deallocate(ptr, layout) }
+    }
+
+    #[inline(always)]
+    unsafe fn reallocate(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<u8>, Error> {
+        unsafe { (**self).reallocate(ptr, old_layout, new_layout) }
+    }
+}
+
+/// The process wide global allocator, as used by `std::alloc::alloc`.
+///
+/// This is the default allocator of every array in the crate.
+#[derive(Debug, Clone, Copy, Default, Hash, PartialEq, Eq)]
+pub struct Global;
+
+unsafe impl RawAllocator for Global {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<u8>, Error> {
+        let ptr = unsafe { std::alloc::alloc(layout) };
+        NonNull::new(ptr).ok_or(Error::Alloc(AllocError::new(layout)))
+    }
+
+    fn allocate_zeroed(&self, layout: Layout) -> Result<NonNull<u8>, Error> {
+        let ptr = unsafe { std::alloc::alloc_zeroed(layout) };
+        NonNull::new(ptr).ok_or(Error::Alloc(AllocError::new(layout)))
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        unsafe { std::alloc::dealloc(ptr.as_ptr(), layout) }
+    }
+
+    unsafe fn reallocate(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<u8>, Error> {
+        let new_ptr = unsafe {
+            std::alloc::realloc(ptr.as_ptr(), old_layout, new_layout.size())
+        };
+        NonNull::new(new_ptr).ok_or(Error::Alloc(AllocError::new(new_layout)))
+    }
+}
+
+/// Allocation entry points of a foreign allocator, as plain function
+/// pointers.
+///
+/// Every function receives the context pointer given to
+/// [`VTableAllocator::new`], so C allocators with an explicit heap handle
+/// (RTOS memory pools, a plugin host's arena, ...) can be plugged in
+/// directly.
+#[derive(Debug, Clone, Copy)]
+pub struct AllocVTable {
+    /// Returns a block of `size` bytes aligned to `align`, or null.
+    pub alloc: unsafe extern "C" fn(
+        ctx: *mut c_void,
+        size: usize,
+        align: usize,
+    ) -> *mut u8,
+    /// Frees a block returned by `alloc` or `realloc`.
+    pub dealloc: unsafe extern "C" fn(
+        ctx: *mut c_void,
+        ptr: *mut u8,
+        size: usize,
+        align: usize,
+    ),
+    /// Resizes a block, returning null and leaving it untouched on failure.
+    /// When `None`, resizing falls back to `alloc`, copy and `dealloc`.
+    pub realloc: Option<
+        unsafe extern "C" fn(
+            ctx: *mut c_void,
+            ptr: *mut u8,
+            old_size: usize,
+            align: usize,
+            new_size: usize,
+        ) -> *mut u8,
+    >,
+}
+
+/// Routes an array's allocations through an [`AllocVTable`].
+///
+/// This is the stable-Rust way of giving individual arrays their own
+/// allocator without implementing [`RawAllocator`]: the vtable and its
+/// context travel inside the array and are used for every allocation,
+/// resize and free it performs.
+///
+/// # Example
+///
+/// ```rust ignore
+/// use runnarr::{allocator::{AllocVTable, VTableAllocator}, runtime_array::ArrayCStyle};
+///
+/// static HOST_ALLOC: AllocVTable = AllocVTable {
+///     alloc: host_alloc,
+///     dealloc: host_free,
+///     realloc: None,
+/// };
+///
+/// let alloc = unsafe { VTableAllocator::new(HOST_ALLOC, host_heap) };
+/// let samples = ArrayCStyle::<f32, _>::zeroed_in(256, alloc).unwrap();
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct VTableAllocator {
+    vtable: AllocVTable,
+    ctx: *mut c_void,
+}
+
+impl VTableAllocator {
+    /// Creates an allocator calling the functions in `vtable` with `ctx`.
+    ///
+    /// # Safety
+    ///
+    /// The functions must uphold the [`RawAllocator`] contract, and `ctx`
+    /// must stay valid for as long as any array uses this allocator.
+    pub const unsafe fn new(vtable: AllocVTable, ctx: *mut c_void) -> Self {
+        Self { vtable, ctx }
+    }
+
+    /// Returns the vtable the allocator calls into.
+    #[inline(always)]
+    pub const fn vtable(&self) -> &AllocVTable {
+        &self.vtable
+    }
+
+    /// Returns the context pointer passed to every call.
+    #[inline(always)]
+    pub const fn ctx(&self) -> *mut c_void {
+        self.ctx
+    }
+}
+
+unsafe impl RawAllocator for VTableAllocator {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<u8>, Error> {
+        let ptr = unsafe {
+            (self.vtable.alloc)(self.ctx, layout.size(), layout.align())
+        };
+        NonNull::new(ptr).ok_or(Error::Alloc(AllocError::new(layout)))
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        unsafe {
+            (self.vtable.dealloc)(
+                self.ctx,
+                ptr.as_ptr(),
+                layout.size(),
+                layout.align(),
+            )
+        }
+    }
+
+    unsafe fn reallocate(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<u8>, Error> {
+        let Some(realloc) = self.vtable.realloc else {
+            let new_ptr = self.allocate(new_layout)?;
+            unsafe {
+                ptr::copy_nonoverlapping(
+                    ptr.as_ptr(),
+                    new_ptr.as_ptr(),
+                    old_layout.size().min(new_layout.size()),
+                );
+                self.deallocate(ptr, old_layout);
+            }
+            return Ok(new_ptr);
+        };
+        let new_ptr = unsafe {
+            realloc(
+                self.ctx,
+                ptr.as_ptr(),
+                old_layout.size(),
+                old_layout.align(),
+                new_layout.size(),
+            )
+        };
+        NonNull::new(new_ptr).ok_or(Error::Alloc(AllocError::new(new_layout)))
+    }
+}
+
+impl<T> ArrayCStyle<T, VTableAllocator> {
+    /// Creates a new uninitialized `Array` whose memory comes from the
+    /// functions in `vtable`.
+    ///
+    /// See [`VTableAllocator`] and [`ArrayCStyle::new`].
+    ///
+    /// # Safety
+    ///
+    /// See [`VTableAllocator::new`].
+    #[track_caller]
+    pub unsafe fn new_with_vtable(
+        size: usize,
+        vtable: AllocVTable,
+        ctx: *mut c_void,
+    ) -> Result<Self, Error> {
+        Self::new_in(size, unsafe { VTableAllocator::new(vtable, ctx) })
+    }
+}
+
+/// What to do after an out-of-memory handler has run.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub enum OomAction {
+    /// Try the allocation again, e.g. after trimming caches.
+    Retry,
+    /// Give up and return the allocation error to the caller.
+    Fail,
+}
+
+/// Describes a failed allocation to the out-of-memory handler.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OomInfo {
+    /// The layout that could not be allocated.
+    pub layout: Layout,
+    /// The element type of the array being allocated.
+    pub type_name: &'static str,
+    /// How many times this allocation has failed so far, starting at 1.
+    pub attempt: usize,
+}
+
+type OomHandler = dyn Fn(&OomInfo) -> OomAction + Send + Sync;
+
+static OOM_HANDLER: RwLock<Option<Arc<OomHandler>>> = RwLock::new(None);
+
+/// Installs a crate-wide hook that runs whenever an array allocation fails.
+///
+/// The handler can log the failure, free memory (trimming caches, releasing
+/// pools) and ask for a [`OomAction::Retry`], or abort the process itself
+/// after printing diagnostics. Returning [`OomAction::Fail`] hands the error
+/// back to the caller as usual. The handler runs for every failed attempt,
+/// so it should use [`OomInfo::attempt`] to stop retrying eventually.
+///
+/// Replaces any previously installed handler.
+///
+/// # Example
+///
+/// ```rust ignore
+/// use runnarr::allocator::{set_oom_handler, OomAction};
+///
+/// set_oom_handler(|info| {
+///     eprintln!("failed to allocate {} bytes for {}", info.layout.size(), info.type_name);
+///     if info.attempt < 3 && CACHE.trim() {
+///         OomAction::Retry
+///     } else {
+///         OomAction::Fail
+///     }
+/// });
+/// ```
+pub fn set_oom_handler<F>(handler: F)
+where
+    F: Fn(&OomInfo) -> OomAction + Send + Sync + 'static,
+{
+    *OOM_HANDLER
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) =
+        Some(Arc::new(handler));
+}
+
+/// Removes the out-of-memory handler, restoring the default of returning
+/// allocation errors straight to the caller.
+pub fn clear_oom_handler() {
+    *OOM_HANDLER
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) = None;
+}
+
+type RetryCallback = dyn Fn(&OomInfo) + Send + Sync;
+
+/// How often, and how patiently, a failed array allocation is retried before
+/// the error reaches the caller.
+///
+/// Long-running services can ride out transient memory pressure (a burst of
+/// requests, a cache that is about to be trimmed) instead of failing hard.
+/// The policy applies to every allocation made by `new`, `zeroed` and their
+/// variants as well as to [`ArrayCStyle::try_grow`], and runs after the
+/// out-of-memory handler has declined to retry.
+///
+/// [`ArrayCStyle::try_grow`]: crate::runtime_array::ArrayCStyle::try_grow
+///
+/// # Example
+///
+/// ```rust ignore
+/// use std::time::Duration;
+/// use runnarr::allocator::{set_retry_policy, RetryPolicy};
+///
+/// set_retry_policy(
+///     RetryPolicy::new(5)
+///         .with_backoff(Duration::from_millis(10), Duration::from_secs(1))
+///         .on_retry(|info| CACHE.trim(info.layout.size())),
+/// );
+/// ```
+#[derive(Clone)]
+pub struct RetryPolicy {
+    retries: usize,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    on_retry: Option<Arc<RetryCallback>>,
+}
+
+impl RetryPolicy {
+    /// Creates a policy that retries a failed allocation up to `retries`
+    /// times, without waiting in between.
+    pub const fn new(retries: usize) -> Self {
+        Self {
+            retries,
+            initial_backoff: Duration::ZERO,
+            max_backoff: Duration::ZERO,
+            on_retry: None,
+        }
+    }
+
+    /// Sleeps before every retry, starting at `initial` and doubling after
+    /// each failed attempt up to `max`.
+    pub const fn with_backoff(
+        mut self,
+        initial: Duration,
+        max: Duration,
+    ) -> Self {
+        self.initial_backoff = initial;
+        self.max_backoff = max;
+        self
+    }
+
+    /// Runs `callback` before every retry, typically to free memory.
+    pub fn on_retry<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(&OomInfo) + Send + Sync + 'static,
+    {
+        self.on_retry = Some(Arc::new(callback));
+        self
+    }
+
+    /// Returns the maximum number of retries.
+    #[inline(always)]
+    pub const fn retries(&self) -> usize {
+        self.retries
+    }
+
+    /// Returns how long to wait before retrying after failure `attempt`.
+    pub fn backoff(&self, attempt: usize) -> Duration {
+        let factor = 1u32
+            .checked_shl(attempt.saturating_sub(1) as u32)
+            .unwrap_or(u32::MAX);
+        self.initial_backoff
+            .saturating_mul(factor)
+            .min(self.max_backoff)
+    }
+}
+
+impl fmt::Debug for RetryPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RetryPolicy")
+            .field("retries", &self.retries)
+            .field("initial_backoff", &self.initial_backoff)
+            .field("max_backoff", &self.max_backoff)
+            .field("on_retry", &self.on_retry.is_some())
+            .finish()
+    }
+}
+
+static RETRY_POLICY: RwLock<Option<RetryPolicy>> = RwLock::new(None);
+
+/// Installs the crate-wide allocation [`RetryPolicy`], replacing any previous
+/// one.
+pub fn set_retry_policy(policy: RetryPolicy) {
+    *RETRY_POLICY
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(policy);
+}
+
+/// Removes the retry policy, so failed allocations are no longer retried
+/// unless the out-of-memory handler asks for it.
+pub fn clear_retry_policy() {
+    *RETRY_POLICY
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) = None;
+}
+
+/// Runs `allocate` until it succeeds or both the out-of-memory handler and
+/// the retry policy give up.
+pub(crate) fn allocate_with_oom_handler(
+    layout: Layout,
+    type_name: &'static str,
+    mut allocate: impl FnMut() -> Result<NonNull<u8>, Error>,
+) -> Result<NonNull<u8>, Error> {
+    let mut attempt = 0;
+    let mut retried = 0;
+    loop {
+        let error = match allocate() {
+            Ok(ptr) => return Ok(ptr),
+            Err(error) => error,
+        };
+        attempt += 1;
+
+        // Clone the handler out so it may itself install a new one.
+        let handler = OOM_HANDLER
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone();
+        let info = OomInfo {
+            layout,
+            type_name,
+            attempt,
+        };
+        if let Some(OomAction::Retry) = handler.map(|handler| handler(&info)) {
+            continue;
+        }
+
+        let policy = RETRY_POLICY
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone();
+        match policy {
+            Some(policy) if retried < policy.retries => {
+                retried += 1;
+                if let Some(callback) = &policy.on_retry {
+                    callback(&info);
+                }
+                std::thread::sleep(policy.backoff(retried));
+            }
+            _ => return Err(error),
+        }
+    }
+}
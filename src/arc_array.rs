@@ -0,0 +1,355 @@
+use std::{
+    fmt,
+    marker::PhantomData,
+    mem::ManuallyDrop,
+    ops::Deref,
+    ptr::{self, NonNull},
+    sync::atomic::{fence, AtomicPtr, AtomicUsize, Ordering},
+};
+
+use crate::{epoch::Domain, error::Error, runtime_array::ArrayCStyle};
+
+/// Refcounts above this are treated as a leak and abort, like `Arc` does.
+const MAX_REFCOUNT: usize = isize::MAX as usize;
+
+struct ArcInner<T> {
+    strong: AtomicUsize,
+    array: ArrayCStyle<T>,
+}
+
+/// An atomically reference-counted, immutable array.
+///
+/// Cloning an `ArcArray` only bumps a counter, so large buffers can be handed
+/// to many threads and tasks without deep copies. Mutation goes through
+/// [`ArcArray::make_mut`], which copies the buffer only while it is shared.
+///
+/// # Example
+///
+/// ```rust ignore
+/// use runnarr::{arc_array::ArcArray, runtime_array::ArrayCStyle};
+///
+/// let weights = ArcArray::new(ArrayCStyle::<f32>::zeroed(1 << 20).unwrap());
+/// let worker_weights = weights.clone(); // O(1)
+/// std::thread::spawn(move || evaluate(&worker_weights));
+/// ```
+pub struct ArcArray<T> {
+    ptr: NonNull<ArcInner<T>>,
+    _marker: PhantomData<ArcInner<T>>,
+}
+
+// Same reasoning as `Arc<T>`: clones on other threads can read the elements
+// (needs `Sync`) and the last clone, on any thread, drops them (needs `Send`).
+unsafe impl<T: Send + Sync> Send for ArcArray<T> {}
+unsafe impl<T: Send + Sync> Sync for ArcArray<T> {}
+
+impl<T> ArcArray<T> {
+    /// Moves `array` behind a reference count.
+    pub fn new(array: ArrayCStyle<T>) -> Self {
+        let inner = Box::new(ArcInner {
+            strong: AtomicUsize::new(1),
+            array,
+        });
+        Self {
+            ptr: NonNull::from(Box::leak(inner)),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Creates a shared array of `size` zeroed elements.
+    #[track_caller]
+    pub fn zeroed(size: usize) -> Result<Self, Error> {
+        Ok(Self::new(ArrayCStyle::zeroed(size)?))
+    }
+
+    #[inline(always)]
+    fn inner(&self) -> &ArcInner<T> {
+        unsafe { self.ptr.as_ref() }
+    }
+
+    /// Returns the shared array.
+    #[inline(always)]
+    pub fn array(&self) -> &ArrayCStyle<T> {
+        &self.inner().array
+    }
+
+    /// Returns the elements as a slice.
+    #[inline(always)]
+    pub fn as_slice(&self) -> &[T] {
+        self.array().as_slice()
+    }
+
+    /// Returns the number of `ArcArray`s sharing this buffer.
+    #[inline(always)]
+    pub fn strong_count(this: &Self) -> usize {
+        this.inner().strong.load(Ordering::Acquire)
+    }
+
+    /// Returns `true` if both handles share the same buffer.
+    #[inline(always)]
+    pub fn ptr_eq(this: &Self, other: &Self) -> bool {
+        this.ptr == other.ptr
+    }
+
+    /// Returns a mutable reference to the array if this is the only handle.
+    pub fn get_mut(this: &mut Self) -> Option<&mut ArrayCStyle<T>> {
+        if this.inner().strong.load(Ordering::Acquire) == 1 {
+            Some(unsafe { &mut this.ptr.as_mut().array })
+        } else {
+            None
+        }
+    }
+
+    /// Returns the array if this is the only handle, or gives the handle
+    /// back otherwise.
+    pub fn try_unwrap(this: Self) -> Result<ArrayCStyle<T>, Self> {
+        if this
+            .inner()
+            .strong
+            .compare_exchange(1, 0, Ordering::Acquire, Ordering::Relaxed)
+            .is_err()
+        {
+            return Err(this);
+        }
+        let this = ManuallyDrop::new(this);
+        let inner = unsafe { Box::from_raw(this.ptr.as_ptr()) };
+        Ok(inner.array)
+    }
+}
+
+impl<T: Clone> ArcArray<T> {
+    /// Returns a mutable reference to the array, copying it first if other
+    /// handles share it (copy-on-write).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the copy cannot be allocated.
+    #[cfg(not(feature = "no-panic"))]
+    #[track_caller]
+    pub fn make_mut(this: &mut Self) -> &mut ArrayCStyle<T> {
+        Self::try_make_mut(this).expect("Failed to allocate array copy")
+    }
+
+    /// Like [`ArcArray::make_mut`], but returns an error instead of
+    /// panicking if the copy cannot be allocated.
+    #[track_caller]
+    pub fn try_make_mut(this: &mut Self) -> Result<&mut ArrayCStyle<T>, Error> {
+        if this.inner().strong.load(Ordering::Acquire) != 1 {
+            *this = ArcArray::new(this.array().try_clone()?);
+        }
+        Ok(unsafe { &mut this.ptr.as_mut().array })
+    }
+}
+
+impl<T> Clone for ArcArray<T> {
+    fn clone(&self) -> Self {
+        let old = self.inner().strong.fetch_add(1, Ordering::Relaxed);
+        if old > MAX_REFCOUNT {
+            std::process::abort();
+        }
+        Self {
+            ptr: self.ptr,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T> Drop for ArcArray<T> {
+    fn drop(&mut self) {
+        if self.inner().strong.fetch_sub(1, Ordering::Release) != 1 {
+            return;
+        }
+        // synchronize with every other handle's release before freeing
+        fence(Ordering::Acquire);
+        drop(unsafe { Box::from_raw(self.ptr.as_ptr()) });
+    }
+}
+
+impl<T> Deref for ArcArray<T> {
+    type Target = [T];
+    fn deref(&self) -> &Self::Target {
+        self.as_slice()
+    }
+}
+
+#[cfg(not(feature = "no-panic"))]
+impl<T> std::ops::Index<usize> for ArcArray<T> {
+    type Output = T;
+    fn index(&self, index: usize) -> &Self::Output {
+        self.array().get(index).expect("Index out of bounds")
+    }
+}
+
+impl<T> From<ArrayCStyle<T>> for ArcArray<T> {
+    fn from(array: ArrayCStyle<T>) -> Self {
+        Self::new(array)
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for ArcArray<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("ArcArray").field(&self.as_slice()).finish()
+    }
+}
+
+/// A slot holding an [`ArcArray`] that can be read and replaced atomically.
+///
+/// Readers call [`AtomicArrayCell::load`] to snapshot the current array and
+/// keep using it for as long as they like, while a writer publishes a
+/// replacement with [`AtomicArrayCell::store`], which makes the pattern a
+/// good fit for hot configuration tables.
+///
+/// Neither side waits for the other. A load pins the cell's epoch
+/// [`Domain`] while it bumps the count of the array it found, and a
+/// replacement hands the cell's own count to the caller but keeps an extra
+/// one that the domain releases once no load can still be holding the old
+/// pointer. A replaced array therefore lives on for up to a couple of
+/// further replacements, or until the cell is dropped.
+///
+/// # Example
+///
+/// ```rust ignore
+/// use runnarr::arc_array::{ArcArray, AtomicArrayCell};
+///
+/// let routes = AtomicArrayCell::new(ArcArray::new(load_routes()?));
+///
+/// // hot path
+/// let table = routes.load();
+/// lookup(&table, key);
+///
+/// // config reload
+/// routes.store(ArcArray::new(load_routes()?));
+/// ```
+pub struct AtomicArrayCell<T> {
+    ptr: AtomicPtr<ArcInner<T>>,
+    /// Defers releasing replaced arrays until no `load` that may have read
+    /// their pointer is still counting it.
+    domain: Domain,
+    _marker: PhantomData<ArcArray<T>>,
+}
+
+unsafe impl<T: Send + Sync> Send for AtomicArrayCell<T> {}
+unsafe impl<T: Send + Sync> Sync for AtomicArrayCell<T> {}
+
+impl<T> AtomicArrayCell<T> {
+    /// Creates a cell holding `array`.
+    pub fn new(array: ArcArray<T>) -> Self {
+        Self {
+            ptr: AtomicPtr::new(Self::into_raw(array)),
+            domain: Domain::new(),
+            _marker: PhantomData,
+        }
+    }
+
+    fn into_raw(array: ArcArray<T>) -> *mut ArcInner<T> {
+        ManuallyDrop::new(array).ptr.as_ptr()
+    }
+
+    /// Turns a pointer owned by the cell back into a handle.
+    unsafe fn from_raw(ptr: *mut ArcInner<T>) -> ArcArray<T> {
+        ArcArray {
+            ptr: unsafe { NonNull::new_unchecked(ptr) },
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns a handle to the array currently in the cell.
+    pub fn load(&self) -> ArcArray<T> {
+        let _guard = self.domain.pin();
+        let current = unsafe {
+            ManuallyDrop::new(Self::from_raw(self.ptr.load(Ordering::SeqCst)))
+        };
+        ArcArray::clone(&current)
+    }
+
+    /// Keeps `previous`, just unlinked from the cell, alive until no `load`
+    /// can still be holding its pointer without having counted it.
+    fn retire(&self, previous: &ArcArray<T>) {
+        unsafe fn release<T>(ptr: *mut ()) {
+            drop(unsafe { AtomicArrayCell::from_raw(ptr as *mut ArcInner<T>) });
+        }
+
+        // a plain function pointer keeps the deferred call `'static` for
+        // any `T`; the domain runs it no later than the cell's drop
+        let retired = RetiredPtr(Self::into_raw(previous.clone()) as *mut ());
+        let release: unsafe fn(*mut ()) = release::<T>;
+        self.domain.defer(move || {
+            let retired = retired;
+            unsafe { release(retired.0) }
+        });
+        self.domain.collect();
+    }
+
+    /// Replaces the array in the cell, returning the previous one.
+    pub fn swap(&self, array: ArcArray<T>) -> ArcArray<T> {
+        let previous = self.ptr.swap(Self::into_raw(array), Ordering::SeqCst);
+        let previous = unsafe { Self::from_raw(previous) };
+        self.retire(&previous);
+        previous
+    }
+
+    /// Replaces the array in the cell.
+    pub fn store(&self, array: ArcArray<T>) {
+        drop(self.swap(array));
+    }
+
+    /// Replaces the array with `new` only if the cell still holds `current`.
+    ///
+    /// # Returns
+    ///
+    /// `Ok` with the previous array on success, or `Err` giving `new` back
+    /// if another writer got there first.
+    pub fn compare_and_swap(
+        &self,
+        current: &ArcArray<T>,
+        new: ArcArray<T>,
+    ) -> Result<ArcArray<T>, ArcArray<T>> {
+        let new = Self::into_raw(new);
+        match self.ptr.compare_exchange(
+            current.ptr.as_ptr(),
+            new,
+            Ordering::SeqCst,
+            Ordering::SeqCst,
+        ) {
+            Ok(previous) => {
+                let previous = unsafe { Self::from_raw(previous) };
+                self.retire(&previous);
+                Ok(previous)
+            }
+            Err(_) => Err(unsafe { Self::from_raw(new) }),
+        }
+    }
+
+    /// Consumes the cell, returning the array it holds.
+    pub fn into_inner(self) -> ArcArray<T> {
+        let mut this = ManuallyDrop::new(self);
+        // releases the replaced arrays still waiting in the domain
+        unsafe { ptr::drop_in_place(&mut this.domain) };
+        unsafe { Self::from_raw(*this.ptr.get_mut()) }
+    }
+}
+
+impl<T> Drop for AtomicArrayCell<T> {
+    fn drop(&mut self) {
+        drop(unsafe { Self::from_raw(*self.ptr.get_mut()) });
+    }
+}
+
+/// Moves a retired array's pointer into the deferred release.
+struct RetiredPtr(*mut ());
+
+// only sent when the cell is shared, which requires `T: Send + Sync`
+unsafe impl Send for RetiredPtr {}
+
+impl<T> From<ArcArray<T>> for AtomicArrayCell<T> {
+    fn from(array: ArcArray<T>) -> Self {
+        Self::new(array)
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for AtomicArrayCell<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("AtomicArrayCell")
+            .field(&self.load())
+            .finish()
+    }
+}
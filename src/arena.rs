@@ -0,0 +1,136 @@
+use std::{alloc::Layout, cell::Cell, ptr::NonNull};
+
+use crate::{
+    allocator::RawAllocator, error::Error, runtime_array::ArrayCStyle,
+    usage::MemoryUsage,
+};
+
+/// An array allocated from an [`ArrayArena`].
+pub type ArenaArray<'a, T> = ArrayCStyle<T, &'a ArrayArena>;
+
+/// A bump allocator handing out many short-lived arrays from one buffer.
+///
+/// Allocating is a pointer bump and freeing an individual array is a no-op
+/// (except for the most recent one, whose space is reclaimed right away).
+/// All space is reclaimed at once by [`ArrayArena::reset`], which needs
+/// `&mut self` and therefore cannot run while any [`ArenaArray`] is alive.
+///
+/// This removes the per-array `malloc`/`free` pair for workloads that create
+/// thousands of tiny arrays per frame and drop them all together.
+///
+/// Like every array in the crate, arena arrays never drop their elements.
+///
+/// # Example
+///
+/// ```rust ignore
+/// use runnarr::arena::ArrayArena;
+///
+/// let mut arena = ArrayArena::new(64 * 1024).unwrap();
+/// for frame in frames {
+///     {
+///         let tokens = arena.alloc_array::<u32>(frame.len()).unwrap();
+///         let spans = arena.alloc_zeroed::<(u32, u32)>(frame.len()).unwrap();
+///         parse(frame, tokens, spans);
+///     }
+///     arena.reset();
+/// }
+/// ```
+#[derive(Debug)]
+pub struct ArrayArena {
+    buffer: ArrayCStyle<u8>,
+    offset: Cell<usize>,
+}
+
+impl ArrayArena {
+    /// Creates an arena owning a single buffer of `capacity` bytes.
+    pub fn new(capacity: usize) -> Result<Self, Error> {
+        Ok(Self {
+            buffer: ArrayCStyle::new(capacity)?,
+            offset: Cell::new(0),
+        })
+    }
+
+    /// Allocates an uninitialized array of `len` elements from the arena.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the arena does not have enough space left.
+    pub fn alloc_array<T>(
+        &self,
+        len: usize,
+    ) -> Result<ArenaArray<'_, T>, Error> {
+        ArrayCStyle::new_in(len, self)
+    }
+
+    /// Allocates an array of `len` zeroed elements from the arena.
+    pub fn alloc_zeroed<T>(
+        &self,
+        len: usize,
+    ) -> Result<ArenaArray<'_, T>, Error> {
+        ArrayCStyle::zeroed_in(len, self)
+    }
+
+    /// Releases every allocation at once.
+    pub fn reset(&mut self) {
+        self.offset.set(0);
+    }
+
+    /// Returns the total size of the arena in bytes.
+    #[inline(always)]
+    pub fn capacity(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// Returns the number of bytes handed out since the last reset,
+    /// including alignment padding.
+    #[inline(always)]
+    pub fn used(&self) -> usize {
+        self.offset.get()
+    }
+
+    /// Returns the number of bytes still available.
+    #[inline(always)]
+    pub fn remaining(&self) -> usize {
+        self.capacity() - self.used()
+    }
+
+    /// Returns the memory held by the arena; the space not handed out yet
+    /// counts as slack.
+    pub fn memory_usage(&self) -> MemoryUsage {
+        MemoryUsage {
+            allocated_bytes: self.buffer.allocated_bytes(),
+            used_bytes: self.used(),
+        }
+    }
+}
+
+unsafe impl RawAllocator for ArrayArena {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<u8>, Error> {
+        let base = self.buffer.ptr() as usize;
+        let start =
+            (base + self.offset.get()).next_multiple_of(layout.align()) - base;
+        let end = start
+            .checked_add(layout.size())
+            .filter(|&end| end <= self.capacity())
+            .ok_or_else(|| {
+                Error::Other(format!(
+                    "Arena exhausted: requested {} bytes, {} remaining",
+                    layout.size(),
+                    self.remaining()
+                ))
+            })?;
+
+        self.offset.set(end);
+        Ok(unsafe { NonNull::new_unchecked(self.buffer.ptr_mut().add(start)) })
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        // Only the most recent allocation can be popped off the bump pointer;
+        // everything else waits for the next reset.
+        let end = ptr.as_ptr() as usize + layout.size();
+        if end == self.buffer.ptr() as usize + self.offset.get() {
+            self.offset
+                .set(ptr.as_ptr() as usize - self.buffer.ptr() as usize);
+        }
+    }
+}
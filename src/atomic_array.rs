@@ -0,0 +1,310 @@
+use std::{
+    fmt,
+    sync::atomic::{
+        AtomicI16, AtomicI32, AtomicI64, AtomicI8, AtomicIsize, AtomicU16,
+        AtomicU32, AtomicU64, AtomicU8, AtomicUsize, Ordering,
+    },
+};
+
+use crate::{error::Error, runtime_array::ArrayCStyle, usage::MemoryUsage};
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// An integer type with a matching `std::sync::atomic` type.
+///
+/// Implemented for every primitive integer that has one; the trait is sealed.
+pub trait AtomicElement: Copy + sealed::Sealed {
+    /// The atomic counterpart, e.g. `AtomicU32` for `u32`.
+    type Atomic: Send + Sync;
+
+    #[doc(hidden)]
+    fn load(atomic: &Self::Atomic, order: Ordering) -> Self;
+    #[doc(hidden)]
+    fn store(atomic: &Self::Atomic, value: Self, order: Ordering);
+    #[doc(hidden)]
+    fn swap(atomic: &Self::Atomic, value: Self, order: Ordering) -> Self;
+    #[doc(hidden)]
+    fn fetch_add(atomic: &Self::Atomic, value: Self, order: Ordering) -> Self;
+    #[doc(hidden)]
+    fn fetch_sub(atomic: &Self::Atomic, value: Self, order: Ordering) -> Self;
+    #[doc(hidden)]
+    fn compare_exchange(
+        atomic: &Self::Atomic,
+        current: Self,
+        new: Self,
+        success: Ordering,
+        failure: Ordering,
+    ) -> Result<Self, Self>;
+}
+
+macro_rules! atomic_element {
+    ($($int:ty => $atomic:ty),* $(,)?) => {
+        $(
+            impl sealed::Sealed for $int {}
+
+            impl AtomicElement for $int {
+                type Atomic = $atomic;
+
+                #[inline(always)]
+                fn load(atomic: &$atomic, order: Ordering) -> Self {
+                    atomic.load(order)
+                }
+
+                #[inline(always)]
+                fn store(atomic: &$atomic, value: Self, order: Ordering) {
+                    atomic.store(value, order)
+                }
+
+                #[inline(always)]
+                fn swap(
+                    atomic: &$atomic,
+                    value: Self,
+                    order: Ordering,
+                ) -> Self {
+                    atomic.swap(value, order)
+                }
+
+                #[inline(always)]
+                fn fetch_add(
+                    atomic: &$atomic,
+                    value: Self,
+                    order: Ordering,
+                ) -> Self {
+                    atomic.fetch_add(value, order)
+                }
+
+                #[inline(always)]
+                fn fetch_sub(
+                    atomic: &$atomic,
+                    value: Self,
+                    order: Ordering,
+                ) -> Self {
+                    atomic.fetch_sub(value, order)
+                }
+
+                #[inline(always)]
+                fn compare_exchange(
+                    atomic: &$atomic,
+                    current: Self,
+                    new: Self,
+                    success: Ordering,
+                    failure: Ordering,
+                ) -> Result<Self, Self> {
+                    atomic.compare_exchange(current, new, success, failure)
+                }
+            }
+        )*
+    };
+}
+
+atomic_element! {
+    u8 => AtomicU8,
+    u16 => AtomicU16,
+    u32 => AtomicU32,
+    u64 => AtomicU64,
+    usize => AtomicUsize,
+    i8 => AtomicI8,
+    i16 => AtomicI16,
+    i32 => AtomicI32,
+    i64 => AtomicI64,
+    isize => AtomicIsize,
+}
+
+/// A runtime sized array of integers that can be updated from many threads
+/// at once.
+///
+/// Every slot is an atomic, so shared counters and histograms only need a
+/// `&AtomicArray` instead of a mutex around the whole buffer. All operations
+/// take the memory [`Ordering`] explicitly, like the std atomics do.
+///
+/// # Example
+///
+/// ```rust ignore
+/// use std::sync::atomic::Ordering;
+/// use runnarr::atomic_array::AtomicArray;
+///
+/// let histogram = AtomicArray::<u64>::zeroed(256).unwrap();
+/// std::thread::scope(|scope| {
+///     for chunk in data.chunks(4096) {
+///         let histogram = &histogram;
+///         scope.spawn(move || {
+///             for &byte in chunk {
+///                 histogram.fetch_add(byte as usize, 1, Ordering::Relaxed);
+///             }
+///         });
+///     }
+/// });
+/// ```
+pub struct AtomicArray<T: AtomicElement> {
+    array: ArrayCStyle<T::Atomic>,
+}
+
+impl<T: AtomicElement> AtomicArray<T> {
+    /// Creates an array of `len` slots, all holding zero.
+    #[track_caller]
+    pub fn zeroed(len: usize) -> Result<Self, Error> {
+        // the atomic integers have the same in-memory representation as
+        // their plain counterparts, so all-zero bytes are a valid zero
+        Ok(Self {
+            array: ArrayCStyle::zeroed(len)?,
+        })
+    }
+
+    /// Creates an array holding a copy of `values`.
+    #[track_caller]
+    pub fn from_slice(values: &[T]) -> Result<Self, Error> {
+        let array = Self::zeroed(values.len())?;
+        for (slot, &value) in array.as_slice().iter().zip(values) {
+            T::store(slot, value, Ordering::Relaxed);
+        }
+        Ok(array)
+    }
+
+    /// Returns the number of slots.
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.array.len()
+    }
+
+    /// Returns `true` if the array has no slots.
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.array.is_empty()
+    }
+
+    /// Returns the atomic at `index`, or `None` if it is out of bounds.
+    #[inline(always)]
+    pub fn get(&self, index: usize) -> Option<&T::Atomic> {
+        self.array.get(index)
+    }
+
+    /// Returns the slots as a slice of atomics.
+    #[inline(always)]
+    pub fn as_slice(&self) -> &[T::Atomic] {
+        self.array.as_slice()
+    }
+
+    /// Copies every slot into a plain array.
+    ///
+    /// Each slot is loaded individually with `order`; the copy is not an
+    /// atomic snapshot of the whole array.
+    #[track_caller]
+    pub fn load_all(&self, order: Ordering) -> Result<ArrayCStyle<T>, Error> {
+        let values = ArrayCStyle::<T>::new(self.len())?;
+        for (index, slot) in self.as_slice().iter().enumerate() {
+            unsafe { values.ptr_mut().add(index).write(T::load(slot, order)) };
+        }
+        Ok(values)
+    }
+
+    /// Returns the memory held by the array.
+    pub fn memory_usage(&self) -> MemoryUsage {
+        self.array.memory_usage()
+    }
+}
+
+#[cfg(not(feature = "no-panic"))]
+impl<T: AtomicElement> AtomicArray<T> {
+    #[inline(always)]
+    #[track_caller]
+    fn slot(&self, index: usize) -> &T::Atomic {
+        self.get(index).expect("Index out of bounds")
+    }
+
+    /// Loads the value at `index`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds or `order` is `Release` or
+    /// `AcqRel`.
+    #[inline(always)]
+    #[track_caller]
+    pub fn load(&self, index: usize, order: Ordering) -> T {
+        T::load(self.slot(index), order)
+    }
+
+    /// Stores `value` at `index`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds or `order` is `Acquire` or
+    /// `AcqRel`.
+    #[inline(always)]
+    #[track_caller]
+    pub fn store(&self, index: usize, value: T, order: Ordering) {
+        T::store(self.slot(index), value, order)
+    }
+
+    /// Stores `value` at `index`, returning the previous value.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    #[inline(always)]
+    #[track_caller]
+    pub fn swap(&self, index: usize, value: T, order: Ordering) -> T {
+        T::swap(self.slot(index), value, order)
+    }
+
+    /// Adds `value` to the slot at `index`, wrapping on overflow, and returns
+    /// the previous value.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    #[inline(always)]
+    #[track_caller]
+    pub fn fetch_add(&self, index: usize, value: T, order: Ordering) -> T {
+        T::fetch_add(self.slot(index), value, order)
+    }
+
+    /// Subtracts `value` from the slot at `index`, wrapping on overflow, and
+    /// returns the previous value.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    #[inline(always)]
+    #[track_caller]
+    pub fn fetch_sub(&self, index: usize, value: T, order: Ordering) -> T {
+        T::fetch_sub(self.slot(index), value, order)
+    }
+
+    /// Stores `new` at `index` if the slot still holds `current`.
+    ///
+    /// # Returns
+    ///
+    /// `Ok` with the previous value on success, or `Err` with the value
+    /// actually found.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds or `failure` is `Release` or
+    /// `AcqRel`.
+    #[inline(always)]
+    #[track_caller]
+    pub fn compare_exchange(
+        &self,
+        index: usize,
+        current: T,
+        new: T,
+        success: Ordering,
+        failure: Ordering,
+    ) -> Result<T, T> {
+        T::compare_exchange(self.slot(index), current, new, success, failure)
+    }
+}
+
+impl<T: AtomicElement + fmt::Debug> fmt::Debug for AtomicArray<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(
+                self.as_slice()
+                    .iter()
+                    .map(|slot| T::load(slot, Ordering::Relaxed)),
+            )
+            .finish()
+    }
+}
@@ -0,0 +1,145 @@
+use std::{
+    fmt,
+    sync::atomic::{AtomicU64, AtomicUsize, Ordering},
+};
+
+use crate::{atomic_array::AtomicArray, error::Error};
+
+const WORD_BITS: usize = u64::BITS as usize;
+
+/// A lock-free allocator of slot indices `0..capacity`.
+///
+/// Every slot is one bit in an [`AtomicArray<u64>`]; acquiring sets a clear
+/// bit with a compare-and-swap and releasing clears it again, so any number
+/// of threads can hand out and return slots without a lock. Searches start
+/// at the word where the last successful one ended, which keeps threads from
+/// all fighting over the first word.
+///
+/// It only manages indices, which makes it the backbone for concurrent
+/// object pools, connection tables and the like that keep their slots in a
+/// separate array.
+///
+/// # Example
+///
+/// ```rust ignore
+/// use runnarr::bitmap_alloc::BitmapAllocator;
+///
+/// let slots = BitmapAllocator::new(MAX_CONNECTIONS).unwrap();
+/// let Some(slot) = slots.acquire() else {
+///     return reject(stream);
+/// };
+/// connections[slot].open(stream);
+/// // ...
+/// slots.release(slot);
+/// ```
+pub struct BitmapAllocator {
+    words: AtomicArray<u64>,
+    capacity: usize,
+    /// Word the next search starts at.
+    hint: AtomicUsize,
+}
+
+impl BitmapAllocator {
+    /// Creates an allocator of `capacity` free slots.
+    #[track_caller]
+    pub fn new(capacity: usize) -> Result<Self, Error> {
+        let words = AtomicArray::<u64>::zeroed(capacity.div_ceil(WORD_BITS))?;
+        // bits past the end of the last word are permanently taken
+        let tail = capacity % WORD_BITS;
+        if let Some(last) = words.as_slice().last().filter(|_| tail != 0) {
+            last.store(!0 << tail, Ordering::Relaxed);
+        }
+        Ok(Self {
+            words,
+            capacity,
+            hint: AtomicUsize::new(0),
+        })
+    }
+
+    /// Returns the number of slots.
+    #[inline(always)]
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Marks a free slot as taken and returns its index, or `None` if every
+    /// slot is taken.
+    pub fn acquire(&self) -> Option<usize> {
+        let words = self.words.as_slice();
+        let start = self.hint.load(Ordering::Relaxed);
+        for offset in 0..words.len() {
+            let index = (start + offset) % words.len();
+            if let Some(bit) = Self::acquire_in(&words[index]) {
+                self.hint.store(index, Ordering::Relaxed);
+                return Some(index * WORD_BITS + bit);
+            }
+        }
+        None
+    }
+
+    /// Sets the lowest clear bit of `word`, if any.
+    fn acquire_in(word: &AtomicU64) -> Option<usize> {
+        let mut current = word.load(Ordering::Relaxed);
+        while current != !0 {
+            let bit = current.trailing_ones() as usize;
+            match word.compare_exchange_weak(
+                current,
+                current | 1 << bit,
+                Ordering::Acquire,
+                Ordering::Relaxed,
+            ) {
+                Ok(_) => return Some(bit),
+                Err(actual) => current = actual,
+            }
+        }
+        None
+    }
+
+    /// Returns slot `index` to the allocator.
+    ///
+    /// # Returns
+    ///
+    /// `false` if `index` is out of bounds or was not taken.
+    pub fn release(&self, index: usize) -> bool {
+        if index >= self.capacity {
+            return false;
+        }
+        let mask = 1 << (index % WORD_BITS);
+        let Some(word) = self.words.get(index / WORD_BITS) else {
+            return false;
+        };
+        word.fetch_and(!mask, Ordering::Release) & mask != 0
+    }
+
+    /// Returns `true` if slot `index` is currently taken.
+    pub fn is_acquired(&self, index: usize) -> bool {
+        index < self.capacity
+            && self.words.get(index / WORD_BITS).is_some_and(|word| {
+                word.load(Ordering::Acquire) & 1 << (index % WORD_BITS) != 0
+            })
+    }
+
+    /// Returns the number of taken slots.
+    ///
+    /// Other threads may acquire or release concurrently, so the result is
+    /// only a snapshot.
+    pub fn acquired(&self) -> usize {
+        let padding = self.words.len() * WORD_BITS - self.capacity;
+        let taken: usize = self
+            .words
+            .as_slice()
+            .iter()
+            .map(|word| word.load(Ordering::Relaxed).count_ones() as usize)
+            .sum();
+        taken - padding
+    }
+}
+
+impl fmt::Debug for BitmapAllocator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BitmapAllocator")
+            .field("capacity", &self.capacity)
+            .field("acquired", &self.acquired())
+            .finish()
+    }
+}
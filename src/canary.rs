@@ -0,0 +1,176 @@
+use std::{alloc::Layout, ptr::NonNull};
+
+use crate::{
+    allocator::{Global, RawAllocator},
+    error::{CanaryRegion, CorruptionError, Error},
+    runtime_array::ArrayCStyle,
+};
+
+/// Number of canary bytes written on each side of a buffer.
+pub const CANARY_LEN: usize = 16;
+
+const CANARY: [u8; CANARY_LEN] = [
+    0xCA, 0xFE, 0xF0, 0x0D, 0xDE, 0xAD, 0xC0, 0xDE, 0xCA, 0xFE, 0xF0, 0x0D,
+    0xDE, 0xAD, 0xC0, 0xDE,
+];
+
+/// An allocator wrapper that surrounds every buffer with canary bytes.
+///
+/// A lighter-weight alternative to [`GuardPages`](crate::os::GuardPages):
+/// instead of faulting on the spot, overruns and underruns are detected after
+/// the fact by checking that the canaries are intact. The check runs when the
+/// buffer is freed, panicking with a [`CorruptionError`] description on
+/// corruption, and on demand through [`ArrayCStyle::check_integrity`].
+///
+/// Each allocation grows by [`CANARY_LEN`] bytes at the end and by
+/// `max(CANARY_LEN, align)` bytes at the start.
+#[derive(Debug, Clone, Copy, Default, Hash, PartialEq, Eq)]
+pub struct Canary<A: RawAllocator = Global> {
+    inner: A,
+}
+
+impl<A: RawAllocator> Canary<A> {
+    /// Wraps `inner` so its allocations are guarded by canaries.
+    pub const fn new(inner: A) -> Self {
+        Self { inner }
+    }
+
+    /// Returns a reference to the wrapped allocator.
+    pub const fn inner(&self) -> &A {
+        &self.inner
+    }
+
+    fn prefix_len(layout: Layout) -> usize {
+        CANARY_LEN.next_multiple_of(layout.align())
+    }
+
+    fn outer_layout(layout: Layout) -> Result<Layout, Error> {
+        let size = Self::prefix_len(layout)
+            .checked_add(layout.size())
+            .and_then(|size| size.checked_add(CANARY_LEN))
+            .ok_or_else(|| {
+                Error::Other("Canary layout overflows usize".to_string())
+            })?;
+        Ok(Layout::from_size_align(size, layout.align())?)
+    }
+
+    /// Writes the canaries around the buffer inside `outer` and returns the
+    /// buffer pointer.
+    unsafe fn arm(outer: NonNull<u8>, layout: Layout) -> NonNull<u8> {
+        unsafe {
+            let buffer = outer.as_ptr().add(Self::prefix_len(layout));
+            buffer
+                .sub(CANARY_LEN)
+                .copy_from_nonoverlapping(CANARY.as_ptr(), CANARY_LEN);
+            buffer
+                .add(layout.size())
+                .copy_from_nonoverlapping(CANARY.as_ptr(), CANARY_LEN);
+            NonNull::new_unchecked(buffer)
+        }
+    }
+
+    /// Verifies the canaries around `buffer`.
+    ///
+    /// # Safety
+    ///
+    /// `buffer` must have been returned by this allocator for `layout`.
+    pub(crate) unsafe fn verify(
+        buffer: *const u8,
+        layout: Layout,
+    ) -> Result<(), CorruptionError> {
+        let regions = [
+            (CanaryRegion::Before, -(CANARY_LEN as isize)),
+            (CanaryRegion::After, layout.size() as isize),
+        ];
+        for (region, start) in regions {
+            for (index, &expected) in CANARY.iter().enumerate() {
+                let offset = start + index as isize;
+                let address = unsafe { buffer.offset(offset) };
+                let found = unsafe { address.read() };
+                if found != expected {
+                    return Err(CorruptionError {
+                        region,
+                        offset,
+                        address: address as usize,
+                        expected,
+                        found,
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+unsafe impl<A: RawAllocator> RawAllocator for Canary<A> {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<u8>, Error> {
+        let outer = self.inner.allocate(Self::outer_layout(layout)?)?;
+        Ok(unsafe { Self::arm(outer, layout) })
+    }
+
+    fn allocate_zeroed(&self, layout: Layout) -> Result<NonNull<u8>, Error> {
+        let outer = self.inner.allocate_zeroed(Self::outer_layout(layout)?)?;
+        Ok(unsafe { Self::arm(outer, layout) })
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        if let Err(error) = unsafe { Self::verify(ptr.as_ptr(), layout) } {
+            // Freeing a block whose surroundings were scribbled over could
+            // corrupt the inner allocator too; leak it and report instead.
+            if std::thread::panicking() {
+                eprintln!("{error}");
+                return;
+            }
+            panic!("{error}");
+        }
+        unsafe {
+            let outer = ptr.as_ptr().sub(Self::prefix_len(layout));
+            self.inner.deallocate(
+                NonNull::new_unchecked(outer),
+                Self::outer_layout(layout)
+                    .expect("Failed to create exit layout"),
+            );
+        }
+    }
+}
+
+impl<T> ArrayCStyle<T, Canary> {
+    /// Creates a new uninitialized `Array` whose buffer is surrounded by
+    /// canaries.
+    ///
+    /// # Example
+    ///
+    /// ```rust ignore
+    /// use runnarr::runtime_array::ArrayCStyle;
+    ///
+    /// let array = ArrayCStyle::<u8, _>::new_with_canary(8).unwrap();
+    /// unsafe { array.ptr_mut().add(8).write(0) };
+    /// assert!(array.check_integrity().is_err());
+    /// ```
+    #[track_caller]
+    pub fn new_with_canary(size: usize) -> Result<Self, Error> {
+        Self::new_in(size, Canary::default())
+    }
+
+    /// Creates a new zeroed `Array` whose buffer is surrounded by canaries.
+    #[track_caller]
+    pub fn zeroed_with_canary(size: usize) -> Result<Self, Error> {
+        Self::zeroed_in(size, Canary::default())
+    }
+}
+
+impl<T, A: RawAllocator> ArrayCStyle<T, Canary<A>> {
+    /// Checks that the canaries around the buffer are still intact.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`CorruptionError`] describing the first corrupted byte.
+    pub fn check_integrity(&self) -> Result<(), CorruptionError> {
+        let layout = self.layout();
+        if layout.size() == 0 {
+            // empty buffers are never allocated and carry no canaries
+            return Ok(());
+        }
+        unsafe { Canary::<A>::verify(self.ptr() as *const u8, layout) }
+    }
+}
@@ -0,0 +1,143 @@
+use std::{cell::Cell, fmt, slice};
+
+use crate::{error::Error, runtime_array::ArrayCStyle};
+
+/// A runtime sized array whose elements can be read and written through a
+/// shared reference.
+///
+/// Every slot is a [`Cell`], so [`CellArray::get`] and [`CellArray::set`]
+/// only need `&self`: a graph algorithm can update a node's neighbors while
+/// it iterates over the same array, without `unsafe` and without borrow
+/// juggling. Values are copied in and out, never borrowed, which is why `T`
+/// must be `Copy`. Like `Cell`, the array cannot be shared between threads.
+///
+/// # Example
+///
+/// ```rust ignore
+/// use runnarr::cell_array::CellArray;
+///
+/// let distance = CellArray::filled(graph.nodes(), u32::MAX)?;
+/// distance.set(source, 0)?;
+/// for node in graph.bfs(source) {
+///     let next = distance.get(node).unwrap() + 1;
+///     for neighbor in graph.neighbors(node) {
+///         if distance.get(neighbor) == Some(u32::MAX) {
+///             distance.set(neighbor, next)?;
+///         }
+///     }
+/// }
+/// ```
+pub struct CellArray<T: Copy> {
+    array: ArrayCStyle<Cell<T>>,
+}
+
+impl<T: Copy> CellArray<T> {
+    /// Wraps an initialized array without copying it.
+    pub fn new(array: ArrayCStyle<T>) -> Self {
+        let align = array.align();
+        let (ptr, len) = array.into_raw_parts();
+        // `Cell<T>` has the same in-memory representation as `T`, so the
+        // buffer keeps its layout
+        let array = unsafe {
+            ArrayCStyle::from_raw_parts_aligned(ptr as *mut Cell<T>, len, align)
+        };
+        Self { array }
+    }
+
+    /// Creates an array of `len` copies of `value`.
+    #[track_caller]
+    pub fn filled(len: usize, value: T) -> Result<Self, Error> {
+        let array = ArrayCStyle::<Cell<T>>::new(len)?;
+        for index in 0..len {
+            unsafe { array.ptr_mut().add(index).write(Cell::new(value)) };
+        }
+        Ok(Self { array })
+    }
+
+    /// Creates an array holding a copy of `values`.
+    #[track_caller]
+    pub fn from_slice(values: &[T]) -> Result<Self, Error> {
+        Ok(Self::new(ArrayCStyle::try_from_slice(values)?))
+    }
+
+    /// Returns the number of elements.
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.array.len()
+    }
+
+    /// Returns `true` if the array holds no elements.
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.array.is_empty()
+    }
+
+    /// Returns a copy of the element at `index`, or `None` if it is out of
+    /// bounds.
+    #[inline(always)]
+    pub fn get(&self, index: usize) -> Option<T> {
+        self.array.get(index).map(Cell::get)
+    }
+
+    /// Writes `value` at `index`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::IndexOutOfBounds`] if `index` is out of bounds.
+    #[inline(always)]
+    pub fn set(&self, index: usize, value: T) -> Result<(), Error> {
+        self.array.at(index)?.set(value);
+        Ok(())
+    }
+
+    /// Writes `value` at `index` and returns the previous element, or
+    /// `None` (leaving the array untouched) if `index` is out of bounds.
+    #[inline(always)]
+    pub fn replace(&self, index: usize, value: T) -> Option<T> {
+        self.array.get(index).map(|cell| cell.replace(value))
+    }
+
+    /// Returns the cell at `index`, or `None` if it is out of bounds.
+    #[inline(always)]
+    pub fn cell(&self, index: usize) -> Option<&Cell<T>> {
+        self.array.get(index)
+    }
+
+    /// Returns the elements as a slice of cells.
+    #[inline(always)]
+    pub fn as_slice(&self) -> &[Cell<T>] {
+        self.array.as_slice()
+    }
+
+    /// Returns the elements as a plain mutable slice; the exclusive borrow
+    /// rules out any other access meanwhile.
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        let cells = self.array.as_mut_slice();
+        unsafe {
+            slice::from_raw_parts_mut(cells.as_mut_ptr() as *mut T, cells.len())
+        }
+    }
+
+    /// Unwraps the underlying array without copying it.
+    pub fn into_array(self) -> ArrayCStyle<T> {
+        let align = self.array.align();
+        let (ptr, len) = self.array.into_raw_parts();
+        unsafe {
+            ArrayCStyle::from_raw_parts_aligned(ptr as *mut T, len, align)
+        }
+    }
+}
+
+impl<T: Copy> From<ArrayCStyle<T>> for CellArray<T> {
+    fn from(array: ArrayCStyle<T>) -> Self {
+        Self::new(array)
+    }
+}
+
+impl<T: Copy + fmt::Debug> fmt::Debug for CellArray<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(self.as_slice().iter().map(Cell::get))
+            .finish()
+    }
+}
@@ -0,0 +1,213 @@
+use std::{
+    borrow::Borrow,
+    collections::hash_map::RandomState,
+    fmt,
+    hash::{BuildHasher, Hash},
+    mem::MaybeUninit,
+    sync::atomic::{AtomicU8, AtomicUsize, Ordering},
+};
+
+use crate::{
+    atomic_array::AtomicArray, error::Error, runtime_array::ArrayCStyle,
+};
+
+/// Slot states; a slot only ever moves forward through them.
+const EMPTY: u8 = 0;
+const WRITING: u8 = 1;
+const FULL: u8 = 2;
+
+/// A fixed-capacity hash set that many threads can insert into at once.
+///
+/// Values live in one array and their slot states in an [`AtomicArray`];
+/// collisions are resolved by linear probing. An insert claims an empty slot
+/// with a compare-and-swap, so there are no locks, and since values are
+/// never removed a lookup can stop at the first empty slot it meets. This
+/// fits deduplication in ingestion pipelines, where the set only grows and
+/// its maximum size is known up front.
+///
+/// The capacity is rounded up to a power of two.
+///
+/// # Example
+///
+/// ```rust ignore
+/// use runnarr::concurrent_set::ConcurrentHashSet;
+///
+/// let seen = ConcurrentHashSet::<u64>::with_capacity(1 << 20).unwrap();
+/// records.par_iter().for_each(|record| {
+///     if seen.insert(record.id) == Ok(true) {
+///         sink.send(record);
+///     }
+/// });
+/// ```
+pub struct ConcurrentHashSet<T, S = RandomState> {
+    states: AtomicArray<u8>,
+    values: ArrayCStyle<MaybeUninit<T>>,
+    /// `values.ptr_mut()`, taken once.
+    slots: *mut MaybeUninit<T>,
+    len: AtomicUsize,
+    hasher: S,
+}
+
+// A value is written once, by the thread that claimed its slot, before the
+// slot turns `FULL`; from then on it is only read.
+unsafe impl<T: Send, S: Send> Send for ConcurrentHashSet<T, S> {}
+unsafe impl<T: Send + Sync, S: Sync> Sync for ConcurrentHashSet<T, S> {}
+
+impl<T: Hash + Eq> ConcurrentHashSet<T> {
+    /// Creates an empty set with room for at least `capacity` values.
+    #[track_caller]
+    pub fn with_capacity(capacity: usize) -> Result<Self, Error> {
+        Self::with_capacity_and_hasher(capacity, RandomState::new())
+    }
+}
+
+impl<T: Hash + Eq, S: BuildHasher> ConcurrentHashSet<T, S> {
+    /// Creates an empty set with room for at least `capacity` values, using
+    /// `hasher` to hash them.
+    #[track_caller]
+    pub fn with_capacity_and_hasher(
+        capacity: usize,
+        hasher: S,
+    ) -> Result<Self, Error> {
+        let capacity =
+            capacity.max(1).checked_next_power_of_two().ok_or_else(|| {
+                Error::Other("Set capacity overflows usize".to_string())
+            })?;
+        let values = ArrayCStyle::new(capacity)?;
+        Ok(Self {
+            states: AtomicArray::zeroed(capacity)?,
+            slots: values.ptr_mut(),
+            values,
+            len: AtomicUsize::new(0),
+            hasher,
+        })
+    }
+
+    /// Returns the slots to probe for `value`, starting at its home slot.
+    fn probe<Q: Hash + ?Sized>(
+        &self,
+        value: &Q,
+    ) -> impl Iterator<Item = usize> {
+        let mask = self.capacity() - 1;
+        let home = self.hasher.hash_one(value) as usize;
+        (0..self.capacity()).map(move |step| home.wrapping_add(step) & mask)
+    }
+
+    /// Waits for a slot that is being written and returns its final state.
+    fn settled(state: &AtomicU8) -> u8 {
+        loop {
+            match state.load(Ordering::Acquire) {
+                WRITING => std::hint::spin_loop(),
+                settled => return settled,
+            }
+        }
+    }
+
+    /// Adds `value` to the set.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(true)` if the value was inserted, `Ok(false)` if an equal value
+    /// was already present.
+    ///
+    /// # Errors
+    ///
+    /// Gives `value` back if the set is full.
+    pub fn insert(&self, value: T) -> Result<bool, T> {
+        let states = self.states.as_slice();
+        for index in self.probe(&value) {
+            let state = &states[index];
+            let mut current = state.load(Ordering::Acquire);
+            if current == EMPTY {
+                match state.compare_exchange(
+                    EMPTY,
+                    WRITING,
+                    Ordering::Acquire,
+                    Ordering::Acquire,
+                ) {
+                    Ok(_) => {
+                        unsafe { (*self.slots.add(index)).write(value) };
+                        state.store(FULL, Ordering::Release);
+                        self.len.fetch_add(1, Ordering::Relaxed);
+                        return Ok(true);
+                    }
+                    Err(actual) => current = actual,
+                }
+            }
+            if current == WRITING {
+                current = Self::settled(state);
+            }
+            debug_assert_eq!(current, FULL);
+            let stored = unsafe { (*self.slots.add(index)).assume_init_ref() };
+            if stored == &value {
+                return Ok(false);
+            }
+        }
+        Err(value)
+    }
+
+    /// Returns `true` if the set contains a value equal to `value`.
+    pub fn contains<Q>(&self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let states = self.states.as_slice();
+        for index in self.probe(value) {
+            if Self::settled(&states[index]) == EMPTY {
+                return false;
+            }
+            let stored = unsafe { (*self.slots.add(index)).assume_init_ref() };
+            if stored.borrow() == value {
+                return true;
+            }
+        }
+        false
+    }
+}
+
+impl<T, S> ConcurrentHashSet<T, S> {
+    /// Returns the number of values the set can hold.
+    #[inline(always)]
+    pub fn capacity(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Returns the number of values in the set.
+    pub fn len(&self) -> usize {
+        self.len.load(Ordering::Relaxed)
+    }
+
+    /// Returns `true` if the set holds no values.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns an iterator over the values, in slot order.
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.states
+            .as_slice()
+            .iter()
+            .enumerate()
+            .filter(|(_, state)| state.load(Ordering::Acquire) == FULL)
+            .map(|(index, _)| unsafe {
+                (*self.slots.add(index)).assume_init_ref()
+            })
+    }
+}
+
+impl<T, S> Drop for ConcurrentHashSet<T, S> {
+    fn drop(&mut self) {
+        for (index, state) in self.states.as_slice().iter().enumerate() {
+            if state.load(Ordering::Relaxed) == FULL {
+                unsafe { (*self.slots.add(index)).assume_init_drop() };
+            }
+        }
+    }
+}
+
+impl<T: fmt::Debug, S> fmt::Debug for ConcurrentHashSet<T, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
@@ -0,0 +1,248 @@
+use std::{fmt, mem::MaybeUninit, slice};
+
+use crate::{error::Error, runtime_array::ArrayCStyle, usage::MemoryUsage};
+
+/// A double-ended queue of fixed capacity.
+///
+/// The elements live in one allocation and wrap around its end, so pushing
+/// and popping at either end is O(1) and never reallocates; unlike
+/// `VecDeque`, a push onto a full deque fails instead of growing it.
+///
+/// # Example
+///
+/// ```rust ignore
+/// use runnarr::containers::Deque;
+///
+/// // a worker's own tasks are taken from the back, thieves take the front
+/// let mut work = Deque::with_capacity(256).unwrap();
+/// work.push_back(task).map_err(|task| overflow.send(task)).ok();
+/// let mine = work.pop_back();
+/// let stolen = work.pop_front();
+/// ```
+pub struct Deque<T> {
+    data: ArrayCStyle<T>,
+    /// Storage position of the front element.
+    head: usize,
+    len: usize,
+}
+
+impl<T> Deque<T> {
+    /// Creates an empty deque with room for `capacity` elements.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the allocation fails.
+    #[track_caller]
+    pub fn with_capacity(capacity: usize) -> Result<Self, Error> {
+        Ok(Self {
+            data: ArrayCStyle::new(capacity)?,
+            head: 0,
+            len: 0,
+        })
+    }
+
+    /// Returns the number of elements.
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the deque holds no elements.
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the number of elements the deque can hold.
+    #[inline(always)]
+    pub fn capacity(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Returns `true` if no more elements fit.
+    #[inline(always)]
+    pub fn is_full(&self) -> bool {
+        self.len == self.capacity()
+    }
+
+    /// Returns the memory held by the deque; free slots count as slack.
+    pub fn memory_usage(&self) -> MemoryUsage {
+        MemoryUsage {
+            allocated_bytes: self.data.allocated_bytes(),
+            used_bytes: self.len * self.data.element_size(),
+        }
+    }
+
+    /// Returns the storage position `index` places after the front, which
+    /// must be below the capacity.
+    #[inline(always)]
+    fn position(&self, index: usize) -> usize {
+        let position = self.head + index;
+        if position >= self.capacity() {
+            position - self.capacity()
+        } else {
+            position
+        }
+    }
+
+    /// Appends `value` after the back element.
+    ///
+    /// # Errors
+    ///
+    /// Gives `value` back if the deque is full.
+    pub fn push_back(&mut self, value: T) -> Result<(), T> {
+        if self.is_full() {
+            return Err(value);
+        }
+        let position = self.position(self.len);
+        unsafe { self.data.ptr_mut().add(position).write(value) };
+        self.len += 1;
+        Ok(())
+    }
+
+    /// Prepends `value` before the front element.
+    ///
+    /// # Errors
+    ///
+    /// Gives `value` back if the deque is full.
+    pub fn push_front(&mut self, value: T) -> Result<(), T> {
+        if self.is_full() {
+            return Err(value);
+        }
+        self.head = self.position(self.capacity() - 1);
+        unsafe { self.data.ptr_mut().add(self.head).write(value) };
+        self.len += 1;
+        Ok(())
+    }
+
+    /// Removes and returns the front element, or `None` if the deque is
+    /// empty.
+    pub fn pop_front(&mut self) -> Option<T> {
+        if self.len == 0 {
+            return None;
+        }
+        let value = unsafe { self.data.ptr_mut().add(self.head).read() };
+        self.head = self.position(1);
+        self.len -= 1;
+        Some(value)
+    }
+
+    /// Removes and returns the back element, or `None` if the deque is
+    /// empty.
+    pub fn pop_back(&mut self) -> Option<T> {
+        if self.len == 0 {
+            return None;
+        }
+        self.len -= 1;
+        let position = self.position(self.len);
+        Some(unsafe { self.data.ptr_mut().add(position).read() })
+    }
+
+    /// Gets a reference to the element `index` places after the front, or
+    /// `None` if `index` is out of bounds.
+    #[inline]
+    pub fn get(&self, index: usize) -> Option<&T> {
+        (index < self.len)
+            .then(|| unsafe { &*self.data.ptr().add(self.position(index)) })
+    }
+
+    /// Gets a mutable reference to the element `index` places after the
+    /// front, or `None` if `index` is out of bounds.
+    #[inline]
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        (index < self.len).then(|| unsafe {
+            &mut *self.data.ptr_mut().add(self.position(index))
+        })
+    }
+
+    /// Returns the front element.
+    #[inline(always)]
+    pub fn front(&self) -> Option<&T> {
+        self.get(0)
+    }
+
+    /// Returns the back element.
+    #[inline(always)]
+    pub fn back(&self) -> Option<&T> {
+        self.get(self.len.checked_sub(1)?)
+    }
+
+    /// Returns the elements, front first, as the part stored before the
+    /// wrap-around point and the part stored after it.
+    pub fn as_slices(&self) -> (&[T], &[T]) {
+        let first = self.len.min(self.capacity() - self.head);
+        unsafe {
+            let ptr = self.data.ptr();
+            (
+                slice::from_raw_parts(ptr.add(self.head), first),
+                slice::from_raw_parts(ptr, self.len - first),
+            )
+        }
+    }
+
+    /// Moves the elements so that they are stored in one piece, front
+    /// first, and returns them as a slice.
+    ///
+    /// Does not move anything if they already are; otherwise the whole
+    /// buffer is rotated in place, without allocating.
+    pub fn make_contiguous(&mut self) -> &mut [T] {
+        if self.head + self.len > self.capacity() {
+            let buffer = unsafe {
+                slice::from_raw_parts_mut(
+                    self.data.ptr_mut() as *mut MaybeUninit<T>,
+                    self.capacity(),
+                )
+            };
+            buffer.rotate_left(self.head);
+            self.head = 0;
+        }
+        unsafe {
+            slice::from_raw_parts_mut(
+                self.data.ptr_mut().add(self.head),
+                self.len,
+            )
+        }
+    }
+
+    /// Returns an iterator over the elements, front first.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &T> {
+        let (first, second) = self.as_slices();
+        first.iter().chain(second)
+    }
+
+    /// Drops every element, keeping the buffer.
+    pub fn clear(&mut self) {
+        while self.pop_front().is_some() {}
+        self.head = 0;
+    }
+}
+
+impl<T> Drop for Deque<T> {
+    fn drop(&mut self) {
+        // the backing array never drops its elements
+        self.clear();
+    }
+}
+
+#[cfg(not(feature = "no-panic"))]
+impl<T> std::ops::Index<usize> for Deque<T> {
+    type Output = T;
+    #[track_caller]
+    fn index(&self, index: usize) -> &Self::Output {
+        self.get(index).expect("Index out of bounds")
+    }
+}
+
+#[cfg(not(feature = "no-panic"))]
+impl<T> std::ops::IndexMut<usize> for Deque<T> {
+    #[track_caller]
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        self.get_mut(index).expect("Index out of bounds")
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Deque<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
@@ -0,0 +1,157 @@
+use std::{error, fmt};
+
+use super::FixedVec;
+use crate::{error::Error, usage::MemoryUsage};
+
+/// Returned when pushing onto a [`FixedStack`] at its depth limit.
+///
+/// The rejected value is handed back, so nothing is lost on overflow.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub struct StackOverflowError<T> {
+    /// The value that did not fit.
+    pub value: T,
+    /// The depth limit of the stack, which it is at.
+    pub max_depth: usize,
+}
+
+impl<T> StackOverflowError<T> {
+    /// Returns the value that did not fit.
+    #[inline(always)]
+    pub fn into_value(self) -> T {
+        self.value
+    }
+}
+
+impl<T> fmt::Display for StackOverflowError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "stack overflow: depth limit of {} reached",
+            self.max_depth
+        )
+    }
+}
+
+impl<T: fmt::Debug> error::Error for StackOverflowError<T> {}
+
+/// A stack with a hard depth limit, over a [`FixedVec`].
+///
+/// All the memory is allocated up front and a push past the limit fails
+/// with a [`StackOverflowError`] instead of growing the stack, so a runaway
+/// program is caught as an error rather than as an allocation failure.
+///
+/// # Example
+///
+/// ```rust ignore
+/// use runnarr::containers::FixedStack;
+///
+/// let mut operands = FixedStack::with_max_depth(1024).unwrap();
+/// match instruction {
+///     Op::Push(value) => operands.push(value).map_err(VmError::from)?,
+///     Op::Add => {
+///         let b = operands.pop().ok_or(VmError::Underflow)?;
+///         let a = operands.pop().ok_or(VmError::Underflow)?;
+///         operands.push(a + b).map_err(VmError::from)?;
+///     }
+/// }
+/// ```
+pub struct FixedStack<T> {
+    items: FixedVec<T>,
+}
+
+impl<T> FixedStack<T> {
+    /// Creates an empty stack holding at most `max_depth` values.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the allocation fails.
+    #[track_caller]
+    pub fn with_max_depth(max_depth: usize) -> Result<Self, Error> {
+        Ok(Self {
+            items: FixedVec::with_capacity(max_depth)?,
+        })
+    }
+
+    /// Returns the number of values on the stack.
+    #[inline(always)]
+    pub fn depth(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Returns the most values the stack can hold.
+    #[inline(always)]
+    pub fn max_depth(&self) -> usize {
+        self.items.capacity()
+    }
+
+    /// Returns `true` if the stack holds no values.
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Returns `true` if the next push overflows.
+    #[inline(always)]
+    pub fn is_full(&self) -> bool {
+        self.items.is_full()
+    }
+
+    /// Returns the memory held by the stack; see
+    /// [`FixedVec::memory_usage`].
+    #[inline(always)]
+    pub fn memory_usage(&self) -> MemoryUsage {
+        self.items.memory_usage()
+    }
+
+    /// Pushes `value` on top of the stack.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`StackOverflowError`] holding `value` if the stack is at
+    /// its depth limit.
+    #[inline]
+    pub fn push(&mut self, value: T) -> Result<(), StackOverflowError<T>> {
+        self.items
+            .try_push(value)
+            .map_err(|value| StackOverflowError {
+                value,
+                max_depth: self.max_depth(),
+            })
+    }
+
+    /// Removes and returns the top value, or `None` if the stack is empty.
+    #[inline(always)]
+    pub fn pop(&mut self) -> Option<T> {
+        self.items.pop()
+    }
+
+    /// Returns the top value, or `None` if the stack is empty.
+    #[inline(always)]
+    pub fn peek(&self) -> Option<&T> {
+        self.items.last()
+    }
+
+    /// Returns the top value mutably, or `None` if the stack is empty.
+    #[inline(always)]
+    pub fn peek_mut(&mut self) -> Option<&mut T> {
+        self.items.last_mut()
+    }
+
+    /// Returns the values, bottom first.
+    #[inline(always)]
+    pub fn as_slice(&self) -> &[T] {
+        self.items.as_slice()
+    }
+
+    /// Drops every value, keeping the buffer.
+    #[inline(always)]
+    pub fn clear(&mut self) {
+        self.items.clear();
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for FixedStack<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.as_slice()).finish()
+    }
+}
@@ -0,0 +1,241 @@
+use std::{
+    fmt,
+    ops::{Deref, DerefMut},
+    ptr, slice,
+};
+
+use crate::{error::Error, runtime_array::ArrayCStyle, usage::MemoryUsage};
+
+/// A vector whose capacity is fixed when it is created.
+///
+/// The buffer is allocated once and never reallocated: pushing onto a full
+/// vector fails instead of growing it, so element addresses stay put and no
+/// push ever calls the allocator. Elements `..len()` are initialized, the
+/// rest of the buffer is spare.
+///
+/// # Example
+///
+/// ```rust ignore
+/// use runnarr::containers::FixedVec;
+///
+/// let mut samples = FixedVec::with_capacity(512).unwrap();
+/// while let Some(sample) = adc.read() {
+///     if samples.try_push(sample).is_err() {
+///         break;
+///     }
+/// }
+/// filter.run(&samples);
+/// ```
+pub struct FixedVec<T> {
+    data: ArrayCStyle<T>,
+    len: usize,
+}
+
+impl<T> FixedVec<T> {
+    /// Creates an empty vector with room for `capacity` elements.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the allocation fails.
+    #[track_caller]
+    pub fn with_capacity(capacity: usize) -> Result<Self, Error> {
+        Ok(Self {
+            data: ArrayCStyle::new(capacity)?,
+            len: 0,
+        })
+    }
+
+    /// Takes over `array` as a full vector, without copying.
+    ///
+    /// With the `shadow` feature, debug builds panic if an element of
+    /// `array` was never written.
+    ///
+    /// # Safety
+    ///
+    /// Every element of `array` must be initialized, as the vector reads and
+    /// drops them all. Arrays from [`ArrayCStyle::zeroed`] (for types valid
+    /// as all zeroes), `collect` or [`ArrayCStyle::try_from_slice`] are;
+    /// arrays from [`ArrayCStyle::new`] are not until written.
+    #[inline]
+    #[track_caller]
+    pub unsafe fn from_array(array: ArrayCStyle<T>) -> Self {
+        #[cfg(feature = "shadow")]
+        for index in 0..array.len() {
+            array.get(index);
+        }
+        Self {
+            len: array.len(),
+            data: array,
+        }
+    }
+
+    /// Returns the number of elements.
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the vector holds no elements.
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the number of elements the vector can hold.
+    #[inline(always)]
+    pub fn capacity(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Returns `true` if no more elements fit.
+    #[inline(always)]
+    pub fn is_full(&self) -> bool {
+        self.len == self.capacity()
+    }
+
+    /// Returns the memory held by the vector; spare capacity counts as
+    /// slack.
+    pub fn memory_usage(&self) -> MemoryUsage {
+        MemoryUsage {
+            allocated_bytes: self.data.allocated_bytes(),
+            used_bytes: self.len * self.data.element_size(),
+        }
+    }
+
+    /// Appends `value` to the end.
+    ///
+    /// # Errors
+    ///
+    /// Gives `value` back if the vector is full.
+    #[inline]
+    pub fn try_push(&mut self, value: T) -> Result<(), T> {
+        if self.is_full() {
+            return Err(value);
+        }
+        unsafe { self.data.ptr_mut().add(self.len).write(value) };
+        self.len += 1;
+        Ok(())
+    }
+
+    /// Appends `value` to the end.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the vector is full; see [`FixedVec::try_push`].
+    #[cfg(not(feature = "no-panic"))]
+    #[inline]
+    #[track_caller]
+    pub fn push(&mut self, value: T) {
+        if self.try_push(value).is_err() {
+            panic!("FixedVec of capacity {} is full", self.capacity());
+        }
+    }
+
+    /// Removes and returns the last element, or `None` if the vector is
+    /// empty.
+    #[inline]
+    pub fn pop(&mut self) -> Option<T> {
+        if self.len == 0 {
+            return None;
+        }
+        self.len -= 1;
+        Some(unsafe { self.data.ptr_mut().add(self.len).read() })
+    }
+
+    /// Inserts `value` at `index`, shifting the elements after it one place
+    /// to the right.
+    ///
+    /// # Errors
+    ///
+    /// Gives `value` back if the vector is full or `index` is greater than
+    /// the length.
+    pub fn insert(&mut self, index: usize, value: T) -> Result<(), T> {
+        if index > self.len || self.is_full() {
+            return Err(value);
+        }
+        unsafe {
+            let slot = self.data.ptr_mut().add(index);
+            ptr::copy(slot, slot.add(1), self.len - index);
+            slot.write(value);
+        }
+        self.len += 1;
+        Ok(())
+    }
+
+    /// Removes and returns the element at `index`, shifting the elements
+    /// after it one place to the left, or returns `None` if `index` is out
+    /// of bounds.
+    pub fn remove(&mut self, index: usize) -> Option<T> {
+        if index >= self.len {
+            return None;
+        }
+        self.len -= 1;
+        unsafe {
+            let slot = self.data.ptr_mut().add(index);
+            let value = slot.read();
+            ptr::copy(slot.add(1), slot, self.len - index);
+            Some(value)
+        }
+    }
+
+    /// Drops the elements from `len` on; does nothing if the vector is not
+    /// longer than `len`.
+    pub fn truncate(&mut self, len: usize) {
+        if len >= self.len {
+            return;
+        }
+        let tail = ptr::slice_from_raw_parts_mut(
+            unsafe { self.data.ptr_mut().add(len) },
+            self.len - len,
+        );
+        // shorten first, so a panicking destructor cannot cause a double drop
+        self.len = len;
+        unsafe { ptr::drop_in_place(tail) };
+    }
+
+    /// Drops every element, keeping the buffer.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.truncate(0);
+    }
+
+    /// Returns the elements as a slice.
+    #[inline(always)]
+    pub fn as_slice(&self) -> &[T] {
+        unsafe { slice::from_raw_parts(self.data.ptr(), self.len) }
+    }
+
+    /// Returns the elements as a mutable slice.
+    #[inline(always)]
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        unsafe { slice::from_raw_parts_mut(self.data.ptr_mut(), self.len) }
+    }
+}
+
+impl<T> Drop for FixedVec<T> {
+    fn drop(&mut self) {
+        // the backing array never drops its elements
+        self.clear();
+    }
+}
+
+impl<T> Deref for FixedVec<T> {
+    type Target = [T];
+    #[inline(always)]
+    fn deref(&self) -> &Self::Target {
+        self.as_slice()
+    }
+}
+
+impl<T> DerefMut for FixedVec<T> {
+    #[inline(always)]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.as_mut_slice()
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for FixedVec<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.as_slice()).finish()
+    }
+}
@@ -0,0 +1,328 @@
+use std::{
+    borrow::Borrow,
+    collections::hash_map::RandomState,
+    fmt,
+    hash::{BuildHasher, Hash},
+    mem, ptr,
+};
+
+use crate::{error::Error, runtime_array::ArrayCStyle, usage::MemoryUsage};
+
+/// Marks a slot holding no entry; an occupied slot stores its entry's
+/// distance from its home slot plus one.
+const EMPTY: u32 = 0;
+
+/// A hash map of fixed capacity using open addressing with Robin Hood
+/// probing.
+///
+/// Keys and values live in two parallel arrays, with a third recording how
+/// far each entry is from the slot its hash points at. An entry that has
+/// probed further takes the slot of one that has probed less, which keeps
+/// every probe sequence short, and removal shifts the following entries
+/// back instead of leaving tombstones. The table is sized at creation so
+/// that it is never more than 7/8 full, and an insert into a full map
+/// fails: the map never rehashes or reallocates.
+///
+/// # Example
+///
+/// ```rust ignore
+/// use runnarr::containers::FlatHashMap;
+///
+/// // order id -> resting order, sized for the worst case at startup
+/// let mut book = FlatHashMap::with_capacity(1 << 20).unwrap();
+/// book.insert(order.id, order).map_err(|_| Reject::BookFull)?;
+/// if let Some(order) = book.remove(&cancel.id) {
+///     send_cancel_ack(order);
+/// }
+/// ```
+pub struct FlatHashMap<K, V, S = RandomState> {
+    keys: ArrayCStyle<K>,
+    values: ArrayCStyle<V>,
+    probes: ArrayCStyle<u32>,
+    len: usize,
+    capacity: usize,
+    hasher: S,
+}
+
+impl<K: Hash + Eq, V> FlatHashMap<K, V> {
+    /// Creates an empty map with room for `capacity` entries, hashing with
+    /// a randomly seeded [`RandomState`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the table size overflows or the allocation
+    /// fails.
+    #[track_caller]
+    pub fn with_capacity(capacity: usize) -> Result<Self, Error> {
+        Self::with_capacity_and_hasher(capacity, RandomState::new())
+    }
+}
+
+impl<K: Hash + Eq, V, S: BuildHasher> FlatHashMap<K, V, S> {
+    /// Creates an empty map with room for `capacity` entries, hashing with
+    /// `hasher`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the table size overflows or the allocation
+    /// fails.
+    #[track_caller]
+    pub fn with_capacity_and_hasher(
+        capacity: usize,
+        hasher: S,
+    ) -> Result<Self, Error> {
+        let slots = match capacity {
+            0 => 0,
+            _ => capacity
+                .checked_mul(8)
+                .map(|slots| slots.div_ceil(7))
+                .and_then(usize::checked_next_power_of_two)
+                .ok_or_else(|| {
+                    Error::Other(format!(
+                        "Capacity {capacity} overflows the table size"
+                    ))
+                })?,
+        };
+        Ok(Self {
+            keys: ArrayCStyle::new(slots)?,
+            values: ArrayCStyle::new(slots)?,
+            probes: ArrayCStyle::zeroed(slots)?,
+            len: 0,
+            capacity,
+            hasher,
+        })
+    }
+
+    /// Returns the number of entries.
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the map holds no entries.
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the number of entries the map can hold.
+    #[inline(always)]
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns `true` if inserting a new key fails.
+    #[inline(always)]
+    pub fn is_full(&self) -> bool {
+        self.len == self.capacity
+    }
+
+    /// Returns the memory held by the map; empty slots count as slack and
+    /// the probe distances as used.
+    pub fn memory_usage(&self) -> MemoryUsage {
+        let probes = self.probes.allocated_bytes();
+        let entry = self.keys.element_size() + self.values.element_size();
+        MemoryUsage {
+            allocated_bytes: self.keys.allocated_bytes()
+                + self.values.allocated_bytes()
+                + probes,
+            used_bytes: self.len * entry + probes,
+        }
+    }
+
+    #[inline(always)]
+    fn next(&self, slot: usize) -> usize {
+        (slot + 1) & (self.probes.len() - 1)
+    }
+
+    #[inline(always)]
+    fn home<Q: Hash + ?Sized>(&self, key: &Q) -> usize {
+        self.hasher.hash_one(key) as usize & (self.probes.len() - 1)
+    }
+
+    /// Returns the slot holding `key`.
+    fn find<Q>(&self, key: &Q) -> Option<usize>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        if self.len == 0 {
+            return None;
+        }
+        let probes = self.probes.as_slice();
+        let mut slot = self.home(key);
+        // an entry further along would have displaced one this close
+        for distance in 1.. {
+            let probe = probes[slot];
+            if probe == EMPTY || probe < distance {
+                break;
+            }
+            if unsafe { (*self.keys.ptr().add(slot)).borrow() } == key {
+                return Some(slot);
+            }
+            slot = self.next(slot);
+        }
+        None
+    }
+
+    /// Inserts `value` under `key`.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(Some(old))` with the value it replaced if `key` was present, or
+    /// `Ok(None)`.
+    ///
+    /// # Errors
+    ///
+    /// Gives `key` and `value` back if `key` is new and the map is full.
+    pub fn insert(&mut self, key: K, value: V) -> Result<Option<V>, (K, V)> {
+        if let Some(slot) = self.find(&key) {
+            let old = unsafe { &mut *self.values.ptr_mut().add(slot) };
+            return Ok(Some(mem::replace(old, value)));
+        }
+        if self.is_full() {
+            return Err((key, value));
+        }
+        let (mut key, mut value) = (key, value);
+        let mut slot = self.home(&key);
+        let mut distance = 1;
+        loop {
+            let (keys, values) = (self.keys.ptr_mut(), self.values.ptr_mut());
+            let probe = &mut self.probes.as_mut_slice()[slot];
+            if *probe == EMPTY {
+                unsafe {
+                    keys.add(slot).write(key);
+                    values.add(slot).write(value);
+                }
+                *probe = distance;
+                break;
+            }
+            if *probe < distance {
+                // the resident is closer to home: it moves on instead
+                mem::swap(probe, &mut distance);
+                unsafe {
+                    ptr::swap(keys.add(slot), &mut key);
+                    ptr::swap(values.add(slot), &mut value);
+                }
+            }
+            slot = self.next(slot);
+            distance += 1;
+        }
+        self.len += 1;
+        Ok(None)
+    }
+
+    /// Returns `true` if the map holds `key`.
+    #[inline]
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.find(key).is_some()
+    }
+
+    /// Gets a reference to the value under `key`.
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let slot = self.find(key)?;
+        Some(unsafe { &*self.values.ptr().add(slot) })
+    }
+
+    /// Gets a mutable reference to the value under `key`.
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let slot = self.find(key)?;
+        Some(unsafe { &mut *self.values.ptr_mut().add(slot) })
+    }
+
+    /// Removes `key` and returns the key and value stored under it.
+    pub fn remove_entry<Q>(&mut self, key: &Q) -> Option<(K, V)>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let mut slot = self.find(key)?;
+        let (keys, values) = (self.keys.ptr_mut(), self.values.ptr_mut());
+        let entry = unsafe { (keys.add(slot).read(), values.add(slot).read()) };
+        // shift the entries after it back until one is at home or a slot
+        // is empty, so no tombstone is needed
+        loop {
+            let next = self.next(slot);
+            let probes = self.probes.as_mut_slice();
+            if probes[next] <= 1 {
+                probes[slot] = EMPTY;
+                break;
+            }
+            probes[slot] = probes[next] - 1;
+            unsafe {
+                ptr::copy_nonoverlapping(keys.add(next), keys.add(slot), 1);
+                ptr::copy_nonoverlapping(values.add(next), values.add(slot), 1);
+            }
+            slot = next;
+        }
+        self.len -= 1;
+        Some(entry)
+    }
+
+    /// Removes `key` and returns the value stored under it.
+    #[inline]
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.remove_entry(key).map(|(_, value)| value)
+    }
+}
+
+impl<K, V, S> FlatHashMap<K, V, S> {
+    /// Returns an iterator over the entries, in slot order.
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+        let (keys, values) = (self.keys.ptr(), self.values.ptr());
+        self.probes
+            .as_slice()
+            .iter()
+            .enumerate()
+            .filter(|&(_, &probe)| probe != EMPTY)
+            .map(move |(slot, _)| unsafe {
+                (&*keys.add(slot), &*values.add(slot))
+            })
+    }
+
+    /// Drops every entry, keeping the table.
+    pub fn clear(&mut self) {
+        let (keys, values) = (self.keys.ptr_mut(), self.values.ptr_mut());
+        for (slot, probe) in self.probes.as_mut_slice().iter_mut().enumerate() {
+            if *probe != EMPTY {
+                // mark the slot empty first, so a panicking destructor
+                // cannot cause a double drop
+                *probe = EMPTY;
+                self.len -= 1;
+                unsafe {
+                    keys.add(slot).drop_in_place();
+                    values.add(slot).drop_in_place();
+                }
+            }
+        }
+    }
+}
+
+impl<K, V, S> Drop for FlatHashMap<K, V, S> {
+    fn drop(&mut self) {
+        // the backing arrays never drop their elements
+        self.clear();
+    }
+}
+
+impl<K: fmt::Debug, V: fmt::Debug, S> fmt::Debug for FlatHashMap<K, V, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
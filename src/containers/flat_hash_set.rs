@@ -0,0 +1,166 @@
+use std::{
+    borrow::Borrow,
+    collections::hash_map::RandomState,
+    fmt,
+    hash::{BuildHasher, Hash},
+};
+
+use super::FlatHashMap;
+use crate::{error::Error, usage::MemoryUsage};
+
+/// A hash set of fixed capacity, over a [`FlatHashMap`] with no values.
+///
+/// It probes the same way, Robin Hood with backward-shift removal, and
+/// like the map it never rehashes or reallocates: an insert into a full
+/// set fails.
+///
+/// # Example
+///
+/// ```rust ignore
+/// use runnarr::containers::FlatHashSet;
+///
+/// // drop replayed packets, remembering at most 4096 sequence numbers
+/// let mut seen = FlatHashSet::with_capacity(4096).unwrap();
+/// for packet in packets {
+///     match seen.insert(packet.sequence) {
+///         Ok(true) => deliver(packet),
+///         Ok(false) => continue,
+///         Err(_) => seen.clear(),
+///     }
+/// }
+/// ```
+pub struct FlatHashSet<T, S = RandomState> {
+    map: FlatHashMap<T, (), S>,
+}
+
+impl<T: Hash + Eq> FlatHashSet<T> {
+    /// Creates an empty set with room for `capacity` values, hashing with
+    /// a randomly seeded [`RandomState`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the table size overflows or the allocation
+    /// fails.
+    #[track_caller]
+    pub fn with_capacity(capacity: usize) -> Result<Self, Error> {
+        Self::with_capacity_and_hasher(capacity, RandomState::new())
+    }
+}
+
+impl<T: Hash + Eq, S: BuildHasher> FlatHashSet<T, S> {
+    /// Creates an empty set with room for `capacity` values, hashing with
+    /// `hasher`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the table size overflows or the allocation
+    /// fails.
+    #[track_caller]
+    pub fn with_capacity_and_hasher(
+        capacity: usize,
+        hasher: S,
+    ) -> Result<Self, Error> {
+        Ok(Self {
+            map: FlatHashMap::with_capacity_and_hasher(capacity, hasher)?,
+        })
+    }
+
+    /// Returns the number of values.
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Returns `true` if the set holds no values.
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Returns the number of values the set can hold.
+    #[inline(always)]
+    pub fn capacity(&self) -> usize {
+        self.map.capacity()
+    }
+
+    /// Returns `true` if inserting a new value fails.
+    #[inline(always)]
+    pub fn is_full(&self) -> bool {
+        self.map.is_full()
+    }
+
+    /// Returns the memory held by the set; see
+    /// [`FlatHashMap::memory_usage`].
+    #[inline(always)]
+    pub fn memory_usage(&self) -> MemoryUsage {
+        self.map.memory_usage()
+    }
+
+    /// Adds `value` to the set.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(true)` if the value is new, or `Ok(false)` if an equal value was
+    /// already present; that one is kept and `value` is dropped.
+    ///
+    /// # Errors
+    ///
+    /// Gives `value` back if it is new and the set is full.
+    #[inline]
+    pub fn insert(&mut self, value: T) -> Result<bool, T> {
+        match self.map.insert(value, ()) {
+            Ok(old) => Ok(old.is_none()),
+            Err((value, ())) => Err(value),
+        }
+    }
+
+    /// Returns `true` if the set holds `value`.
+    #[inline]
+    pub fn contains<Q>(&self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.map.contains_key(value)
+    }
+
+    /// Removes `value`, returning `true` if it was present.
+    #[inline]
+    pub fn remove<Q>(&mut self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.map.remove_entry(value).is_some()
+    }
+
+    /// Removes and returns the value equal to `value`, if present.
+    #[inline]
+    pub fn take<Q>(&mut self, value: &Q) -> Option<T>
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.map.remove_entry(value).map(|(value, ())| value)
+    }
+}
+
+impl<T, S> FlatHashSet<T, S> {
+    /// Returns an iterator over the values, in slot order.
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.map.iter().map(|(value, _)| value)
+    }
+
+    /// Drops every value, keeping the table.
+    #[inline(always)]
+    pub fn clear(&mut self) {
+        self.map.clear();
+    }
+}
+
+impl<T: fmt::Debug, S> fmt::Debug for FlatHashSet<T, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
@@ -0,0 +1,342 @@
+use std::fmt;
+
+use super::FixedVec;
+use crate::{error::Error, runtime_array::ArrayCStyle, usage::MemoryUsage};
+
+/// Moves the element at `index` up until its parent is not `before` it.
+fn sift_up<T>(
+    items: &mut [T],
+    mut index: usize,
+    before: &impl Fn(&T, &T) -> bool,
+) {
+    while index > 0 {
+        let parent = (index - 1) / 2;
+        if !before(&items[index], &items[parent]) {
+            break;
+        }
+        items.swap(index, parent);
+        index = parent;
+    }
+}
+
+/// Moves the element at `index` down until no child is `before` it.
+fn sift_down<T>(
+    items: &mut [T],
+    mut index: usize,
+    before: &impl Fn(&T, &T) -> bool,
+) {
+    loop {
+        let left = 2 * index + 1;
+        if left >= items.len() {
+            break;
+        }
+        let right = left + 1;
+        let child =
+            if right < items.len() && before(&items[right], &items[left]) {
+                right
+            } else {
+                left
+            };
+        if !before(&items[child], &items[index]) {
+            break;
+        }
+        items.swap(index, child);
+        index = child;
+    }
+}
+
+/// Reorders `items` into a heap, bottom-up in O(n).
+fn heapify<T>(items: &mut [T], before: &impl Fn(&T, &T) -> bool) {
+    for index in (0..items.len() / 2).rev() {
+        sift_down(items, index, before);
+    }
+}
+
+fn push<T>(
+    items: &mut FixedVec<T>,
+    value: T,
+    before: &impl Fn(&T, &T) -> bool,
+) -> Result<(), T> {
+    items.try_push(value)?;
+    let last = items.len() - 1;
+    sift_up(items, last, before);
+    Ok(())
+}
+
+fn pop<T>(
+    items: &mut FixedVec<T>,
+    before: &impl Fn(&T, &T) -> bool,
+) -> Option<T> {
+    let last = items.len().checked_sub(1)?;
+    items.swap(0, last);
+    let top = items.pop();
+    sift_down(items, 0, before);
+    top
+}
+
+/// A binary max-heap of fixed capacity, over a [`FixedVec`].
+///
+/// [`FixedHeap::peek`] is the greatest element; wrap the elements in
+/// [`std::cmp::Reverse`] for a min-heap. Pushing and popping are
+/// O(log n) and never allocate, and a push onto a full heap fails instead
+/// of growing it. See [`FixedHeapByKey`] to order by a key.
+///
+/// # Example
+///
+/// ```rust ignore
+/// use std::cmp::Reverse;
+/// use runnarr::containers::FixedHeap;
+///
+/// // the k highest scores: a min-heap whose top is the one to beat
+/// let mut best = FixedHeap::with_capacity(k).unwrap();
+/// for score in scores {
+///     if best.is_full() && best.peek().is_some_and(|top| top.0 >= score) {
+///         continue;
+///     }
+///     if best.is_full() {
+///         best.pop();
+///     }
+///     best.push(Reverse(score)).ok();
+/// }
+/// ```
+pub struct FixedHeap<T> {
+    items: FixedVec<T>,
+}
+
+impl<T: Ord> FixedHeap<T> {
+    /// Creates an empty heap with room for `capacity` elements.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the allocation fails.
+    #[track_caller]
+    pub fn with_capacity(capacity: usize) -> Result<Self, Error> {
+        Ok(Self {
+            items: FixedVec::with_capacity(capacity)?,
+        })
+    }
+
+    /// Turns the elements of `items` into a heap in place, in O(n) and
+    /// without copying; its spare capacity is kept.
+    pub fn from_vec(mut items: FixedVec<T>) -> Self {
+        heapify(&mut items, &T::gt);
+        Self { items }
+    }
+
+    /// Turns `array` into a full heap in place, in O(n) and without
+    /// copying; see [`FixedHeap::from_vec`].
+    ///
+    /// # Safety
+    ///
+    /// Every element of `array` must be initialized; see
+    /// [`FixedVec::from_array`].
+    #[track_caller]
+    pub unsafe fn heapify(array: ArrayCStyle<T>) -> Self {
+        Self::from_vec(unsafe { FixedVec::from_array(array) })
+    }
+
+    /// Returns the number of elements.
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Returns `true` if the heap holds no elements.
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Returns the number of elements the heap can hold.
+    #[inline(always)]
+    pub fn capacity(&self) -> usize {
+        self.items.capacity()
+    }
+
+    /// Returns `true` if no more elements fit.
+    #[inline(always)]
+    pub fn is_full(&self) -> bool {
+        self.items.is_full()
+    }
+
+    /// Returns the memory held by the heap; see
+    /// [`FixedVec::memory_usage`].
+    #[inline(always)]
+    pub fn memory_usage(&self) -> MemoryUsage {
+        self.items.memory_usage()
+    }
+
+    /// Adds `value` to the heap.
+    ///
+    /// # Errors
+    ///
+    /// Gives `value` back if the heap is full.
+    pub fn push(&mut self, value: T) -> Result<(), T> {
+        push(&mut self.items, value, &T::gt)
+    }
+
+    /// Removes and returns the greatest element, or `None` if the heap is
+    /// empty.
+    pub fn pop(&mut self) -> Option<T> {
+        pop(&mut self.items, &T::gt)
+    }
+
+    /// Returns the greatest element, or `None` if the heap is empty.
+    #[inline(always)]
+    pub fn peek(&self) -> Option<&T> {
+        self.items.first()
+    }
+
+    /// Returns the elements in heap order: the greatest first, the rest in
+    /// no particular order.
+    #[inline(always)]
+    pub fn as_slice(&self) -> &[T] {
+        self.items.as_slice()
+    }
+
+    /// Drops every element, keeping the buffer.
+    #[inline(always)]
+    pub fn clear(&mut self) {
+        self.items.clear();
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for FixedHeap<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.items.iter()).finish()
+    }
+}
+
+/// A binary heap of fixed capacity whose top is the element with the
+/// greatest key, as computed by `key`; see [`FixedHeap`].
+///
+/// The key is recomputed at every comparison, so it should be cheap, such
+/// as reading a field.
+///
+/// # Example
+///
+/// ```rust ignore
+/// use std::cmp::Reverse;
+/// use runnarr::containers::FixedHeapByKey;
+///
+/// // timers ordered by the earliest deadline
+/// let deadline = |timer: &Timer| Reverse(timer.deadline);
+/// let mut timers = FixedHeapByKey::with_capacity(64, deadline).unwrap();
+/// timers.push(Timer::new(now + timeout, callback)).ok();
+/// while timers.peek().is_some_and(|timer| timer.deadline <= now) {
+///     timers.pop().map(Timer::fire);
+/// }
+/// ```
+pub struct FixedHeapByKey<T, F> {
+    items: FixedVec<T>,
+    key: F,
+}
+
+impl<T, K: Ord, F: Fn(&T) -> K> FixedHeapByKey<T, F> {
+    /// Creates an empty heap with room for `capacity` elements, ordered by
+    /// `key`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the allocation fails.
+    #[track_caller]
+    pub fn with_capacity(capacity: usize, key: F) -> Result<Self, Error> {
+        Ok(Self {
+            items: FixedVec::with_capacity(capacity)?,
+            key,
+        })
+    }
+
+    /// Turns the elements of `items` into a heap ordered by `key` in
+    /// place, in O(n) and without copying; its spare capacity is kept.
+    pub fn from_vec(mut items: FixedVec<T>, key: F) -> Self {
+        heapify(&mut items, &|a, b| key(a) > key(b));
+        Self { items, key }
+    }
+
+    /// Turns `array` into a full heap ordered by `key` in place, in O(n)
+    /// and without copying; see [`FixedHeapByKey::from_vec`].
+    ///
+    /// # Safety
+    ///
+    /// Every element of `array` must be initialized; see
+    /// [`FixedVec::from_array`].
+    #[track_caller]
+    pub unsafe fn heapify(array: ArrayCStyle<T>, key: F) -> Self {
+        Self::from_vec(unsafe { FixedVec::from_array(array) }, key)
+    }
+
+    /// Returns the number of elements.
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Returns `true` if the heap holds no elements.
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Returns the number of elements the heap can hold.
+    #[inline(always)]
+    pub fn capacity(&self) -> usize {
+        self.items.capacity()
+    }
+
+    /// Returns `true` if no more elements fit.
+    #[inline(always)]
+    pub fn is_full(&self) -> bool {
+        self.items.is_full()
+    }
+
+    /// Returns the memory held by the heap; see
+    /// [`FixedVec::memory_usage`].
+    #[inline(always)]
+    pub fn memory_usage(&self) -> MemoryUsage {
+        self.items.memory_usage()
+    }
+
+    /// Adds `value` to the heap.
+    ///
+    /// # Errors
+    ///
+    /// Gives `value` back if the heap is full.
+    pub fn push(&mut self, value: T) -> Result<(), T> {
+        let key = &self.key;
+        push(&mut self.items, value, &|a, b| key(a) > key(b))
+    }
+
+    /// Removes and returns the element with the greatest key, or `None` if
+    /// the heap is empty.
+    pub fn pop(&mut self) -> Option<T> {
+        let key = &self.key;
+        pop(&mut self.items, &|a, b| key(a) > key(b))
+    }
+
+    /// Returns the element with the greatest key, or `None` if the heap is
+    /// empty.
+    #[inline(always)]
+    pub fn peek(&self) -> Option<&T> {
+        self.items.first()
+    }
+
+    /// Returns the elements in heap order: the one with the greatest key
+    /// first, the rest in no particular order.
+    #[inline(always)]
+    pub fn as_slice(&self) -> &[T] {
+        self.items.as_slice()
+    }
+
+    /// Drops every element, keeping the buffer.
+    #[inline(always)]
+    pub fn clear(&mut self) {
+        self.items.clear();
+    }
+}
+
+impl<T: fmt::Debug, F> fmt::Debug for FixedHeapByKey<T, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.items.iter()).finish()
+    }
+}
@@ -0,0 +1,20 @@
+//! Fixed-capacity containers over one [`ArrayCStyle`] allocation, for code
+//! that must never reallocate once it is running.
+//!
+//! [`ArrayCStyle`]: crate::runtime_array::ArrayCStyle
+
+mod deque;
+mod fixed_stack;
+mod fixed_vec;
+mod flat_hash_map;
+mod flat_hash_set;
+mod heap;
+mod ring_buffer;
+
+pub use deque::Deque;
+pub use fixed_stack::{FixedStack, StackOverflowError};
+pub use fixed_vec::FixedVec;
+pub use flat_hash_map::FlatHashMap;
+pub use flat_hash_set::FlatHashSet;
+pub use heap::{FixedHeap, FixedHeapByKey};
+pub use ring_buffer::{OverflowPolicy, RingBuffer};
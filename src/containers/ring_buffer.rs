@@ -0,0 +1,200 @@
+use std::fmt;
+
+use super::Deque;
+use crate::{error::Error, usage::MemoryUsage};
+
+/// What [`RingBuffer::push_back`] does when the buffer is full.
+#[derive(Debug, Default, Clone, Copy, Hash, PartialEq, Eq)]
+pub enum OverflowPolicy {
+    /// Refuse the new element, giving it back.
+    #[default]
+    Reject,
+    /// Drop the oldest element to make room, returning it.
+    Overwrite,
+}
+
+/// A circular buffer of fixed capacity, appended to at the back and drained
+/// from the front.
+///
+/// The elements live in one allocation and wrap around its end, so pushing
+/// and popping never move or reallocate anything. Index `0` is always the
+/// oldest element, wherever it is stored. When the buffer is full the
+/// [`OverflowPolicy`] decides whether a push is rejected or evicts the
+/// oldest element, which makes an overwriting buffer a window over the
+/// most recent `capacity` values.
+///
+/// # Example
+///
+/// ```rust ignore
+/// use runnarr::containers::{OverflowPolicy, RingBuffer};
+///
+/// // the last 1000 log lines, for dumping on a crash
+/// let mut history =
+///     RingBuffer::with_capacity(1000, OverflowPolicy::Overwrite).unwrap();
+/// for line in lines {
+///     history.push_back(line).ok();
+/// }
+/// for line in history.iter() {
+///     eprintln!("{line}");
+/// }
+/// ```
+pub struct RingBuffer<T> {
+    /// The elements, oldest at the front; the ring arithmetic lives there.
+    deque: Deque<T>,
+    policy: OverflowPolicy,
+}
+
+impl<T> RingBuffer<T> {
+    /// Creates an empty buffer with room for `capacity` elements, applying
+    /// `policy` once it is full.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the allocation fails.
+    #[track_caller]
+    pub fn with_capacity(
+        capacity: usize,
+        policy: OverflowPolicy,
+    ) -> Result<Self, Error> {
+        Ok(Self {
+            deque: Deque::with_capacity(capacity)?,
+            policy,
+        })
+    }
+
+    /// Returns the number of elements.
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.deque.len()
+    }
+
+    /// Returns `true` if the buffer holds no elements.
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.deque.is_empty()
+    }
+
+    /// Returns the number of elements the buffer can hold.
+    #[inline(always)]
+    pub fn capacity(&self) -> usize {
+        self.deque.capacity()
+    }
+
+    /// Returns `true` if the next push applies the overflow policy.
+    #[inline(always)]
+    pub fn is_full(&self) -> bool {
+        self.deque.is_full()
+    }
+
+    /// Returns what a push onto a full buffer does.
+    #[inline(always)]
+    pub fn policy(&self) -> OverflowPolicy {
+        self.policy
+    }
+
+    /// Returns the memory held by the buffer; free slots count as slack.
+    pub fn memory_usage(&self) -> MemoryUsage {
+        self.deque.memory_usage()
+    }
+
+    /// Appends `value` after the newest element.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(None)` if there was room, or `Ok(Some(oldest))` with the element
+    /// evicted to make room under [`OverflowPolicy::Overwrite`].
+    ///
+    /// # Errors
+    ///
+    /// Gives `value` back if the buffer is full under
+    /// [`OverflowPolicy::Reject`], or has no capacity at all.
+    pub fn push_back(&mut self, value: T) -> Result<Option<T>, T> {
+        let value = match self.deque.push_back(value) {
+            Ok(()) => return Ok(None),
+            Err(value) => value,
+        };
+        if self.policy == OverflowPolicy::Reject {
+            return Err(value);
+        }
+        // a zero-capacity buffer has nothing to evict and stays full
+        let Some(oldest) = self.deque.pop_front() else {
+            return Err(value);
+        };
+        self.deque.push_back(value).map(|()| Some(oldest))
+    }
+
+    /// Removes and returns the oldest element, or `None` if the buffer is
+    /// empty.
+    #[inline(always)]
+    pub fn pop_front(&mut self) -> Option<T> {
+        self.deque.pop_front()
+    }
+
+    /// Gets a reference to the element `index` places after the oldest, or
+    /// `None` if `index` is out of bounds.
+    #[inline(always)]
+    pub fn get(&self, index: usize) -> Option<&T> {
+        self.deque.get(index)
+    }
+
+    /// Gets a mutable reference to the element `index` places after the
+    /// oldest, or `None` if `index` is out of bounds.
+    #[inline(always)]
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        self.deque.get_mut(index)
+    }
+
+    /// Returns the oldest element.
+    #[inline(always)]
+    pub fn front(&self) -> Option<&T> {
+        self.deque.front()
+    }
+
+    /// Returns the newest element.
+    #[inline(always)]
+    pub fn back(&self) -> Option<&T> {
+        self.deque.back()
+    }
+
+    /// Returns the elements, oldest first, as the part stored before the
+    /// wrap-around point and the part stored after it.
+    #[inline(always)]
+    pub fn as_slices(&self) -> (&[T], &[T]) {
+        self.deque.as_slices()
+    }
+
+    /// Returns an iterator over the elements, oldest first.
+    #[inline(always)]
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &T> {
+        self.deque.iter()
+    }
+
+    /// Drops every element, keeping the buffer.
+    #[inline(always)]
+    pub fn clear(&mut self) {
+        self.deque.clear();
+    }
+}
+
+#[cfg(not(feature = "no-panic"))]
+impl<T> std::ops::Index<usize> for RingBuffer<T> {
+    type Output = T;
+    #[track_caller]
+    fn index(&self, index: usize) -> &Self::Output {
+        self.get(index).expect("Index out of bounds")
+    }
+}
+
+#[cfg(not(feature = "no-panic"))]
+impl<T> std::ops::IndexMut<usize> for RingBuffer<T> {
+    #[track_caller]
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        self.get_mut(index).expect("Index out of bounds")
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for RingBuffer<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
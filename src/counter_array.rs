@@ -0,0 +1,144 @@
+use std::{
+    fmt,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use crate::{
+    aligned::CacheAligned, error::Error, runtime_array::ArrayCStyle,
+    usage::MemoryUsage,
+};
+
+/// An array of event counters that threads can bump without slowing each
+/// other down.
+///
+/// Every counter is an [`AtomicU64`] padded to its own cache line, so
+/// threads hammering neighboring counters (one per shard, core or
+/// connection) never invalidate each other's caches the way a packed
+/// [`AtomicArray<u64>`](crate::atomic_array::AtomicArray) would. The price
+/// is 64 bytes per counter. Counters are updated with `Relaxed` ordering:
+/// they count, they do not synchronize.
+///
+/// # Example
+///
+/// ```rust ignore
+/// use runnarr::counter_array::CounterArray;
+///
+/// let requests = CounterArray::zeroed(SHARDS).unwrap();
+/// // on each shard's worker thread
+/// requests.inc(shard);
+/// // in the metrics exporter
+/// let per_shard = requests.snapshot()?;
+/// ```
+pub struct CounterArray {
+    counters: ArrayCStyle<CacheAligned<AtomicU64>>,
+}
+
+impl CounterArray {
+    /// Creates `len` counters, all at zero.
+    #[track_caller]
+    pub fn zeroed(len: usize) -> Result<Self, Error> {
+        // a padded atomic zero is all-zero bytes
+        Ok(Self {
+            counters: ArrayCStyle::zeroed(len)?,
+        })
+    }
+
+    /// Returns the number of counters.
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.counters.len()
+    }
+
+    /// Returns `true` if there are no counters.
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.counters.is_empty()
+    }
+
+    /// Returns the counter at `index`, or `None` if it is out of bounds.
+    #[inline(always)]
+    pub fn get(&self, index: usize) -> Option<&AtomicU64> {
+        self.counters.get(index).map(|counter| &counter.0)
+    }
+
+    /// Copies every counter into a plain array.
+    ///
+    /// Each counter is read individually, so the copy is not an atomic
+    /// snapshot of the whole array.
+    #[track_caller]
+    pub fn snapshot(&self) -> Result<ArrayCStyle<u64>, Error> {
+        let values = ArrayCStyle::<u64>::new(self.len())?;
+        for (index, counter) in self.counters.as_slice().iter().enumerate() {
+            let value = counter.load(Ordering::Relaxed);
+            unsafe { values.ptr_mut().add(index).write(value) };
+        }
+        Ok(values)
+    }
+
+    /// Returns the sum of all counters, wrapping on overflow.
+    pub fn sum(&self) -> u64 {
+        self.counters.as_slice().iter().fold(0, |sum, counter| {
+            sum.wrapping_add(counter.load(Ordering::Relaxed))
+        })
+    }
+
+    /// Returns the memory held by the counters.
+    pub fn memory_usage(&self) -> MemoryUsage {
+        self.counters.memory_usage()
+    }
+}
+
+#[cfg(not(feature = "no-panic"))]
+impl CounterArray {
+    #[inline(always)]
+    #[track_caller]
+    fn counter(&self, index: usize) -> &AtomicU64 {
+        self.get(index).expect("Index out of bounds")
+    }
+
+    /// Adds 1 to the counter at `index`, wrapping on overflow.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    #[inline(always)]
+    #[track_caller]
+    pub fn inc(&self, index: usize) {
+        self.add(index, 1);
+    }
+
+    /// Adds `n` to the counter at `index`, wrapping on overflow.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    #[inline(always)]
+    #[track_caller]
+    pub fn add(&self, index: usize, n: u64) {
+        self.counter(index).fetch_add(n, Ordering::Relaxed);
+    }
+
+    /// Returns the current value of the counter at `index`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    #[inline(always)]
+    #[track_caller]
+    pub fn load(&self, index: usize) -> u64 {
+        self.counter(index).load(Ordering::Relaxed)
+    }
+}
+
+impl fmt::Debug for CounterArray {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(
+                self.counters
+                    .as_slice()
+                    .iter()
+                    .map(|counter| counter.load(Ordering::Relaxed)),
+            )
+            .finish()
+    }
+}
@@ -0,0 +1,143 @@
+use std::{fmt, ops::Deref};
+
+use crate::{allocator::Global, error::Error, runtime_array::ArrayCStyle};
+
+/// A clone-on-write array that either borrows a slice or owns an
+/// [`ArrayCStyle`].
+///
+/// Read-mostly pipelines can pass borrowed data through untouched and only
+/// pay for a copy when a stage actually needs to modify it through
+/// [`CowArray::to_mut`].
+///
+/// # Example
+///
+/// ```rust ignore
+/// use runnarr::cow_array::CowArray;
+///
+/// fn clamp(samples: &mut CowArray<'_, f32>) {
+///     if samples.iter().any(|sample| sample.abs() > 1.0) {
+///         for sample in samples.to_mut().as_mut_slice() {
+///             *sample = sample.clamp(-1.0, 1.0);
+///         }
+///     }
+/// }
+/// ```
+pub enum CowArray<'a, T> {
+    Borrowed(&'a [T]),
+    Owned(ArrayCStyle<T>),
+}
+
+impl<T> CowArray<'_, T> {
+    /// Returns `true` if the data is still borrowed.
+    #[inline(always)]
+    pub const fn is_borrowed(&self) -> bool {
+        matches!(self, CowArray::Borrowed(_))
+    }
+
+    /// Returns `true` if the data is owned.
+    #[inline(always)]
+    pub const fn is_owned(&self) -> bool {
+        !self.is_borrowed()
+    }
+
+    /// Returns the elements as a slice, whichever variant holds them.
+    #[inline(always)]
+    pub fn as_slice(&self) -> &[T] {
+        match self {
+            CowArray::Borrowed(slice) => slice,
+            CowArray::Owned(array) => array.as_slice(),
+        }
+    }
+}
+
+impl<T: Clone> CowArray<'_, T> {
+    /// Returns a mutable reference to the owned array, cloning the borrowed
+    /// data into a new array first if needed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the clone cannot be allocated.
+    #[cfg(not(feature = "no-panic"))]
+    #[track_caller]
+    pub fn to_mut(&mut self) -> &mut ArrayCStyle<T> {
+        self.try_to_mut().expect("Failed to allocate array clone")
+    }
+
+    /// Like [`CowArray::to_mut`], but returns an error instead of panicking
+    /// if the clone cannot be allocated.
+    #[track_caller]
+    pub fn try_to_mut(&mut self) -> Result<&mut ArrayCStyle<T>, Error> {
+        if let CowArray::Borrowed(slice) = *self {
+            *self = CowArray::Owned(Self::copy(slice)?);
+        }
+        match self {
+            CowArray::Owned(array) => Ok(array),
+            CowArray::Borrowed(_) => unreachable!(),
+        }
+    }
+
+    /// Extracts the owned array, cloning the borrowed data if needed.
+    #[cfg(not(feature = "no-panic"))]
+    #[track_caller]
+    pub fn into_owned(self) -> ArrayCStyle<T> {
+        self.try_into_owned()
+            .expect("Failed to allocate array clone")
+    }
+
+    /// Like [`CowArray::into_owned`], but returns an error instead of
+    /// panicking if the clone cannot be allocated.
+    #[track_caller]
+    pub fn try_into_owned(self) -> Result<ArrayCStyle<T>, Error> {
+        match self {
+            CowArray::Borrowed(slice) => Self::copy(slice),
+            CowArray::Owned(array) => Ok(array),
+        }
+    }
+
+    #[track_caller]
+    fn copy(slice: &[T]) -> Result<ArrayCStyle<T>, Error> {
+        ArrayCStyle::try_from_iter_in(slice.iter().cloned(), Global)
+    }
+}
+
+impl<T> Deref for CowArray<'_, T> {
+    type Target = [T];
+    fn deref(&self) -> &Self::Target {
+        self.as_slice()
+    }
+}
+
+impl<'a, T> From<&'a [T]> for CowArray<'a, T> {
+    fn from(slice: &'a [T]) -> Self {
+        CowArray::Borrowed(slice)
+    }
+}
+
+impl<T> From<ArrayCStyle<T>> for CowArray<'_, T> {
+    fn from(array: ArrayCStyle<T>) -> Self {
+        CowArray::Owned(array)
+    }
+}
+
+#[cfg(not(feature = "no-panic"))]
+impl<T: Clone> Clone for CowArray<'_, T> {
+    fn clone(&self) -> Self {
+        match self {
+            CowArray::Borrowed(slice) => CowArray::Borrowed(slice),
+            CowArray::Owned(array) => CowArray::Owned(array.clone()),
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for CowArray<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CowArray::Borrowed(slice) => {
+                f.debug_tuple("Borrowed").field(slice).finish()
+            }
+            CowArray::Owned(array) => {
+                f.debug_tuple("Owned").field(&array.as_slice()).finish()
+            }
+        }
+    }
+}
@@ -0,0 +1,153 @@
+use std::{
+    io,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use tokio::io::{AsyncBufRead, AsyncRead, AsyncWrite, ReadBuf};
+
+use crate::{
+    allocator::{Global, RawAllocator},
+    runtime_array::ArrayCStyle,
+};
+
+/// An in-memory byte buffer implementing tokio's [`AsyncRead`],
+/// [`AsyncBufRead`] and [`AsyncWrite`], so an [`ArrayCStyle<u8>`] can stand
+/// in for a socket or file in async code and tests.
+///
+/// Reads and writes share one position, like [`std::io::Cursor`], and never
+/// return [`Poll::Pending`]. The buffer does not grow: once the position
+/// reaches the end, reads return 0 bytes and writes return `Ok(0)` (which
+/// makes `write_all` fail with [`io::ErrorKind::WriteZero`]).
+///
+/// Every byte read must have been initialized, e.g. by allocating with
+/// [`ArrayCStyle::zeroed`] or writing it through the cursor first.
+///
+/// # Example
+///
+/// ```rust ignore
+/// use runnarr::cursor::ArrayCursor;
+/// use tokio::io::{AsyncReadExt, AsyncWriteExt};
+///
+/// let mut wire = ArrayCursor::new(ArrayCStyle::<u8>::zeroed(4096)?);
+/// encode_frame(&mut wire, &request).await?;
+///
+/// wire.set_position(0);
+/// let decoded = decode_frame(&mut wire).await?;
+/// ```
+#[derive(Debug)]
+pub struct ArrayCursor<A: RawAllocator = Global> {
+    array: ArrayCStyle<u8, A>,
+    position: usize,
+}
+
+// the buffer is never pinned; only the position moves
+impl<A: RawAllocator> Unpin for ArrayCursor<A> {}
+
+impl<A: RawAllocator> ArrayCursor<A> {
+    /// Wraps `array`, starting at position 0.
+    pub fn new(array: ArrayCStyle<u8, A>) -> Self {
+        Self { array, position: 0 }
+    }
+
+    /// Returns the offset the next read or write starts at.
+    #[inline(always)]
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Moves the cursor to `position`; positions past the end behave like
+    /// the end.
+    #[inline(always)]
+    pub fn set_position(&mut self, position: usize) {
+        self.position = position;
+    }
+
+    /// Returns the bytes between the position and the end of the buffer.
+    pub fn remaining_slice(&self) -> &[u8] {
+        let start = self.position.min(self.array.len());
+        &self.array.as_slice()[start..]
+    }
+
+    /// Returns the underlying array.
+    #[inline(always)]
+    pub fn get_ref(&self) -> &ArrayCStyle<u8, A> {
+        &self.array
+    }
+
+    /// Returns the underlying array mutably.
+    #[inline(always)]
+    pub fn get_mut(&mut self) -> &mut ArrayCStyle<u8, A> {
+        &mut self.array
+    }
+
+    /// Unwraps the underlying array.
+    pub fn into_inner(self) -> ArrayCStyle<u8, A> {
+        self.array
+    }
+}
+
+impl<A: RawAllocator> From<ArrayCStyle<u8, A>> for ArrayCursor<A> {
+    fn from(array: ArrayCStyle<u8, A>) -> Self {
+        Self::new(array)
+    }
+}
+
+impl<A: RawAllocator> AsyncRead for ArrayCursor<A> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        let source = this.remaining_slice();
+        let len = source.len().min(buf.remaining());
+        buf.put_slice(&source[..len]);
+        this.position += len;
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<A: RawAllocator> AsyncBufRead for ArrayCursor<A> {
+    fn poll_fill_buf(
+        self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+    ) -> Poll<io::Result<&[u8]>> {
+        Poll::Ready(Ok(self.get_mut().remaining_slice()))
+    }
+
+    fn consume(self: Pin<&mut Self>, amount: usize) {
+        let this = self.get_mut();
+        this.position = this.position.saturating_add(amount);
+    }
+}
+
+impl<A: RawAllocator> AsyncWrite for ArrayCursor<A> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        let start = this.position.min(this.array.len());
+        let target = &mut this.array.as_mut_slice()[start..];
+        let len = target.len().min(buf.len());
+        target[..len].copy_from_slice(&buf[..len]);
+        this.position = start + len;
+        Poll::Ready(Ok(len))
+    }
+
+    fn poll_flush(
+        self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+    ) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_shutdown(
+        self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+    ) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+}
@@ -0,0 +1,214 @@
+use std::{
+    cell::UnsafeCell,
+    fmt,
+    ops::{Deref, DerefMut},
+    sync::atomic::{AtomicBool, AtomicUsize, Ordering},
+};
+
+use crate::{error::Error, runtime_array::ArrayCStyle};
+
+/// Two equal-size arrays: a front one that readers see and a back one that a
+/// writer fills, exchanged by an atomic [`DoubleBuffer::swap`].
+///
+/// Readers pin the current front with [`DoubleBuffer::front`]; a writer gets
+/// the back through [`DoubleBuffer::back_mut`], which first waits for
+/// readers still pinning that array from before the last swap. Readers never
+/// wait, so a render thread always gets a complete frame while the
+/// simulation writes the next one.
+///
+/// # Example
+///
+/// ```rust ignore
+/// use runnarr::double_buffer::DoubleBuffer;
+///
+/// let frames = DoubleBuffer::<Particle>::zeroed(100_000).unwrap();
+/// std::thread::scope(|scope| {
+///     scope.spawn(|| loop {
+///         step(&frames.front(), &mut frames.back_mut().unwrap());
+///         frames.swap();
+///     });
+///     scope.spawn(|| loop {
+///         render(&frames.front());
+///     });
+/// });
+/// ```
+pub struct DoubleBuffer<T> {
+    buffers: [UnsafeCell<ArrayCStyle<T>>; 2],
+    len: usize,
+    /// Index of the front buffer.
+    front: AtomicUsize,
+    /// Live [`FrontGuard`]s per buffer.
+    readers: [AtomicUsize; 2],
+    /// Whether a [`BackGuard`] is alive.
+    writing: AtomicBool,
+}
+
+// Readers only get `&T` into the front, the single writer only gets `&mut T`
+// into the back once no reader pins it, and `writing` keeps swaps out while
+// the writer works.
+unsafe impl<T: Send> Send for DoubleBuffer<T> {}
+unsafe impl<T: Send + Sync> Sync for DoubleBuffer<T> {}
+
+impl<T> DoubleBuffer<T> {
+    /// Creates a double buffer showing `front` first.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the arrays differ in length.
+    pub fn new(
+        front: ArrayCStyle<T>,
+        back: ArrayCStyle<T>,
+    ) -> Result<Self, Error> {
+        if front.len() != back.len() {
+            return Err(Error::Other(format!(
+                "Double buffer halves differ in length: {} and {}",
+                front.len(),
+                back.len()
+            )));
+        }
+        Ok(Self {
+            len: front.len(),
+            buffers: [UnsafeCell::new(front), UnsafeCell::new(back)],
+            front: AtomicUsize::new(0),
+            readers: [AtomicUsize::new(0), AtomicUsize::new(0)],
+            writing: AtomicBool::new(false),
+        })
+    }
+
+    /// Creates a double buffer of two zeroed arrays of `len` elements.
+    #[track_caller]
+    pub fn zeroed(len: usize) -> Result<Self, Error> {
+        Self::new(ArrayCStyle::zeroed(len)?, ArrayCStyle::zeroed(len)?)
+    }
+
+    /// Returns the number of elements in each array.
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the arrays hold no elements.
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Pins the current front array for reading.
+    pub fn front(&self) -> FrontGuard<'_, T> {
+        loop {
+            let index = self.front.load(Ordering::SeqCst);
+            self.readers[index].fetch_add(1, Ordering::SeqCst);
+            // a swap between the load and the increment may have turned the
+            // array into the back, which a writer could already be filling
+            if self.front.load(Ordering::SeqCst) == index {
+                return FrontGuard { owner: self, index };
+            }
+            self.readers[index].fetch_sub(1, Ordering::SeqCst);
+        }
+    }
+
+    /// Returns exclusive access to the back array, or `None` if another
+    /// writer holds it.
+    ///
+    /// Spins until readers that pinned the array before the last swap have
+    /// let go of it, so the calling thread must not hold such a guard itself.
+    pub fn back_mut(&self) -> Option<BackGuard<'_, T>> {
+        if self.writing.swap(true, Ordering::SeqCst) {
+            return None;
+        }
+        let index = 1 - self.front.load(Ordering::SeqCst);
+        while self.readers[index].load(Ordering::SeqCst) != 0 {
+            std::hint::spin_loop();
+        }
+        Some(BackGuard { owner: self, index })
+    }
+
+    /// Makes the back array the front one and vice versa.
+    ///
+    /// # Returns
+    ///
+    /// `false`, without swapping, if a [`BackGuard`] is alive.
+    pub fn swap(&self) -> bool {
+        if self.writing.swap(true, Ordering::SeqCst) {
+            return false;
+        }
+        self.front.fetch_xor(1, Ordering::SeqCst);
+        self.writing.store(false, Ordering::SeqCst);
+        true
+    }
+
+    /// Unwraps the arrays as `(front, back)`.
+    pub fn into_arrays(self) -> (ArrayCStyle<T>, ArrayCStyle<T>) {
+        let front = self.front.into_inner();
+        let [first, second] = self.buffers.map(UnsafeCell::into_inner);
+        if front == 0 {
+            (first, second)
+        } else {
+            (second, first)
+        }
+    }
+}
+
+impl<T> fmt::Debug for DoubleBuffer<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DoubleBuffer")
+            .field("len", &self.len())
+            .field("front", &self.front.load(Ordering::Relaxed))
+            .finish_non_exhaustive()
+    }
+}
+
+/// Shared access to the front array of a [`DoubleBuffer`].
+///
+/// The array stays readable even if the buffers are swapped while the guard
+/// is alive; the writer waits for it before reusing the array.
+pub struct FrontGuard<'a, T> {
+    owner: &'a DoubleBuffer<T>,
+    index: usize,
+}
+
+impl<T> Deref for FrontGuard<'_, T> {
+    type Target = [T];
+    fn deref(&self) -> &Self::Target {
+        unsafe { (*self.owner.buffers[self.index].get()).as_slice() }
+    }
+}
+
+impl<T> Drop for FrontGuard<'_, T> {
+    fn drop(&mut self) {
+        self.owner.readers[self.index].fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// Exclusive access to the back array of a [`DoubleBuffer`].
+pub struct BackGuard<'a, T> {
+    owner: &'a DoubleBuffer<T>,
+    index: usize,
+}
+
+impl<T> BackGuard<'_, T> {
+    /// Swaps the array to the front and releases it.
+    pub fn publish(self) {
+        // still holding `writing`, so no other swap can interleave
+        self.owner.front.fetch_xor(1, Ordering::SeqCst);
+    }
+}
+
+impl<T> Deref for BackGuard<'_, T> {
+    type Target = [T];
+    fn deref(&self) -> &Self::Target {
+        unsafe { (*self.owner.buffers[self.index].get()).as_slice() }
+    }
+}
+
+impl<T> DerefMut for BackGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        unsafe { (*self.owner.buffers[self.index].get()).as_mut_slice() }
+    }
+}
+
+impl<T> Drop for BackGuard<'_, T> {
+    fn drop(&mut self) {
+        self.owner.writing.store(false, Ordering::SeqCst);
+    }
+}
@@ -0,0 +1,323 @@
+use std::{
+    fmt,
+    marker::PhantomData,
+    ops::Deref,
+    ptr,
+    sync::{
+        atomic::{AtomicPtr, AtomicUsize, Ordering},
+        Mutex, MutexGuard, PoisonError,
+    },
+};
+
+use crate::{aligned::CacheAligned, error::Error, runtime_array::ArrayCStyle};
+
+type Deferred = Box<dyn FnOnce() + Send>;
+
+/// An epoch-based reclamation domain.
+///
+/// Readers [`pin`](Domain::pin) the domain while they hold pointers into a
+/// shared structure. A writer that unlinks memory hands its destruction to
+/// [`Domain::defer`], and it only runs once every reader that could still
+/// see the memory has unpinned, which [`Domain::collect`] detects by
+/// advancing a global epoch.
+///
+/// Readers pinned in the current or previous epoch are counted in two
+/// cache-line-padded counters (one per epoch parity), so pinning is a
+/// couple of atomic operations and never blocks. A reader that stays pinned
+/// forever holds back reclamation, not progress.
+///
+/// [`EpochArray`] frees its outgrown buffers through a domain, and
+/// [`AtomicArrayCell`](crate::arc_array::AtomicArrayCell) uses one to
+/// release replaced arrays without making writers wait for readers.
+#[derive(Default)]
+pub struct Domain {
+    epoch: CacheAligned<AtomicUsize>,
+    readers: [CacheAligned<AtomicUsize>; 2],
+    garbage: Mutex<Vec<(usize, Deferred)>>,
+}
+
+impl Domain {
+    /// Creates a domain with nothing to reclaim.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Marks the calling thread as reading until the guard is dropped.
+    pub fn pin(&self) -> Guard<'_> {
+        loop {
+            let epoch = self.epoch.load(Ordering::SeqCst);
+            self.readers[epoch & 1].fetch_add(1, Ordering::SeqCst);
+            // re-check so the reader is counted under the epoch it observes
+            if self.epoch.load(Ordering::SeqCst) == epoch {
+                return Guard {
+                    domain: self,
+                    parity: epoch & 1,
+                };
+            }
+            self.readers[epoch & 1].fetch_sub(1, Ordering::SeqCst);
+        }
+    }
+
+    /// Schedules `f` to run once no reader pinned now can still be reading.
+    ///
+    /// Call it after the memory `f` frees has been unlinked.
+    pub fn defer(&self, f: impl FnOnce() + Send + 'static) {
+        let epoch = self.epoch.load(Ordering::SeqCst);
+        self.lock_garbage().push((epoch, Box::new(f)));
+    }
+
+    /// Advances the epoch if possible and runs every deferred function that
+    /// has become safe.
+    ///
+    /// # Returns
+    ///
+    /// The number of deferred functions run.
+    pub fn collect(&self) -> usize {
+        let epoch = self.epoch.load(Ordering::SeqCst);
+        // readers of the previous epoch share a counter with the next one
+        if self.readers[(epoch + 1) & 1].load(Ordering::SeqCst) == 0 {
+            let _ = self.epoch.compare_exchange(
+                epoch,
+                epoch + 1,
+                Ordering::SeqCst,
+                Ordering::SeqCst,
+            );
+        }
+        let epoch = self.epoch.load(Ordering::SeqCst);
+
+        // memory retired in epoch `e` is unreachable for readers pinned in
+        // `e + 1` or later, and the readers of `e` are gone once `e + 2`
+        // has begun
+        let ready: Vec<Deferred> = {
+            let mut garbage = self.lock_garbage();
+            let (ready, pending) = garbage
+                .drain(..)
+                .partition::<Vec<_>, _>(|(retired, _)| retired + 2 <= epoch);
+            *garbage = pending;
+            ready.into_iter().map(|(_, f)| f).collect()
+        };
+        let count = ready.len();
+        for f in ready {
+            f();
+        }
+        count
+    }
+
+    /// Returns the number of deferred functions still waiting.
+    pub fn pending(&self) -> usize {
+        self.lock_garbage().len()
+    }
+
+    fn lock_garbage(&self) -> MutexGuard<'_, Vec<(usize, Deferred)>> {
+        self.garbage.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl Drop for Domain {
+    fn drop(&mut self) {
+        // no guard can outlive the domain, so everything is safe now
+        let garbage = self
+            .garbage
+            .get_mut()
+            .unwrap_or_else(PoisonError::into_inner);
+        for (_, f) in garbage.drain(..) {
+            f();
+        }
+    }
+}
+
+impl fmt::Debug for Domain {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Domain")
+            .field("epoch", &self.epoch.load(Ordering::Relaxed))
+            .field("pending", &self.pending())
+            .finish()
+    }
+}
+
+/// Keeps a [`Domain`] pinned; see [`Domain::pin`].
+pub struct Guard<'a> {
+    domain: &'a Domain,
+    parity: usize,
+}
+
+impl Drop for Guard<'_> {
+    fn drop(&mut self) {
+        self.domain.readers[self.parity].fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+impl fmt::Debug for Guard<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Guard").finish_non_exhaustive()
+    }
+}
+
+/// A shared array that can grow while other threads read it.
+///
+/// Readers get a slice of the current buffer through
+/// [`EpochArray::read`] without taking a lock. [`EpochArray::grow`] moves
+/// the elements into a larger buffer and publishes it; the old buffer is
+/// only freed through the array's [`Domain`] once no reader can still be
+/// looking at it.
+///
+/// Elements must be `Copy`. A grow copies them bit for bit while readers
+/// may still hold references into the old buffer, so a type with interior
+/// mutability (atomics, `Mutex`, cells) would lose writes made through
+/// those references, or duplicate a held lock. `Copy` rules such types out
+/// and means nothing needs dropping, which the crate's arrays never do.
+///
+/// # Example
+///
+/// ```rust ignore
+/// use runnarr::epoch::EpochArray;
+///
+/// let symbols = EpochArray::new(ArrayCStyle::<SymbolId>::zeroed(1024)?);
+///
+/// // lookup threads
+/// let table = symbols.read();
+/// resolve(&table, id);
+///
+/// // loader thread, when the table fills up
+/// symbols.grow(1024, |_| SymbolId::NONE)?;
+/// ```
+pub struct EpochArray<T> {
+    current: AtomicPtr<ArrayCStyle<T>>,
+    domain: Domain,
+    writer: Mutex<()>,
+    _marker: PhantomData<ArrayCStyle<T>>,
+}
+
+// Readers only get `&T`; a grow copies the elements into a new buffer under
+// the writer lock, and the old one is freed by the domain (hence `'static`
+// for the deferred free, which may run on any thread).
+unsafe impl<T: Send> Send for EpochArray<T> {}
+unsafe impl<T: Send + Sync> Sync for EpochArray<T> {}
+
+impl<T: Copy + Send + 'static> EpochArray<T> {
+    /// Shares an initialized array.
+    pub fn new(array: ArrayCStyle<T>) -> Self {
+        Self {
+            current: AtomicPtr::new(Box::into_raw(Box::new(array))),
+            domain: Domain::new(),
+            writer: Mutex::new(()),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns a view of the current buffer.
+    ///
+    /// The view stays valid, and keeps its length, even if the array grows
+    /// meanwhile.
+    pub fn read(&self) -> EpochArrayRef<'_, T> {
+        let guard = self.domain.pin();
+        let array = unsafe { &*self.current.load(Ordering::SeqCst) };
+        EpochArrayRef {
+            slice: array.as_slice(),
+            _guard: guard,
+        }
+    }
+
+    /// Returns the current number of elements.
+    pub fn len(&self) -> usize {
+        self.read().len()
+    }
+
+    /// Returns `true` if the array currently holds no elements.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Grows the array by `additional` elements, initializing the new slot
+    /// at index `i` to `fill(i)`.
+    ///
+    /// Concurrent growers are serialized. The previous buffer is freed once
+    /// its last reader is done.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the new buffer cannot be allocated; the array is
+    /// left unchanged.
+    #[track_caller]
+    pub fn grow(
+        &self,
+        additional: usize,
+        mut fill: impl FnMut(usize) -> T,
+    ) -> Result<(), Error> {
+        let _writer =
+            self.writer.lock().unwrap_or_else(PoisonError::into_inner);
+        let old_ptr = self.current.load(Ordering::SeqCst);
+        let old = unsafe { &*old_ptr };
+        let len = old.len().checked_add(additional).ok_or_else(|| {
+            Error::Other("Epoch array length overflows usize".to_string())
+        })?;
+
+        let grown = ArrayCStyle::<T>::new(len)?;
+        unsafe {
+            // `Copy` elements have no interior mutability, so readers of the
+            // old buffer can never diverge from the copies
+            ptr::copy_nonoverlapping(old.ptr(), grown.ptr_mut(), old.len());
+            for index in old.len()..len {
+                grown.ptr_mut().add(index).write(fill(index));
+            }
+        }
+        self.current
+            .store(Box::into_raw(Box::new(grown)), Ordering::SeqCst);
+
+        let old = SendPtr(old_ptr);
+        self.domain.defer(move || {
+            let old = old;
+            drop(unsafe { Box::from_raw(old.0) });
+        });
+        self.domain.collect();
+        Ok(())
+    }
+
+    /// Returns the array's reclamation domain, e.g. to call
+    /// [`Domain::collect`] after readers have finished.
+    #[inline(always)]
+    pub fn domain(&self) -> &Domain {
+        &self.domain
+    }
+
+    /// Unwraps the current buffer.
+    pub fn into_array(self) -> ArrayCStyle<T> {
+        let current = self.current.swap(ptr::null_mut(), Ordering::SeqCst);
+        // frees the retired buffers along with the domain
+        drop(self);
+        *unsafe { Box::from_raw(current) }
+    }
+}
+
+impl<T> Drop for EpochArray<T> {
+    fn drop(&mut self) {
+        let current = *self.current.get_mut();
+        if !current.is_null() {
+            drop(unsafe { Box::from_raw(current) });
+        }
+    }
+}
+
+impl<T: fmt::Debug + Copy + Send + 'static> fmt::Debug for EpochArray<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("EpochArray").field(&&*self.read()).finish()
+    }
+}
+
+/// Moves a retired buffer into the deferred free.
+struct SendPtr<T>(*mut ArrayCStyle<T>);
+
+unsafe impl<T: Send> Send for SendPtr<T> {}
+
+/// A pinned view of an [`EpochArray`]'s buffer.
+pub struct EpochArrayRef<'a, T> {
+    slice: &'a [T],
+    _guard: Guard<'a>,
+}
+
+impl<T> Deref for EpochArrayRef<'_, T> {
+    type Target = [T];
+    fn deref(&self) -> &Self::Target {
+        self.slice
+    }
+}
@@ -1,10 +1,691 @@
-use std::alloc::LayoutError;
+use std::{
+    alloc::{Layout, LayoutError},
+    cell::RefCell,
+    error,
+    ffi::{CStr, CString},
+    fmt, io,
+};
 
+/// The error type of the crate.
 #[derive(Debug)]
-pub struct BaseError(pub String);
+#[non_exhaustive]
+pub enum Error {
+    /// The requested length and alignment do not form a valid memory
+    /// layout, e.g. because the size overflows `isize`.
+    Layout(LayoutError),
+    /// The allocator could not provide the memory.
+    Alloc(AllocError),
+    /// A canary around an allocation was overwritten.
+    Corruption(CorruptionError),
+    /// An array could not be grown.
+    Grow(GrowError),
+    /// An element was accessed past the end of an array.
+    IndexOutOfBounds {
+        /// The index that was accessed.
+        index: usize,
+        /// The length of the array.
+        len: usize,
+    },
+    /// An operating-system or file I/O call failed.
+    Io(io::Error),
+    /// Any other failure, described by a message.
+    Other(String),
+}
+
+/// Shorthand for results whose error defaults to the crate's [`Error`].
+pub type Result<T, E = Error> = core::result::Result<T, E>;
+
+/// Shorthand for results of allocation paths; see [`AllocationError`].
+pub type AllocationResult<T> = Result<T, AllocationError>;
+
+/// Shorthand for results of memory-mapping and file paths; see [`IoError`].
+pub type IoResult<T> = Result<T, IoError>;
+
+/// Shorthand for results of container operations; see [`ContainerError`].
+pub type ContainerResult<T> = Result<T, ContainerError>;
+
+/// Shorthand for results handed across the FFI boundary; see [`FfiError`].
+pub type FfiResult<T> = Result<T, FfiError>;
+
+/// The broad subsystem an [`Error`] comes from.
+///
+/// Matching on the category instead of the individual variants keeps
+/// downstream error handling short and stable as new variants are added.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ErrorCategory {
+    /// Obtaining or resizing memory failed: [`Error::Layout`],
+    /// [`Error::Alloc`] and [`Error::Grow`].
+    Allocation,
+    /// An element was accessed incorrectly: [`Error::IndexOutOfBounds`].
+    Access,
+    /// Memory was found corrupted: [`Error::Corruption`].
+    Integrity,
+    /// The operating system or a file reported an error: [`Error::Io`].
+    Io,
+    /// Anything else: [`Error::Other`].
+    Other,
+}
+
+/// Error code of a successful call; see [`Error::code`].
+pub const CODE_OK: i32 = 0;
+/// Error code of [`Error::Layout`].
+pub const CODE_LAYOUT: i32 = 1;
+/// Error code of [`Error::Alloc`].
+pub const CODE_ALLOC: i32 = 2;
+/// Error code of [`Error::Corruption`].
+pub const CODE_CORRUPTION: i32 = 3;
+/// Error code of [`Error::Grow`].
+pub const CODE_GROW: i32 = 4;
+/// Error code of [`Error::IndexOutOfBounds`].
+pub const CODE_INDEX_OUT_OF_BOUNDS: i32 = 5;
+/// Error code of [`Error::Other`].
+pub const CODE_OTHER: i32 = 6;
+/// Error code of [`Error::Io`].
+pub const CODE_IO: i32 = 7;
+
+impl Error {
+    /// Returns a stable, `errno`-style code identifying the kind of error,
+    /// for reporting failures across an FFI boundary.
+    ///
+    /// Codes are positive and never change between releases; `0`
+    /// ([`CODE_OK`]) is reserved for success.
+    pub const fn code(&self) -> i32 {
+        match self {
+            Error::Layout(_) => CODE_LAYOUT,
+            Error::Alloc(_) => CODE_ALLOC,
+            Error::Corruption(_) => CODE_CORRUPTION,
+            Error::Grow(_) => CODE_GROW,
+            Error::IndexOutOfBounds { .. } => CODE_INDEX_OUT_OF_BOUNDS,
+            Error::Io(_) => CODE_IO,
+            Error::Other(_) => CODE_OTHER,
+        }
+    }
+
+    /// Returns the subsystem the error comes from.
+    ///
+    /// # Example
+    ///
+    /// ```rust ignore
+    /// use runnarr::error::ErrorCategory;
+    ///
+    /// match error.category() {
+    ///     ErrorCategory::Allocation => shed_load(),
+    ///     ErrorCategory::Io => retry_later(),
+    ///     _ => return Err(error),
+    /// }
+    /// ```
+    pub const fn category(&self) -> ErrorCategory {
+        match self {
+            Error::Layout(_) | Error::Alloc(_) | Error::Grow(_) => {
+                ErrorCategory::Allocation
+            }
+            Error::IndexOutOfBounds { .. } => ErrorCategory::Access,
+            Error::Corruption(_) => ErrorCategory::Integrity,
+            Error::Io(_) => ErrorCategory::Io,
+            Error::Other(_) => ErrorCategory::Other,
+        }
+    }
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<FfiError>> =
+        const { RefCell::new(None) };
+}
+
+/// Records `error` as the calling thread's last error and returns its code.
+///
+/// C entry points catch errors with this instead of letting them (or
+/// panics) cross the FFI boundary; the caller then inspects
+/// [`last_error_code`] and [`last_error_message`].
+///
+/// # Example
+///
+/// ```rust ignore
+/// use runnarr::error::{set_last_error, CODE_OK};
+///
+/// #[no_mangle]
+/// pub extern "C" fn runnarr_array_new(len: usize, out: *mut *mut Array) -> i32 {
+///     match ArrayCStyle::zeroed(len) {
+///         Ok(array) => {
+///             unsafe { *out = Box::into_raw(Box::new(array)) };
+///             CODE_OK
+///         }
+///         Err(error) => set_last_error(&error),
+///     }
+/// }
+/// ```
+pub fn set_last_error(error: &Error) -> i32 {
+    let error = FfiError::from(error);
+    let code = error.code();
+    LAST_ERROR.with(|last| *last.borrow_mut() = Some(error));
+    code
+}
+
+/// Returns the code of the calling thread's last error, or [`CODE_OK`] if
+/// none was recorded.
+pub fn last_error_code() -> i32 {
+    LAST_ERROR
+        .with(|last| last.borrow().as_ref().map_or(CODE_OK, FfiError::code))
+}
+
+/// Returns the message of the calling thread's last error as a C string.
+pub fn last_error_message() -> Option<CString> {
+    LAST_ERROR
+        .with(|last| last.borrow().as_ref().map(|error| error.message.clone()))
+}
+
+/// Forgets the calling thread's last error.
+pub fn clear_last_error() {
+    LAST_ERROR.with(|last| *last.borrow_mut() = None);
+}
+
+/// Former name of [`Error`].
+#[deprecated(note = "renamed to `Error`")]
+pub type BaseError = Error;
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Layout(error) => write!(f, "invalid array layout: {error}"),
+            Error::Alloc(error) => error.fmt(f),
+            Error::Corruption(error) => error.fmt(f),
+            Error::Grow(error) => error.fmt(f),
+            Error::IndexOutOfBounds { index, len } => write!(
+                f,
+                "index {index} is out of bounds for an array of length {len}"
+            ),
+            Error::Io(error) => error.fmt(f),
+            Error::Other(message) => f.write_str(message),
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Error::Layout(error) => Some(error),
+            Error::Alloc(error) => Some(error),
+            Error::Corruption(error) => Some(error),
+            Error::Grow(error) => Some(error),
+            Error::Io(error) => Some(error),
+            Error::IndexOutOfBounds { .. } | Error::Other(_) => None,
+        }
+    }
+}
+
+impl From<LayoutError> for Error {
+    fn from(error_value: LayoutError) -> Self {
+        Error::Layout(error_value)
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(error_value: io::Error) -> Self {
+        Error::Io(error_value)
+    }
+}
+
+/// Converts the error for code built on `std::io`.
+///
+/// [`Error::Io`] is unwrapped back into the original `io::Error`; every
+/// other variant is wrapped with the closest [`io::ErrorKind`]:
+///
+/// | variant                   | kind           |
+/// |---------------------------|----------------|
+/// | `Alloc`                   | `OutOfMemory`  |
+/// | `Grow` (length fits)      | `OutOfMemory`  |
+/// | `Grow` (length overflows) | `InvalidInput` |
+/// | `Layout`                  | `InvalidInput` |
+/// | `IndexOutOfBounds`        | `InvalidInput` |
+/// | `Corruption`              | `InvalidData`  |
+/// | `Other`                   | `Other`        |
+impl From<Error> for io::Error {
+    fn from(error_value: Error) -> Self {
+        let kind = match error_value {
+            Error::Io(error) => return error,
+            Error::Alloc(_) => io::ErrorKind::OutOfMemory,
+            Error::Grow(GrowError {
+                requested: Some(_), ..
+            }) => io::ErrorKind::OutOfMemory,
+            Error::Grow(_)
+            | Error::Layout(_)
+            | Error::IndexOutOfBounds { .. } => io::ErrorKind::InvalidInput,
+            Error::Corruption(_) => io::ErrorKind::InvalidData,
+            Error::Other(_) => io::ErrorKind::Other,
+        };
+        io::Error::new(kind, error_value)
+    }
+}
+
+/// Returned when an allocator runs out of memory.
+///
+/// Creating, copying and formatting this error never allocates, so the
+/// failure path stays safe when the process is under memory pressure.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub struct AllocError {
+    /// The number of bytes that were requested.
+    pub size: usize,
+    /// The alignment that was requested.
+    pub align: usize,
+}
+
+impl AllocError {
+    /// Describes a failed request for `layout`.
+    #[inline(always)]
+    pub const fn new(layout: Layout) -> Self {
+        Self {
+            size: layout.size(),
+            align: layout.align(),
+        }
+    }
+}
+
+impl fmt::Display for AllocError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "memory allocation of {} bytes aligned to {} failed",
+            self.size, self.align
+        )
+    }
+}
+
+impl error::Error for AllocError {}
+
+impl From<AllocError> for Error {
+    fn from(error_value: AllocError) -> Self {
+        Error::Alloc(error_value)
+    }
+}
+
+/// The guard region in which a corrupted canary byte was found.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub enum CanaryRegion {
+    /// The canary in front of the first element (a buffer underrun).
+    Before,
+    /// The canary behind the last element (a buffer overrun).
+    After,
+}
+
+/// Describes the first corrupted canary byte found around an allocation.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub struct CorruptionError {
+    /// Which side of the buffer was overwritten.
+    pub region: CanaryRegion,
+    /// Byte offset of the corrupted byte relative to the start of the
+    /// buffer; negative for bytes in front of it.
+    pub offset: isize,
+    /// Address of the corrupted byte.
+    pub address: usize,
+    /// The canary byte that should have been there.
+    pub expected: u8,
+    /// The byte actually found.
+    pub found: u8,
+}
+
+impl fmt::Display for CorruptionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind = match self.region {
+            CanaryRegion::Before => "underrun",
+            CanaryRegion::After => "overrun",
+        };
+        write!(
+            f,
+            "buffer {kind} detected at offset {} ({:#x}): expected canary byte {:#04x}, found {:#04x}",
+            self.offset, self.address, self.expected, self.found
+        )
+    }
+}
+
+impl error::Error for CorruptionError {}
+
+impl From<CorruptionError> for Error {
+    fn from(error_value: CorruptionError) -> Self {
+        Error::Corruption(error_value)
+    }
+}
+
+/// Returned when an array could not be grown.
+///
+/// The array is left exactly as it was, contents included.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub struct GrowError {
+    /// The length the array was asked to grow to, or `None` if computing it
+    /// overflowed.
+    pub requested: Option<usize>,
+    /// The length of the array, unchanged by the failed attempt.
+    pub current: usize,
+}
+
+impl fmt::Display for GrowError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.requested {
+            Some(requested) => write!(
+                f,
+                "failed to grow array from {} to {requested} elements",
+                self.current
+            ),
+            None => write!(
+                f,
+                "failed to grow array of {} elements: length overflows usize",
+                self.current
+            ),
+        }
+    }
+}
+
+impl error::Error for GrowError {}
+
+impl From<GrowError> for Error {
+    fn from(error_value: GrowError) -> Self {
+        Error::Grow(error_value)
+    }
+}
+
+/// The errors of the [`ErrorCategory::Allocation`] category.
+///
+/// Allocation paths can return this narrower type so callers only match the
+/// failures that can actually happen; `?` rolls it up into [`Error`], and
+/// `AllocationError::try_from(error)` narrows an [`Error`] back down.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum AllocationError {
+    /// See [`Error::Layout`].
+    Layout(LayoutError),
+    /// See [`Error::Alloc`].
+    Alloc(AllocError),
+    /// See [`Error::Grow`].
+    Grow(GrowError),
+}
+
+impl fmt::Display for AllocationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AllocationError::Layout(error) => {
+                write!(f, "invalid array layout: {error}")
+            }
+            AllocationError::Alloc(error) => error.fmt(f),
+            AllocationError::Grow(error) => error.fmt(f),
+        }
+    }
+}
+
+impl error::Error for AllocationError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            AllocationError::Layout(error) => Some(error),
+            AllocationError::Alloc(error) => Some(error),
+            AllocationError::Grow(error) => Some(error),
+        }
+    }
+}
+
+impl From<LayoutError> for AllocationError {
+    fn from(error_value: LayoutError) -> Self {
+        AllocationError::Layout(error_value)
+    }
+}
+
+impl From<AllocError> for AllocationError {
+    fn from(error_value: AllocError) -> Self {
+        AllocationError::Alloc(error_value)
+    }
+}
 
-impl From<LayoutError> for BaseError {
+impl From<GrowError> for AllocationError {
+    fn from(error_value: GrowError) -> Self {
+        AllocationError::Grow(error_value)
+    }
+}
+
+impl From<AllocationError> for Error {
+    fn from(error_value: AllocationError) -> Self {
+        match error_value {
+            AllocationError::Layout(error) => Error::Layout(error),
+            AllocationError::Alloc(error) => Error::Alloc(error),
+            AllocationError::Grow(error) => Error::Grow(error),
+        }
+    }
+}
+
+impl TryFrom<Error> for AllocationError {
+    type Error = Error;
+
+    /// Narrows `error` to its allocation failure, or gives it back if it
+    /// belongs to another category.
+    fn try_from(error_value: Error) -> Result<Self, Error> {
+        match error_value {
+            Error::Layout(error) => Ok(AllocationError::Layout(error)),
+            Error::Alloc(error) => Ok(AllocationError::Alloc(error)),
+            Error::Grow(error) => Ok(AllocationError::Grow(error)),
+            other => Err(other),
+        }
+    }
+}
+
+/// The errors of memory-mapping and file paths: the
+/// [`ErrorCategory::Io`] failures plus the allocation failures of sizing the
+/// mapping or buffer.
+///
+/// `?` rolls it up into [`Error`], and `IoError::try_from(error)` narrows an
+/// [`Error`] back down.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum IoError {
+    /// See [`Error::Io`].
+    Io(io::Error),
+    /// See [`Error::Layout`].
+    Layout(LayoutError),
+    /// See [`Error::Alloc`].
+    Alloc(AllocError),
+}
+
+impl fmt::Display for IoError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IoError::Io(error) => error.fmt(f),
+            IoError::Layout(error) => {
+                write!(f, "invalid array layout: {error}")
+            }
+            IoError::Alloc(error) => error.fmt(f),
+        }
+    }
+}
+
+impl error::Error for IoError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            IoError::Io(error) => Some(error),
+            IoError::Layout(error) => Some(error),
+            IoError::Alloc(error) => Some(error),
+        }
+    }
+}
+
+impl From<io::Error> for IoError {
+    fn from(error_value: io::Error) -> Self {
+        IoError::Io(error_value)
+    }
+}
+
+impl From<LayoutError> for IoError {
     fn from(error_value: LayoutError) -> Self {
-        BaseError(error_value.to_string())
+        IoError::Layout(error_value)
+    }
+}
+
+impl From<AllocError> for IoError {
+    fn from(error_value: AllocError) -> Self {
+        IoError::Alloc(error_value)
+    }
+}
+
+impl From<IoError> for Error {
+    fn from(error_value: IoError) -> Self {
+        match error_value {
+            IoError::Io(error) => Error::Io(error),
+            IoError::Layout(error) => Error::Layout(error),
+            IoError::Alloc(error) => Error::Alloc(error),
+        }
+    }
+}
+
+impl From<IoError> for io::Error {
+    fn from(error_value: IoError) -> Self {
+        Error::from(error_value).into()
+    }
+}
+
+impl TryFrom<Error> for IoError {
+    type Error = Error;
+
+    /// Narrows `error` to its I/O failure, or gives it back if it belongs
+    /// to another subsystem.
+    fn try_from(error_value: Error) -> Result<Self, Error> {
+        match error_value {
+            Error::Io(error) => Ok(IoError::Io(error)),
+            Error::Layout(error) => Ok(IoError::Layout(error)),
+            Error::Alloc(error) => Ok(IoError::Alloc(error)),
+            other => Err(other),
+        }
+    }
+}
+
+/// The errors of container operations: the [`ErrorCategory::Access`]
+/// failures plus the allocation failures of their backing storage.
+///
+/// Running out of fixed capacity is not an error here; containers hand the
+/// rejected value back instead. `?` rolls it up into [`Error`], and
+/// `ContainerError::try_from(error)` narrows an [`Error`] back down.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ContainerError {
+    /// See [`Error::IndexOutOfBounds`].
+    IndexOutOfBounds {
+        /// The index that was accessed.
+        index: usize,
+        /// The length of the container.
+        len: usize,
+    },
+    /// The backing storage could not be allocated or grown.
+    Allocation(AllocationError),
+}
+
+impl fmt::Display for ContainerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ContainerError::IndexOutOfBounds { index, len } => write!(
+                f,
+                "index {index} is out of bounds for an array of length {len}"
+            ),
+            ContainerError::Allocation(error) => error.fmt(f),
+        }
+    }
+}
+
+impl error::Error for ContainerError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            ContainerError::IndexOutOfBounds { .. } => None,
+            ContainerError::Allocation(error) => Some(error),
+        }
+    }
+}
+
+impl From<AllocationError> for ContainerError {
+    fn from(error_value: AllocationError) -> Self {
+        ContainerError::Allocation(error_value)
+    }
+}
+
+impl From<AllocError> for ContainerError {
+    fn from(error_value: AllocError) -> Self {
+        ContainerError::Allocation(error_value.into())
+    }
+}
+
+impl From<GrowError> for ContainerError {
+    fn from(error_value: GrowError) -> Self {
+        ContainerError::Allocation(error_value.into())
+    }
+}
+
+impl From<ContainerError> for Error {
+    fn from(error_value: ContainerError) -> Self {
+        match error_value {
+            ContainerError::IndexOutOfBounds { index, len } => {
+                Error::IndexOutOfBounds { index, len }
+            }
+            ContainerError::Allocation(error) => error.into(),
+        }
+    }
+}
+
+impl TryFrom<Error> for ContainerError {
+    type Error = Error;
+
+    /// Narrows `error` to its container failure, or gives it back if it
+    /// belongs to another subsystem.
+    fn try_from(error_value: Error) -> Result<Self, Error> {
+        match error_value {
+            Error::IndexOutOfBounds { index, len } => {
+                Ok(ContainerError::IndexOutOfBounds { index, len })
+            }
+            other => {
+                AllocationError::try_from(other).map(ContainerError::Allocation)
+            }
+        }
+    }
+}
+
+/// An error as reported across the FFI boundary: its stable code and a
+/// NUL-terminated message.
+///
+/// This is what [`set_last_error`] records. Unlike the other subsystem
+/// errors it only converts one way, since a C caller sees nothing but the
+/// code and the text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FfiError {
+    code: i32,
+    message: CString,
+}
+
+impl FfiError {
+    /// Returns the error's code; see [`Error::code`].
+    #[inline(always)]
+    pub const fn code(&self) -> i32 {
+        self.code
+    }
+
+    /// Returns the error's message as a C string.
+    #[inline(always)]
+    pub fn message(&self) -> &CStr {
+        &self.message
+    }
+}
+
+impl fmt::Display for FfiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (code {})", self.message.to_string_lossy(), self.code)
+    }
+}
+
+impl error::Error for FfiError {}
+
+impl From<&Error> for FfiError {
+    fn from(error_value: &Error) -> Self {
+        let message = error_value.to_string().replace('\0', " ");
+        Self {
+            code: error_value.code(),
+            // interior NUL bytes were replaced, so this never falls back
+            message: CString::new(message).unwrap_or_default(),
+        }
+    }
+}
+
+impl From<Error> for FfiError {
+    fn from(error_value: Error) -> Self {
+        Self::from(&error_value)
     }
 }
@@ -0,0 +1,204 @@
+use std::{fmt, mem::ManuallyDrop};
+
+use crate::{error::Error, runtime_array::ArrayCStyle, usage::MemoryUsage};
+
+/// Marks the end of the free list.
+const NO_SLOT: usize = usize::MAX;
+
+const WORD_BITS: usize = u64::BITS as usize;
+
+/// A slot holds either a live value or, while free, the index of the next
+/// free slot.
+union Node<T> {
+    value: ManuallyDrop<T>,
+    next_free: usize,
+}
+
+/// A fixed-capacity object pool handing out stable slot indices.
+///
+/// Free slots are chained into a list through the storage itself, so
+/// [`FreeListArray::alloc_slot`] and [`FreeListArray::free_slot`] are O(1)
+/// and need no memory besides the buffer and one occupancy bit per slot.
+/// Indices stay valid until the slot is freed and are then reused, most
+/// recently freed first.
+///
+/// # Example
+///
+/// ```rust ignore
+/// use runnarr::free_list::FreeListArray;
+///
+/// let mut particles = FreeListArray::with_capacity(4096).unwrap();
+/// let spark = particles.alloc_slot(Particle::spark()).unwrap();
+/// particles[spark].update(dt);
+/// particles.free_slot(spark);
+/// ```
+pub struct FreeListArray<T> {
+    nodes: ArrayCStyle<Node<T>>,
+    occupied: ArrayCStyle<u64>,
+    free_head: usize,
+    len: usize,
+}
+
+impl<T> FreeListArray<T> {
+    /// Creates a pool with room for `capacity` values.
+    #[track_caller]
+    pub fn with_capacity(capacity: usize) -> Result<Self, Error> {
+        let nodes = ArrayCStyle::<Node<T>>::new(capacity)?;
+        for index in 0..capacity {
+            let next_free = if index + 1 < capacity {
+                index + 1
+            } else {
+                NO_SLOT
+            };
+            unsafe { nodes.ptr_mut().add(index).write(Node { next_free }) };
+        }
+        Ok(Self {
+            nodes,
+            occupied: ArrayCStyle::zeroed(capacity.div_ceil(WORD_BITS))?,
+            free_head: if capacity == 0 { NO_SLOT } else { 0 },
+            len: 0,
+        })
+    }
+
+    /// Returns the number of occupied slots.
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if no slot is occupied.
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the total number of slots.
+    #[inline(always)]
+    pub fn capacity(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Returns the memory held by the pool; free slots count as slack and
+    /// the occupancy bits as used.
+    pub fn memory_usage(&self) -> MemoryUsage {
+        let bitmap = self.occupied.allocated_bytes();
+        MemoryUsage {
+            allocated_bytes: self.nodes.allocated_bytes() + bitmap,
+            used_bytes: self.len * self.nodes.element_size() + bitmap,
+        }
+    }
+
+    /// Returns `true` if `index` is a slot currently holding a value.
+    #[inline(always)]
+    pub fn is_allocated(&self, index: usize) -> bool {
+        index < self.capacity()
+            && self.occupied.as_slice()[index / WORD_BITS]
+                & (1 << (index % WORD_BITS))
+                != 0
+    }
+
+    fn set_allocated(&mut self, index: usize, allocated: bool) {
+        let bit = 1 << (index % WORD_BITS);
+        if allocated {
+            self.occupied.as_mut_slice()[index / WORD_BITS] |= bit;
+        } else {
+            self.occupied.as_mut_slice()[index / WORD_BITS] &= !bit;
+        }
+    }
+
+    /// Moves `value` into a free slot and returns its index.
+    ///
+    /// # Errors
+    ///
+    /// Gives `value` back if every slot is occupied.
+    pub fn alloc_slot(&mut self, value: T) -> Result<usize, T> {
+        let index = self.free_head;
+        if index == NO_SLOT {
+            return Err(value);
+        }
+        let node = &mut self.nodes.as_mut_slice()[index];
+        self.free_head = unsafe { node.next_free };
+        node.value = ManuallyDrop::new(value);
+        self.set_allocated(index, true);
+        self.len += 1;
+        Ok(index)
+    }
+
+    /// Takes the value out of slot `index` and puts the slot back on the
+    /// free list.
+    ///
+    /// Returns `None` if the slot was not allocated.
+    pub fn free_slot(&mut self, index: usize) -> Option<T> {
+        if !self.is_allocated(index) {
+            return None;
+        }
+        let node = &mut self.nodes.as_mut_slice()[index];
+        let value = unsafe { ManuallyDrop::take(&mut node.value) };
+        node.next_free = self.free_head;
+        self.free_head = index;
+        self.set_allocated(index, false);
+        self.len -= 1;
+        Some(value)
+    }
+
+    /// Gets a reference to the value in slot `index`, or `None` if the slot
+    /// is free.
+    #[inline(always)]
+    pub fn get(&self, index: usize) -> Option<&T> {
+        if !self.is_allocated(index) {
+            return None;
+        }
+        Some(unsafe { &self.nodes.as_slice()[index].value })
+    }
+
+    /// Gets a mutable reference to the value in slot `index`, or `None` if
+    /// the slot is free.
+    #[inline(always)]
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        if !self.is_allocated(index) {
+            return None;
+        }
+        Some(unsafe { &mut self.nodes.as_mut_slice()[index].value })
+    }
+
+    /// Returns an iterator over the occupied slots and their values.
+    pub fn iter(&self) -> impl Iterator<Item = (usize, &T)> {
+        (0..self.capacity())
+            .filter_map(|index| self.get(index).map(|value| (index, value)))
+    }
+}
+
+impl<T> Drop for FreeListArray<T> {
+    fn drop(&mut self) {
+        for index in 0..self.capacity() {
+            if self.is_allocated(index) {
+                unsafe {
+                    ManuallyDrop::drop(
+                        &mut self.nodes.as_mut_slice()[index].value,
+                    )
+                };
+            }
+        }
+    }
+}
+
+#[cfg(not(feature = "no-panic"))]
+impl<T> std::ops::Index<usize> for FreeListArray<T> {
+    type Output = T;
+    fn index(&self, index: usize) -> &Self::Output {
+        self.get(index).expect("Slot is not allocated")
+    }
+}
+
+#[cfg(not(feature = "no-panic"))]
+impl<T> std::ops::IndexMut<usize> for FreeListArray<T> {
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        self.get_mut(index).expect("Slot is not allocated")
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for FreeListArray<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
@@ -1,5 +1,61 @@
+pub mod aligned;
+pub mod allocator;
+pub mod arc_array;
+pub mod arena;
+pub mod atomic_array;
+pub mod bitmap_alloc;
+#[cfg(feature = "canary")]
+pub mod canary;
+pub mod cell_array;
+pub mod concurrent_set;
+pub mod containers;
+pub mod counter_array;
+pub mod cow_array;
+#[cfg(feature = "tokio")]
+pub mod cursor;
+pub mod double_buffer;
+pub mod epoch;
 pub mod error;
+pub mod free_list;
+pub mod matrix;
+#[cfg(unix)]
+pub mod mmap_array;
+#[cfg(feature = "nalgebra")]
+pub mod nalgebra_interop;
+#[cfg(feature = "ndarray")]
+pub mod ndarray_interop;
+#[cfg(feature = "npy")]
+pub mod npy;
+#[cfg(all(target_os = "linux", feature = "numa"))]
+pub mod numa;
+#[cfg(unix)]
+pub mod os;
+#[cfg(feature = "rayon")]
+pub mod par;
+pub mod pool;
+pub mod queues;
+pub mod reduce;
+pub mod refcell_array;
 pub mod runtime_array;
+pub mod scratch;
+pub mod secret;
+pub mod seqlock_array;
+#[cfg(feature = "shadow")]
+mod shadow;
+pub mod sharded_array;
+#[cfg(unix)]
+pub mod shared_array;
+pub mod slot_array;
+pub mod small_array;
+pub mod stack_alloc;
+#[cfg(feature = "stats")]
+pub mod stats;
+#[cfg(feature = "async")]
+pub mod stream;
+pub mod sync_array;
+pub mod typed_array;
+pub mod usage;
+pub mod versioned_array;
 
 #[cfg(test)]
 mod test;
@@ -0,0 +1,780 @@
+use std::{
+    fmt,
+    ops::{Add, Range},
+};
+
+use super::{
+    element_count, shape_mismatch, view::diagonal, ArrayView1D, ArrayView2D,
+    ArrayViewMut1D, ArrayViewMut2D,
+};
+use crate::{error::Error, runtime_array::ArrayCStyle};
+
+/// Side of the square tiles the transposes work in; 32 elements of up to 8
+/// bytes keep a tile's rows within one page and its columns within L1.
+const TRANSPOSE_BLOCK: usize = 32;
+
+/// The order in which a matrix's elements are stored.
+#[derive(Debug, Default, Clone, Copy, Hash, PartialEq, Eq)]
+pub enum Layout {
+    /// Rows one after another: element `(row, col)` is at
+    /// `row * ncols + col`, as in C.
+    #[default]
+    RowMajor,
+    /// Columns one after another: element `(row, col)` is at
+    /// `col * nrows + row`, as in Fortran, BLAS and LAPACK.
+    ColumnMajor,
+}
+
+impl Layout {
+    /// Returns the row and column strides of an `nrows` by `ncols` matrix.
+    #[inline(always)]
+    fn strides(self, (nrows, ncols): (usize, usize)) -> (usize, usize) {
+        match self {
+            Self::RowMajor => (ncols, 1),
+            Self::ColumnMajor => (1, nrows),
+        }
+    }
+}
+
+/// A two-dimensional array stored in one contiguous allocation, row-major
+/// unless created with [`Layout::ColumnMajor`].
+///
+/// In the default layout, element `(row, col)` lives at `row * ncols + col`
+/// of the underlying [`ArrayCStyle`], so every row is a plain slice and the
+/// whole matrix can be handed to code expecting a flat buffer. A
+/// column-major matrix can be handed to Fortran-style libraries the same
+/// way; indexing, views, rows and columns all follow its layout. As with
+/// the 1-D array, the elements are not dropped with the matrix.
+///
+/// # Example
+///
+/// ```rust ignore
+/// use runnarr::matrix::Array2D;
+///
+/// let mut grid = Array2D::<f32>::zeroed(480, 640)?;
+/// grid[(10, 20)] = 1.0;
+/// for value in grid.row_mut(10).unwrap().iter_mut() {
+///     *value *= 0.5;
+/// }
+/// ```
+pub struct Array2D<T> {
+    data: ArrayCStyle<T>,
+    nrows: usize,
+    ncols: usize,
+    layout: Layout,
+}
+
+impl<T> Array2D<T> {
+    /// Creates an uninitialized `nrows` by `ncols` matrix.
+    ///
+    /// Like [`ArrayCStyle::new`], the elements must be written before they
+    /// are read.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the element count overflows or the allocation
+    /// fails.
+    #[track_caller]
+    pub fn new(nrows: usize, ncols: usize) -> Result<Self, Error> {
+        Self::new_with_layout(nrows, ncols, Layout::RowMajor)
+    }
+
+    /// Creates an uninitialized `nrows` by `ncols` matrix stored in
+    /// `layout`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the element count overflows or the allocation
+    /// fails.
+    #[track_caller]
+    pub fn new_with_layout(
+        nrows: usize,
+        ncols: usize,
+        layout: Layout,
+    ) -> Result<Self, Error> {
+        let len = element_count(&[nrows, ncols])?;
+        Ok(Self {
+            data: ArrayCStyle::new(len)?,
+            nrows,
+            ncols,
+            layout,
+        })
+    }
+
+    /// Creates an `nrows` by `ncols` matrix with every byte set to zero.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the element count overflows or the allocation
+    /// fails.
+    #[track_caller]
+    pub fn zeroed(nrows: usize, ncols: usize) -> Result<Self, Error> {
+        Self::zeroed_with_layout(nrows, ncols, Layout::RowMajor)
+    }
+
+    /// Creates an `nrows` by `ncols` matrix stored in `layout`, with every
+    /// byte set to zero.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the element count overflows or the allocation
+    /// fails.
+    #[track_caller]
+    pub fn zeroed_with_layout(
+        nrows: usize,
+        ncols: usize,
+        layout: Layout,
+    ) -> Result<Self, Error> {
+        let len = element_count(&[nrows, ncols])?;
+        Ok(Self {
+            data: ArrayCStyle::zeroed(len)?,
+            nrows,
+            ncols,
+            layout,
+        })
+    }
+
+    /// Creates an `nrows` by `ncols` matrix holding clones of `value`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the element count overflows or the allocation
+    /// fails.
+    #[track_caller]
+    pub fn filled(nrows: usize, ncols: usize, value: T) -> Result<Self, Error>
+    where
+        T: Clone,
+    {
+        let matrix = Self::new(nrows, ncols)?;
+        for index in 0..matrix.len() {
+            unsafe { matrix.data.ptr_mut().add(index).write(value.clone()) };
+        }
+        Ok(matrix)
+    }
+
+    /// Interprets a row-major array as an `nrows` by `ncols` matrix, without
+    /// copying.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the array length is not `nrows * ncols`.
+    pub fn from_array(
+        data: ArrayCStyle<T>,
+        nrows: usize,
+        ncols: usize,
+    ) -> Result<Self, Error> {
+        Self::from_array_with_layout(data, nrows, ncols, Layout::RowMajor)
+    }
+
+    /// Interprets an array stored in `layout` as an `nrows` by `ncols`
+    /// matrix, without copying.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the array length is not `nrows * ncols`.
+    ///
+    /// # Example
+    ///
+    /// ```rust ignore
+    /// // a matrix filled in by a Fortran routine, used in place
+    /// let a =
+    ///     Array2D::from_array_with_layout(buffer, m, n, Layout::ColumnMajor)?;
+    /// let pivot = a[(0, 0)];
+    /// ```
+    pub fn from_array_with_layout(
+        data: ArrayCStyle<T>,
+        nrows: usize,
+        ncols: usize,
+        layout: Layout,
+    ) -> Result<Self, Error> {
+        if element_count(&[nrows, ncols])? != data.len() {
+            return Err(shape_mismatch(&[nrows, ncols], data.len()));
+        }
+        Ok(Self {
+            data,
+            nrows,
+            ncols,
+            layout,
+        })
+    }
+
+    /// Returns the number of rows.
+    #[inline(always)]
+    pub fn nrows(&self) -> usize {
+        self.nrows
+    }
+
+    /// Returns the number of columns.
+    #[inline(always)]
+    pub fn ncols(&self) -> usize {
+        self.ncols
+    }
+
+    /// Returns `(nrows, ncols)`.
+    #[inline(always)]
+    pub fn shape(&self) -> (usize, usize) {
+        (self.nrows, self.ncols)
+    }
+
+    /// Returns the order the elements are stored in.
+    #[inline(always)]
+    pub fn layout(&self) -> Layout {
+        self.layout
+    }
+
+    /// Returns the distance, in elements, between neighboring rows and
+    /// between neighboring columns: `(ncols, 1)` for a row-major matrix and
+    /// `(1, nrows)` for a column-major one.
+    #[inline(always)]
+    pub fn strides(&self) -> (usize, usize) {
+        self.layout.strides(self.shape())
+    }
+
+    /// Returns the number of elements.
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Returns `true` if the matrix has no elements.
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Returns the position of element `(row, col)` in
+    /// [`Array2D::as_slice`], following the layout: `row * ncols + col` for
+    /// a row-major matrix, `col * nrows + row` for a column-major one.
+    ///
+    /// Out-of-bounds positions are caught by a debug assertion only; use
+    /// [`Array2D::get`] to check them in release builds as well.
+    ///
+    /// # Example
+    ///
+    /// ```rust ignore
+    /// let flat = image.as_slice();
+    /// let pixel = flat[image.linear_index(y, x)];
+    /// ```
+    #[inline(always)]
+    pub fn linear_index(&self, row: usize, col: usize) -> usize {
+        debug_assert!(
+            row < self.nrows && col < self.ncols,
+            "index ({row}, {col}) out of bounds for a {}x{} matrix",
+            self.nrows,
+            self.ncols
+        );
+        let (row_stride, col_stride) = self.strides();
+        row * row_stride + col * col_stride
+    }
+
+    /// Returns the `(row, col)` of the element at position `index` in
+    /// [`Array2D::as_slice`]; the inverse of [`Array2D::linear_index`].
+    ///
+    /// Out-of-bounds positions are caught by a debug assertion only.
+    #[inline(always)]
+    pub fn from_linear(&self, index: usize) -> (usize, usize) {
+        debug_assert!(
+            index < self.len(),
+            "linear index {index} out of bounds for a {}x{} matrix",
+            self.nrows,
+            self.ncols
+        );
+        match self.layout {
+            Layout::RowMajor => (index / self.ncols, index % self.ncols),
+            Layout::ColumnMajor => (index % self.nrows, index / self.nrows),
+        }
+    }
+
+    #[inline(always)]
+    fn offset(&self, row: usize, col: usize) -> Option<usize> {
+        (row < self.nrows && col < self.ncols)
+            .then(|| self.linear_index(row, col))
+    }
+
+    /// Returns the element at `(row, col)`, or `None` if it is out of
+    /// bounds.
+    #[inline(always)]
+    pub fn get(&self, row: usize, col: usize) -> Option<&T> {
+        self.data.get(self.offset(row, col)?)
+    }
+
+    /// Returns the element at `(row, col)` mutably, or `None` if it is out
+    /// of bounds.
+    #[inline(always)]
+    pub fn get_mut(&mut self, row: usize, col: usize) -> Option<&mut T> {
+        let offset = self.offset(row, col)?;
+        self.data.get_mut(offset)
+    }
+
+    fn out_of_bounds(&self, row: usize, col: usize) -> Error {
+        if row >= self.nrows {
+            Error::IndexOutOfBounds {
+                index: row,
+                len: self.nrows,
+            }
+        } else {
+            Error::IndexOutOfBounds {
+                index: col,
+                len: self.ncols,
+            }
+        }
+    }
+
+    /// Returns the element at `(row, col)`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::IndexOutOfBounds`] carrying the row and the number
+    /// of rows if `row` is out of bounds, or else the column and the number
+    /// of columns.
+    #[inline(always)]
+    pub fn at(&self, row: usize, col: usize) -> Result<&T, Error> {
+        match self.offset(row, col) {
+            Some(offset) => self.data.at(offset),
+            None => Err(self.out_of_bounds(row, col)),
+        }
+    }
+
+    /// Returns the element at `(row, col)` mutably.
+    ///
+    /// # Errors
+    ///
+    /// See [`Array2D::at`].
+    #[inline(always)]
+    pub fn at_mut(&mut self, row: usize, col: usize) -> Result<&mut T, Error> {
+        match self.offset(row, col) {
+            Some(offset) => self.data.at_mut(offset),
+            None => Err(self.out_of_bounds(row, col)),
+        }
+    }
+
+    /// Returns row `row` as a view, or `None` if it is out of bounds.
+    ///
+    /// The view is contiguous in a row-major matrix, where
+    /// [`ArrayView1D::as_slice`] turns it into a slice, and strided in a
+    /// column-major one.
+    #[inline(always)]
+    pub fn row(&self, row: usize) -> Option<ArrayView1D<'_, T>> {
+        self.as_view().row(row)
+    }
+
+    /// Returns row `row` as a mutable view, or `None` if it is out of
+    /// bounds.
+    #[inline(always)]
+    pub fn row_mut(&mut self, row: usize) -> Option<ArrayViewMut1D<'_, T>> {
+        let (ncols, (row_stride, col_stride)) = (self.ncols, self.strides());
+        let ptr = self.data.as_mut_slice().as_mut_ptr();
+        (row < self.nrows).then(|| unsafe {
+            ArrayViewMut1D::from_raw(
+                ptr.add(row * row_stride),
+                ncols,
+                col_stride,
+            )
+        })
+    }
+
+    /// Returns column `col` as a strided view, or `None` if it is out of
+    /// bounds.
+    #[inline(always)]
+    pub fn col(&self, col: usize) -> Option<ArrayView1D<'_, T>> {
+        self.as_view().col(col)
+    }
+
+    /// Returns column `col` as a mutable strided view, or `None` if it is
+    /// out of bounds.
+    #[inline(always)]
+    pub fn col_mut(&mut self, col: usize) -> Option<ArrayViewMut1D<'_, T>> {
+        let (nrows, (row_stride, col_stride)) = (self.nrows, self.strides());
+        let ptr = self.data.as_mut_slice().as_mut_ptr();
+        (col < self.ncols).then(|| unsafe {
+            ArrayViewMut1D::from_raw(
+                ptr.add(col * col_stride),
+                nrows,
+                row_stride,
+            )
+        })
+    }
+
+    /// Returns an iterator over the rows, as views; see [`Array2D::row`].
+    pub fn rows(
+        &self,
+    ) -> impl DoubleEndedIterator<Item = ArrayView1D<'_, T>> + ExactSizeIterator
+    {
+        let (ncols, (row_stride, col_stride)) = (self.ncols, self.strides());
+        let ptr = self.data.ptr();
+        (0..self.nrows).map(move |row| unsafe {
+            ArrayView1D::from_raw(ptr.add(row * row_stride), ncols, col_stride)
+        })
+    }
+
+    /// Returns an iterator over the rows, as mutable views.
+    ///
+    /// # Example
+    ///
+    /// ```rust ignore
+    /// for mut row in features.rows_mut() {
+    ///     let norm = row.as_view().iter().map(|x| x * x).sum::<f32>().sqrt();
+    ///     row.iter_mut().for_each(|x| *x /= norm);
+    /// }
+    /// ```
+    pub fn rows_mut(
+        &mut self,
+    ) -> impl DoubleEndedIterator<Item = ArrayViewMut1D<'_, T>> + ExactSizeIterator
+    {
+        let (ncols, (row_stride, col_stride)) = (self.ncols, self.strides());
+        let ptr = self.data.as_mut_slice().as_mut_ptr();
+        // distinct rows never share an element
+        (0..self.nrows).map(move |row| unsafe {
+            ArrayViewMut1D::from_raw(
+                ptr.add(row * row_stride),
+                ncols,
+                col_stride,
+            )
+        })
+    }
+
+    /// Returns an iterator over the columns, as strided views.
+    ///
+    /// # Example
+    ///
+    /// ```rust ignore
+    /// let means: Vec<f64> = samples
+    ///     .cols()
+    ///     .map(|col| col.iter().sum::<f64>() / col.len() as f64)
+    ///     .collect();
+    /// ```
+    pub fn cols(
+        &self,
+    ) -> impl DoubleEndedIterator<Item = ArrayView1D<'_, T>> + ExactSizeIterator
+    {
+        let (nrows, (row_stride, col_stride)) = (self.nrows, self.strides());
+        let ptr = self.data.ptr();
+        (0..self.ncols).map(move |col| unsafe {
+            ArrayView1D::from_raw(ptr.add(col * col_stride), nrows, row_stride)
+        })
+    }
+
+    /// Returns an iterator over the columns, as mutable strided views.
+    pub fn cols_mut(
+        &mut self,
+    ) -> impl DoubleEndedIterator<Item = ArrayViewMut1D<'_, T>> + ExactSizeIterator
+    {
+        let (nrows, (row_stride, col_stride)) = (self.nrows, self.strides());
+        let ptr = self.data.as_mut_slice().as_mut_ptr();
+        // distinct columns never share an element
+        (0..self.ncols).map(move |col| unsafe {
+            ArrayViewMut1D::from_raw(
+                ptr.add(col * col_stride),
+                nrows,
+                row_stride,
+            )
+        })
+    }
+
+    /// Returns the main diagonal as a strided view.
+    #[inline(always)]
+    pub fn diag(&self) -> ArrayView1D<'_, T> {
+        self.as_view().diag()
+    }
+
+    /// Returns diagonal `offset` as a strided view; see
+    /// [`ArrayView2D::diag_offset`].
+    #[inline(always)]
+    pub fn diag_offset(&self, offset: isize) -> ArrayView1D<'_, T> {
+        self.as_view().diag_offset(offset)
+    }
+
+    /// Returns the main diagonal as a mutable strided view.
+    ///
+    /// # Example
+    ///
+    /// ```rust ignore
+    /// // Tikhonov regularization: A + λI
+    /// for value in gram.diag_mut().iter_mut() {
+    ///     *value += lambda;
+    /// }
+    /// ```
+    #[inline(always)]
+    pub fn diag_mut(&mut self) -> ArrayViewMut1D<'_, T> {
+        self.diag_offset_mut(0)
+    }
+
+    /// Returns diagonal `offset` as a mutable strided view; see
+    /// [`ArrayView2D::diag_offset`].
+    pub fn diag_offset_mut(&mut self, offset: isize) -> ArrayViewMut1D<'_, T> {
+        let (start, len, stride) =
+            diagonal(self.shape(), self.strides(), offset);
+        let ptr = self.data.as_mut_slice().as_mut_ptr();
+        unsafe { ArrayViewMut1D::from_raw(ptr.add(start), len, stride) }
+    }
+
+    /// Returns the sum of the main diagonal; see [`ArrayView2D::trace`].
+    #[inline(always)]
+    pub fn trace(&self) -> T
+    where
+        T: Copy + Default + Add<Output = T>,
+    {
+        self.as_view().trace()
+    }
+
+    /// Returns a view of the whole matrix.
+    #[inline(always)]
+    pub fn as_view(&self) -> ArrayView2D<'_, T> {
+        unsafe {
+            ArrayView2D::from_raw(self.data.ptr(), self.shape(), self.strides())
+        }
+    }
+
+    /// Returns a mutable view of the whole matrix.
+    #[inline(always)]
+    pub fn as_view_mut(&mut self) -> ArrayViewMut2D<'_, T> {
+        let (shape, strides) = (self.shape(), self.strides());
+        let ptr = self.data.as_mut_slice().as_mut_ptr();
+        unsafe { ArrayViewMut2D::from_raw(ptr, shape, strides) }
+    }
+
+    /// Returns the view of rows `rows` and columns `cols`, without copying,
+    /// or `None` if either range is out of bounds.
+    ///
+    /// The view keeps the matrix's strides, so in a row-major matrix it is
+    /// contiguous only when it spans whole rows.
+    ///
+    /// # Example
+    ///
+    /// ```rust ignore
+    /// for (r, c) in tile_origins {
+    ///     let tile = matrix.view(r..r + 16, c..c + 16).unwrap();
+    ///     process(tile);
+    /// }
+    /// ```
+    #[inline(always)]
+    pub fn view(
+        &self,
+        rows: Range<usize>,
+        cols: Range<usize>,
+    ) -> Option<ArrayView2D<'_, T>> {
+        self.as_view().view(rows, cols)
+    }
+
+    /// Returns the mutable view of rows `rows` and columns `cols`, without
+    /// copying, or `None` if either range is out of bounds.
+    pub fn view_mut(
+        &mut self,
+        rows: Range<usize>,
+        cols: Range<usize>,
+    ) -> Option<ArrayViewMut2D<'_, T>> {
+        self.as_view_mut().into_view(rows, cols)
+    }
+
+    /// Returns the transpose as a view, by swapping the strides; no element
+    /// moves.
+    ///
+    /// The transpose of a row-major matrix is a column-major view over the
+    /// same storage, which is what column-major libraries expect from a
+    /// row-major buffer.
+    #[inline(always)]
+    pub fn t(&self) -> ArrayView2D<'_, T> {
+        self.as_view().t()
+    }
+
+    /// Transposes a square matrix in place, swapping elements across the
+    /// diagonal block by block; the layout is kept.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error, leaving the matrix untouched, if it is not square.
+    pub fn transpose_square(&mut self) -> Result<(), Error> {
+        let n = self.nrows;
+        if n != self.ncols {
+            return Err(Error::Other(format!(
+                "Cannot transpose a {}x{} matrix in place",
+                self.nrows, self.ncols
+            )));
+        }
+        let elements = self.data.as_mut_slice();
+        for block_row in (0..n).step_by(TRANSPOSE_BLOCK) {
+            for block_col in (block_row..n).step_by(TRANSPOSE_BLOCK) {
+                let rows = block_row..(block_row + TRANSPOSE_BLOCK).min(n);
+                for row in rows {
+                    let cols = block_col.max(row + 1)
+                        ..(block_col + TRANSPOSE_BLOCK).min(n);
+                    for col in cols {
+                        elements.swap(row * n + col, col * n + row);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns all elements in storage order, see [`Array2D::layout`].
+    #[inline(always)]
+    pub fn as_slice(&self) -> &[T] {
+        self.data.as_slice()
+    }
+
+    /// Returns all elements in storage order, mutably.
+    #[inline(always)]
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        self.data.as_mut_slice()
+    }
+
+    /// Returns the underlying array, in storage order.
+    #[inline(always)]
+    pub fn as_array(&self) -> &ArrayCStyle<T> {
+        &self.data
+    }
+
+    /// Unwraps the underlying array, in storage order.
+    pub fn into_array(self) -> ArrayCStyle<T> {
+        self.data
+    }
+}
+
+impl<T: Clone> Array2D<T> {
+    /// Clones element `(row, col)` of the matrix into `target` at
+    /// `row * row_step + col * col_step`, walking the matrix in square
+    /// tiles so that both the reads and the writes stay within a few cache
+    /// lines at a time.
+    fn copy_tiled(&self, target: &Self, (row_step, col_step): (usize, usize)) {
+        let (nrows, ncols) = self.shape();
+        let (row_stride, col_stride) = self.strides();
+        let source = self.data.as_slice();
+        let target: *mut T = target.data.ptr_mut();
+        for block_row in (0..nrows).step_by(TRANSPOSE_BLOCK) {
+            for block_col in (0..ncols).step_by(TRANSPOSE_BLOCK) {
+                for row in block_row..(block_row + TRANSPOSE_BLOCK).min(nrows) {
+                    for col in
+                        block_col..(block_col + TRANSPOSE_BLOCK).min(ncols)
+                    {
+                        let value =
+                            source[row * row_stride + col * col_stride].clone();
+                        let offset = row * row_step + col * col_step;
+                        unsafe { target.add(offset).write(value) };
+                    }
+                }
+            }
+        }
+    }
+
+    /// Copies the transpose into a new matrix of the same layout, any shape
+    /// allowed.
+    ///
+    /// The copy walks the matrix in square tiles so that both the reads and
+    /// the writes stay within a few cache lines at a time.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the new matrix cannot be allocated.
+    #[track_caller]
+    pub fn transposed(&self) -> Result<Self, Error> {
+        let transposed =
+            Self::new_with_layout(self.ncols, self.nrows, self.layout)?;
+        let (row_stride, col_stride) = transposed.strides();
+        self.copy_tiled(&transposed, (col_stride, row_stride));
+        Ok(transposed)
+    }
+
+    /// Copies the matrix into a new one stored in `layout`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the new matrix cannot be allocated.
+    ///
+    /// # Example
+    ///
+    /// ```rust ignore
+    /// let fortran = matrix.to_layout(Layout::ColumnMajor)?;
+    /// lapack_routine(fortran.as_slice(), fortran.nrows());
+    /// ```
+    #[track_caller]
+    pub fn to_layout(&self, layout: Layout) -> Result<Self, Error> {
+        if layout == self.layout {
+            return self.try_clone();
+        }
+        let copy = Self::new_with_layout(self.nrows, self.ncols, layout)?;
+        self.copy_tiled(&copy, copy.strides());
+        Ok(copy)
+    }
+
+    /// Returns the matrix itself if it is row-major, or else a row-major
+    /// copy kept in `copy`, for kernels that want contiguous rows.
+    pub(crate) fn row_major<'a>(
+        &'a self,
+        copy: &'a mut Option<Self>,
+    ) -> Result<&'a Self, Error> {
+        match self.layout {
+            Layout::RowMajor => Ok(self),
+            Layout::ColumnMajor => {
+                Ok(copy.insert(self.to_layout(Layout::RowMajor)?))
+            }
+        }
+    }
+
+    /// Creates a deep copy of the matrix.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the copy cannot be allocated.
+    #[track_caller]
+    pub fn try_clone(&self) -> Result<Self, Error> {
+        Ok(Self {
+            data: self.data.try_clone()?,
+            nrows: self.nrows,
+            ncols: self.ncols,
+            layout: self.layout,
+        })
+    }
+}
+
+#[cfg(not(feature = "no-panic"))]
+impl<T: Clone> Clone for Array2D<T> {
+    #[track_caller]
+    fn clone(&self) -> Self {
+        self.try_clone().expect("Failed to allocate matrix clone")
+    }
+}
+
+#[cfg(not(feature = "no-panic"))]
+impl<T> Array2D<T> {
+    #[track_caller]
+    fn checked_offset(&self, row: usize, col: usize) -> usize {
+        match self.offset(row, col) {
+            Some(offset) => offset,
+            None => panic!(
+                "Index ({row}, {col}) out of bounds for a {}x{} matrix",
+                self.nrows, self.ncols
+            ),
+        }
+    }
+}
+
+/// Indexes by `(row, col)` in either layout.
+///
+/// # Panics
+///
+/// Panics, naming the index and the shape, if `(row, col)` is out of
+/// bounds.
+#[cfg(not(feature = "no-panic"))]
+impl<T> std::ops::Index<(usize, usize)> for Array2D<T> {
+    type Output = T;
+    #[track_caller]
+    fn index(&self, (row, col): (usize, usize)) -> &Self::Output {
+        &self.as_slice()[self.checked_offset(row, col)]
+    }
+}
+
+#[cfg(not(feature = "no-panic"))]
+impl<T> std::ops::IndexMut<(usize, usize)> for Array2D<T> {
+    #[track_caller]
+    fn index_mut(&mut self, (row, col): (usize, usize)) -> &mut Self::Output {
+        let offset = self.checked_offset(row, col);
+        &mut self.as_mut_slice()[offset]
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Array2D<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.as_view().fmt(f)
+    }
+}
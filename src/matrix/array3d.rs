@@ -0,0 +1,400 @@
+use std::{fmt, ops::Range};
+
+use super::{element_count, shape_mismatch, ArrayView2D, ArrayViewMut2D};
+use crate::{error::Error, runtime_array::ArrayCStyle};
+
+/// A three-dimensional array of shape `(nx, ny, nz)` stored in one
+/// contiguous allocation, `z` varying fastest.
+///
+/// Element `(x, y, z)` lives at `(x * ny + y) * nz + z`, so every `x`-plane
+/// and every slab of consecutive `x`-planes is contiguous. Planes along any
+/// axis are available as strided [`ArrayView2D`]s. As with the 1-D array,
+/// the elements are not dropped with the volume.
+///
+/// # Example
+///
+/// ```rust ignore
+/// use runnarr::matrix::Array3D;
+///
+/// let mut voxels = Array3D::<u8>::zeroed(256, 256, 128)?;
+/// voxels[(10, 20, 30)] = 255;
+/// let mid_section = voxels.plane_z(64).unwrap();
+/// render(&mid_section);
+/// ```
+pub struct Array3D<T> {
+    data: ArrayCStyle<T>,
+    shape: (usize, usize, usize),
+}
+
+impl<T> Array3D<T> {
+    /// Creates an uninitialized volume of shape `(nx, ny, nz)`.
+    ///
+    /// Like [`ArrayCStyle::new`], the elements must be written before they
+    /// are read.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the element count overflows or the allocation
+    /// fails.
+    #[track_caller]
+    pub fn new(nx: usize, ny: usize, nz: usize) -> Result<Self, Error> {
+        let len = element_count(&[nx, ny, nz])?;
+        Ok(Self {
+            data: ArrayCStyle::new(len)?,
+            shape: (nx, ny, nz),
+        })
+    }
+
+    /// Creates a volume of shape `(nx, ny, nz)` with every byte set to zero.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the element count overflows or the allocation
+    /// fails.
+    #[track_caller]
+    pub fn zeroed(nx: usize, ny: usize, nz: usize) -> Result<Self, Error> {
+        let len = element_count(&[nx, ny, nz])?;
+        Ok(Self {
+            data: ArrayCStyle::zeroed(len)?,
+            shape: (nx, ny, nz),
+        })
+    }
+
+    /// Interprets an array laid out as described on [`Array3D`] as a volume
+    /// of shape `(nx, ny, nz)`, without copying.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the array length is not `nx * ny * nz`.
+    pub fn from_array(
+        data: ArrayCStyle<T>,
+        (nx, ny, nz): (usize, usize, usize),
+    ) -> Result<Self, Error> {
+        if element_count(&[nx, ny, nz])? != data.len() {
+            return Err(shape_mismatch(&[nx, ny, nz], data.len()));
+        }
+        Ok(Self {
+            data,
+            shape: (nx, ny, nz),
+        })
+    }
+
+    /// Returns `(nx, ny, nz)`.
+    #[inline(always)]
+    pub fn shape(&self) -> (usize, usize, usize) {
+        self.shape
+    }
+
+    /// Returns the number of elements.
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Returns `true` if the volume has no elements.
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Returns the element at `(x, y, z)`, or `None` if it is out of bounds.
+    #[inline(always)]
+    pub fn get(&self, x: usize, y: usize, z: usize) -> Option<&T> {
+        self.data.get(offset(self.shape, (x, y, z))?)
+    }
+
+    /// Returns the element at `(x, y, z)` mutably, or `None` if it is out of
+    /// bounds.
+    #[inline(always)]
+    pub fn get_mut(&mut self, x: usize, y: usize, z: usize) -> Option<&mut T> {
+        self.data.get_mut(offset(self.shape, (x, y, z))?)
+    }
+
+    /// Returns the element at `(x, y, z)`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::IndexOutOfBounds`] carrying the first out-of-bounds
+    /// coordinate and the extent of its axis.
+    pub fn at(&self, x: usize, y: usize, z: usize) -> Result<&T, Error> {
+        match offset(self.shape, (x, y, z)) {
+            Some(offset) => self.data.at(offset),
+            None => Err(out_of_bounds(self.shape, (x, y, z))),
+        }
+    }
+
+    /// Returns the element at `(x, y, z)` mutably.
+    ///
+    /// # Errors
+    ///
+    /// See [`Array3D::at`].
+    pub fn at_mut(
+        &mut self,
+        x: usize,
+        y: usize,
+        z: usize,
+    ) -> Result<&mut T, Error> {
+        match offset(self.shape, (x, y, z)) {
+            Some(offset) => self.data.at_mut(offset),
+            None => Err(out_of_bounds(self.shape, (x, y, z))),
+        }
+    }
+
+    /// Returns the `(y, z)` plane at `x`, or `None` if `x` is out of bounds.
+    pub fn plane_x(&self, x: usize) -> Option<ArrayView2D<'_, T>> {
+        let (nx, ny, nz) = self.shape;
+        (x < nx).then(|| unsafe {
+            ArrayView2D::from_raw(
+                self.data.ptr().add(x * ny * nz),
+                (ny, nz),
+                (nz, 1),
+            )
+        })
+    }
+
+    /// Returns the `(x, z)` plane at `y`, or `None` if `y` is out of bounds.
+    pub fn plane_y(&self, y: usize) -> Option<ArrayView2D<'_, T>> {
+        let (nx, ny, nz) = self.shape;
+        (y < ny).then(|| unsafe {
+            ArrayView2D::from_raw(
+                self.data.ptr().add(y * nz),
+                (nx, nz),
+                (ny * nz, 1),
+            )
+        })
+    }
+
+    /// Returns the `(x, y)` plane at `z`, or `None` if `z` is out of bounds.
+    pub fn plane_z(&self, z: usize) -> Option<ArrayView2D<'_, T>> {
+        let (nx, ny, nz) = self.shape;
+        (z < nz).then(|| unsafe {
+            ArrayView2D::from_raw(
+                self.data.ptr().add(z),
+                (nx, ny),
+                (ny * nz, nz),
+            )
+        })
+    }
+
+    /// Returns the `(y, z)` plane at `x` mutably, or `None` if `x` is out of
+    /// bounds.
+    pub fn plane_x_mut(&mut self, x: usize) -> Option<ArrayViewMut2D<'_, T>> {
+        let (nx, ny, nz) = self.shape;
+        let ptr = self.data.as_mut_slice().as_mut_ptr();
+        (x < nx).then(|| unsafe {
+            ArrayViewMut2D::from_raw(ptr.add(x * ny * nz), (ny, nz), (nz, 1))
+        })
+    }
+
+    /// Returns the `(x, z)` plane at `y` mutably, or `None` if `y` is out of
+    /// bounds.
+    pub fn plane_y_mut(&mut self, y: usize) -> Option<ArrayViewMut2D<'_, T>> {
+        let (nx, ny, nz) = self.shape;
+        let ptr = self.data.as_mut_slice().as_mut_ptr();
+        (y < ny).then(|| unsafe {
+            ArrayViewMut2D::from_raw(ptr.add(y * nz), (nx, nz), (ny * nz, 1))
+        })
+    }
+
+    /// Returns the `(x, y)` plane at `z` mutably, or `None` if `z` is out of
+    /// bounds.
+    pub fn plane_z_mut(&mut self, z: usize) -> Option<ArrayViewMut2D<'_, T>> {
+        let (nx, ny, nz) = self.shape;
+        let ptr = self.data.as_mut_slice().as_mut_ptr();
+        (z < nz).then(|| unsafe {
+            ArrayViewMut2D::from_raw(ptr.add(z), (nx, ny), (ny * nz, nz))
+        })
+    }
+
+    /// Returns the `x`-planes in `xs` as a sub-volume, or `None` if the range
+    /// is out of bounds.
+    pub fn slab(&self, xs: Range<usize>) -> Option<Array3DView<'_, T>> {
+        let (nx, ny, nz) = self.shape;
+        if xs.start > xs.end || xs.end > nx {
+            return None;
+        }
+        Some(Array3DView {
+            data: &self.data.as_slice()[xs.start * ny * nz..xs.end * ny * nz],
+            shape: (xs.len(), ny, nz),
+        })
+    }
+
+    /// Returns the `x`-planes in `xs` as a mutable sub-volume, or `None` if
+    /// the range is out of bounds.
+    pub fn slab_mut(
+        &mut self,
+        xs: Range<usize>,
+    ) -> Option<Array3DViewMut<'_, T>> {
+        let (nx, ny, nz) = self.shape;
+        if xs.start > xs.end || xs.end > nx {
+            return None;
+        }
+        Some(Array3DViewMut {
+            data: &mut self.data.as_mut_slice()
+                [xs.start * ny * nz..xs.end * ny * nz],
+            shape: (xs.len(), ny, nz),
+        })
+    }
+
+    /// Returns all elements in storage order.
+    #[inline(always)]
+    pub fn as_slice(&self) -> &[T] {
+        self.data.as_slice()
+    }
+
+    /// Returns all elements in storage order, mutably.
+    #[inline(always)]
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        self.data.as_mut_slice()
+    }
+
+    /// Unwraps the underlying array.
+    pub fn into_array(self) -> ArrayCStyle<T> {
+        self.data
+    }
+}
+
+#[inline(always)]
+fn offset(
+    (nx, ny, nz): (usize, usize, usize),
+    (x, y, z): (usize, usize, usize),
+) -> Option<usize> {
+    (x < nx && y < ny && z < nz).then(|| (x * ny + y) * nz + z)
+}
+
+fn out_of_bounds(
+    (nx, ny, nz): (usize, usize, usize),
+    (x, y, z): (usize, usize, usize),
+) -> Error {
+    let (index, len) = if x >= nx {
+        (x, nx)
+    } else if y >= ny {
+        (y, ny)
+    } else {
+        (z, nz)
+    };
+    Error::IndexOutOfBounds { index, len }
+}
+
+#[cfg(not(feature = "no-panic"))]
+impl<T> std::ops::Index<(usize, usize, usize)> for Array3D<T> {
+    type Output = T;
+    #[track_caller]
+    fn index(&self, (x, y, z): (usize, usize, usize)) -> &Self::Output {
+        self.get(x, y, z).expect("Index out of bounds")
+    }
+}
+
+#[cfg(not(feature = "no-panic"))]
+impl<T> std::ops::IndexMut<(usize, usize, usize)> for Array3D<T> {
+    #[track_caller]
+    fn index_mut(
+        &mut self,
+        (x, y, z): (usize, usize, usize),
+    ) -> &mut Self::Output {
+        self.get_mut(x, y, z).expect("Index out of bounds")
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Array3D<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let planes = (0..self.shape.0).filter_map(|x| self.plane_x(x));
+        f.debug_list().entries(planes).finish()
+    }
+}
+
+/// A borrowed slab of consecutive `x`-planes of an [`Array3D`].
+pub struct Array3DView<'a, T> {
+    data: &'a [T],
+    shape: (usize, usize, usize),
+}
+
+impl<'a, T> Array3DView<'a, T> {
+    /// Returns `(nx, ny, nz)` of the slab.
+    #[inline(always)]
+    pub fn shape(&self) -> (usize, usize, usize) {
+        self.shape
+    }
+
+    /// Returns the element at `(x, y, z)`, relative to the slab, or `None`
+    /// if it is out of bounds.
+    #[inline(always)]
+    pub fn get(&self, x: usize, y: usize, z: usize) -> Option<&'a T> {
+        self.data.get(offset(self.shape, (x, y, z))?)
+    }
+
+    /// Returns the slab's elements in storage order.
+    #[inline(always)]
+    pub fn as_slice(&self) -> &'a [T] {
+        self.data
+    }
+}
+
+#[cfg(not(feature = "no-panic"))]
+impl<T> std::ops::Index<(usize, usize, usize)> for Array3DView<'_, T> {
+    type Output = T;
+    #[track_caller]
+    fn index(&self, (x, y, z): (usize, usize, usize)) -> &Self::Output {
+        self.get(x, y, z).expect("Index out of bounds")
+    }
+}
+
+/// A mutable slab of consecutive `x`-planes of an [`Array3D`].
+pub struct Array3DViewMut<'a, T> {
+    data: &'a mut [T],
+    shape: (usize, usize, usize),
+}
+
+impl<T> Array3DViewMut<'_, T> {
+    /// Returns `(nx, ny, nz)` of the slab.
+    #[inline(always)]
+    pub fn shape(&self) -> (usize, usize, usize) {
+        self.shape
+    }
+
+    /// Returns the element at `(x, y, z)`, relative to the slab, or `None`
+    /// if it is out of bounds.
+    #[inline(always)]
+    pub fn get(&self, x: usize, y: usize, z: usize) -> Option<&T> {
+        self.data.get(offset(self.shape, (x, y, z))?)
+    }
+
+    /// Returns the element at `(x, y, z)`, relative to the slab, mutably, or
+    /// `None` if it is out of bounds.
+    #[inline(always)]
+    pub fn get_mut(&mut self, x: usize, y: usize, z: usize) -> Option<&mut T> {
+        self.data.get_mut(offset(self.shape, (x, y, z))?)
+    }
+
+    /// Returns the slab's elements in storage order.
+    #[inline(always)]
+    pub fn as_slice(&self) -> &[T] {
+        self.data
+    }
+
+    /// Returns the slab's elements in storage order, mutably.
+    #[inline(always)]
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        self.data
+    }
+}
+
+#[cfg(not(feature = "no-panic"))]
+impl<T> std::ops::Index<(usize, usize, usize)> for Array3DViewMut<'_, T> {
+    type Output = T;
+    #[track_caller]
+    fn index(&self, (x, y, z): (usize, usize, usize)) -> &Self::Output {
+        self.get(x, y, z).expect("Index out of bounds")
+    }
+}
+
+#[cfg(not(feature = "no-panic"))]
+impl<T> std::ops::IndexMut<(usize, usize, usize)> for Array3DViewMut<'_, T> {
+    #[track_caller]
+    fn index_mut(
+        &mut self,
+        (x, y, z): (usize, usize, usize),
+    ) -> &mut Self::Output {
+        self.get_mut(x, y, z).expect("Index out of bounds")
+    }
+}
@@ -0,0 +1,987 @@
+use std::{fmt, marker::PhantomData, ops::Range, slice};
+
+use super::{element_count, shape_mismatch};
+use crate::{
+    allocator::RawAllocator, error::Error, runtime_array::ArrayCStyle,
+};
+
+/// Returns the strides of a row-major (C order) array of `shape`.
+pub(crate) fn row_major_strides(shape: &[usize]) -> Vec<usize> {
+    let mut strides = vec![0; shape.len()];
+    let mut stride = 1;
+    for (axis, &extent) in shape.iter().enumerate().rev() {
+        strides[axis] = stride;
+        stride *= extent;
+    }
+    strides
+}
+
+/// Returns the row-major strides for viewing `len` contiguous elements as
+/// `shape`.
+fn reshape_strides(shape: &[usize], len: usize) -> Result<Vec<usize>, Error> {
+    if element_count(shape)? != len {
+        return Err(shape_mismatch(shape, len));
+    }
+    Ok(row_major_strides(shape))
+}
+
+/// The error for reshaping elements that are not laid out row-major.
+fn not_contiguous() -> Error {
+    Error::Other("Cannot reshape a non-contiguous array without copying".into())
+}
+
+/// Returns the storage offset of `index`, or `None` if it has the wrong
+/// rank or is out of bounds.
+#[inline(always)]
+fn offset_of(
+    shape: &[usize],
+    strides: &[usize],
+    index: &[usize],
+) -> Option<usize> {
+    if index.len() != shape.len() {
+        return None;
+    }
+    let mut offset = 0;
+    for ((&i, &extent), &stride) in index.iter().zip(shape).zip(strides) {
+        if i >= extent {
+            return None;
+        }
+        offset += i * stride;
+    }
+    Some(offset)
+}
+
+/// Explains why `index` does not address an element of `shape`.
+fn index_error(shape: &[usize], index: &[usize]) -> Error {
+    if index.len() != shape.len() {
+        return Error::Other(format!(
+            "Index of rank {} used on an array of rank {}",
+            index.len(),
+            shape.len()
+        ));
+    }
+    let (&index, &len) = index
+        .iter()
+        .zip(shape)
+        .find(|(&i, &extent)| i >= extent)
+        .unwrap_or((&0, &0));
+    Error::IndexOutOfBounds { index, len }
+}
+
+/// Walks the storage offsets of an array in logical (row-major) order.
+#[derive(Clone)]
+struct Offsets {
+    shape: Vec<usize>,
+    strides: Vec<usize>,
+    index: Vec<usize>,
+    offset: usize,
+    remaining: usize,
+}
+
+impl Offsets {
+    fn new(shape: &[usize], strides: &[usize]) -> Self {
+        Self {
+            shape: shape.to_vec(),
+            strides: strides.to_vec(),
+            index: vec![0; shape.len()],
+            offset: 0,
+            remaining: shape.iter().product(),
+        }
+    }
+}
+
+impl Iterator for Offsets {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+        let current = self.offset;
+        // odometer step: bump the last axis, carrying into earlier ones
+        for axis in (0..self.shape.len()).rev() {
+            self.index[axis] += 1;
+            self.offset += self.strides[axis];
+            if self.index[axis] < self.shape[axis] {
+                break;
+            }
+            self.offset -= self.strides[axis] * self.shape[axis];
+            self.index[axis] = 0;
+        }
+        Some(current)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+/// An array of any rank, with its shape and strides known at runtime.
+///
+/// Element `index` lives at the sum of `index[axis] * strides[axis]` in the
+/// underlying [`ArrayCStyle`]. Arrays built by the constructors are
+/// row-major (the last axis varies fastest), but the strides are kept
+/// explicitly so the axes can be reordered without moving any element, and
+/// every view and iterator honors them. This makes it usable for tensors
+/// whose rank is only known at runtime. As with the 1-D array, the elements
+/// are not dropped with the array.
+///
+/// # Example
+///
+/// ```rust ignore
+/// use runnarr::matrix::ArrayND;
+///
+/// let mut batch = ArrayND::<f32>::zeroed(&[32, 3, 224, 224])?;
+/// batch[[0, 1, 10, 10]] = 0.5;
+/// let first_image = batch.view().index_axis(0, 0).unwrap();
+/// assert_eq!(first_image.shape(), [3, 224, 224]);
+/// ```
+pub struct ArrayND<T> {
+    data: ArrayCStyle<T>,
+    shape: Vec<usize>,
+    strides: Vec<usize>,
+}
+
+impl<T> ArrayND<T> {
+    /// Creates an uninitialized row-major array of `shape`.
+    ///
+    /// Like [`ArrayCStyle::new`], the elements must be written before they
+    /// are read.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the element count overflows or the allocation
+    /// fails.
+    #[track_caller]
+    pub fn new(shape: &[usize]) -> Result<Self, Error> {
+        let len = element_count(shape)?;
+        Ok(Self {
+            data: ArrayCStyle::new(len)?,
+            shape: shape.to_vec(),
+            strides: row_major_strides(shape),
+        })
+    }
+
+    /// Creates a row-major array of `shape` with every byte set to zero.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the element count overflows or the allocation
+    /// fails.
+    #[track_caller]
+    pub fn zeroed(shape: &[usize]) -> Result<Self, Error> {
+        let len = element_count(shape)?;
+        Ok(Self {
+            data: ArrayCStyle::zeroed(len)?,
+            shape: shape.to_vec(),
+            strides: row_major_strides(shape),
+        })
+    }
+
+    /// Creates a row-major array of `shape` holding clones of `value`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the element count overflows or the allocation
+    /// fails.
+    #[track_caller]
+    pub fn filled(shape: &[usize], value: T) -> Result<Self, Error>
+    where
+        T: Clone,
+    {
+        let array = Self::new(shape)?;
+        for index in 0..array.len() {
+            unsafe { array.data.ptr_mut().add(index).write(value.clone()) };
+        }
+        Ok(array)
+    }
+
+    /// Interprets a row-major array as an array of `shape`, without
+    /// copying.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the array length does not match the shape.
+    pub fn from_array(
+        data: ArrayCStyle<T>,
+        shape: &[usize],
+    ) -> Result<Self, Error> {
+        if element_count(shape)? != data.len() {
+            return Err(shape_mismatch(shape, data.len()));
+        }
+        Ok(Self {
+            data,
+            shape: shape.to_vec(),
+            strides: row_major_strides(shape),
+        })
+    }
+
+    /// Returns the extent of every axis.
+    #[inline(always)]
+    pub fn shape(&self) -> &[usize] {
+        &self.shape
+    }
+
+    /// Returns the distance, in elements, between neighbors along every
+    /// axis.
+    #[inline(always)]
+    pub fn strides(&self) -> &[usize] {
+        &self.strides
+    }
+
+    /// Returns the number of axes.
+    #[inline(always)]
+    pub fn ndim(&self) -> usize {
+        self.shape.len()
+    }
+
+    /// Returns the number of elements.
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Returns `true` if the array has no elements.
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Returns the element at `index`, or `None` if it has the wrong rank or
+    /// is out of bounds.
+    #[inline(always)]
+    pub fn get(&self, index: &[usize]) -> Option<&T> {
+        self.data.get(offset_of(&self.shape, &self.strides, index)?)
+    }
+
+    /// Returns the element at `index` mutably, or `None` if it has the wrong
+    /// rank or is out of bounds.
+    #[inline(always)]
+    pub fn get_mut(&mut self, index: &[usize]) -> Option<&mut T> {
+        let offset = offset_of(&self.shape, &self.strides, index)?;
+        self.data.get_mut(offset)
+    }
+
+    /// Returns the element at `index`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::IndexOutOfBounds`] carrying the first out-of-bounds
+    /// coordinate and the extent of its axis, or [`Error::Other`] if
+    /// `index` has the wrong rank.
+    pub fn at(&self, index: &[usize]) -> Result<&T, Error> {
+        self.get(index)
+            .ok_or_else(|| index_error(&self.shape, index))
+    }
+
+    /// Returns the element at `index` mutably.
+    ///
+    /// # Errors
+    ///
+    /// See [`ArrayND::at`].
+    pub fn at_mut(&mut self, index: &[usize]) -> Result<&mut T, Error> {
+        let error = index_error(&self.shape, index);
+        self.get_mut(index).ok_or(error)
+    }
+
+    /// Returns an iterator over the elements in logical (row-major) order.
+    pub fn iter(&self) -> impl Iterator<Item = &T> + '_ {
+        self.view().into_iter()
+    }
+
+    /// Returns an iterator over the elements in logical (row-major) order,
+    /// mutably.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut T> + '_ {
+        self.view_mut().into_iter()
+    }
+
+    /// Returns `true` if the elements are stored row-major without gaps.
+    pub fn is_contiguous(&self) -> bool {
+        is_row_major(&self.shape, &self.strides)
+    }
+
+    /// Returns the elements as one row-major slice, or `None` if the axes
+    /// have been reordered.
+    pub fn as_slice(&self) -> Option<&[T]> {
+        self.is_contiguous().then(|| self.data.as_slice())
+    }
+
+    /// Returns the elements as one mutable row-major slice, or `None` if the
+    /// axes have been reordered.
+    pub fn as_mut_slice(&mut self) -> Option<&mut [T]> {
+        if self.is_contiguous() {
+            Some(self.data.as_mut_slice())
+        } else {
+            None
+        }
+    }
+
+    /// Returns a view of the whole array.
+    pub fn view(&self) -> ArrayViewND<'_, T> {
+        ArrayViewND {
+            ptr: self.data.ptr(),
+            shape: self.shape.clone(),
+            strides: self.strides.clone(),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns a view with the axes in reverse order; see
+    /// [`ArrayViewND::t`].
+    pub fn t(&self) -> ArrayViewND<'_, T> {
+        self.view().t()
+    }
+
+    /// Returns the array stretched to `shape` under NumPy broadcasting
+    /// rules, without copying; see [`ArrayViewND::broadcast`].
+    pub fn broadcast(&self, shape: &[usize]) -> Option<ArrayViewND<'_, T>> {
+        self.view().broadcast(shape)
+    }
+
+    /// Returns a mutable view of the whole array.
+    pub fn view_mut(&mut self) -> ArrayViewMutND<'_, T> {
+        ArrayViewMutND {
+            ptr: self.data.as_mut_slice().as_mut_ptr(),
+            shape: self.shape.clone(),
+            strides: self.strides.clone(),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns a view of the elements under a new shape with the same
+    /// number of elements, in row-major order, without copying.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the element counts differ or the axes have been
+    /// reordered, since the elements would then need to be copied.
+    pub fn reshape(
+        &self,
+        shape: &[usize],
+    ) -> Result<ArrayViewND<'_, T>, Error> {
+        self.view().reshape(shape)
+    }
+
+    /// Returns a mutable view of the elements under a new shape; see
+    /// [`ArrayND::reshape`].
+    ///
+    /// # Errors
+    ///
+    /// See [`ArrayND::reshape`].
+    pub fn reshape_mut(
+        &mut self,
+        shape: &[usize],
+    ) -> Result<ArrayViewMutND<'_, T>, Error> {
+        if !self.is_contiguous() {
+            return Err(not_contiguous());
+        }
+        let strides = reshape_strides(shape, self.len())?;
+        Ok(ArrayViewMutND {
+            ptr: self.data.as_mut_slice().as_mut_ptr(),
+            shape: shape.to_vec(),
+            strides,
+            _marker: PhantomData,
+        })
+    }
+
+    /// Reverses the order of the axes, by reversing the shape and strides;
+    /// no element moves. For a matrix this is the transpose.
+    pub fn reversed_axes(mut self) -> Self {
+        self.shape.reverse();
+        self.strides.reverse();
+        self
+    }
+
+    /// Unwraps the underlying array, in storage order.
+    pub fn into_array(self) -> ArrayCStyle<T> {
+        self.data
+    }
+}
+
+impl<T, A: RawAllocator> ArrayCStyle<T, A> {
+    /// Returns a view of the elements as a row-major array of `shape`,
+    /// without copying, e.g. to index a decoded flat buffer as a matrix.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `shape` does not hold exactly
+    /// [`ArrayCStyle::len`] elements.
+    ///
+    /// # Example
+    ///
+    /// ```rust ignore
+    /// let pixels = decode(frame)?;
+    /// let image = pixels.reshape(&[height, width, 3])?;
+    /// let red = image[[y, x, 0]];
+    /// ```
+    pub fn reshape(
+        &self,
+        shape: &[usize],
+    ) -> Result<ArrayViewND<'_, T>, Error> {
+        let strides = reshape_strides(shape, self.len())?;
+        Ok(ArrayViewND {
+            ptr: self.ptr(),
+            shape: shape.to_vec(),
+            strides,
+            _marker: PhantomData,
+        })
+    }
+
+    /// Returns a mutable view of the elements as a row-major array of
+    /// `shape`; see [`ArrayCStyle::reshape`].
+    ///
+    /// # Errors
+    ///
+    /// See [`ArrayCStyle::reshape`].
+    pub fn reshape_mut(
+        &mut self,
+        shape: &[usize],
+    ) -> Result<ArrayViewMutND<'_, T>, Error> {
+        let strides = reshape_strides(shape, self.len())?;
+        Ok(ArrayViewMutND {
+            ptr: self.as_mut_slice().as_mut_ptr(),
+            shape: shape.to_vec(),
+            strides,
+            _marker: PhantomData,
+        })
+    }
+}
+
+/// Returns `true` if `strides` are the row-major strides of `shape`,
+/// ignoring axes of extent 1 (whose stride never matters).
+fn is_row_major(shape: &[usize], strides: &[usize]) -> bool {
+    if shape.contains(&0) {
+        return true;
+    }
+    let mut expected = 1;
+    for (&extent, &stride) in shape.iter().zip(strides).rev() {
+        if extent != 1 && stride != expected {
+            return false;
+        }
+        expected *= extent;
+    }
+    true
+}
+
+impl<T: Clone> ArrayND<T> {
+    /// Creates a deep, row-major copy of the array.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the copy cannot be allocated.
+    #[track_caller]
+    pub fn try_clone(&self) -> Result<Self, Error> {
+        self.view().to_array_nd()
+    }
+}
+
+#[cfg(not(feature = "no-panic"))]
+impl<T: Clone> Clone for ArrayND<T> {
+    #[track_caller]
+    fn clone(&self) -> Self {
+        self.try_clone().expect("Failed to allocate array clone")
+    }
+}
+
+#[cfg(not(feature = "no-panic"))]
+impl<T> std::ops::Index<&[usize]> for ArrayND<T> {
+    type Output = T;
+    #[track_caller]
+    fn index(&self, index: &[usize]) -> &Self::Output {
+        self.get(index).expect("Index out of bounds")
+    }
+}
+
+#[cfg(not(feature = "no-panic"))]
+impl<T> std::ops::IndexMut<&[usize]> for ArrayND<T> {
+    #[track_caller]
+    fn index_mut(&mut self, index: &[usize]) -> &mut Self::Output {
+        self.get_mut(index).expect("Index out of bounds")
+    }
+}
+
+#[cfg(not(feature = "no-panic"))]
+impl<T, const N: usize> std::ops::Index<[usize; N]> for ArrayND<T> {
+    type Output = T;
+    #[track_caller]
+    fn index(&self, index: [usize; N]) -> &Self::Output {
+        self.get(&index).expect("Index out of bounds")
+    }
+}
+
+#[cfg(not(feature = "no-panic"))]
+impl<T, const N: usize> std::ops::IndexMut<[usize; N]> for ArrayND<T> {
+    #[track_caller]
+    fn index_mut(&mut self, index: [usize; N]) -> &mut Self::Output {
+        self.get_mut(&index).expect("Index out of bounds")
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for ArrayND<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.view().fmt(f)
+    }
+}
+
+/// A borrowed, strided window of any rank into another array's elements.
+///
+/// Element `index` lives at the sum of `index[axis] * strides[axis]`
+/// elements past the start of the window.
+pub struct ArrayViewND<'a, T> {
+    ptr: *const T,
+    shape: Vec<usize>,
+    strides: Vec<usize>,
+    _marker: PhantomData<&'a [T]>,
+}
+
+// A view is a shared borrow of `T`s, exactly like `&[T]`.
+unsafe impl<T: Sync> Send for ArrayViewND<'_, T> {}
+unsafe impl<T: Sync> Sync for ArrayViewND<'_, T> {}
+
+impl<T> Clone for ArrayViewND<'_, T> {
+    fn clone(&self) -> Self {
+        Self {
+            ptr: self.ptr,
+            shape: self.shape.clone(),
+            strides: self.strides.clone(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<'a, T> ArrayViewND<'a, T> {
+    /// # Safety
+    ///
+    /// Every index inside `shape` must address an initialized element that
+    /// stays borrowed for `'a`.
+    pub(crate) unsafe fn from_raw(
+        ptr: *const T,
+        shape: Vec<usize>,
+        strides: Vec<usize>,
+    ) -> Self {
+        Self {
+            ptr,
+            shape,
+            strides,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns the extent of every axis.
+    #[inline(always)]
+    pub fn shape(&self) -> &[usize] {
+        &self.shape
+    }
+
+    /// Returns the distance, in elements, between neighbors along every
+    /// axis.
+    #[inline(always)]
+    pub fn strides(&self) -> &[usize] {
+        &self.strides
+    }
+
+    /// Returns a pointer to the element at the all-zero index.
+    #[inline(always)]
+    pub fn as_ptr(&self) -> *const T {
+        self.ptr
+    }
+
+    /// Returns the number of axes.
+    #[inline(always)]
+    pub fn ndim(&self) -> usize {
+        self.shape.len()
+    }
+
+    /// Returns the number of elements.
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.shape.iter().product()
+    }
+
+    /// Returns `true` if the view has no elements.
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the element at `index`, or `None` if it has the wrong rank or
+    /// is out of bounds.
+    #[inline(always)]
+    pub fn get(&self, index: &[usize]) -> Option<&'a T> {
+        let offset = offset_of(&self.shape, &self.strides, index)?;
+        Some(unsafe { &*self.ptr.add(offset) })
+    }
+
+    /// Returns `true` if the elements are laid out row-major without gaps.
+    pub fn is_contiguous(&self) -> bool {
+        is_row_major(&self.shape, &self.strides)
+    }
+
+    /// Returns the elements as one row-major slice, or `None` if the view is
+    /// not contiguous.
+    pub fn as_slice(&self) -> Option<&'a [T]> {
+        self.is_contiguous()
+            .then(|| unsafe { slice::from_raw_parts(self.ptr, self.len()) })
+    }
+
+    /// Returns the sub-view at position `index` along `axis`, which has one
+    /// axis less, or `None` if `axis` or `index` is out of bounds.
+    pub fn index_axis(&self, axis: usize, index: usize) -> Option<Self> {
+        if index >= *self.shape.get(axis)? {
+            return None;
+        }
+        let (mut shape, mut strides) =
+            (self.shape.clone(), self.strides.clone());
+        shape.remove(axis);
+        let stride = strides.remove(axis);
+        Some(unsafe {
+            Self::from_raw(self.ptr.add(index * stride), shape, strides)
+        })
+    }
+
+    /// Returns the sub-view covering `range` along `axis`, or `None` if
+    /// `axis` or `range` is out of bounds.
+    pub fn slice_axis(&self, axis: usize, range: Range<usize>) -> Option<Self> {
+        if range.start > range.end || range.end > *self.shape.get(axis)? {
+            return None;
+        }
+        let mut shape = self.shape.clone();
+        shape[axis] = range.len();
+        let start = range.start * self.strides[axis];
+        Some(unsafe {
+            Self::from_raw(self.ptr.add(start), shape, self.strides.clone())
+        })
+    }
+
+    /// Returns the view stretched to `shape` under NumPy broadcasting rules,
+    /// without copying, or `None` if it cannot be.
+    ///
+    /// Axes are matched from the last one backwards; an axis of extent 1,
+    /// or one missing from the front, repeats its elements by taking a
+    /// stride of 0.
+    pub fn broadcast(&self, shape: &[usize]) -> Option<Self> {
+        let extra = shape.len().checked_sub(self.ndim())?;
+        let mut strides = vec![0; shape.len()];
+        for (axis, (&extent, &stride)) in
+            self.shape.iter().zip(&self.strides).enumerate()
+        {
+            match (extent, shape[extra + axis]) {
+                (1, _) => {}
+                (from, to) if from == to => strides[extra + axis] = stride,
+                _ => return None,
+            }
+        }
+        Some(unsafe { Self::from_raw(self.ptr, shape.to_vec(), strides) })
+    }
+
+    /// Returns the same elements under a new shape with the same number of
+    /// elements, in row-major order, without copying.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the element counts differ or the view is not
+    /// contiguous, since the elements would then need to be copied.
+    pub fn reshape(&self, shape: &[usize]) -> Result<Self, Error> {
+        if !self.is_contiguous() {
+            return Err(not_contiguous());
+        }
+        let strides = reshape_strides(shape, self.len())?;
+        Ok(unsafe { Self::from_raw(self.ptr, shape.to_vec(), strides) })
+    }
+
+    /// Returns the view with its axes in reverse order, by reversing the
+    /// shape and strides; for a matrix this is the transpose.
+    pub fn t(&self) -> Self {
+        let mut view = self.clone();
+        view.shape.reverse();
+        view.strides.reverse();
+        view
+    }
+
+    /// Copies the view into a new row-major array.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the array cannot be allocated.
+    #[track_caller]
+    pub fn to_array_nd(&self) -> Result<ArrayND<T>, Error>
+    where
+        T: Clone,
+    {
+        let array = ArrayND::new(&self.shape)?;
+        let target: *mut T = array.data.ptr_mut();
+        for (index, value) in self.clone().into_iter().enumerate() {
+            unsafe { target.add(index).write(value.clone()) };
+        }
+        Ok(array)
+    }
+}
+
+impl<'a, T> IntoIterator for ArrayViewND<'a, T> {
+    type Item = &'a T;
+    type IntoIter = NdIter<'a, T>;
+
+    /// Iterates over the elements in logical (row-major) order.
+    fn into_iter(self) -> Self::IntoIter {
+        NdIter {
+            ptr: self.ptr,
+            offsets: Offsets::new(&self.shape, &self.strides),
+            _marker: PhantomData,
+        }
+    }
+}
+
+#[cfg(not(feature = "no-panic"))]
+impl<T> std::ops::Index<&[usize]> for ArrayViewND<'_, T> {
+    type Output = T;
+    #[track_caller]
+    fn index(&self, index: &[usize]) -> &Self::Output {
+        self.get(index).expect("Index out of bounds")
+    }
+}
+
+#[cfg(not(feature = "no-panic"))]
+impl<T, const N: usize> std::ops::Index<[usize; N]> for ArrayViewND<'_, T> {
+    type Output = T;
+    #[track_caller]
+    fn index(&self, index: [usize; N]) -> &Self::Output {
+        self.get(&index).expect("Index out of bounds")
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for ArrayViewND<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // nested lists, one level per axis
+        if self.ndim() == 0 {
+            return match self.get(&[]) {
+                Some(value) => value.fmt(f),
+                None => f.write_str("[]"),
+            };
+        }
+        let mut list = f.debug_list();
+        for index in 0..self.shape[0] {
+            if let Some(inner) = self.index_axis(0, index) {
+                list.entry(&inner);
+            }
+        }
+        list.finish()
+    }
+}
+
+/// An iterator over the elements of an [`ArrayViewND`] in logical order.
+pub struct NdIter<'a, T> {
+    ptr: *const T,
+    offsets: Offsets,
+    _marker: PhantomData<&'a T>,
+}
+
+impl<'a, T> Iterator for NdIter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        let offset = self.offsets.next()?;
+        Some(unsafe { &*self.ptr.add(offset) })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.offsets.size_hint()
+    }
+}
+
+impl<T> ExactSizeIterator for NdIter<'_, T> {}
+
+/// A mutable, strided window of any rank into another array's elements; see
+/// [`ArrayViewND`].
+pub struct ArrayViewMutND<'a, T> {
+    ptr: *mut T,
+    shape: Vec<usize>,
+    strides: Vec<usize>,
+    _marker: PhantomData<&'a mut [T]>,
+}
+
+// A mutable view is an exclusive borrow of `T`s, exactly like `&mut [T]`.
+unsafe impl<T: Send> Send for ArrayViewMutND<'_, T> {}
+unsafe impl<T: Sync> Sync for ArrayViewMutND<'_, T> {}
+
+impl<T> ArrayViewMutND<'_, T> {
+    /// Reborrows the view immutably.
+    pub fn as_view(&self) -> ArrayViewND<'_, T> {
+        unsafe {
+            ArrayViewND::from_raw(
+                self.ptr,
+                self.shape.clone(),
+                self.strides.clone(),
+            )
+        }
+    }
+
+    /// Returns the extent of every axis.
+    #[inline(always)]
+    pub fn shape(&self) -> &[usize] {
+        &self.shape
+    }
+
+    /// Returns the distance, in elements, between neighbors along every
+    /// axis.
+    #[inline(always)]
+    pub fn strides(&self) -> &[usize] {
+        &self.strides
+    }
+
+    /// Returns a mutable pointer to the element at the all-zero index.
+    #[inline(always)]
+    pub fn as_mut_ptr(&mut self) -> *mut T {
+        self.ptr
+    }
+
+    /// Returns the number of axes.
+    #[inline(always)]
+    pub fn ndim(&self) -> usize {
+        self.shape.len()
+    }
+
+    /// Returns the number of elements.
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.shape.iter().product()
+    }
+
+    /// Returns `true` if the view has no elements.
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the element at `index`, or `None` if it has the wrong rank or
+    /// is out of bounds.
+    #[inline(always)]
+    pub fn get(&self, index: &[usize]) -> Option<&T> {
+        let offset = offset_of(&self.shape, &self.strides, index)?;
+        Some(unsafe { &*self.ptr.add(offset) })
+    }
+
+    /// Returns the element at `index` mutably, or `None` if it has the wrong
+    /// rank or is out of bounds.
+    #[inline(always)]
+    pub fn get_mut(&mut self, index: &[usize]) -> Option<&mut T> {
+        let offset = offset_of(&self.shape, &self.strides, index)?;
+        Some(unsafe { &mut *self.ptr.add(offset) })
+    }
+
+    /// Consumes the view, returning the mutable sub-view at position `index`
+    /// along `axis`, or `None` if `axis` or `index` is out of bounds.
+    pub fn index_axis(mut self, axis: usize, index: usize) -> Option<Self> {
+        if index >= *self.shape.get(axis)? {
+            return None;
+        }
+        self.shape.remove(axis);
+        let stride = self.strides.remove(axis);
+        self.ptr = unsafe { self.ptr.add(index * stride) };
+        Some(self)
+    }
+
+    /// Consumes the view, returning the mutable sub-view covering `range`
+    /// along `axis`, or `None` if `axis` or `range` is out of bounds.
+    pub fn slice_axis(
+        mut self,
+        axis: usize,
+        range: Range<usize>,
+    ) -> Option<Self> {
+        if range.start > range.end || range.end > *self.shape.get(axis)? {
+            return None;
+        }
+        self.shape[axis] = range.len();
+        self.ptr = unsafe { self.ptr.add(range.start * self.strides[axis]) };
+        Some(self)
+    }
+
+    /// Writes a clone of `value` into every element, dropping the old ones.
+    pub fn fill(&mut self, value: T)
+    where
+        T: Clone,
+    {
+        for offset in Offsets::new(&self.shape, &self.strides) {
+            unsafe { *self.ptr.add(offset) = value.clone() };
+        }
+    }
+}
+
+impl<'a, T> IntoIterator for ArrayViewMutND<'a, T> {
+    type Item = &'a mut T;
+    type IntoIter = NdIterMut<'a, T>;
+
+    /// Iterates over the elements in logical (row-major) order.
+    fn into_iter(self) -> Self::IntoIter {
+        NdIterMut {
+            ptr: self.ptr,
+            offsets: Offsets::new(&self.shape, &self.strides),
+            _marker: PhantomData,
+        }
+    }
+}
+
+#[cfg(not(feature = "no-panic"))]
+impl<T> std::ops::Index<&[usize]> for ArrayViewMutND<'_, T> {
+    type Output = T;
+    #[track_caller]
+    fn index(&self, index: &[usize]) -> &Self::Output {
+        self.get(index).expect("Index out of bounds")
+    }
+}
+
+#[cfg(not(feature = "no-panic"))]
+impl<T> std::ops::IndexMut<&[usize]> for ArrayViewMutND<'_, T> {
+    #[track_caller]
+    fn index_mut(&mut self, index: &[usize]) -> &mut Self::Output {
+        self.get_mut(index).expect("Index out of bounds")
+    }
+}
+
+#[cfg(not(feature = "no-panic"))]
+impl<T, const N: usize> std::ops::Index<[usize; N]> for ArrayViewMutND<'_, T> {
+    type Output = T;
+    #[track_caller]
+    fn index(&self, index: [usize; N]) -> &Self::Output {
+        self.get(&index).expect("Index out of bounds")
+    }
+}
+
+#[cfg(not(feature = "no-panic"))]
+impl<T, const N: usize> std::ops::IndexMut<[usize; N]>
+    for ArrayViewMutND<'_, T>
+{
+    #[track_caller]
+    fn index_mut(&mut self, index: [usize; N]) -> &mut Self::Output {
+        self.get_mut(&index).expect("Index out of bounds")
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for ArrayViewMutND<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.as_view().fmt(f)
+    }
+}
+
+/// An iterator over the elements of an [`ArrayViewMutND`] in logical order.
+pub struct NdIterMut<'a, T> {
+    ptr: *mut T,
+    offsets: Offsets,
+    _marker: PhantomData<&'a mut T>,
+}
+
+impl<'a, T> Iterator for NdIterMut<'a, T> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<&'a mut T> {
+        let offset = self.offsets.next()?;
+        // distinct indices of a view address distinct elements
+        Some(unsafe { &mut *self.ptr.add(offset) })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.offsets.size_hint()
+    }
+}
+
+impl<T> ExactSizeIterator for NdIterMut<'_, T> {}
@@ -0,0 +1,308 @@
+use std::ops::Add;
+
+use super::{Array2D, ArrayND, ArrayViewND, Float};
+use crate::{error::Error, runtime_array::ArrayCStyle};
+
+/// An axis of an array, counted from 0: for a matrix, `Axis(0)` runs down
+/// the rows and `Axis(1)` along the columns.
+///
+/// Reducing along an axis removes it, so `sum_axis(Axis(0))` of a matrix
+/// has one sum per column and `sum_axis(Axis(1))` one sum per row.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Axis(pub usize);
+
+fn empty_axis(Axis(axis): Axis) -> Error {
+    Error::Other(format!("Cannot reduce along empty axis {axis}"))
+}
+
+/// Returns the lesser of two values; a NaN is only kept if it comes first.
+#[inline(always)]
+fn lesser<T: PartialOrd>(current: T, value: T) -> T {
+    if value < current {
+        value
+    } else {
+        current
+    }
+}
+
+/// Returns the greater of two values; a NaN is only kept if it comes first.
+#[inline(always)]
+fn greater<T: PartialOrd>(current: T, value: T) -> T {
+    if value > current {
+        value
+    } else {
+        current
+    }
+}
+
+impl<T: Copy> ArrayViewND<'_, T> {
+    /// Combines the lanes along `axis` element by element with `op`,
+    /// starting from the first lane, so the lanes are walked in the order
+    /// they are stored in.
+    ///
+    /// # Returns
+    ///
+    /// `None` if the axis is empty.
+    fn reduce_axis(
+        &self,
+        axis: Axis,
+        op: impl Fn(T, T) -> T,
+    ) -> Result<Option<ArrayND<T>>, Error> {
+        let Some(&extent) = self.shape().get(axis.0) else {
+            return Err(Error::IndexOutOfBounds {
+                index: axis.0,
+                len: self.ndim(),
+            });
+        };
+        let mut shape = self.shape().to_vec();
+        shape.remove(axis.0);
+        if extent == 0 {
+            return Ok(None);
+        }
+        let lane = |index| self.index_axis(axis.0, index).into_iter().flatten();
+        let mut reduced = ArrayCStyle::<T>::new(shape.iter().product())?;
+        for (index, &value) in lane(0).enumerate() {
+            unsafe { reduced.ptr_mut().add(index).write(value) };
+        }
+        for index in 1..extent {
+            for (slot, &value) in
+                reduced.as_mut_slice().iter_mut().zip(lane(index))
+            {
+                *slot = op(*slot, value);
+            }
+        }
+        ArrayND::from_array(reduced, &shape).map(Some)
+    }
+
+    /// Returns the sums along `axis`, an array with that axis removed.
+    ///
+    /// `T::default()` must be the additive identity, as it is for the
+    /// primitive numbers; sums along an empty axis are all
+    /// `T::default()`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `axis` is out of bounds or the result cannot be
+    /// allocated.
+    ///
+    /// # Example
+    ///
+    /// ```rust ignore
+    /// // [batch, channel, height, width] -> [batch, channel] totals
+    /// let rows = batch.view().sum_axis(Axis(3))?;
+    /// let totals = rows.sum_axis(Axis(2))?;
+    /// ```
+    #[track_caller]
+    pub fn sum_axis(&self, axis: Axis) -> Result<ArrayND<T>, Error>
+    where
+        T: Default + Add<Output = T>,
+    {
+        match self.reduce_axis(axis, Add::add)? {
+            Some(sums) => Ok(sums),
+            None => {
+                let mut shape = self.shape().to_vec();
+                shape.remove(axis.0);
+                ArrayND::filled(&shape, T::default())
+            }
+        }
+    }
+
+    /// Returns the means along `axis`, an array with that axis removed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `axis` is out of bounds or empty, or the result
+    /// cannot be allocated.
+    #[track_caller]
+    pub fn mean_axis(&self, axis: Axis) -> Result<ArrayND<T>, Error>
+    where
+        T: Float,
+    {
+        let mut sums = self
+            .reduce_axis(axis, Add::add)?
+            .ok_or_else(|| empty_axis(axis))?;
+        let count = T::from_usize(self.shape()[axis.0]);
+        sums.iter_mut().for_each(|sum| *sum = *sum / count);
+        Ok(sums)
+    }
+
+    /// Returns the smallest elements along `axis`, an array with that axis
+    /// removed.
+    ///
+    /// Elements are compared with `<`, so a float NaN is only kept when it
+    /// comes first along the axis.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `axis` is out of bounds or empty, or the result
+    /// cannot be allocated.
+    #[track_caller]
+    pub fn min_axis(&self, axis: Axis) -> Result<ArrayND<T>, Error>
+    where
+        T: PartialOrd,
+    {
+        self.reduce_axis(axis, lesser)?
+            .ok_or_else(|| empty_axis(axis))
+    }
+
+    /// Returns the largest elements along `axis`, an array with that axis
+    /// removed; see [`ArrayViewND::min_axis`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `axis` is out of bounds or empty, or the result
+    /// cannot be allocated.
+    #[track_caller]
+    pub fn max_axis(&self, axis: Axis) -> Result<ArrayND<T>, Error>
+    where
+        T: PartialOrd,
+    {
+        self.reduce_axis(axis, greater)?
+            .ok_or_else(|| empty_axis(axis))
+    }
+}
+
+impl<T: Copy> ArrayND<T> {
+    /// Returns the sums along `axis`; see [`ArrayViewND::sum_axis`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `axis` is out of bounds or the result cannot be
+    /// allocated.
+    #[inline(always)]
+    #[track_caller]
+    pub fn sum_axis(&self, axis: Axis) -> Result<ArrayND<T>, Error>
+    where
+        T: Default + Add<Output = T>,
+    {
+        self.view().sum_axis(axis)
+    }
+
+    /// Returns the means along `axis`; see [`ArrayViewND::mean_axis`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `axis` is out of bounds or empty, or the result
+    /// cannot be allocated.
+    #[inline(always)]
+    #[track_caller]
+    pub fn mean_axis(&self, axis: Axis) -> Result<ArrayND<T>, Error>
+    where
+        T: Float,
+    {
+        self.view().mean_axis(axis)
+    }
+
+    /// Returns the smallest elements along `axis`; see
+    /// [`ArrayViewND::min_axis`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `axis` is out of bounds or empty, or the result
+    /// cannot be allocated.
+    #[inline(always)]
+    #[track_caller]
+    pub fn min_axis(&self, axis: Axis) -> Result<ArrayND<T>, Error>
+    where
+        T: PartialOrd,
+    {
+        self.view().min_axis(axis)
+    }
+
+    /// Returns the largest elements along `axis`; see
+    /// [`ArrayViewND::max_axis`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `axis` is out of bounds or empty, or the result
+    /// cannot be allocated.
+    #[inline(always)]
+    #[track_caller]
+    pub fn max_axis(&self, axis: Axis) -> Result<ArrayND<T>, Error>
+    where
+        T: PartialOrd,
+    {
+        self.view().max_axis(axis)
+    }
+}
+
+impl<T: Copy> Array2D<T> {
+    /// Returns the matrix as a two-axis view, in its layout.
+    fn as_view_nd(&self) -> ArrayViewND<'_, T> {
+        let view = self.as_view();
+        let (row_stride, col_stride) = view.strides();
+        unsafe {
+            ArrayViewND::from_raw(
+                view.as_ptr(),
+                vec![self.nrows(), self.ncols()],
+                vec![row_stride, col_stride],
+            )
+        }
+    }
+
+    /// Returns the sums along `axis`: one per column for `Axis(0)`, one per
+    /// row for `Axis(1)`; see [`ArrayViewND::sum_axis`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `axis` is not 0 or 1, or the result cannot be
+    /// allocated.
+    ///
+    /// # Example
+    ///
+    /// ```rust ignore
+    /// let column_totals = table.sum_axis(Axis(0))?;
+    /// let row_totals = table.sum_axis(Axis(1))?;
+    /// ```
+    #[track_caller]
+    pub fn sum_axis(&self, axis: Axis) -> Result<ArrayCStyle<T>, Error>
+    where
+        T: Default + Add<Output = T>,
+    {
+        Ok(self.as_view_nd().sum_axis(axis)?.into_array())
+    }
+
+    /// Returns the means along `axis`; see [`Array2D::sum_axis`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `axis` is not 0 or 1 or is empty, or the result
+    /// cannot be allocated.
+    #[track_caller]
+    pub fn mean_axis(&self, axis: Axis) -> Result<ArrayCStyle<T>, Error>
+    where
+        T: Float,
+    {
+        Ok(self.as_view_nd().mean_axis(axis)?.into_array())
+    }
+
+    /// Returns the smallest elements along `axis`; see
+    /// [`Array2D::sum_axis`] and [`ArrayViewND::min_axis`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `axis` is not 0 or 1 or is empty, or the result
+    /// cannot be allocated.
+    #[track_caller]
+    pub fn min_axis(&self, axis: Axis) -> Result<ArrayCStyle<T>, Error>
+    where
+        T: PartialOrd,
+    {
+        Ok(self.as_view_nd().min_axis(axis)?.into_array())
+    }
+
+    /// Returns the largest elements along `axis`; see
+    /// [`Array2D::sum_axis`] and [`ArrayViewND::min_axis`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `axis` is not 0 or 1 or is empty, or the result
+    /// cannot be allocated.
+    #[track_caller]
+    pub fn max_axis(&self, axis: Axis) -> Result<ArrayCStyle<T>, Error>
+    where
+        T: PartialOrd,
+    {
+        Ok(self.as_view_nd().max_axis(axis)?.into_array())
+    }
+}
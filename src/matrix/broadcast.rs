@@ -0,0 +1,191 @@
+use std::ops::{
+    Add, AddAssign, Div, DivAssign, Mul, MulAssign, Sub, SubAssign,
+};
+
+use super::{ArrayND, ArrayViewND};
+use crate::{error::Error, runtime_array::ArrayCStyle};
+
+/// Returns the shape that arrays of shapes `a` and `b` broadcast to under
+/// NumPy rules.
+///
+/// The shapes are aligned at their last axis; each pair of extents must be
+/// equal or contain a 1, and missing leading axes count as 1.
+///
+/// # Errors
+///
+/// Returns an error if some pair of extents differs and neither is 1.
+///
+/// # Example
+///
+/// ```rust ignore
+/// assert_eq!(broadcast_shape(&[4, 1, 3], &[5, 1])?, [4, 5, 3]);
+/// ```
+pub fn broadcast_shape(a: &[usize], b: &[usize]) -> Result<Vec<usize>, Error> {
+    let ndim = a.len().max(b.len());
+    let extent = |shape: &[usize], axis: usize| {
+        (axis + shape.len())
+            .checked_sub(ndim)
+            .map_or(1, |axis| shape[axis])
+    };
+    (0..ndim)
+        .map(|axis| match (extent(a, axis), extent(b, axis)) {
+            (1, other) | (other, 1) => Ok(other),
+            (x, y) if x == y => Ok(x),
+            _ => Err(Error::Other(format!(
+                "Cannot broadcast shapes {a:?} and {b:?}"
+            ))),
+        })
+        .collect()
+}
+
+impl<T> ArrayND<T> {
+    /// Creates a zero-dimensional array holding `value`, which broadcasts
+    /// against any shape.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the allocation fails.
+    #[track_caller]
+    pub fn scalar(value: T) -> Result<Self, Error> {
+        let data = ArrayCStyle::<T>::new(1)?;
+        unsafe { data.ptr_mut().write(value) };
+        Self::from_array(data, &[])
+    }
+
+    /// Combines the elements of `self` and `rhs` pairwise after
+    /// broadcasting both to a common shape.
+    fn broadcast_zip(
+        &self,
+        rhs: &ArrayND<T>,
+        mut op: impl FnMut(&T, &T) -> T,
+    ) -> Result<Self, Error> {
+        let shape = broadcast_shape(self.shape(), rhs.shape())?;
+        let (lhs, rhs) = (stretch(self, &shape)?, stretch(rhs, &shape)?);
+        let data = ArrayCStyle::<T>::new(lhs.len())?;
+        let target = data.ptr_mut();
+        for (index, (x, y)) in lhs.into_iter().zip(rhs).enumerate() {
+            unsafe { target.add(index).write(op(x, y)) };
+        }
+        Self::from_array(data, &shape)
+    }
+
+    /// Updates every element of `self` with the matching element of `rhs`,
+    /// broadcast to `self`'s shape.
+    fn broadcast_zip_assign(
+        &mut self,
+        rhs: &ArrayND<T>,
+        mut op: impl FnMut(&mut T, &T),
+    ) -> Result<(), Error> {
+        let rhs = stretch(rhs, self.shape())?;
+        for (x, y) in self.iter_mut().zip(rhs) {
+            op(x, y);
+        }
+        Ok(())
+    }
+}
+
+/// Views `array` broadcast to `shape`, explaining why when it cannot be.
+fn stretch<'a, T>(
+    array: &'a ArrayND<T>,
+    shape: &[usize],
+) -> Result<ArrayViewND<'a, T>, Error> {
+    array.broadcast(shape).ok_or_else(|| {
+        Error::Other(format!(
+            "Cannot broadcast shape {:?} to {shape:?}",
+            array.shape()
+        ))
+    })
+}
+
+/// Implements one elementwise operator: the fallible `try_` methods on
+/// [`ArrayND`] and, unless panics are disabled, the `std::ops` traits on top.
+macro_rules! broadcast_op {
+    (
+        $Op:ident::$op:ident,
+        $OpAssign:ident::$op_assign:ident,
+        $try_op:ident,
+        $try_op_assign:ident,
+        $verb:literal
+    ) => {
+        impl<T: Clone + $Op<Output = T>> ArrayND<T> {
+            #[doc = concat!(
+                "Returns a new array of `self` ", $verb, " `rhs` elementwise, ",
+                "broadcasting both to a common shape.\n\n",
+                "# Errors\n\n",
+                "Returns an error if the shapes do not broadcast together or ",
+                "the allocation fails."
+            )]
+            #[track_caller]
+            pub fn $try_op(&self, rhs: &ArrayND<T>) -> Result<Self, Error> {
+                self.broadcast_zip(rhs, |x, y| x.clone().$op(y.clone()))
+            }
+        }
+
+        impl<T: Clone + $OpAssign> ArrayND<T> {
+            #[doc = concat!(
+                "Updates `self` in place to `self` ", $verb, " `rhs` ",
+                "elementwise, broadcasting `rhs` to `self`'s shape.\n\n",
+                "# Errors\n\n",
+                "Returns an error, leaving `self` untouched, if `rhs` does ",
+                "not broadcast to `self`'s shape."
+            )]
+            pub fn $try_op_assign(
+                &mut self,
+                rhs: &ArrayND<T>,
+            ) -> Result<(), Error> {
+                self.broadcast_zip_assign(rhs, |x, y| x.$op_assign(y.clone()))
+            }
+        }
+
+        #[cfg(not(feature = "no-panic"))]
+        impl<T: Clone + $Op<Output = T>> $Op<&ArrayND<T>>
+            for &ArrayND<T>
+        {
+            type Output = ArrayND<T>;
+            #[track_caller]
+            fn $op(self, rhs: &ArrayND<T>) -> Self::Output {
+                self.$try_op(rhs).unwrap_or_else(|error| panic!("{error}"))
+            }
+        }
+
+        #[cfg(not(feature = "no-panic"))]
+        impl<T: Clone + $OpAssign> $OpAssign<&ArrayND<T>>
+            for ArrayND<T>
+        {
+            #[track_caller]
+            fn $op_assign(&mut self, rhs: &ArrayND<T>) {
+                self.$try_op_assign(rhs)
+                    .unwrap_or_else(|error| panic!("{error}"))
+            }
+        }
+    };
+}
+
+broadcast_op!(
+    Add::add,
+    AddAssign::add_assign,
+    try_add,
+    try_add_assign,
+    "plus"
+);
+broadcast_op!(
+    Sub::sub,
+    SubAssign::sub_assign,
+    try_sub,
+    try_sub_assign,
+    "minus"
+);
+broadcast_op!(
+    Mul::mul,
+    MulAssign::mul_assign,
+    try_mul,
+    try_mul_assign,
+    "times"
+);
+broadcast_op!(
+    Div::div,
+    DivAssign::div_assign,
+    try_div,
+    try_div_assign,
+    "divided by"
+);
@@ -0,0 +1,157 @@
+use std::ptr;
+
+use super::{Array2D, Array3D, ArrayView2D, ArrayViewMut2D, Axis};
+use crate::error::Error;
+
+/// Copies `source` into the equally shaped `target`, a whole row at a time
+/// where both keep their rows contiguous and element by element otherwise.
+fn copy_view<T: Copy>(
+    source: ArrayView2D<'_, T>,
+    mut target: ArrayViewMut2D<'_, T>,
+) {
+    let (nrows, ncols) = source.shape();
+    let (source_rows, source_cols) = source.strides();
+    let (target_rows, target_cols) = target.strides();
+    let (from, to) = (source.as_ptr(), target.as_mut_ptr());
+    for row in 0..nrows {
+        let (from, to) =
+            unsafe { (from.add(row * source_rows), to.add(row * target_rows)) };
+        if source_cols == 1 && target_cols == 1 {
+            unsafe { ptr::copy_nonoverlapping(from, to, ncols) };
+            continue;
+        }
+        for col in 0..ncols {
+            unsafe {
+                to.add(col * target_cols)
+                    .write(*from.add(col * source_cols))
+            };
+        }
+    }
+}
+
+/// Checks that `arrays` is not empty and returns the first shape, failing
+/// if any other array disagrees with it outside axis `free`, if given.
+fn common_shape<T>(
+    arrays: &[&Array2D<T>],
+    free: Option<usize>,
+) -> Result<(usize, usize), Error> {
+    let Some(first) = arrays.first() else {
+        return Err(Error::Other(
+            "Cannot join an empty list of matrices".into(),
+        ));
+    };
+    let agrees = |(nrows, ncols): (usize, usize)| match free {
+        Some(0) => ncols == first.ncols(),
+        Some(_) => nrows == first.nrows(),
+        None => (nrows, ncols) == first.shape(),
+    };
+    match arrays.iter().find(|array| !agrees(array.shape())) {
+        Some(array) => Err(Error::Other(format!(
+            "Cannot join a {}x{} matrix with a {}x{} matrix",
+            first.nrows(),
+            first.ncols(),
+            array.nrows(),
+            array.ncols()
+        ))),
+        None => Ok(first.shape()),
+    }
+}
+
+/// Returns the matrices joined end to end along `axis`: one under the other
+/// for `Axis(0)`, side by side for `Axis(1)`.
+///
+/// The output is allocated once, row-major, and every input is copied into
+/// its place a row at a time, whatever its layout.
+///
+/// # Errors
+///
+/// Returns an error if `arrays` is empty, `axis` is not 0 or 1, the
+/// matrices differ in their other extent, or the output cannot be
+/// allocated.
+///
+/// # Example
+///
+/// ```rust ignore
+/// use runnarr::matrix::{concat, Axis};
+///
+/// let batches: Vec<Array2D<f32>> = load_batches()?;
+/// let dataset = concat(Axis(0), &batches.iter().collect::<Vec<_>>())?;
+/// ```
+#[track_caller]
+pub fn concat<T: Copy>(
+    axis: Axis,
+    arrays: &[&Array2D<T>],
+) -> Result<Array2D<T>, Error> {
+    if axis.0 > 1 {
+        return Err(Error::IndexOutOfBounds {
+            index: axis.0,
+            len: 2,
+        });
+    }
+    let (nrows, ncols) = common_shape(arrays, Some(axis.0))?;
+    let extent = |array: &&Array2D<T>| match axis.0 {
+        0 => array.nrows(),
+        _ => array.ncols(),
+    };
+    let total = arrays.iter().map(extent).sum();
+    let mut joined = match axis.0 {
+        0 => Array2D::new(total, ncols)?,
+        _ => Array2D::new(nrows, total)?,
+    };
+    let mut start = 0;
+    for array in arrays {
+        let end = start + extent(array);
+        let target = match axis.0 {
+            0 => joined.view_mut(start..end, 0..ncols),
+            _ => joined.view_mut(0..nrows, start..end),
+        };
+        if let Some(target) = target {
+            copy_view(array.as_view(), target);
+        }
+        start = end;
+    }
+    Ok(joined)
+}
+
+/// Returns the equally shaped matrices stacked along a new `axis` of a
+/// volume: `Axis(0)` gives shape `(arrays.len(), nrows, ncols)`, `Axis(1)`
+/// gives `(nrows, arrays.len(), ncols)` and `Axis(2)` gives
+/// `(nrows, ncols, arrays.len())`.
+///
+/// The output is allocated once and every input is copied into its plane,
+/// a row at a time where the plane's rows are contiguous.
+///
+/// # Errors
+///
+/// Returns an error if `arrays` is empty, `axis` is greater than 2, the
+/// shapes differ, or the output cannot be allocated.
+#[track_caller]
+pub fn stack<T: Copy>(
+    axis: Axis,
+    arrays: &[&Array2D<T>],
+) -> Result<Array3D<T>, Error> {
+    if axis.0 > 2 {
+        return Err(Error::IndexOutOfBounds {
+            index: axis.0,
+            len: 3,
+        });
+    }
+    let (nrows, ncols) = common_shape(arrays, None)?;
+    let count = arrays.len();
+    let mut stacked = match axis.0 {
+        0 => Array3D::new(count, nrows, ncols)?,
+        1 => Array3D::new(nrows, count, ncols)?,
+        _ => Array3D::new(nrows, ncols, count)?,
+    };
+    for (index, array) in arrays.iter().enumerate() {
+        let target = match axis.0 {
+            0 => stacked.plane_x_mut(index),
+            1 => stacked.plane_y_mut(index),
+            _ => stacked.plane_z_mut(index),
+        };
+        if let Some(target) = target {
+            copy_view(array.as_view(), target);
+        }
+    }
+    Ok(stacked)
+}
@@ -0,0 +1,279 @@
+#[cfg(feature = "rayon")]
+use rayon::{
+    iter::{IndexedParallelIterator, ParallelIterator},
+    slice::ParallelSliceMut,
+};
+
+use super::{Array2D, BorderMode, Layout};
+use crate::error::Error;
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// An element type the convolutions accept; samples are combined in `f32`.
+///
+/// Implemented for `f32` and `u8`; the trait is sealed.
+pub trait Sample: Copy + Send + Sync + sealed::Sealed {
+    #[doc(hidden)]
+    fn to_f32(self) -> f32;
+    #[doc(hidden)]
+    fn from_f32(value: f32) -> Self;
+}
+
+impl sealed::Sealed for f32 {}
+
+impl Sample for f32 {
+    #[inline(always)]
+    fn to_f32(self) -> f32 {
+        self
+    }
+
+    #[inline(always)]
+    fn from_f32(value: f32) -> Self {
+        value
+    }
+}
+
+impl sealed::Sealed for u8 {}
+
+impl Sample for u8 {
+    #[inline(always)]
+    fn to_f32(self) -> f32 {
+        f32::from(self)
+    }
+
+    /// Rounds to the nearest integer and saturates to `0..=255`.
+    #[inline(always)]
+    fn from_f32(value: f32) -> Self {
+        value.round().clamp(0.0, 255.0) as u8
+    }
+}
+
+/// Calls `op(row_index, row)` for every `width`-long row of `rows`, on the
+/// thread pool with the `rayon` feature.
+fn for_each_row<T: Send>(
+    rows: &mut [T],
+    width: usize,
+    op: impl Fn(usize, &mut [T]) + Send + Sync,
+) {
+    #[cfg(feature = "rayon")]
+    rows.par_chunks_mut(width)
+        .enumerate()
+        .for_each(|(index, row)| op(index, row));
+    #[cfg(not(feature = "rayon"))]
+    rows.chunks_mut(width)
+        .enumerate()
+        .for_each(|(index, row)| op(index, row));
+}
+
+/// Copies `source` into an `f32` matrix extended by the given margins, with
+/// the margins filled according to `border`.
+fn padded_f32<T: Sample>(
+    source: &Array2D<T>,
+    (top, bottom): (usize, usize),
+    (left, right): (usize, usize),
+    border: BorderMode<T>,
+) -> Result<Array2D<f32>, Error> {
+    let (nrows, ncols) = source.shape();
+    let width = left + ncols + right;
+    let fill = match border {
+        BorderMode::Constant(value) => value.to_f32(),
+        _ => 0.0,
+    };
+    let mut padded = Array2D::<f32>::zeroed(top + nrows + bottom, width)?;
+    for_each_row(padded.as_mut_slice(), width, |row, target| {
+        let Some(row) = border.source_index(row as isize - top as isize, nrows)
+        else {
+            target.fill(fill);
+            return;
+        };
+        let source = &source.as_slice()[row * ncols..(row + 1) * ncols];
+        for (col, slot) in target.iter_mut().enumerate() {
+            *slot = border
+                .source_index(col as isize - left as isize, ncols)
+                .map_or(fill, |col| source[col].to_f32());
+        }
+    });
+    Ok(padded)
+}
+
+/// Splits `kernel` into a column and a row factor whose outer product is
+/// the kernel, if it has rank one.
+fn separate(kernel: &Array2D<f32>) -> Option<(Vec<f32>, Vec<f32>)> {
+    let (pivot, &peak) = kernel
+        .as_slice()
+        .iter()
+        .enumerate()
+        .max_by(|(_, a), (_, b)| a.abs().total_cmp(&b.abs()))?;
+    if peak == 0.0 {
+        return None;
+    }
+    let (row, col) = (pivot / kernel.ncols(), pivot % kernel.ncols());
+    let column: Vec<f32> = (0..kernel.nrows())
+        .map(|i| kernel.as_slice()[i * kernel.ncols() + col])
+        .collect();
+    let factor: Vec<f32> =
+        kernel.row(row)?.iter().map(|&value| value / peak).collect();
+    let tolerance = peak.abs() * 1e-6;
+    let rank_one = kernel.rows().zip(&column).all(|(values, &scale)| {
+        values
+            .iter()
+            .zip(&factor)
+            .all(|(&value, &f)| (value - scale * f).abs() <= tolerance)
+    });
+    rank_one.then_some((column, factor))
+}
+
+impl<T: Sample> Array2D<T> {
+    /// Returns the 2-D convolution of the matrix with `kernel`, of the same
+    /// shape as the matrix.
+    ///
+    /// The kernel is flipped, as convolution requires (for symmetric blur
+    /// kernels this makes no difference), and centered on each output
+    /// element: its center is `(nrows / 2, ncols / 2)` of the kernel before
+    /// flipping. Samples past the edges come from `border`. Samples are
+    /// combined in `f32`; `u8` results are rounded and saturated.
+    ///
+    /// Kernels that are the outer product of a column and a row (box and
+    /// Gaussian blurs, Sobel) are detected and applied as two 1-D passes,
+    /// see [`Array2D::convolve2d_separable`]. With the `rayon` feature,
+    /// rows are computed on the thread pool. The result has the matrix's
+    /// layout; column-major matrices and kernels are worked on as row-major
+    /// copies.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the kernel is empty or an intermediate buffer
+    /// cannot be allocated.
+    ///
+    /// # Example
+    ///
+    /// ```rust ignore
+    /// let sobel_x = Array2D::from_array(
+    ///     [1.0, 0.0, -1.0, 2.0, 0.0, -2.0, 1.0, 0.0, -1.0][..].into(),
+    ///     3,
+    ///     3,
+    /// )?;
+    /// let edges = frame.convolve2d(&sobel_x, BorderMode::Replicate)?;
+    /// ```
+    #[track_caller]
+    pub fn convolve2d(
+        &self,
+        kernel: &Array2D<f32>,
+        border: BorderMode<T>,
+    ) -> Result<Self, Error> {
+        if kernel.is_empty() {
+            return Err(Error::Other(
+                "Cannot convolve with an empty kernel".into(),
+            ));
+        }
+        let mut weights = None;
+        let kernel = kernel.row_major(&mut weights)?;
+        if self.layout() == Layout::ColumnMajor {
+            let mut source = None;
+            return self
+                .row_major(&mut source)?
+                .convolve2d(kernel, border)?
+                .to_layout(Layout::ColumnMajor);
+        }
+        if let Some((column, row)) = separate(kernel) {
+            return self.convolve2d_separable(&column, &row, border);
+        }
+        let (nrows, ncols) = self.shape();
+        if self.is_empty() {
+            return Self::new(nrows, ncols);
+        }
+        let (kh, kw) = kernel.shape();
+        // correlating with the flipped kernel is convolving with the kernel
+        let flipped: Vec<f32> =
+            kernel.as_slice().iter().rev().copied().collect();
+        let (top, left) = (kh - 1 - kh / 2, kw - 1 - kw / 2);
+        let padded = padded_f32(self, (top, kh / 2), (left, kw / 2), border)?;
+        let width = padded.ncols();
+        let mut output = Self::new(nrows, ncols)?;
+        for_each_row(output.as_mut_slice(), ncols, |row, target| {
+            let mut sums = vec![0.0f32; ncols];
+            for (i, weights) in flipped.chunks_exact(kw).enumerate() {
+                let start = (row + i) * width;
+                let source = &padded.as_slice()[start..start + width];
+                for (j, &weight) in weights.iter().enumerate() {
+                    for (sum, &value) in sums.iter_mut().zip(&source[j..]) {
+                        *sum += weight * value;
+                    }
+                }
+            }
+            for (slot, sum) in target.iter_mut().zip(sums) {
+                unsafe { std::ptr::write(slot, T::from_f32(sum)) };
+            }
+        });
+        Ok(output)
+    }
+
+    /// Returns the convolution of the matrix with the kernel whose element
+    /// `(i, j)` is `column[i] * row[j]`, applied as a horizontal pass with
+    /// `row` followed by a vertical pass with `column`.
+    ///
+    /// This costs `column.len() + row.len()` multiplications per element
+    /// instead of their product. Centering, borders and rounding are as for
+    /// [`Array2D::convolve2d`]; the intermediate pass is kept in `f32`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if either factor is empty or an intermediate buffer
+    /// cannot be allocated.
+    #[track_caller]
+    pub fn convolve2d_separable(
+        &self,
+        column: &[f32],
+        row: &[f32],
+        border: BorderMode<T>,
+    ) -> Result<Self, Error> {
+        if column.is_empty() || row.is_empty() {
+            return Err(Error::Other(
+                "Cannot convolve with an empty kernel".into(),
+            ));
+        }
+        if self.layout() == Layout::ColumnMajor {
+            let mut source = None;
+            return self
+                .row_major(&mut source)?
+                .convolve2d_separable(column, row, border)?
+                .to_layout(Layout::ColumnMajor);
+        }
+        let (nrows, ncols) = self.shape();
+        if self.is_empty() {
+            return Self::new(nrows, ncols);
+        }
+        let (kh, kw) = (column.len(), row.len());
+        let (top, left) = (kh - 1 - kh / 2, kw - 1 - kw / 2);
+        let padded = padded_f32(self, (top, kh / 2), (left, kw / 2), border)?;
+        let width = padded.ncols();
+        let mut output = Self::new(nrows, ncols)?;
+        // horizontal pass over every padded row, so the vertical pass needs
+        // no border handling of its own
+        let mut horizontal = Array2D::<f32>::zeroed(padded.nrows(), ncols)?;
+        for_each_row(horizontal.as_mut_slice(), ncols, |index, target| {
+            let source = &padded.as_slice()[index * width..(index + 1) * width];
+            for (j, &weight) in row.iter().rev().enumerate() {
+                for (sum, &value) in target.iter_mut().zip(&source[j..]) {
+                    *sum += weight * value;
+                }
+            }
+        });
+        for_each_row(output.as_mut_slice(), ncols, |index, target| {
+            let mut sums = vec![0.0f32; ncols];
+            for (i, &weight) in column.iter().rev().enumerate() {
+                let start = (index + i) * ncols;
+                let source = &horizontal.as_slice()[start..start + ncols];
+                for (sum, &value) in sums.iter_mut().zip(source) {
+                    *sum += weight * value;
+                }
+            }
+            for (slot, sum) in target.iter_mut().zip(sums) {
+                unsafe { std::ptr::write(slot, T::from_f32(sum)) };
+            }
+        });
+        Ok(output)
+    }
+}
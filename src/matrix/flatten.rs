@@ -0,0 +1,80 @@
+use super::{Array2D, Array3D, ArrayND, ArrayView2D, ArrayViewND};
+use crate::{cow_array::CowArray, error::Error};
+
+impl<'a, T: Clone> ArrayViewND<'a, T> {
+    /// Returns the elements in logical (row-major) order as one flat array:
+    /// borrowed when the view is contiguous, or else copied.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a copy is needed and cannot be allocated.
+    ///
+    /// # Example
+    ///
+    /// ```rust ignore
+    /// // a kernel written for flat arrays, fed a tensor without a copy
+    /// let flat = tensor.flatten()?;
+    /// let peak = flat.iter().copied().fold(f32::MIN, f32::max);
+    /// ```
+    #[track_caller]
+    pub fn flatten(&self) -> Result<CowArray<'a, T>, Error> {
+        match self.as_slice() {
+            Some(slice) => Ok(CowArray::Borrowed(slice)),
+            None => Ok(CowArray::Owned(self.to_array_nd()?.into_array())),
+        }
+    }
+}
+
+impl<T: Clone> ArrayND<T> {
+    /// Returns the elements in logical order as one flat array; see
+    /// [`ArrayViewND::flatten`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the axes have been reordered, so a copy is
+    /// needed, and it cannot be allocated.
+    #[inline(always)]
+    #[track_caller]
+    pub fn flatten(&self) -> Result<CowArray<'_, T>, Error> {
+        self.view().flatten()
+    }
+}
+
+impl<'a, T: Clone> ArrayView2D<'a, T> {
+    /// Returns the elements row after row as one flat array: borrowed when
+    /// the view is contiguous, or else copied.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a copy is needed and cannot be allocated.
+    #[track_caller]
+    pub fn flatten(&self) -> Result<CowArray<'a, T>, Error> {
+        match self.as_slice() {
+            Some(slice) => Ok(CowArray::Borrowed(slice)),
+            None => Ok(CowArray::Owned(self.to_array2d()?.into_array())),
+        }
+    }
+}
+
+impl<T: Clone> Array2D<T> {
+    /// Returns the elements row after row as one flat array: borrowed from
+    /// a row-major matrix, copied from a column-major one.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a copy is needed and cannot be allocated.
+    #[inline(always)]
+    #[track_caller]
+    pub fn flatten(&self) -> Result<CowArray<'_, T>, Error> {
+        self.as_view().flatten()
+    }
+}
+
+impl<T> Array3D<T> {
+    /// Returns the elements in storage order, `z` fastest, as one flat
+    /// array; a volume is always contiguous, so this never copies.
+    #[inline(always)]
+    pub fn flatten(&self) -> CowArray<'_, T> {
+        CowArray::Borrowed(self.as_slice())
+    }
+}
@@ -0,0 +1,353 @@
+use std::{fmt, mem, ops::Range};
+
+use super::{Array2D, Layout};
+use crate::{
+    allocator::RawAllocator, error::Error, runtime_array::ArrayCStyle,
+};
+
+/// The byte layout of an interleaved image: `height` rows of `width`
+/// pixels, each pixel `channels` bytes, rows starting `pitch` bytes apart.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+struct ImageLayout {
+    width: usize,
+    height: usize,
+    pitch: usize,
+    channels: usize,
+}
+
+impl ImageLayout {
+    /// Checks the layout and that `len` bytes hold it; the last row may
+    /// stop short of its padding, as some capture APIs allocate that way.
+    fn new(
+        len: usize,
+        width: usize,
+        height: usize,
+        pitch: usize,
+        channels: usize,
+    ) -> Result<Self, Error> {
+        let row_len = width.checked_mul(channels).filter(|_| channels > 0);
+        let Some(row_len) = row_len.filter(|&row_len| row_len <= pitch) else {
+            return Err(Error::Other(format!(
+                "Image rows of {width} pixels with {channels} channels do not \
+                 fit a pitch of {pitch} bytes"
+            )));
+        };
+        let needed = match height {
+            0 => Some(0),
+            _ => pitch
+                .checked_mul(height - 1)
+                .and_then(|padded| padded.checked_add(row_len)),
+        };
+        if needed.is_none_or(|needed| needed > len) {
+            return Err(Error::Other(format!(
+                "A {width}x{height} image with a pitch of {pitch} bytes does \
+                 not fit in {len} bytes"
+            )));
+        }
+        Ok(Self {
+            width,
+            height,
+            pitch,
+            channels,
+        })
+    }
+
+    #[inline(always)]
+    fn row_len(&self) -> usize {
+        self.width * self.channels
+    }
+
+    /// Returns the byte range of row `y`, without its padding.
+    #[inline(always)]
+    fn row(&self, y: usize) -> Option<Range<usize>> {
+        let start = y.checked_mul(self.pitch).filter(|_| y < self.height)?;
+        Some(start..start + self.row_len())
+    }
+
+    /// Returns the byte range of pixel `(x, y)`.
+    #[inline(always)]
+    fn pixel(&self, x: usize, y: usize) -> Option<Range<usize>> {
+        let row = self.row(y).filter(|_| x < self.width)?;
+        let start = row.start + x * self.channels;
+        Some(start..start + self.channels)
+    }
+}
+
+/// A borrowed interleaved image over a byte buffer whose rows may be padded,
+/// such as a camera frame.
+///
+/// Pixel `(x, y)` is the `channels` bytes starting at
+/// `y * pitch + x * channels`. The bytes between the end of a row's pixels
+/// and the next row (the padding) are never exposed, so per-row processing
+/// cannot run into them by accident.
+///
+/// # Example
+///
+/// ```rust ignore
+/// use runnarr::matrix::ImageView;
+///
+/// // 640x480 RGB frame, rows padded to 2048 bytes by the driver
+/// let frame = ImageView::new(&buffer, 640, 480, 2048, 3)?;
+/// let [r, g, b] = frame.pixel(10, 20).unwrap() else { unreachable!() };
+/// for row in frame.rows() {
+///     process(row);
+/// }
+/// ```
+#[derive(Clone, Copy)]
+pub struct ImageView<'a> {
+    bytes: &'a [u8],
+    layout: ImageLayout,
+}
+
+impl<'a> ImageView<'a> {
+    /// Interprets `bytes` as a `width` by `height` image of `channels`-byte
+    /// pixels with rows `pitch` bytes apart.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `channels` is zero, a row of pixels is longer
+    /// than `pitch`, or `bytes` is too short for the image.
+    pub fn new(
+        bytes: &'a [u8],
+        width: usize,
+        height: usize,
+        pitch: usize,
+        channels: usize,
+    ) -> Result<Self, Error> {
+        let layout =
+            ImageLayout::new(bytes.len(), width, height, pitch, channels)?;
+        Ok(Self { bytes, layout })
+    }
+
+    /// Interprets a matrix of bytes as an image of `channels`-byte pixels,
+    /// one matrix row per image row.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the matrix is column-major, or if `channels` is
+    /// zero or does not divide the number of columns.
+    pub fn from_array2d(
+        matrix: &'a Array2D<u8>,
+        channels: usize,
+    ) -> Result<Self, Error> {
+        let (height, pitch) = matrix.shape();
+        if matrix.layout() == Layout::ColumnMajor {
+            return Err(Error::Other(
+                "Cannot view a column-major matrix as an image".into(),
+            ));
+        }
+        if channels == 0 || pitch % channels != 0 {
+            return Err(Error::Other(format!(
+                "Rows of {pitch} bytes do not hold whole pixels of {channels} \
+                 channels"
+            )));
+        }
+        Self::new(matrix.as_slice(), pitch / channels, height, pitch, channels)
+    }
+
+    /// Returns the width in pixels.
+    #[inline(always)]
+    pub fn width(&self) -> usize {
+        self.layout.width
+    }
+
+    /// Returns the height in pixels.
+    #[inline(always)]
+    pub fn height(&self) -> usize {
+        self.layout.height
+    }
+
+    /// Returns the distance, in bytes, between the starts of neighboring
+    /// rows.
+    #[inline(always)]
+    pub fn pitch(&self) -> usize {
+        self.layout.pitch
+    }
+
+    /// Returns the number of bytes per pixel.
+    #[inline(always)]
+    pub fn channels(&self) -> usize {
+        self.layout.channels
+    }
+
+    /// Returns the bytes of pixel `(x, y)`, or `None` if it is out of
+    /// bounds.
+    #[inline(always)]
+    pub fn pixel(&self, x: usize, y: usize) -> Option<&'a [u8]> {
+        Some(&self.bytes[self.layout.pixel(x, y)?])
+    }
+
+    /// Returns the pixels of row `y`, without the padding, or `None` if it
+    /// is out of bounds.
+    #[inline(always)]
+    pub fn row(&self, y: usize) -> Option<&'a [u8]> {
+        Some(&self.bytes[self.layout.row(y)?])
+    }
+
+    /// Returns an iterator over the rows, without the padding.
+    pub fn rows(
+        &self,
+    ) -> impl DoubleEndedIterator<Item = &'a [u8]> + ExactSizeIterator + 'a
+    {
+        let (bytes, layout) = (self.bytes, self.layout);
+        (0..layout.height).map(move |y| {
+            let start = y * layout.pitch;
+            &bytes[start..start + layout.row_len()]
+        })
+    }
+
+    /// Copies the pixels into a matrix with one row per image row and
+    /// `width * channels` columns, dropping the padding.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the matrix cannot be allocated.
+    #[track_caller]
+    pub fn to_array2d(&self) -> Result<Array2D<u8>, Error> {
+        let mut packed =
+            Array2D::<u8>::zeroed(self.height(), self.layout.row_len())?;
+        for (mut target, source) in packed.rows_mut().zip(self.rows()) {
+            for (slot, &byte) in target.iter_mut().zip(source) {
+                *slot = byte;
+            }
+        }
+        Ok(packed)
+    }
+}
+
+impl fmt::Debug for ImageView<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ImageView")
+            .field("width", &self.layout.width)
+            .field("height", &self.layout.height)
+            .field("pitch", &self.layout.pitch)
+            .field("channels", &self.layout.channels)
+            .finish()
+    }
+}
+
+/// A mutable interleaved image over a byte buffer whose rows may be padded;
+/// see [`ImageView`].
+pub struct ImageViewMut<'a> {
+    bytes: &'a mut [u8],
+    layout: ImageLayout,
+}
+
+impl<'a> ImageViewMut<'a> {
+    /// Interprets `bytes` as a mutable image; see [`ImageView::new`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `channels` is zero, a row of pixels is longer
+    /// than `pitch`, or `bytes` is too short for the image.
+    pub fn new(
+        bytes: &'a mut [u8],
+        width: usize,
+        height: usize,
+        pitch: usize,
+        channels: usize,
+    ) -> Result<Self, Error> {
+        let layout =
+            ImageLayout::new(bytes.len(), width, height, pitch, channels)?;
+        Ok(Self { bytes, layout })
+    }
+
+    /// Reborrows the image immutably.
+    #[inline(always)]
+    pub fn as_view(&self) -> ImageView<'_> {
+        ImageView {
+            bytes: self.bytes,
+            layout: self.layout,
+        }
+    }
+
+    /// Returns the width in pixels.
+    #[inline(always)]
+    pub fn width(&self) -> usize {
+        self.layout.width
+    }
+
+    /// Returns the height in pixels.
+    #[inline(always)]
+    pub fn height(&self) -> usize {
+        self.layout.height
+    }
+
+    /// Returns the distance, in bytes, between the starts of neighboring
+    /// rows.
+    #[inline(always)]
+    pub fn pitch(&self) -> usize {
+        self.layout.pitch
+    }
+
+    /// Returns the number of bytes per pixel.
+    #[inline(always)]
+    pub fn channels(&self) -> usize {
+        self.layout.channels
+    }
+
+    /// Returns the bytes of pixel `(x, y)` mutably, or `None` if it is out
+    /// of bounds.
+    #[inline(always)]
+    pub fn pixel_mut(&mut self, x: usize, y: usize) -> Option<&mut [u8]> {
+        Some(&mut self.bytes[self.layout.pixel(x, y)?])
+    }
+
+    /// Returns the pixels of row `y` mutably, without the padding, or `None`
+    /// if it is out of bounds.
+    #[inline(always)]
+    pub fn row_mut(&mut self, y: usize) -> Option<&mut [u8]> {
+        Some(&mut self.bytes[self.layout.row(y)?])
+    }
+
+    /// Returns an iterator over the rows, mutably and without the padding.
+    pub fn rows_mut(&mut self) -> impl ExactSizeIterator<Item = &mut [u8]> {
+        let (pitch, row_len) = (self.layout.pitch, self.layout.row_len());
+        let mut rest = &mut self.bytes[..];
+        (0..self.layout.height).map(move |_| {
+            // the last row may stop short of its padding
+            let split = pitch.min(rest.len());
+            let (row, tail) = mem::take(&mut rest).split_at_mut(split);
+            rest = tail;
+            &mut row[..row_len]
+        })
+    }
+}
+
+impl fmt::Debug for ImageViewMut<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.as_view().fmt(f)
+    }
+}
+
+impl<A: RawAllocator> ArrayCStyle<u8, A> {
+    /// Interprets the bytes as an image; see [`ImageView::new`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the layout is invalid or does not fit.
+    pub fn as_image(
+        &self,
+        width: usize,
+        height: usize,
+        pitch: usize,
+        channels: usize,
+    ) -> Result<ImageView<'_>, Error> {
+        ImageView::new(self.as_slice(), width, height, pitch, channels)
+    }
+
+    /// Interprets the bytes as a mutable image; see [`ImageView::new`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the layout is invalid or does not fit.
+    pub fn as_image_mut(
+        &mut self,
+        width: usize,
+        height: usize,
+        pitch: usize,
+        channels: usize,
+    ) -> Result<ImageViewMut<'_>, Error> {
+        ImageViewMut::new(self.as_mut_slice(), width, height, pitch, channels)
+    }
+}
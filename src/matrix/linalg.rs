@@ -0,0 +1,259 @@
+use std::ops::{Add, AddAssign, Div, Mul};
+
+#[cfg(feature = "rayon")]
+use rayon::{
+    iter::{IndexedParallelIterator, ParallelIterator},
+    slice::ParallelSliceMut,
+};
+
+use super::Array2D;
+use crate::{
+    allocator::RawAllocator, error::Error, runtime_array::ArrayCStyle,
+};
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// A floating-point element the linear-algebra kernels work on.
+///
+/// Implemented for `f32` and `f64`; the trait is sealed.
+pub trait Float:
+    Copy
+    + Default
+    + Add<Output = Self>
+    + AddAssign
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Send
+    + Sync
+    + sealed::Sealed
+{
+    #[doc(hidden)]
+    fn sqrt(self) -> Self;
+    #[doc(hidden)]
+    fn from_usize(value: usize) -> Self;
+}
+
+macro_rules! float {
+    ($($float:ty),* $(,)?) => {
+        $(
+            impl sealed::Sealed for $float {}
+
+            impl Float for $float {
+                #[inline(always)]
+                fn sqrt(self) -> Self {
+                    <$float>::sqrt(self)
+                }
+
+                #[inline(always)]
+                fn from_usize(value: usize) -> Self {
+                    value as $float
+                }
+            }
+        )*
+    };
+}
+
+float!(f32, f64);
+
+/// Rows of the output computed together, and the unit of work handed to the
+/// thread pool.
+const ROW_BLOCK: usize = 64;
+/// Length of the shared dimension walked per pass, so a strip of `b` stays
+/// in cache while a row block reuses it.
+const DEPTH_BLOCK: usize = 128;
+/// Columns of the output updated per pass.
+const COL_BLOCK: usize = 256;
+
+/// Returns the matrix product `a × b`.
+///
+/// The product is computed block by block so the strips of `a`, `b` and the
+/// result being combined stay in cache, with the innermost loop running
+/// along contiguous rows where the compiler can vectorize it. With the
+/// `rayon` feature, blocks of result rows are computed on the thread pool.
+/// Column-major operands are copied to row-major first; the result is
+/// row-major.
+///
+/// # Errors
+///
+/// Returns an error if `a` has a different number of columns than `b` has
+/// rows, or if the result or a row-major copy cannot be allocated.
+///
+/// # Example
+///
+/// ```rust ignore
+/// use runnarr::matrix::{matmul, Array2D};
+///
+/// let a = Array2D::<f32>::filled(4096, 4096, 0.5)?;
+/// let b = Array2D::<f32>::filled(4096, 4096, 2.0)?;
+/// let c = matmul(&a, &b)?;
+/// assert_eq!(c[(0, 0)], 4096.0);
+/// ```
+#[track_caller]
+pub fn matmul<T: Float>(
+    a: &Array2D<T>,
+    b: &Array2D<T>,
+) -> Result<Array2D<T>, Error> {
+    if a.ncols() != b.nrows() {
+        return Err(Error::Other(format!(
+            "Cannot multiply a {}x{} matrix by a {}x{} matrix",
+            a.nrows(),
+            a.ncols(),
+            b.nrows(),
+            b.ncols()
+        )));
+    }
+    // all-zero bytes are 0.0 for both float types
+    let mut c = Array2D::<T>::zeroed(a.nrows(), b.ncols())?;
+    let (depth, ncols) = (a.ncols(), b.ncols());
+    if c.is_empty() || depth == 0 {
+        return Ok(c);
+    }
+    let (mut a_copy, mut b_copy) = (None, None);
+    let a = a.row_major(&mut a_copy)?.as_slice();
+    let b = b.row_major(&mut b_copy)?.as_slice();
+    let chunk = ROW_BLOCK * ncols;
+    let kernel = |(block, c_rows): (usize, &mut [T])| {
+        let first = block * ROW_BLOCK * depth;
+        let a_rows = &a[first..first + c_rows.len() / ncols * depth];
+        multiply_block(a_rows, b, c_rows, depth, ncols);
+    };
+    #[cfg(feature = "rayon")]
+    {
+        c.as_mut_slice()
+            .par_chunks_mut(chunk)
+            .enumerate()
+            .for_each(kernel);
+    }
+    #[cfg(not(feature = "rayon"))]
+    {
+        c.as_mut_slice()
+            .chunks_mut(chunk)
+            .enumerate()
+            .for_each(kernel);
+    }
+    Ok(c)
+}
+
+/// Accumulates `a_rows × b` into `c_rows`, where `a_rows` holds the rows of
+/// `a` matching `c_rows` and every matrix is row-major.
+fn multiply_block<T: Float>(
+    a_rows: &[T],
+    b: &[T],
+    c_rows: &mut [T],
+    depth: usize,
+    ncols: usize,
+) {
+    for k0 in (0..depth).step_by(DEPTH_BLOCK) {
+        let k1 = (k0 + DEPTH_BLOCK).min(depth);
+        for j0 in (0..ncols).step_by(COL_BLOCK) {
+            let j1 = (j0 + COL_BLOCK).min(ncols);
+            for (a_row, c_row) in a_rows
+                .chunks_exact(depth)
+                .zip(c_rows.chunks_exact_mut(ncols))
+            {
+                let c_row = &mut c_row[j0..j1];
+                for k in k0..k1 {
+                    let scale = a_row[k];
+                    let b_row = &b[k * ncols + j0..k * ncols + j1];
+                    for (c, &b) in c_row.iter_mut().zip(b_row) {
+                        *c += scale * b;
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Returns the matrix-vector product `a × x`, with one element per row of
+/// `a`.
+///
+/// # Errors
+///
+/// Returns an error if `x` does not have one element per column of `a`, or
+/// if the result or a row-major copy of a column-major `a` cannot be
+/// allocated.
+#[track_caller]
+pub fn matvec<T: Float, A: RawAllocator>(
+    a: &Array2D<T>,
+    x: &ArrayCStyle<T, A>,
+) -> Result<ArrayCStyle<T>, Error> {
+    if a.ncols() != x.len() {
+        return Err(Error::Other(format!(
+            "Cannot multiply a {}x{} matrix by a vector of {} elements",
+            a.nrows(),
+            a.ncols(),
+            x.len()
+        )));
+    }
+    let mut copy = None;
+    let a = a.row_major(&mut copy)?;
+    let y = ArrayCStyle::<T>::new(a.nrows())?;
+    for (index, row) in a.rows().enumerate() {
+        unsafe {
+            y.ptr_mut()
+                .add(index)
+                .write(dot_product(row.iter(), x.as_slice()))
+        };
+    }
+    Ok(y)
+}
+
+/// Returns the outer product `x × yᵀ`, whose element `(i, j)` is
+/// `x[i] * y[j]`.
+///
+/// # Errors
+///
+/// Returns an error if the result cannot be allocated.
+#[track_caller]
+pub fn outer<T: Float, A: RawAllocator, B: RawAllocator>(
+    x: &ArrayCStyle<T, A>,
+    y: &ArrayCStyle<T, B>,
+) -> Result<Array2D<T>, Error> {
+    let mut product = Array2D::<T>::new(x.len(), y.len())?;
+    for (mut row, &scale) in product.rows_mut().zip(x.as_slice()) {
+        for (slot, &value) in row.iter_mut().zip(y.as_slice()) {
+            unsafe { std::ptr::write(slot, scale * value) };
+        }
+    }
+    Ok(product)
+}
+
+/// Sums the pairwise products of two equally long sequences.
+#[inline(always)]
+fn dot_product<'a, T: Float + 'a>(
+    x: impl IntoIterator<Item = &'a T>,
+    y: impl IntoIterator<Item = &'a T>,
+) -> T {
+    x.into_iter()
+        .zip(y)
+        .fold(T::default(), |sum, (&x, &y)| sum + x * y)
+}
+
+impl<T: Float, A: RawAllocator> ArrayCStyle<T, A> {
+    /// Returns the dot product of two vectors, the sum of their pairwise
+    /// products.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the vectors differ in length.
+    pub fn dot<B: RawAllocator>(
+        &self,
+        other: &ArrayCStyle<T, B>,
+    ) -> Result<T, Error> {
+        if self.len() != other.len() {
+            return Err(Error::Other(format!(
+                "Cannot take the dot product of vectors of {} and {} elements",
+                self.len(),
+                other.len()
+            )));
+        }
+        Ok(dot_product(self.as_slice(), other.as_slice()))
+    }
+
+    /// Returns the Euclidean (L2) norm of the vector.
+    pub fn norm(&self) -> T {
+        dot_product(self.as_slice(), self.as_slice()).sqrt()
+    }
+}
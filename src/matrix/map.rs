@@ -0,0 +1,107 @@
+use super::Array2D;
+use crate::{error::Error, runtime_array::ArrayCStyle};
+
+impl<T> Array2D<T> {
+    /// Returns a new matrix of the same shape and layout holding `f` of
+    /// every element.
+    ///
+    /// The elements are visited in storage order, so `f` runs over one
+    /// contiguous slice whatever the layout.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the new matrix cannot be allocated.
+    ///
+    /// # Example
+    ///
+    /// ```rust ignore
+    /// let gray: Array2D<u8> = load_gray()?;
+    /// let normalized = gray.map(|&value| value as f32 / 255.0)?;
+    /// ```
+    #[track_caller]
+    pub fn map<U>(
+        &self,
+        mut f: impl FnMut(&T) -> U,
+    ) -> Result<Array2D<U>, Error> {
+        let data = ArrayCStyle::<U>::new(self.len())?;
+        let target = data.ptr_mut();
+        for (index, value) in self.as_slice().iter().enumerate() {
+            unsafe { target.add(index).write(f(value)) };
+        }
+        Array2D::from_array_with_layout(
+            data,
+            self.nrows(),
+            self.ncols(),
+            self.layout(),
+        )
+    }
+
+    /// Applies `f` to every element in place, in storage order.
+    ///
+    /// # Example
+    ///
+    /// ```rust ignore
+    /// weights.map_inplace(|weight| *weight = weight.max(0.0));
+    /// ```
+    #[inline]
+    pub fn map_inplace(&mut self, f: impl FnMut(&mut T)) {
+        self.as_mut_slice().iter_mut().for_each(f);
+    }
+
+    /// Returns a new matrix in `self`'s layout holding `f` of every pair of
+    /// elements at the same `(row, col)` of `self` and `other`.
+    ///
+    /// When both matrices share a layout their storage is zipped slice by
+    /// slice; otherwise `other` is read at the position of each element of
+    /// `self`. Unlike the broadcasting operators on
+    /// [`ArrayND`](super::ArrayND), the shapes must match exactly.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the shapes differ or the new matrix cannot be
+    /// allocated.
+    ///
+    /// # Example
+    ///
+    /// ```rust ignore
+    /// let blended = foreground.zip_with(&background, |&fg, &bg| {
+    ///     alpha * fg + (1.0 - alpha) * bg
+    /// })?;
+    /// ```
+    #[track_caller]
+    pub fn zip_with<U, V>(
+        &self,
+        other: &Array2D<U>,
+        mut f: impl FnMut(&T, &U) -> V,
+    ) -> Result<Array2D<V>, Error> {
+        if self.shape() != other.shape() {
+            return Err(Error::Other(format!(
+                "Cannot zip a {}x{} matrix with a {}x{} matrix",
+                self.nrows(),
+                self.ncols(),
+                other.nrows(),
+                other.ncols()
+            )));
+        }
+        let data = ArrayCStyle::<V>::new(self.len())?;
+        let target = data.ptr_mut();
+        let (values, others) = (self.as_slice(), other.as_slice());
+        if self.layout() == other.layout() {
+            for (index, (x, y)) in values.iter().zip(others).enumerate() {
+                unsafe { target.add(index).write(f(x, y)) };
+            }
+        } else {
+            for (index, x) in values.iter().enumerate() {
+                let (row, col) = self.from_linear(index);
+                let y = &others[other.linear_index(row, col)];
+                unsafe { target.add(index).write(f(x, y)) };
+            }
+        }
+        Array2D::from_array_with_layout(
+            data,
+            self.nrows(),
+            self.ncols(),
+            self.layout(),
+        )
+    }
+}
@@ -0,0 +1,47 @@
+mod array2d;
+mod array3d;
+mod array_nd;
+mod axis;
+mod broadcast;
+mod concat;
+mod convolve;
+mod flatten;
+mod image;
+mod linalg;
+mod map;
+mod pad;
+mod pnm;
+mod tiles;
+mod view;
+
+pub use array2d::{Array2D, Layout};
+pub use array3d::{Array3D, Array3DView, Array3DViewMut};
+pub use array_nd::{ArrayND, ArrayViewMutND, ArrayViewND, NdIter, NdIterMut};
+pub use axis::Axis;
+pub use broadcast::broadcast_shape;
+pub use concat::{concat, stack};
+pub use convolve::Sample;
+pub use image::{ImageView, ImageViewMut};
+pub use linalg::{matmul, matvec, outer, Float};
+pub use pad::BorderMode;
+pub use pnm::{load_pnm, load_raw, read_pnm, read_raw};
+pub use tiles::{Tiles, TilesMut};
+pub use view::{ArrayView1D, ArrayView2D, ArrayViewMut1D, ArrayViewMut2D};
+
+use crate::error::Error;
+
+/// Multiplies the extents of a shape, failing if the element count
+/// overflows `usize`.
+fn element_count(shape: &[usize]) -> Result<usize, Error> {
+    shape
+        .iter()
+        .try_fold(1usize, |count, &extent| count.checked_mul(extent))
+        .ok_or_else(|| Error::Other(format!("Shape {shape:?} overflows usize")))
+}
+
+/// The error for an array whose length does not match the shape it is given.
+fn shape_mismatch(shape: &[usize], len: usize) -> Error {
+    Error::Other(format!(
+        "Shape {shape:?} does not match an array of {len} elements"
+    ))
+}
@@ -0,0 +1,199 @@
+use std::ops::Range;
+
+use super::{Array2D, Layout};
+use crate::error::Error;
+
+/// How samples outside a matrix are made up when an operation reaches past
+/// its edges.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub enum BorderMode<T> {
+    /// Every sample outside is this value.
+    Constant(T),
+    /// Samples outside repeat the nearest edge sample: `a a | a b c | c c`.
+    Replicate,
+    /// Samples outside mirror the inside without repeating the edge sample:
+    /// `c b | a b c | b a`.
+    Reflect,
+}
+
+impl<T> BorderMode<T> {
+    /// Maps a possibly out-of-range index along an axis of `len` samples to
+    /// the sample it reads, or `None` for a constant border.
+    pub(crate) fn source_index(
+        &self,
+        index: isize,
+        len: usize,
+    ) -> Option<usize> {
+        if (0..len as isize).contains(&index) {
+            return Some(index as usize);
+        }
+        match self {
+            Self::Constant(_) => None,
+            Self::Replicate => Some(index.clamp(0, len as isize - 1) as usize),
+            Self::Reflect if len == 1 => Some(0),
+            Self::Reflect => {
+                let period = 2 * (len as isize - 1);
+                let index = index.rem_euclid(period);
+                let index = if index < len as isize {
+                    index
+                } else {
+                    period - index
+                };
+                Some(index as usize)
+            }
+        }
+    }
+}
+
+/// Checks that `range` runs forwards and ends within `len`.
+fn check_range(range: &Range<usize>, len: usize) -> Result<(), Error> {
+    if range.start > range.end || range.end > len {
+        return Err(Error::IndexOutOfBounds {
+            index: range.end.max(range.start),
+            len,
+        });
+    }
+    Ok(())
+}
+
+impl<T: Copy> Array2D<T> {
+    /// Returns a copy of the matrix extended by `rows.0` rows above,
+    /// `rows.1` below, `cols.0` columns to the left and `cols.1` to the
+    /// right, filled according to `border`, in the matrix's layout.
+    ///
+    /// The output is allocated once; the inside of every row is copied in
+    /// one go, and rows past the top and bottom are copies of already
+    /// padded rows.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the padded matrix (or, for a column-major matrix,
+    /// a row-major copy) cannot be allocated, or if the matrix is empty and
+    /// a non-constant border has nothing to repeat.
+    ///
+    /// # Example
+    ///
+    /// ```rust ignore
+    /// // one pixel of mirrored border on every side for a 3x3 filter
+    /// let padded = frame.pad((1, 1), (1, 1), BorderMode::Reflect)?;
+    /// ```
+    #[track_caller]
+    pub fn pad(
+        &self,
+        (top, bottom): (usize, usize),
+        (left, right): (usize, usize),
+        border: BorderMode<T>,
+    ) -> Result<Self, Error> {
+        let (nrows, ncols) = self.shape();
+        let grows = top + bottom + left + right > 0;
+        if self.is_empty()
+            && grows
+            && !matches!(border, BorderMode::Constant(_))
+        {
+            return Err(Error::Other(
+                "Cannot pad an empty matrix by repeating its edges".into(),
+            ));
+        }
+        if self.layout() == Layout::ColumnMajor {
+            let mut source = None;
+            return self
+                .row_major(&mut source)?
+                .pad((top, bottom), (left, right), border)?
+                .to_layout(Layout::ColumnMajor);
+        }
+        let (height, width) = (top + nrows + bottom, left + ncols + right);
+        let mut padded = Self::new(height, width)?;
+        if padded.is_empty() {
+            return Ok(padded);
+        }
+        let sample = |row: &[T], col: usize| {
+            let index =
+                border.source_index(col as isize - left as isize, ncols);
+            match (index, border) {
+                (Some(col), _) => row[col],
+                (None, BorderMode::Constant(value)) => value,
+                (None, _) => unreachable!("only constant borders skip samples"),
+            }
+        };
+        let target = padded.as_mut_slice();
+        // the source rows first, then the rows around them
+        for row in 0..nrows {
+            let source = &self.as_slice()[row * ncols..(row + 1) * ncols];
+            let start = (top + row) * width;
+            let target = &mut target[start..start + width];
+            target[left..left + ncols].copy_from_slice(source);
+            for col in (0..left).chain(left + ncols..width) {
+                target[col] = sample(source, col);
+            }
+        }
+        for row in (0..top).chain(top + nrows..height) {
+            let start = row * width;
+            match border.source_index(row as isize - top as isize, nrows) {
+                Some(source) => {
+                    let source = (top + source) * width;
+                    target.copy_within(source..source + width, start);
+                }
+                None => {
+                    let BorderMode::Constant(value) = border else {
+                        unreachable!("only constant borders skip rows")
+                    };
+                    target[start..start + width].fill(value);
+                }
+            }
+        }
+        Ok(padded)
+    }
+
+    /// Returns a copy of rows `rows` and columns `cols` of the matrix, in
+    /// its layout, copying the part of each row (or column, if the matrix
+    /// is column-major) in one go.
+    ///
+    /// Use [`Array2D::view`] to look at the region without copying.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if either range is out of bounds or the copy cannot
+    /// be allocated.
+    #[track_caller]
+    pub fn crop(
+        &self,
+        rows: Range<usize>,
+        cols: Range<usize>,
+    ) -> Result<Self, Error> {
+        let (nrows, ncols) = self.shape();
+        check_range(&rows, nrows)?;
+        check_range(&cols, ncols)?;
+        if self.layout() == Layout::ColumnMajor {
+            let mut cropped = Self::new_with_layout(
+                rows.len(),
+                cols.len(),
+                Layout::ColumnMajor,
+            )?;
+            let height = rows.len();
+            if cropped.is_empty() {
+                return Ok(cropped);
+            }
+            let targets = cropped.as_mut_slice().chunks_exact_mut(height);
+            for (target, col) in targets.zip(cols) {
+                let start = col * nrows;
+                target.copy_from_slice(
+                    &self.as_slice()[start + rows.start..start + rows.end],
+                );
+            }
+            return Ok(cropped);
+        }
+        let mut cropped = Self::new(rows.len(), cols.len())?;
+        if cropped.is_empty() {
+            return Ok(cropped);
+        }
+        let width = cols.len();
+        let targets = cropped.as_mut_slice().chunks_exact_mut(width);
+        for (target, row) in targets.zip(rows) {
+            let start = row * ncols;
+            target.copy_from_slice(
+                &self.as_slice()[start + cols.start..start + cols.end],
+            );
+        }
+        Ok(cropped)
+    }
+}
@@ -0,0 +1,195 @@
+use std::{
+    fs::File,
+    io::{BufReader, BufWriter, Read, Write},
+    path::Path,
+};
+
+use super::{Array2D, ImageView};
+use crate::error::Error;
+
+fn invalid_header(reason: &str) -> Error {
+    Error::Other(format!("Invalid PNM header: {reason}"))
+}
+
+fn read_byte<R: Read>(reader: &mut R) -> Result<u8, Error> {
+    let mut byte = [0];
+    reader.read_exact(&mut byte)?;
+    Ok(byte[0])
+}
+
+/// Reads one decimal header field, skipping the whitespace and `#` comments
+/// before it, and consumes the single whitespace byte after it.
+fn read_field<R: Read>(reader: &mut R) -> Result<usize, Error> {
+    let mut byte = read_byte(reader)?;
+    loop {
+        match byte {
+            b'#' => {
+                while byte != b'\n' {
+                    byte = read_byte(reader)?;
+                }
+            }
+            _ if byte.is_ascii_whitespace() => byte = read_byte(reader)?,
+            _ => break,
+        }
+    }
+    let mut value = 0usize;
+    while byte.is_ascii_digit() {
+        value = value
+            .checked_mul(10)
+            .and_then(|value| value.checked_add(usize::from(byte - b'0')))
+            .ok_or_else(|| invalid_header("field overflows usize"))?;
+        byte = read_byte(reader)?;
+    }
+    if !byte.is_ascii_whitespace() {
+        return Err(invalid_header("expected a decimal field"));
+    }
+    Ok(value)
+}
+
+/// Reads a binary PGM (`P5`, grayscale) or PPM (`P6`, RGB) image with 8-bit
+/// samples.
+///
+/// # Returns
+///
+/// The pixels as a matrix with one row per image row and `width * channels`
+/// columns, and the number of channels: 1 for PGM, 3 for PPM. Wrap them
+/// with [`ImageView::from_array2d`] for per-pixel access.
+///
+/// # Errors
+///
+/// Returns an error if reading fails, the header is malformed or the
+/// samples are wider than 8 bits.
+///
+/// # Example
+///
+/// ```rust ignore
+/// let (pixels, channels) = runnarr::matrix::load_pnm("frame.ppm")?;
+/// let frame = ImageView::from_array2d(&pixels, channels)?;
+/// ```
+pub fn read_pnm<R: Read>(mut reader: R) -> Result<(Array2D<u8>, usize), Error> {
+    let magic = [read_byte(&mut reader)?, read_byte(&mut reader)?];
+    let channels = match &magic {
+        b"P5" => 1,
+        b"P6" => 3,
+        _ => return Err(invalid_header("expected P5 or P6")),
+    };
+    let width = read_field(&mut reader)?;
+    let height = read_field(&mut reader)?;
+    let max_value = read_field(&mut reader)?;
+    if !(1..=255).contains(&max_value) {
+        return Err(invalid_header("only 8-bit samples are supported"));
+    }
+    let row_len = width
+        .checked_mul(channels)
+        .ok_or_else(|| invalid_header("width overflows usize"))?;
+    let mut pixels = Array2D::zeroed(height, row_len)?;
+    reader.read_exact(pixels.as_mut_slice())?;
+    Ok((pixels, channels))
+}
+
+/// Reads a PGM or PPM image from the file at `path`; see [`read_pnm`].
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be opened or read, or does not hold
+/// a supported image.
+pub fn load_pnm<P: AsRef<Path>>(
+    path: P,
+) -> Result<(Array2D<u8>, usize), Error> {
+    read_pnm(BufReader::new(File::open(path)?))
+}
+
+/// Reads `height` rows of `width` pixels of `channels` bytes, with no
+/// header and no padding.
+///
+/// # Errors
+///
+/// Returns an error if reading fails or ends early, or the matrix cannot be
+/// allocated.
+pub fn read_raw<R: Read>(
+    mut reader: R,
+    width: usize,
+    height: usize,
+    channels: usize,
+) -> Result<Array2D<u8>, Error> {
+    let row_len = width.checked_mul(channels).ok_or_else(|| {
+        Error::Other("Raw image row length overflows usize".into())
+    })?;
+    let mut pixels = Array2D::zeroed(height, row_len)?;
+    reader.read_exact(pixels.as_mut_slice())?;
+    Ok(pixels)
+}
+
+/// Reads a raw image from the file at `path`; see [`read_raw`].
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be opened or is too short.
+pub fn load_raw<P: AsRef<Path>>(
+    path: P,
+    width: usize,
+    height: usize,
+    channels: usize,
+) -> Result<Array2D<u8>, Error> {
+    read_raw(BufReader::new(File::open(path)?), width, height, channels)
+}
+
+impl ImageView<'_> {
+    /// Writes the image as a binary PGM (one channel) or PPM (three
+    /// channels), dropping the row padding.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the image has another number of channels or
+    /// writing fails.
+    pub fn write_pnm<W: Write>(&self, mut writer: W) -> Result<(), Error> {
+        let magic = match self.channels() {
+            1 => "P5",
+            3 => "P6",
+            channels => {
+                return Err(Error::Other(format!(
+                    "Cannot write a {channels}-channel image as PGM or PPM"
+                )))
+            }
+        };
+        write!(writer, "{magic}\n{} {}\n255\n", self.width(), self.height())?;
+        self.write_raw(writer)
+    }
+
+    /// Writes the image to a PGM or PPM file at `path`, creating or
+    /// truncating it; see [`ImageView::write_pnm`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be created or written, or the
+    /// image has an unsupported number of channels.
+    pub fn save_pnm<P: AsRef<Path>>(&self, path: P) -> Result<(), Error> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write_pnm(&mut writer)?;
+        Ok(writer.flush()?)
+    }
+
+    /// Writes the pixels row after row with no header and no padding.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if writing fails.
+    pub fn write_raw<W: Write>(&self, mut writer: W) -> Result<(), Error> {
+        for row in self.rows() {
+            writer.write_all(row)?;
+        }
+        Ok(())
+    }
+
+    /// Writes the pixels to a raw file at `path`, creating or truncating it;
+    /// see [`ImageView::write_raw`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be created or written.
+    pub fn save_raw<P: AsRef<Path>>(&self, path: P) -> Result<(), Error> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write_raw(&mut writer)?;
+        Ok(writer.flush()?)
+    }
+}
@@ -0,0 +1,261 @@
+use std::{iter::FusedIterator, marker::PhantomData};
+
+use super::{Array2D, ArrayView2D, ArrayViewMut2D};
+use crate::error::Error;
+
+/// The tiles of a matrix in row-major order, the last tile of each row and
+/// column of tiles cut short by the matrix edges.
+#[derive(Debug, Clone, Copy)]
+struct TileGrid {
+    shape: (usize, usize),
+    tile: (usize, usize),
+    grid_cols: usize,
+    front: usize,
+    back: usize,
+}
+
+impl TileGrid {
+    fn new(
+        shape: (usize, usize),
+        (tile_rows, tile_cols): (usize, usize),
+    ) -> Result<Self, Error> {
+        if tile_rows == 0 || tile_cols == 0 {
+            return Err(Error::Other(format!(
+                "Cannot split a matrix into {tile_rows}x{tile_cols} tiles"
+            )));
+        }
+        let grid_rows = shape.0.div_ceil(tile_rows);
+        let grid_cols = shape.1.div_ceil(tile_cols);
+        Ok(Self {
+            shape,
+            tile: (tile_rows, tile_cols),
+            grid_cols,
+            front: 0,
+            back: grid_rows * grid_cols,
+        })
+    }
+
+    #[inline(always)]
+    fn len(&self) -> usize {
+        self.back - self.front
+    }
+
+    /// Returns the origin and shape of tile `index`.
+    #[inline(always)]
+    fn region(&self, index: usize) -> ((usize, usize), (usize, usize)) {
+        let row = index / self.grid_cols * self.tile.0;
+        let col = index % self.grid_cols * self.tile.1;
+        let nrows = self.tile.0.min(self.shape.0 - row);
+        let ncols = self.tile.1.min(self.shape.1 - col);
+        ((row, col), (nrows, ncols))
+    }
+
+    fn next(&mut self) -> Option<((usize, usize), (usize, usize))> {
+        (self.front < self.back).then(|| {
+            self.front += 1;
+            self.region(self.front - 1)
+        })
+    }
+
+    fn next_back(&mut self) -> Option<((usize, usize), (usize, usize))> {
+        (self.front < self.back).then(|| {
+            self.back -= 1;
+            self.region(self.back)
+        })
+    }
+}
+
+/// An iterator over the tiles of a matrix, as `((row, col), view)` pairs
+/// where `(row, col)` is the tile's first element; see
+/// [`ArrayView2D::tiles`].
+#[derive(Clone)]
+pub struct Tiles<'a, T> {
+    view: ArrayView2D<'a, T>,
+    grid: TileGrid,
+}
+
+impl<'a, T> Tiles<'a, T> {
+    fn tile(
+        &self,
+        ((row, col), shape): ((usize, usize), (usize, usize)),
+    ) -> ((usize, usize), ArrayView2D<'a, T>) {
+        let (row_stride, col_stride) = self.view.strides();
+        let offset = row * row_stride + col * col_stride;
+        let ptr = unsafe { self.view.as_ptr().add(offset) };
+        let tile =
+            unsafe { ArrayView2D::from_raw(ptr, shape, self.view.strides()) };
+        ((row, col), tile)
+    }
+}
+
+impl<'a, T> Iterator for Tiles<'a, T> {
+    type Item = ((usize, usize), ArrayView2D<'a, T>);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let region = self.grid.next()?;
+        Some(self.tile(region))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.grid.len(), Some(self.grid.len()))
+    }
+}
+
+impl<T> DoubleEndedIterator for Tiles<'_, T> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let region = self.grid.next_back()?;
+        Some(self.tile(region))
+    }
+}
+
+impl<T> ExactSizeIterator for Tiles<'_, T> {}
+
+impl<T> FusedIterator for Tiles<'_, T> {}
+
+/// An iterator over the tiles of a matrix as mutable views, paired with
+/// their first element; see [`ArrayViewMut2D::into_tiles`].
+pub struct TilesMut<'a, T> {
+    ptr: *mut T,
+    strides: (usize, usize),
+    grid: TileGrid,
+    _marker: PhantomData<&'a mut [T]>,
+}
+
+// The tiles are disjoint exclusive borrows of `T`s, like
+// `slice::ChunksMut`.
+unsafe impl<T: Send> Send for TilesMut<'_, T> {}
+unsafe impl<T: Sync> Sync for TilesMut<'_, T> {}
+
+impl<'a, T> TilesMut<'a, T> {
+    fn tile(
+        &self,
+        ((row, col), shape): ((usize, usize), (usize, usize)),
+    ) -> ((usize, usize), ArrayViewMut2D<'a, T>) {
+        let offset = row * self.strides.0 + col * self.strides.1;
+        // every tile is handed out once and no two tiles overlap
+        let tile = unsafe {
+            ArrayViewMut2D::from_raw(self.ptr.add(offset), shape, self.strides)
+        };
+        ((row, col), tile)
+    }
+}
+
+impl<'a, T> Iterator for TilesMut<'a, T> {
+    type Item = ((usize, usize), ArrayViewMut2D<'a, T>);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let region = self.grid.next()?;
+        Some(self.tile(region))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.grid.len(), Some(self.grid.len()))
+    }
+}
+
+impl<T> DoubleEndedIterator for TilesMut<'_, T> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let region = self.grid.next_back()?;
+        Some(self.tile(region))
+    }
+}
+
+impl<T> ExactSizeIterator for TilesMut<'_, T> {}
+
+impl<T> FusedIterator for TilesMut<'_, T> {}
+
+impl<'a, T> ArrayView2D<'a, T> {
+    /// Returns an iterator over `tile_rows` by `tile_cols` sub-views,
+    /// row of tiles after row of tiles, each paired with the position of
+    /// its first element. Tiles along the bottom and right edges are cut
+    /// short when the shape is not a multiple of the tile shape.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if either tile extent is zero.
+    ///
+    /// # Example
+    ///
+    /// ```rust ignore
+    /// // blocked transpose: each tile is read and written while in cache
+    /// for ((row, col), tile) in source.tiles(32, 32)? {
+    ///     let mut target = transposed
+    ///         .view_mut(col..col + tile.ncols(), row..row + tile.nrows())
+    ///         .unwrap();
+    ///     for i in 0..tile.nrows() {
+    ///         for j in 0..tile.ncols() {
+    ///             target[(j, i)] = tile[(i, j)];
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    pub fn tiles(
+        &self,
+        tile_rows: usize,
+        tile_cols: usize,
+    ) -> Result<Tiles<'a, T>, Error> {
+        Ok(Tiles {
+            view: *self,
+            grid: TileGrid::new(self.shape(), (tile_rows, tile_cols))?,
+        })
+    }
+}
+
+impl<'a, T> ArrayViewMut2D<'a, T> {
+    /// Consumes the view, returning an iterator over its tiles as mutable
+    /// sub-views; see [`ArrayView2D::tiles`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if either tile extent is zero.
+    pub fn into_tiles(
+        mut self,
+        tile_rows: usize,
+        tile_cols: usize,
+    ) -> Result<TilesMut<'a, T>, Error> {
+        Ok(TilesMut {
+            grid: TileGrid::new(self.shape(), (tile_rows, tile_cols))?,
+            strides: self.strides(),
+            ptr: self.as_mut_ptr(),
+            _marker: PhantomData,
+        })
+    }
+}
+
+impl<T> Array2D<T> {
+    /// Returns an iterator over the matrix's tiles; see
+    /// [`ArrayView2D::tiles`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if either tile extent is zero.
+    #[inline(always)]
+    pub fn tiles(
+        &self,
+        tile_rows: usize,
+        tile_cols: usize,
+    ) -> Result<Tiles<'_, T>, Error> {
+        self.as_view().tiles(tile_rows, tile_cols)
+    }
+
+    /// Returns an iterator over the matrix's tiles as mutable views; see
+    /// [`ArrayView2D::tiles`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if either tile extent is zero.
+    #[inline(always)]
+    pub fn tiles_mut(
+        &mut self,
+        tile_rows: usize,
+        tile_cols: usize,
+    ) -> Result<TilesMut<'_, T>, Error> {
+        self.as_view_mut().into_tiles(tile_rows, tile_cols)
+    }
+}
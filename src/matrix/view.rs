@@ -0,0 +1,720 @@
+use std::{
+    fmt,
+    marker::PhantomData,
+    ops::{Add, Range},
+    slice,
+};
+
+use super::Array2D;
+use crate::{error::Error, runtime_array::ArrayCStyle};
+
+/// A borrowed, possibly strided, two-dimensional window into another
+/// array's elements.
+///
+/// Element `(row, col)` lives `row * row_stride + col * col_stride`
+/// elements past the start of the window, so one type covers whole
+/// matrices, planes of volumes, transposes and sub-regions without copying.
+pub struct ArrayView2D<'a, T> {
+    ptr: *const T,
+    nrows: usize,
+    ncols: usize,
+    row_stride: usize,
+    col_stride: usize,
+    _marker: PhantomData<&'a [T]>,
+}
+
+// A view is a shared borrow of `T`s, exactly like `&[T]`.
+unsafe impl<T: Sync> Send for ArrayView2D<'_, T> {}
+unsafe impl<T: Sync> Sync for ArrayView2D<'_, T> {}
+
+impl<T> Clone for ArrayView2D<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for ArrayView2D<'_, T> {}
+
+impl<'a, T> ArrayView2D<'a, T> {
+    /// # Safety
+    ///
+    /// Every `(row, col)` inside the shape must address an initialized
+    /// element that stays borrowed for `'a`.
+    pub(crate) unsafe fn from_raw(
+        ptr: *const T,
+        (nrows, ncols): (usize, usize),
+        (row_stride, col_stride): (usize, usize),
+    ) -> Self {
+        Self {
+            ptr,
+            nrows,
+            ncols,
+            row_stride,
+            col_stride,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns the number of rows.
+    #[inline(always)]
+    pub fn nrows(&self) -> usize {
+        self.nrows
+    }
+
+    /// Returns the number of columns.
+    #[inline(always)]
+    pub fn ncols(&self) -> usize {
+        self.ncols
+    }
+
+    /// Returns `(nrows, ncols)`.
+    #[inline(always)]
+    pub fn shape(&self) -> (usize, usize) {
+        (self.nrows, self.ncols)
+    }
+
+    /// Returns the distance, in elements, between neighboring rows and
+    /// between neighboring columns.
+    #[inline(always)]
+    pub fn strides(&self) -> (usize, usize) {
+        (self.row_stride, self.col_stride)
+    }
+
+    /// Returns a pointer to the element at `(0, 0)`.
+    #[inline(always)]
+    pub fn as_ptr(&self) -> *const T {
+        self.ptr
+    }
+
+    /// Returns the number of elements.
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.nrows * self.ncols
+    }
+
+    /// Returns `true` if the view has no elements.
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the element at `(row, col)`, or `None` if it is out of
+    /// bounds.
+    #[inline(always)]
+    pub fn get(&self, row: usize, col: usize) -> Option<&'a T> {
+        (row < self.nrows && col < self.ncols).then(|| unsafe {
+            &*self.ptr.add(row * self.row_stride + col * self.col_stride)
+        })
+    }
+
+    /// Returns row `row` as a strided view, or `None` if it is out of
+    /// bounds.
+    #[inline(always)]
+    pub fn row(&self, row: usize) -> Option<ArrayView1D<'a, T>> {
+        (row < self.nrows).then(|| unsafe {
+            let ptr = self.ptr.add(row * self.row_stride);
+            ArrayView1D::from_raw(ptr, self.ncols, self.col_stride)
+        })
+    }
+
+    /// Returns column `col` as a strided view, or `None` if it is out of
+    /// bounds.
+    #[inline(always)]
+    pub fn col(&self, col: usize) -> Option<ArrayView1D<'a, T>> {
+        (col < self.ncols).then(|| unsafe {
+            let ptr = self.ptr.add(col * self.col_stride);
+            ArrayView1D::from_raw(ptr, self.nrows, self.row_stride)
+        })
+    }
+
+    /// Returns the sub-view covering `rows` × `cols`, without copying, or
+    /// `None` if either range is out of bounds.
+    ///
+    /// # Example
+    ///
+    /// ```rust ignore
+    /// let window = image.as_view().view(8..16, 8..16).unwrap();
+    /// let mean = window.iter().sum::<f32>() / window.len() as f32;
+    /// ```
+    pub fn view(&self, rows: Range<usize>, cols: Range<usize>) -> Option<Self> {
+        let strides = self.strides();
+        let offset = region_offset(self.shape(), strides, &rows, &cols)?;
+        Some(unsafe {
+            Self::from_raw(
+                self.ptr.add(offset),
+                (rows.len(), cols.len()),
+                strides,
+            )
+        })
+    }
+
+    /// Returns the main diagonal as a strided view.
+    #[inline(always)]
+    pub fn diag(&self) -> ArrayView1D<'a, T> {
+        self.diag_offset(0)
+    }
+
+    /// Returns diagonal `offset` as a strided view: the main diagonal for 0,
+    /// the ones above it for positive offsets and below it for negative
+    /// ones. Diagonals past the corners are empty.
+    pub fn diag_offset(&self, offset: isize) -> ArrayView1D<'a, T> {
+        let (start, len, stride) =
+            diagonal(self.shape(), self.strides(), offset);
+        unsafe { ArrayView1D::from_raw(self.ptr.add(start), len, stride) }
+    }
+
+    /// Returns the sum of the main diagonal.
+    ///
+    /// `T::default()` must be the additive identity, as it is for the
+    /// primitive numbers.
+    pub fn trace(&self) -> T
+    where
+        T: Copy + Default + Add<Output = T>,
+    {
+        self.diag()
+            .iter()
+            .fold(T::default(), |sum, &value| sum + value)
+    }
+
+    /// Returns the transposed view, by swapping the strides.
+    #[inline(always)]
+    pub fn t(&self) -> Self {
+        unsafe {
+            Self::from_raw(
+                self.ptr,
+                (self.ncols, self.nrows),
+                (self.col_stride, self.row_stride),
+            )
+        }
+    }
+
+    /// Returns an iterator over the elements in row-major order.
+    pub fn iter(&self) -> impl Iterator<Item = &'a T> + 'a {
+        let view = *self;
+        (0..view.nrows).flat_map(move |row| {
+            (0..view.ncols).map(move |col| unsafe {
+                &*view.ptr.add(row * view.row_stride + col * view.col_stride)
+            })
+        })
+    }
+
+    /// Returns `true` if the elements are laid out row-major without gaps.
+    pub fn is_contiguous(&self) -> bool {
+        (self.col_stride == 1 || self.ncols <= 1)
+            && (self.row_stride == self.ncols || self.nrows <= 1)
+    }
+
+    /// Returns the elements as one row-major slice, or `None` if the view is
+    /// not contiguous.
+    pub fn as_slice(&self) -> Option<&'a [T]> {
+        self.is_contiguous()
+            .then(|| unsafe { slice::from_raw_parts(self.ptr, self.len()) })
+    }
+
+    /// Copies the view into a new matrix.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the matrix cannot be allocated.
+    #[track_caller]
+    pub fn to_array2d(&self) -> Result<Array2D<T>, Error>
+    where
+        T: Clone,
+    {
+        let matrix = Array2D::new(self.nrows, self.ncols)?;
+        let target: *mut T = matrix.as_array().ptr_mut();
+        for (index, value) in self.iter().enumerate() {
+            unsafe { target.add(index).write(value.clone()) };
+        }
+        Ok(matrix)
+    }
+}
+
+#[cfg(not(feature = "no-panic"))]
+impl<T> std::ops::Index<(usize, usize)> for ArrayView2D<'_, T> {
+    type Output = T;
+    #[track_caller]
+    fn index(&self, (row, col): (usize, usize)) -> &Self::Output {
+        self.get(row, col).expect("Index out of bounds")
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for ArrayView2D<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        debug_rows(f, *self)
+    }
+}
+
+/// Formats a view as a list of rows.
+fn debug_rows<T: fmt::Debug>(
+    f: &mut fmt::Formatter<'_>,
+    view: ArrayView2D<'_, T>,
+) -> fmt::Result {
+    let mut rows = f.debug_list();
+    for row in 0..view.nrows {
+        let values = (0..view.ncols).filter_map(|col| view.get(row, col));
+        rows.entry(&DebugIter(values));
+    }
+    rows.finish()
+}
+
+/// Formats the items of a cloneable iterator as a list.
+struct DebugIter<I>(I);
+
+impl<I: Iterator + Clone> fmt::Debug for DebugIter<I>
+where
+    I::Item: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.0.clone()).finish()
+    }
+}
+
+/// Locates diagonal `offset` of a matrix: above the main diagonal for
+/// positive offsets, below it for negative ones.
+///
+/// # Returns
+///
+/// The element offset of its first element, its length and its stride.
+pub(super) fn diagonal(
+    (nrows, ncols): (usize, usize),
+    (row_stride, col_stride): (usize, usize),
+    offset: isize,
+) -> (usize, usize, usize) {
+    let shift = offset.unsigned_abs();
+    let (start, len) = if offset >= 0 {
+        (shift * col_stride, nrows.min(ncols.saturating_sub(shift)))
+    } else {
+        (shift * row_stride, nrows.saturating_sub(shift).min(ncols))
+    };
+    // an empty diagonal must not point past the matrix
+    let start = if len == 0 { 0 } else { start };
+    (start, len, row_stride + col_stride)
+}
+
+/// Returns the element offset of the region's first element, or `None` if
+/// either range falls outside `shape` or runs backwards.
+fn region_offset(
+    (nrows, ncols): (usize, usize),
+    (row_stride, col_stride): (usize, usize),
+    rows: &Range<usize>,
+    cols: &Range<usize>,
+) -> Option<usize> {
+    let fits = |range: &Range<usize>, len| {
+        range.start <= range.end && range.end <= len
+    };
+    if !fits(rows, nrows) || !fits(cols, ncols) {
+        return None;
+    }
+    // an empty region must not point past the matrix
+    if rows.is_empty() || cols.is_empty() {
+        return Some(0);
+    }
+    Some(rows.start * row_stride + cols.start * col_stride)
+}
+
+/// A mutable, possibly strided, two-dimensional window into another array's
+/// elements; see [`ArrayView2D`].
+pub struct ArrayViewMut2D<'a, T> {
+    ptr: *mut T,
+    nrows: usize,
+    ncols: usize,
+    row_stride: usize,
+    col_stride: usize,
+    _marker: PhantomData<&'a mut [T]>,
+}
+
+// A mutable view is an exclusive borrow of `T`s, exactly like `&mut [T]`.
+unsafe impl<T: Send> Send for ArrayViewMut2D<'_, T> {}
+unsafe impl<T: Sync> Sync for ArrayViewMut2D<'_, T> {}
+
+impl<'a, T> ArrayViewMut2D<'a, T> {
+    /// # Safety
+    ///
+    /// Every `(row, col)` inside the shape must address a distinct
+    /// initialized element that stays exclusively borrowed for `'a`.
+    pub(crate) unsafe fn from_raw(
+        ptr: *mut T,
+        (nrows, ncols): (usize, usize),
+        (row_stride, col_stride): (usize, usize),
+    ) -> Self {
+        Self {
+            ptr,
+            nrows,
+            ncols,
+            row_stride,
+            col_stride,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Reborrows the view immutably.
+    #[inline(always)]
+    pub fn as_view(&self) -> ArrayView2D<'_, T> {
+        unsafe {
+            ArrayView2D::from_raw(
+                self.ptr,
+                self.shape(),
+                (self.row_stride, self.col_stride),
+            )
+        }
+    }
+
+    /// Returns the number of rows.
+    #[inline(always)]
+    pub fn nrows(&self) -> usize {
+        self.nrows
+    }
+
+    /// Returns the number of columns.
+    #[inline(always)]
+    pub fn ncols(&self) -> usize {
+        self.ncols
+    }
+
+    /// Returns `(nrows, ncols)`.
+    #[inline(always)]
+    pub fn shape(&self) -> (usize, usize) {
+        (self.nrows, self.ncols)
+    }
+
+    /// Returns the distance, in elements, between neighboring rows and
+    /// between neighboring columns.
+    #[inline(always)]
+    pub fn strides(&self) -> (usize, usize) {
+        (self.row_stride, self.col_stride)
+    }
+
+    /// Returns a mutable pointer to the element at `(0, 0)`.
+    #[inline(always)]
+    pub fn as_mut_ptr(&mut self) -> *mut T {
+        self.ptr
+    }
+
+    /// Returns the number of elements.
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.nrows * self.ncols
+    }
+
+    /// Returns `true` if the view has no elements.
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the element at `(row, col)`, or `None` if it is out of
+    /// bounds.
+    #[inline(always)]
+    pub fn get(&self, row: usize, col: usize) -> Option<&T> {
+        self.as_view().get(row, col)
+    }
+
+    /// Returns the mutable sub-view covering `rows` × `cols`, without
+    /// copying, or `None` if either range is out of bounds.
+    pub fn view_mut(
+        &mut self,
+        rows: Range<usize>,
+        cols: Range<usize>,
+    ) -> Option<ArrayViewMut2D<'_, T>> {
+        let strides = (self.row_stride, self.col_stride);
+        unsafe { ArrayViewMut2D::from_raw(self.ptr, self.shape(), strides) }
+            .into_view(rows, cols)
+    }
+
+    /// Consumes the view, returning the mutable sub-view covering `rows` ×
+    /// `cols`, or `None` if either range is out of bounds.
+    pub fn into_view(
+        mut self,
+        rows: Range<usize>,
+        cols: Range<usize>,
+    ) -> Option<Self> {
+        let strides = (self.row_stride, self.col_stride);
+        let offset = region_offset(self.shape(), strides, &rows, &cols)?;
+        self.ptr = unsafe { self.ptr.add(offset) };
+        (self.nrows, self.ncols) = (rows.len(), cols.len());
+        Some(self)
+    }
+
+    /// Returns the element at `(row, col)` mutably, or `None` if it is out
+    /// of bounds.
+    #[inline(always)]
+    pub fn get_mut(&mut self, row: usize, col: usize) -> Option<&mut T> {
+        (row < self.nrows && col < self.ncols).then(|| unsafe {
+            &mut *self.ptr.add(row * self.row_stride + col * self.col_stride)
+        })
+    }
+
+    /// Returns an iterator over the elements in row-major order, mutably.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut T> + '_ {
+        let (ptr, ncols) = (self.ptr, self.ncols);
+        let (row_stride, col_stride) = (self.row_stride, self.col_stride);
+        // the view's elements are distinct, so each is handed out once
+        (0..self.nrows).flat_map(move |row| {
+            (0..ncols).map(move |col| unsafe {
+                &mut *ptr.add(row * row_stride + col * col_stride)
+            })
+        })
+    }
+
+    /// Writes a clone of `value` into every element, dropping the old ones.
+    pub fn fill(&mut self, value: T)
+    where
+        T: Clone,
+    {
+        for slot in self.iter_mut() {
+            *slot = value.clone();
+        }
+    }
+}
+
+#[cfg(not(feature = "no-panic"))]
+impl<T> std::ops::Index<(usize, usize)> for ArrayViewMut2D<'_, T> {
+    type Output = T;
+    #[track_caller]
+    fn index(&self, (row, col): (usize, usize)) -> &Self::Output {
+        self.get(row, col).expect("Index out of bounds")
+    }
+}
+
+#[cfg(not(feature = "no-panic"))]
+impl<T> std::ops::IndexMut<(usize, usize)> for ArrayViewMut2D<'_, T> {
+    #[track_caller]
+    fn index_mut(&mut self, (row, col): (usize, usize)) -> &mut Self::Output {
+        self.get_mut(row, col).expect("Index out of bounds")
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for ArrayViewMut2D<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        debug_rows(f, self.as_view())
+    }
+}
+
+/// A borrowed, possibly strided, one-dimensional window into another array's
+/// elements, such as a column or a diagonal of a matrix.
+///
+/// Element `index` lives `index * stride` elements past the start of the
+/// window.
+pub struct ArrayView1D<'a, T> {
+    ptr: *const T,
+    len: usize,
+    stride: usize,
+    _marker: PhantomData<&'a [T]>,
+}
+
+// A view is a shared borrow of `T`s, exactly like `&[T]`.
+unsafe impl<T: Sync> Send for ArrayView1D<'_, T> {}
+unsafe impl<T: Sync> Sync for ArrayView1D<'_, T> {}
+
+impl<T> Clone for ArrayView1D<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for ArrayView1D<'_, T> {}
+
+impl<'a, T> ArrayView1D<'a, T> {
+    /// # Safety
+    ///
+    /// Every index below `len` must address an initialized element that
+    /// stays borrowed for `'a`.
+    pub(crate) unsafe fn from_raw(
+        ptr: *const T,
+        len: usize,
+        stride: usize,
+    ) -> Self {
+        Self {
+            ptr,
+            len,
+            stride,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns the number of elements.
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the view has no elements.
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the distance, in elements, between neighbors.
+    #[inline(always)]
+    pub fn stride(&self) -> usize {
+        self.stride
+    }
+
+    /// Returns the element at `index`, or `None` if it is out of bounds.
+    #[inline(always)]
+    pub fn get(&self, index: usize) -> Option<&'a T> {
+        (index < self.len)
+            .then(|| unsafe { &*self.ptr.add(index * self.stride) })
+    }
+
+    /// Returns an iterator over the elements.
+    pub fn iter(
+        &self,
+    ) -> impl DoubleEndedIterator<Item = &'a T> + ExactSizeIterator + 'a {
+        let view = *self;
+        (0..view.len)
+            .map(move |index| unsafe { &*view.ptr.add(index * view.stride) })
+    }
+
+    /// Returns the elements as a slice, or `None` if they are not adjacent.
+    pub fn as_slice(&self) -> Option<&'a [T]> {
+        (self.stride == 1 || self.len <= 1)
+            .then(|| unsafe { slice::from_raw_parts(self.ptr, self.len) })
+    }
+
+    /// Copies the view into a new array.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the array cannot be allocated.
+    #[track_caller]
+    pub fn to_array(&self) -> Result<ArrayCStyle<T>, Error>
+    where
+        T: Clone,
+    {
+        let array = ArrayCStyle::<T>::new(self.len)?;
+        for (index, value) in self.iter().enumerate() {
+            unsafe { array.ptr_mut().add(index).write(value.clone()) };
+        }
+        Ok(array)
+    }
+}
+
+#[cfg(not(feature = "no-panic"))]
+impl<T> std::ops::Index<usize> for ArrayView1D<'_, T> {
+    type Output = T;
+    #[track_caller]
+    fn index(&self, index: usize) -> &Self::Output {
+        self.get(index).expect("Index out of bounds")
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for ArrayView1D<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+/// A mutable, possibly strided, one-dimensional window into another array's
+/// elements; see [`ArrayView1D`].
+pub struct ArrayViewMut1D<'a, T> {
+    ptr: *mut T,
+    len: usize,
+    stride: usize,
+    _marker: PhantomData<&'a mut [T]>,
+}
+
+// A mutable view is an exclusive borrow of `T`s, exactly like `&mut [T]`.
+unsafe impl<T: Send> Send for ArrayViewMut1D<'_, T> {}
+unsafe impl<T: Sync> Sync for ArrayViewMut1D<'_, T> {}
+
+impl<T> ArrayViewMut1D<'_, T> {
+    /// # Safety
+    ///
+    /// Every index below `len` must address a distinct initialized element
+    /// that stays exclusively borrowed for the view's lifetime.
+    pub(crate) unsafe fn from_raw(
+        ptr: *mut T,
+        len: usize,
+        stride: usize,
+    ) -> Self {
+        Self {
+            ptr,
+            len,
+            stride,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Reborrows the view immutably.
+    #[inline(always)]
+    pub fn as_view(&self) -> ArrayView1D<'_, T> {
+        unsafe { ArrayView1D::from_raw(self.ptr, self.len, self.stride) }
+    }
+
+    /// Returns the number of elements.
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the view has no elements.
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the element at `index`, or `None` if it is out of bounds.
+    #[inline(always)]
+    pub fn get(&self, index: usize) -> Option<&T> {
+        self.as_view().get(index)
+    }
+
+    /// Returns the element at `index` mutably, or `None` if it is out of
+    /// bounds.
+    #[inline(always)]
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        (index < self.len)
+            .then(|| unsafe { &mut *self.ptr.add(index * self.stride) })
+    }
+
+    /// Returns an iterator over the elements, mutably.
+    pub fn iter_mut(
+        &mut self,
+    ) -> impl DoubleEndedIterator<Item = &mut T> + ExactSizeIterator + '_ {
+        let (ptr, stride) = (self.ptr, self.stride);
+        // the view's elements are distinct, so each is handed out once
+        (0..self.len).map(move |index| unsafe { &mut *ptr.add(index * stride) })
+    }
+
+    /// Returns the elements as a mutable slice, or `None` if they are not
+    /// adjacent.
+    pub fn as_mut_slice(&mut self) -> Option<&mut [T]> {
+        (self.stride == 1 || self.len <= 1)
+            .then(|| unsafe { slice::from_raw_parts_mut(self.ptr, self.len) })
+    }
+
+    /// Writes a clone of `value` into every element, dropping the old ones.
+    pub fn fill(&mut self, value: T)
+    where
+        T: Clone,
+    {
+        for slot in self.iter_mut() {
+            *slot = value.clone();
+        }
+    }
+}
+
+#[cfg(not(feature = "no-panic"))]
+impl<T> std::ops::Index<usize> for ArrayViewMut1D<'_, T> {
+    type Output = T;
+    #[track_caller]
+    fn index(&self, index: usize) -> &Self::Output {
+        self.get(index).expect("Index out of bounds")
+    }
+}
+
+#[cfg(not(feature = "no-panic"))]
+impl<T> std::ops::IndexMut<usize> for ArrayViewMut1D<'_, T> {
+    #[track_caller]
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        self.get_mut(index).expect("Index out of bounds")
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for ArrayViewMut1D<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.as_view().fmt(f)
+    }
+}
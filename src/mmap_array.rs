@@ -0,0 +1,297 @@
+use std::{
+    fs::{File, OpenOptions},
+    mem,
+    os::unix::io::AsRawFd,
+    path::Path,
+    ptr::NonNull,
+    slice,
+};
+
+use crate::{
+    error::Error,
+    os::{advise_range, map_shared, os_error, unmap, Advice},
+};
+
+/// A persistent array backed by a memory-mapped file.
+///
+/// The array exposes the same indexing and iteration API as
+/// [`ArrayCStyle`](crate::runtime_array::ArrayCStyle), but its contents live
+/// in a file mapped with `MAP_SHARED`. The kernel pages data in and out on
+/// demand, so arrays larger than the available RAM work without changing the
+/// calling code. Writes reach the file eventually; use [`MmapArray::flush`] or
+/// [`MmapArray::sync`] to control when.
+///
+/// # Element types
+///
+/// The file is reinterpreted as a sequence of `T`, which is why
+/// [`MmapArray::create`] and [`MmapArray::open`] are `unsafe`: every bit
+/// pattern the file contains must be a valid `T`, which `Copy` alone does
+/// not guarantee (`bool`, references). Plain numeric types are the intended
+/// use. Another process modifying or truncating the file while it is
+/// mapped is observable through the array and may fault.
+///
+/// # Example
+///
+/// ```rust ignore
+/// use runnarr::mmap_array::MmapArray;
+///
+/// let mut samples =
+///     unsafe { MmapArray::<f32>::create("samples.bin", 1 << 20) }.unwrap();
+/// samples[42] = 1.0;
+/// samples.sync().unwrap();
+///
+/// let reopened = unsafe { MmapArray::<f32>::open("samples.bin") }.unwrap();
+/// assert_eq!(reopened[42], 1.0);
+/// ```
+#[derive(Debug)]
+pub struct MmapArray<T: Copy> {
+    len: usize,
+    ptr: *mut T,
+    file: File,
+}
+
+impl<T: Copy> MmapArray<T> {
+    /// Creates (or truncates) the file at `path` so it holds `len` zeroed
+    /// elements and maps it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be created, resized or mapped.
+    ///
+    /// # Safety
+    ///
+    /// `T` must be valid for every bit pattern: the elements start out
+    /// zeroed, and other processes may write the file while it is mapped.
+    pub unsafe fn create<P: AsRef<Path>>(
+        path: P,
+        len: usize,
+    ) -> Result<Self, Error> {
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(path)?;
+
+        let byte_len =
+            len.checked_mul(mem::size_of::<T>()).ok_or_else(|| {
+                Error::Other("Mapped array size overflows usize".to_string())
+            })?;
+        file.set_len(byte_len as u64)?;
+
+        Self::map(file, len)
+    }
+
+    /// Opens the existing file at `path` and maps it as an array of `T`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be opened for reading and writing
+    /// or its size is not a multiple of `size_of::<T>()`.
+    ///
+    /// # Safety
+    ///
+    /// Every element the file holds must be a valid `T`; see
+    /// [`MmapArray::create`].
+    pub unsafe fn open<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let file = OpenOptions::new().read(true).write(true).open(path)?;
+
+        let byte_len = file.metadata()?.len() as usize;
+        let elem_size = mem::size_of::<T>().max(1);
+        if !byte_len.is_multiple_of(elem_size) {
+            return Err(Error::Other(format!(
+                "File size {byte_len} is not a multiple of the element size {elem_size}"
+            )));
+        }
+
+        Self::map(file, byte_len / elem_size)
+    }
+
+    fn map(file: File, len: usize) -> Result<Self, Error> {
+        let byte_len = len * mem::size_of::<T>();
+        if byte_len == 0 {
+            return Ok(Self {
+                len,
+                ptr: NonNull::dangling().as_ptr(),
+                file,
+            });
+        }
+
+        let ptr = map_shared(file.as_raw_fd(), byte_len)?;
+
+        Ok(Self {
+            len,
+            ptr: ptr.as_ptr() as *mut T,
+            file,
+        })
+    }
+
+    /// Returns the length of the array.
+    #[inline(always)]
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the array holds no elements.
+    #[inline(always)]
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns a raw pointer to the start of the mapping.
+    #[inline(always)]
+    pub const fn ptr(&self) -> *const T {
+        self.ptr
+    }
+
+    #[inline(always)]
+    pub fn ptr_mut(&self) -> *mut T {
+        self.ptr
+    }
+
+    /// Gets a reference to the element at `index`, or `None` if it is out of
+    /// bounds.
+    #[inline(always)]
+    pub fn get(&self, index: usize) -> Option<&T> {
+        if index >= self.len {
+            return None;
+        }
+        Some(unsafe { &*self.ptr.add(index) })
+    }
+
+    /// Gets a mutable reference to the element at `index`, or `None` if it
+    /// is out of bounds.
+    #[inline(always)]
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        if index >= self.len {
+            return None;
+        }
+        Some(unsafe { &mut *self.ptr.add(index) })
+    }
+
+    /// Returns the mapped elements as a slice.
+    #[inline(always)]
+    pub fn as_slice(&self) -> &[T] {
+        unsafe { slice::from_raw_parts(self.ptr, self.len) }
+    }
+
+    /// Returns the mapped elements as a mutable slice.
+    #[inline(always)]
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        unsafe { slice::from_raw_parts_mut(self.ptr, self.len) }
+    }
+
+    /// Schedules all modified pages to be written back to the file without
+    /// waiting for the write to complete.
+    pub fn flush(&self) -> Result<(), Error> {
+        self.msync(libc::MS_ASYNC)
+    }
+
+    /// Writes all modified pages back to the file and waits until they,
+    /// together with the file metadata, have reached the storage device.
+    pub fn sync(&self) -> Result<(), Error> {
+        self.msync(libc::MS_SYNC)?;
+        Ok(self.file.sync_all()?)
+    }
+
+    /// Passes a paging hint for the whole mapping to the kernel.
+    ///
+    /// See [`Advice`].
+    pub fn advise(&self, advice: Advice) -> Result<(), Error> {
+        advise_range(
+            self.ptr as *const u8,
+            self.len * mem::size_of::<T>(),
+            advice,
+        )
+    }
+
+    fn msync(&self, flags: libc::c_int) -> Result<(), Error> {
+        let byte_len = self.len * mem::size_of::<T>();
+        if byte_len == 0 {
+            return Ok(());
+        }
+        let result = unsafe {
+            libc::msync(self.ptr as *mut libc::c_void, byte_len, flags)
+        };
+        if result != 0 {
+            return Err(os_error("msync"));
+        }
+        Ok(())
+    }
+}
+
+impl<T: Copy> Drop for MmapArray<T> {
+    fn drop(&mut self) {
+        let byte_len = self.len * mem::size_of::<T>();
+        if byte_len == 0 {
+            return;
+        }
+        unsafe {
+            unmap(NonNull::new_unchecked(self.ptr as *mut u8), byte_len);
+        }
+    }
+}
+
+#[cfg(not(feature = "no-panic"))]
+impl<T: Copy> std::ops::Index<usize> for MmapArray<T> {
+    type Output = T;
+    fn index(&self, index: usize) -> &Self::Output {
+        self.get(index).expect("Index out of bounds")
+    }
+}
+
+#[cfg(not(feature = "no-panic"))]
+impl<T: Copy> std::ops::IndexMut<usize> for MmapArray<T> {
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        self.get_mut(index).expect("Index out of bounds")
+    }
+}
+
+/// An owning iterator over the elements of a [`MmapArray`].
+///
+/// The mapping is released once the iterator is dropped; the file keeps its
+/// contents.
+#[derive(Debug)]
+pub struct MmapArrayIntoIter<T: Copy> {
+    array: MmapArray<T>,
+    index: usize,
+}
+
+impl<T: Copy> Iterator for MmapArrayIntoIter<T> {
+    type Item = T;
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.array.get(self.index).copied();
+        if item.is_some() {
+            self.index += 1;
+        }
+        item
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.array.len - self.index;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<T: Copy> ExactSizeIterator for MmapArrayIntoIter<T> {}
+
+impl<T: Copy> IntoIterator for MmapArray<T> {
+    type Item = T;
+    type IntoIter = MmapArrayIntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        Self::IntoIter {
+            array: self,
+            index: 0,
+        }
+    }
+}
+
+impl<'a, T: Copy> IntoIterator for &'a MmapArray<T> {
+    type Item = &'a T;
+    type IntoIter = slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.as_slice().iter()
+    }
+}
@@ -0,0 +1,155 @@
+//! Conversions between this crate's arrays and [`nalgebra`]'s dynamically
+//! sized matrices and vectors.
+//!
+//! Vectors hand their allocation across without copying, through a `Vec`.
+//! Matrices cannot: [`Array2D`] is row-major while [`DMatrix`] is
+//! column-major, so owned matrices are copied. Views are zero-copy in both
+//! directions, since `nalgebra` views carry a stride for rows and one for
+//! columns just like [`ArrayView2D`].
+
+use nalgebra::{
+    DMatrix, DMatrixView, DMatrixViewMut, DVector, DVectorView, DVectorViewMut,
+    Dyn, Matrix, Scalar, ViewStorage, ViewStorageMut,
+};
+
+use crate::{
+    allocator::RawAllocator,
+    error::Error,
+    matrix::{Array2D, ArrayView2D, ArrayViewMut2D, Layout},
+    runtime_array::ArrayCStyle,
+};
+
+/// A `nalgebra` matrix view with arbitrary row and column strides.
+pub type StridedMatrixView<'a, T> = DMatrixView<'a, T, Dyn, Dyn>;
+
+/// A mutable `nalgebra` matrix view with arbitrary row and column strides.
+pub type StridedMatrixViewMut<'a, T> = DMatrixViewMut<'a, T, Dyn, Dyn>;
+
+impl<T: Scalar> ArrayCStyle<T> {
+    /// Converts the array into a `nalgebra` vector, handing over the buffer
+    /// without copying.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the array is over-aligned.
+    pub fn into_dvector(self) -> Result<DVector<T>, Error> {
+        self.into_vec().map(DVector::from_vec)
+    }
+
+    /// Converts a `nalgebra` vector into an array, taking over its buffer
+    /// without copying.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the buffer has spare capacity and the copy that
+    /// drops it cannot be allocated.
+    #[track_caller]
+    pub fn from_dvector(vector: DVector<T>) -> Result<Self, Error> {
+        Self::from_vec(vector.data.into())
+    }
+}
+
+impl<T: Scalar, A: RawAllocator> ArrayCStyle<T, A> {
+    /// Returns a `nalgebra` view of the elements as a column vector.
+    pub fn as_dvector_view(&self) -> DVectorView<'_, T> {
+        DVectorView::from_slice(self.as_slice(), self.len())
+    }
+
+    /// Returns a mutable `nalgebra` view of the elements as a column vector.
+    pub fn as_dvector_view_mut(&mut self) -> DVectorViewMut<'_, T> {
+        let len = self.len();
+        DVectorViewMut::from_slice(self.as_mut_slice(), len)
+    }
+}
+
+impl<T: Scalar> Array2D<T> {
+    /// Copies the matrix into a `nalgebra` matrix; the copy of a
+    /// column-major matrix keeps the element order.
+    ///
+    /// To avoid the copy, hand `nalgebra` a view from
+    /// [`Array2D::as_dmatrix_view`] instead.
+    pub fn to_dmatrix(&self) -> DMatrix<T> {
+        let (nrows, ncols) = self.shape();
+        match self.layout() {
+            Layout::RowMajor => {
+                DMatrix::from_row_slice(nrows, ncols, self.as_slice())
+            }
+            Layout::ColumnMajor => {
+                DMatrix::from_column_slice(nrows, ncols, self.as_slice())
+            }
+        }
+    }
+
+    /// Copies a `nalgebra` matrix into a new matrix.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the matrix cannot be allocated.
+    #[track_caller]
+    pub fn from_dmatrix(matrix: &DMatrix<T>) -> Result<Self, Error> {
+        let (nrows, ncols) = matrix.shape();
+        let data = ArrayCStyle::<T>::new(nrows * ncols)?;
+        let target = data.ptr_mut();
+        for row in 0..nrows {
+            for col in 0..ncols {
+                let value = matrix[(row, col)].clone();
+                unsafe { target.add(row * ncols + col).write(value) };
+            }
+        }
+        Self::from_array(data, nrows, ncols)
+    }
+
+    /// Returns a `nalgebra` view of the matrix, without copying.
+    pub fn as_dmatrix_view(&self) -> StridedMatrixView<'_, T> {
+        self.as_view().into_dmatrix_view()
+    }
+
+    /// Returns a mutable `nalgebra` view of the matrix, without copying.
+    pub fn as_dmatrix_view_mut(&mut self) -> StridedMatrixViewMut<'_, T> {
+        self.as_view_mut().into_dmatrix_view()
+    }
+}
+
+impl<'a, T: Scalar> ArrayView2D<'a, T> {
+    /// Converts the view into a `nalgebra` view of the same elements.
+    pub fn into_dmatrix_view(self) -> StridedMatrixView<'a, T> {
+        let (nrows, ncols) = self.shape();
+        let (row_stride, col_stride) = self.strides();
+        let storage = unsafe {
+            ViewStorage::from_raw_parts(
+                self.as_ptr(),
+                (Dyn(nrows), Dyn(ncols)),
+                (Dyn(row_stride), Dyn(col_stride)),
+            )
+        };
+        Matrix::from_data(storage)
+    }
+
+    /// Converts a `nalgebra` view into a view of the same elements.
+    pub fn from_dmatrix_view<RStride, CStride>(
+        view: DMatrixView<'a, T, RStride, CStride>,
+    ) -> Self
+    where
+        RStride: nalgebra::Dim,
+        CStride: nalgebra::Dim,
+    {
+        unsafe { Self::from_raw(view.as_ptr(), view.shape(), view.strides()) }
+    }
+}
+
+impl<'a, T: Scalar> ArrayViewMut2D<'a, T> {
+    /// Converts the view into a mutable `nalgebra` view of the same
+    /// elements.
+    pub fn into_dmatrix_view(mut self) -> StridedMatrixViewMut<'a, T> {
+        let (nrows, ncols) = self.shape();
+        let (row_stride, col_stride) = self.strides();
+        let storage = unsafe {
+            ViewStorageMut::from_raw_parts(
+                self.as_mut_ptr(),
+                (Dyn(nrows), Dyn(ncols)),
+                (Dyn(row_stride), Dyn(col_stride)),
+            )
+        };
+        Matrix::from_data(storage)
+    }
+}
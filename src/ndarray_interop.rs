@@ -0,0 +1,243 @@
+//! Conversions between this crate's arrays and [`ndarray`]'s.
+//!
+//! Owned arrays hand their allocation across without copying: both sides
+//! keep elements in a buffer from the global allocator, so the buffer is
+//! passed through a `Vec`. Views are rebuilt around the same pointer,
+//! shape and strides. Keep in mind that `ndarray` drops its elements while
+//! [`ArrayCStyle`] never does.
+
+use ndarray::{
+    Array1, Array2, ArrayD, ArrayView1, ArrayView2, ArrayViewD, ArrayViewMut1,
+    ArrayViewMut2, ArrayViewMutD, IxDyn, ShapeBuilder,
+};
+
+use crate::{
+    allocator::RawAllocator,
+    error::Error,
+    matrix::{
+        Array2D, ArrayND, ArrayView2D, ArrayViewMut2D, ArrayViewMutND,
+        ArrayViewND, Layout,
+    },
+    runtime_array::ArrayCStyle,
+};
+
+/// Takes the buffer of a standard-layout `ndarray` array whose first
+/// element starts it.
+fn vec_from_ndarray<T, D: ndarray::Dimension>(
+    array: ndarray::Array<T, D>,
+) -> Result<Vec<T>, Error> {
+    if !array.is_standard_layout() {
+        return Err(Error::Other(
+            "Cannot take an ndarray array that is not row-major".into(),
+        ));
+    }
+    match array.into_raw_vec_and_offset() {
+        (vec, None | Some(0)) => Ok(vec),
+        (_, Some(offset)) => Err(Error::Other(format!(
+            "Cannot take an ndarray array starting {offset} elements into \
+             its buffer"
+        ))),
+    }
+}
+
+/// Converts `ndarray` strides to this crate's, which cannot be negative.
+fn strides_from_ndarray(strides: &[isize]) -> Result<Vec<usize>, Error> {
+    strides
+        .iter()
+        .map(|&stride| {
+            usize::try_from(stride).map_err(|_| {
+                Error::Other(format!(
+                    "Cannot view ndarray strides {strides:?} without copying"
+                ))
+            })
+        })
+        .collect()
+}
+
+fn shape_error(error: ndarray::ShapeError) -> Error {
+    Error::Other(error.to_string())
+}
+
+impl<T> ArrayCStyle<T> {
+    /// Converts the array into an `ndarray` vector, handing over the
+    /// buffer without copying.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the array is over-aligned.
+    pub fn into_ndarray(self) -> Result<Array1<T>, Error> {
+        self.into_vec().map(Array1::from_vec)
+    }
+
+    /// Converts an `ndarray` vector into an array, taking over its buffer
+    /// without copying.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the vector is not contiguous or starts past the
+    /// beginning of its buffer.
+    #[track_caller]
+    pub fn from_ndarray(array: Array1<T>) -> Result<Self, Error> {
+        ArrayCStyle::from_vec(vec_from_ndarray(array)?)
+    }
+}
+
+impl<T, A: RawAllocator> ArrayCStyle<T, A> {
+    /// Returns an `ndarray` view of the elements.
+    pub fn as_ndarray_view(&self) -> ArrayView1<'_, T> {
+        ArrayView1::from(self.as_slice())
+    }
+
+    /// Returns a mutable `ndarray` view of the elements.
+    pub fn as_ndarray_view_mut(&mut self) -> ArrayViewMut1<'_, T> {
+        ArrayViewMut1::from(self.as_mut_slice())
+    }
+}
+
+impl<T> Array2D<T> {
+    /// Converts the matrix into an `ndarray` matrix of the same layout,
+    /// handing over the buffer without copying.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the underlying array is over-aligned.
+    pub fn into_ndarray(self) -> Result<Array2<T>, Error> {
+        let (shape, layout) = (self.shape(), self.layout());
+        let vec = self.into_array().into_vec()?;
+        match layout {
+            Layout::RowMajor => Array2::from_shape_vec(shape, vec),
+            Layout::ColumnMajor => Array2::from_shape_vec(shape.f(), vec),
+        }
+        .map_err(shape_error)
+    }
+
+    /// Converts an `ndarray` matrix into a matrix, taking over its buffer
+    /// without copying. Column-major (Fortran order) matrices become
+    /// column-major matrices.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the matrix is neither row-major nor column-major
+    /// or starts past the beginning of its buffer.
+    #[track_caller]
+    pub fn from_ndarray(array: Array2<T>) -> Result<Self, Error> {
+        let (nrows, ncols) = array.dim();
+        // the transpose of a Fortran-order matrix is row-major
+        let fortran =
+            !array.is_standard_layout() && array.t().is_standard_layout();
+        let (array, layout) = if fortran {
+            (array.reversed_axes(), Layout::ColumnMajor)
+        } else {
+            (array, Layout::RowMajor)
+        };
+        Self::from_array_with_layout(
+            ArrayCStyle::from_vec(vec_from_ndarray(array)?)?,
+            nrows,
+            ncols,
+            layout,
+        )
+    }
+
+    /// Returns an `ndarray` view of the matrix.
+    pub fn as_ndarray_view(&self) -> ArrayView2<'_, T> {
+        self.as_view().into_ndarray()
+    }
+
+    /// Returns a mutable `ndarray` view of the matrix.
+    pub fn as_ndarray_view_mut(&mut self) -> ArrayViewMut2<'_, T> {
+        self.as_view_mut().into_ndarray()
+    }
+}
+
+impl<T> ArrayND<T> {
+    /// Converts the array into a dynamic-rank `ndarray` array, handing over
+    /// the buffer without copying.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the underlying array is over-aligned.
+    pub fn into_ndarray(self) -> Result<ArrayD<T>, Error> {
+        let shape = IxDyn(self.shape()).strides(IxDyn(self.strides()));
+        let vec = self.into_array().into_vec()?;
+        ArrayD::from_shape_vec(shape, vec).map_err(shape_error)
+    }
+
+    /// Converts an `ndarray` array of any rank into an array, taking over
+    /// its buffer without copying.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the array is not row-major or starts past the
+    /// beginning of its buffer.
+    #[track_caller]
+    pub fn from_ndarray<D: ndarray::Dimension>(
+        array: ndarray::Array<T, D>,
+    ) -> Result<Self, Error> {
+        let shape = array.shape().to_vec();
+        Self::from_array(
+            ArrayCStyle::from_vec(vec_from_ndarray(array)?)?,
+            &shape,
+        )
+    }
+}
+
+impl<'a, T> ArrayView2D<'a, T> {
+    /// Converts the view into an `ndarray` view of the same elements.
+    pub fn into_ndarray(self) -> ArrayView2<'a, T> {
+        let shape = self.shape().strides(self.strides());
+        unsafe { ArrayView2::from_shape_ptr(shape, self.as_ptr()) }
+    }
+
+    /// Converts an `ndarray` view into a view of the same elements.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the view has negative strides.
+    pub fn from_ndarray(view: ArrayView2<'a, T>) -> Result<Self, Error> {
+        let strides = strides_from_ndarray(view.strides())?;
+        Ok(unsafe {
+            Self::from_raw(view.as_ptr(), view.dim(), (strides[0], strides[1]))
+        })
+    }
+}
+
+impl<'a, T> ArrayViewMut2D<'a, T> {
+    /// Converts the view into a mutable `ndarray` view of the same elements.
+    pub fn into_ndarray(mut self) -> ArrayViewMut2<'a, T> {
+        let shape = self.shape().strides(self.strides());
+        unsafe { ArrayViewMut2::from_shape_ptr(shape, self.as_mut_ptr()) }
+    }
+}
+
+impl<'a, T> ArrayViewND<'a, T> {
+    /// Converts the view into a dynamic-rank `ndarray` view of the same
+    /// elements.
+    pub fn into_ndarray(self) -> ArrayViewD<'a, T> {
+        let shape = IxDyn(self.shape()).strides(IxDyn(self.strides()));
+        unsafe { ArrayViewD::from_shape_ptr(shape, self.as_ptr()) }
+    }
+
+    /// Converts an `ndarray` view of any rank into a view of the same
+    /// elements.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the view has negative strides.
+    pub fn from_ndarray<D: ndarray::Dimension>(
+        view: ndarray::ArrayView<'a, T, D>,
+    ) -> Result<Self, Error> {
+        let strides = strides_from_ndarray(view.strides())?;
+        Ok(unsafe {
+            Self::from_raw(view.as_ptr(), view.shape().to_vec(), strides)
+        })
+    }
+}
+
+impl<'a, T> ArrayViewMutND<'a, T> {
+    /// Converts the view into a mutable dynamic-rank `ndarray` view of the
+    /// same elements.
+    pub fn into_ndarray(mut self) -> ArrayViewMutD<'a, T> {
+        let shape = IxDyn(self.shape()).strides(IxDyn(self.strides()));
+        unsafe { ArrayViewMutD::from_shape_ptr(shape, self.as_mut_ptr()) }
+    }
+}
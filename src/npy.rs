@@ -0,0 +1,664 @@
+//! Reading and writing NumPy's `.npy` format, and with the `npz` feature
+//! its `.npz` archives, so arrays can round-trip with Python scripts.
+//!
+//! Arrays of the primitive integer and float types are supported. Data in
+//! either byte order is read; data is always written little-endian, as
+//! NumPy does on every common platform. Fortran-order files are read
+//! without reordering into arrays whose strides (or [`Layout`]) say so, and
+//! such arrays are written back the same way.
+//!
+//! # Example
+//!
+//! ```rust ignore
+//! use runnarr::matrix::ArrayND;
+//!
+//! // np.save("weights.npy", np.zeros((64, 32), dtype=np.float32))
+//! let weights = ArrayND::<f32>::load_npy("weights.npy")?;
+//! assert_eq!(weights.shape(), [64, 32]);
+//! weights.save_npy("weights-copy.npy")?;
+//! ```
+
+use std::{
+    fs::File,
+    io::{self, BufReader, BufWriter, Read, Write},
+    path::Path,
+};
+
+use crate::{
+    allocator::RawAllocator,
+    error::Error,
+    matrix::{Array2D, ArrayND, Layout},
+    runtime_array::ArrayCStyle,
+};
+
+const MAGIC: &[u8; 6] = b"\x93NUMPY";
+
+/// Elements converted per read or write call.
+const CHUNK: usize = 4096;
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// An element type with a NumPy dtype.
+///
+/// Implemented for the primitive integers up to 64 bits, `f32` and `f64`;
+/// the trait is sealed.
+pub trait NpyElement: Copy + sealed::Sealed {
+    /// The dtype's kind and size in bytes, as in `"f4"`.
+    #[doc(hidden)]
+    const DTYPE: &'static str;
+    #[doc(hidden)]
+    const SIZE: usize;
+    #[doc(hidden)]
+    fn write_le(self, bytes: &mut [u8]);
+    #[doc(hidden)]
+    fn read(bytes: &[u8], big_endian: bool) -> Self;
+}
+
+macro_rules! npy_element {
+    ($($type:ty => $dtype:literal),* $(,)?) => {
+        $(
+            impl sealed::Sealed for $type {}
+
+            impl NpyElement for $type {
+                const DTYPE: &'static str = $dtype;
+                const SIZE: usize = std::mem::size_of::<$type>();
+
+                #[inline(always)]
+                fn write_le(self, bytes: &mut [u8]) {
+                    bytes.copy_from_slice(&self.to_le_bytes());
+                }
+
+                #[inline(always)]
+                fn read(bytes: &[u8], big_endian: bool) -> Self {
+                    let mut raw = [0; std::mem::size_of::<$type>()];
+                    raw.copy_from_slice(bytes);
+                    if big_endian {
+                        <$type>::from_be_bytes(raw)
+                    } else {
+                        <$type>::from_le_bytes(raw)
+                    }
+                }
+            }
+        )*
+    };
+}
+
+npy_element!(
+    u8 => "u1", i8 => "i1", u16 => "u2", i16 => "i2",
+    u32 => "u4", i32 => "i4", u64 => "u8", i64 => "i8",
+    f32 => "f4", f64 => "f8",
+);
+
+fn invalid_header(reason: &str) -> Error {
+    Error::Other(format!("Invalid NPY header: {reason}"))
+}
+
+/// The parsed header dictionary of a `.npy` file.
+struct Header {
+    big_endian: bool,
+    fortran_order: bool,
+    shape: Vec<usize>,
+}
+
+/// Returns the text after `'key':` in a header dictionary.
+fn header_value<'a>(header: &'a str, key: &str) -> Result<&'a str, Error> {
+    let quoted = [format!("'{key}'"), format!("\"{key}\"")];
+    let start = quoted
+        .iter()
+        .find_map(|quoted| Some(header.find(quoted)? + quoted.len()))
+        .ok_or_else(|| invalid_header(&format!("missing '{key}'")))?;
+    header[start..]
+        .trim_start()
+        .strip_prefix(':')
+        .map(str::trim_start)
+        .ok_or_else(|| invalid_header(&format!("malformed '{key}'")))
+}
+
+/// Parses the header dictionary, checking its dtype against `T`'s.
+fn parse_header<T: NpyElement>(header: &str) -> Result<Header, Error> {
+    let descr = header_value(header, "descr")?;
+    let descr = descr
+        .strip_prefix(['\'', '"'])
+        .and_then(|descr| descr.split(['\'', '"']).next())
+        .ok_or_else(|| invalid_header("malformed 'descr'"))?;
+    let order = descr.chars().next().map_or(0, char::len_utf8);
+    let (order, dtype) = descr.split_at(order);
+    let big_endian = match order {
+        "<" | "|" => false,
+        ">" => true,
+        "=" => cfg!(target_endian = "big"),
+        _ => return Err(invalid_header("unsupported 'descr'")),
+    };
+    if dtype != T::DTYPE {
+        return Err(Error::Other(format!(
+            "Cannot read NPY data of dtype '{descr}' as '{}'",
+            T::DTYPE
+        )));
+    }
+    let fortran_order = match header_value(header, "fortran_order")? {
+        value if value.starts_with("True") => true,
+        value if value.starts_with("False") => false,
+        _ => return Err(invalid_header("malformed 'fortran_order'")),
+    };
+    let shape = header_value(header, "shape")?
+        .strip_prefix('(')
+        .and_then(|shape| shape.split(')').next())
+        .ok_or_else(|| invalid_header("malformed 'shape'"))?
+        .split(',')
+        .map(str::trim)
+        .filter(|extent| !extent.is_empty())
+        .map(|extent| {
+            extent
+                .parse()
+                .map_err(|_| invalid_header("malformed 'shape'"))
+        })
+        .collect::<Result<_, _>>()?;
+    Ok(Header {
+        big_endian,
+        fortran_order,
+        shape,
+    })
+}
+
+fn read_header<T: NpyElement, R: Read>(
+    reader: &mut R,
+) -> Result<Header, Error> {
+    let mut preamble = [0; 8];
+    reader.read_exact(&mut preamble)?;
+    if preamble[..6] != MAGIC[..] {
+        return Err(invalid_header("not an NPY file"));
+    }
+    let len = match preamble[6] {
+        1 => {
+            let mut len = [0; 2];
+            reader.read_exact(&mut len)?;
+            usize::from(u16::from_le_bytes(len))
+        }
+        2 | 3 => {
+            let mut len = [0; 4];
+            reader.read_exact(&mut len)?;
+            u32::from_le_bytes(len) as usize
+        }
+        version => {
+            return Err(invalid_header(&format!(
+                "unsupported version {version}"
+            )))
+        }
+    };
+    // read what is there instead of trusting `len` with an allocation
+    let mut header = Vec::new();
+    reader.take(len as u64).read_to_end(&mut header)?;
+    if header.len() < len {
+        return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into());
+    }
+    let header = String::from_utf8(header)
+        .map_err(|_| invalid_header("header is not UTF-8"))?;
+    parse_header::<T>(&header)
+}
+
+/// Reads the `len` elements following the header.
+///
+/// The array grows, doubling, as the data arrives rather than being
+/// allocated for `len` up front, so a short stream whose header claims a
+/// huge shape fails at its end instead of allocating that shape.
+fn read_data<T: NpyElement, R: Read>(
+    reader: &mut R,
+    len: usize,
+    big_endian: bool,
+) -> Result<ArrayCStyle<T>, Error> {
+    let mut array = ArrayCStyle::<T>::new(CHUNK.min(len))?;
+    let mut bytes = vec![0; CHUNK.min(len) * T::SIZE];
+    let mut filled = 0;
+    while filled < len {
+        if filled == array.len() {
+            array.try_grow(array.len().min(len - filled))?;
+        }
+        let count = CHUNK.min(len - filled);
+        let bytes = &mut bytes[..count * T::SIZE];
+        reader.read_exact(bytes)?;
+        let target = unsafe { array.ptr_mut().add(filled) };
+        for (index, bytes) in bytes.chunks_exact(T::SIZE).enumerate() {
+            unsafe { target.add(index).write(T::read(bytes, big_endian)) };
+        }
+        filled += count;
+    }
+    Ok(array)
+}
+
+/// Reads a whole `.npy` stream into its shape, order and elements.
+fn read<T: NpyElement, R: Read>(
+    mut reader: R,
+) -> Result<(Header, ArrayCStyle<T>), Error> {
+    let header = read_header::<T, _>(&mut reader)?;
+    let len = header
+        .shape
+        .iter()
+        .try_fold(1usize, |len, &extent| len.checked_mul(extent))
+        .ok_or_else(|| invalid_header("shape overflows usize"))?;
+    let data = read_data(&mut reader, len, header.big_endian)?;
+    Ok((header, data))
+}
+
+/// Writes a version 1.0 header, or 2.0 if the dictionary is too long,
+/// padded so the data starts 64-byte aligned.
+fn write_header<T: NpyElement, W: Write>(
+    writer: &mut W,
+    fortran_order: bool,
+    shape: &[usize],
+) -> Result<(), Error> {
+    let shape = match shape {
+        [extent] => format!("({extent},)"),
+        _ => {
+            let extents: Vec<_> = shape.iter().map(usize::to_string).collect();
+            format!("({})", extents.join(", "))
+        }
+    };
+    let order = if fortran_order { "True" } else { "False" };
+    let dtype = if T::SIZE == 1 { "|" } else { "<" };
+    let mut header = format!(
+        "{{'descr': '{dtype}{}', 'fortran_order': {order}, 'shape': {shape}, }}",
+        T::DTYPE
+    );
+    // magic, version and header length: 10 bytes in 1.0, 12 in 2.0
+    let padded = |preamble: usize| {
+        (preamble + header.len() + 1).next_multiple_of(64) - preamble
+    };
+    let short = padded(10) <= usize::from(u16::MAX);
+    let len = if short { padded(10) } else { padded(12) };
+    header.extend(std::iter::repeat_n(' ', len - header.len() - 1));
+    header.push('\n');
+    writer.write_all(MAGIC)?;
+    if short {
+        writer.write_all(&[1, 0])?;
+        writer.write_all(&(header.len() as u16).to_le_bytes())?;
+    } else {
+        let len = u32::try_from(header.len())
+            .map_err(|_| Error::Other("NPY header is too long".into()))?;
+        writer.write_all(&[2, 0])?;
+        writer.write_all(&len.to_le_bytes())?;
+    }
+    Ok(writer.write_all(header.as_bytes())?)
+}
+
+/// Writes the elements little-endian, a chunk at a time.
+fn write_data<'a, T: NpyElement + 'a, W: Write>(
+    writer: &mut W,
+    elements: impl IntoIterator<Item = &'a T>,
+) -> Result<(), Error> {
+    let mut bytes = vec![0; CHUNK * T::SIZE];
+    let mut filled = 0;
+    for &element in elements {
+        element.write_le(&mut bytes[filled..filled + T::SIZE]);
+        filled += T::SIZE;
+        if filled == bytes.len() {
+            writer.write_all(&bytes)?;
+            filled = 0;
+        }
+    }
+    Ok(writer.write_all(&bytes[..filled])?)
+}
+
+/// Writes a whole `.npy` stream.
+fn write<'a, T: NpyElement + 'a, W: Write>(
+    mut writer: W,
+    fortran_order: bool,
+    shape: &[usize],
+    elements: impl IntoIterator<Item = &'a T>,
+) -> Result<(), Error> {
+    write_header::<T, _>(&mut writer, fortran_order, shape)?;
+    write_data(&mut writer, elements)?;
+    Ok(writer.flush()?)
+}
+
+fn save<P: AsRef<Path>>(
+    path: P,
+    write: impl FnOnce(&mut BufWriter<File>) -> Result<(), Error>,
+) -> Result<(), Error> {
+    write(&mut BufWriter::new(File::create(path)?))
+}
+
+fn rank_mismatch(expected: usize, shape: &[usize]) -> Error {
+    Error::Other(format!(
+        "Cannot read NPY data of shape {shape:?} into an array of rank \
+         {expected}"
+    ))
+}
+
+impl<T: NpyElement> ArrayND<T> {
+    /// Reads a `.npy` stream of any rank; Fortran-order data keeps its
+    /// order and gets column-major strides.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if reading fails, the header is malformed, or the
+    /// dtype is not `T`'s.
+    #[track_caller]
+    pub fn read_npy<R: Read>(reader: R) -> Result<Self, Error> {
+        let (header, data) = read(reader)?;
+        if header.fortran_order {
+            let mut shape = header.shape;
+            shape.reverse();
+            return Ok(Self::from_array(data, &shape)?.reversed_axes());
+        }
+        Self::from_array(data, &header.shape)
+    }
+
+    /// Reads the `.npy` file at `path`; see [`ArrayND::read_npy`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be opened or read, or does not
+    /// hold `T`s.
+    #[track_caller]
+    pub fn load_npy<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        Self::read_npy(BufReader::new(File::open(path)?))
+    }
+
+    /// Writes the array as a `.npy` stream: in C order when it is
+    /// row-major, in Fortran order when its axes are reversed from that,
+    /// and copied into C order otherwise.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if writing fails.
+    pub fn write_npy<W: Write>(&self, writer: W) -> Result<(), Error> {
+        if let Some(elements) = self.as_slice() {
+            return write(writer, false, self.shape(), elements);
+        }
+        match self.t().as_slice() {
+            Some(elements) => write(writer, true, self.shape(), elements),
+            None => write(writer, false, self.shape(), self.iter()),
+        }
+    }
+
+    /// Writes the array to a `.npy` file at `path`, creating or truncating
+    /// it; see [`ArrayND::write_npy`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be created or written.
+    pub fn save_npy<P: AsRef<Path>>(&self, path: P) -> Result<(), Error> {
+        save(path, |writer| self.write_npy(writer))
+    }
+}
+
+impl<T: NpyElement> Array2D<T> {
+    /// Reads a two-dimensional `.npy` stream; Fortran-order data becomes a
+    /// [`Layout::ColumnMajor`] matrix without reordering.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if reading fails, the header is malformed, or the
+    /// data is not a matrix of `T`s.
+    #[track_caller]
+    pub fn read_npy<R: Read>(reader: R) -> Result<Self, Error> {
+        let (header, data) = read(reader)?;
+        let &[nrows, ncols] = &header.shape[..] else {
+            return Err(rank_mismatch(2, &header.shape));
+        };
+        let layout = if header.fortran_order {
+            Layout::ColumnMajor
+        } else {
+            Layout::RowMajor
+        };
+        Self::from_array_with_layout(data, nrows, ncols, layout)
+    }
+
+    /// Reads the `.npy` file at `path`; see [`Array2D::read_npy`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be opened or read, or does not
+    /// hold a matrix of `T`s.
+    #[track_caller]
+    pub fn load_npy<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        Self::read_npy(BufReader::new(File::open(path)?))
+    }
+
+    /// Writes the matrix as a `.npy` stream in its own layout, C order for
+    /// row-major and Fortran order for column-major.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if writing fails.
+    pub fn write_npy<W: Write>(&self, writer: W) -> Result<(), Error> {
+        let fortran_order = self.layout() == Layout::ColumnMajor;
+        let shape = [self.nrows(), self.ncols()];
+        write(writer, fortran_order, &shape, self.as_slice())
+    }
+
+    /// Writes the matrix to a `.npy` file at `path`, creating or truncating
+    /// it; see [`Array2D::write_npy`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be created or written.
+    pub fn save_npy<P: AsRef<Path>>(&self, path: P) -> Result<(), Error> {
+        save(path, |writer| self.write_npy(writer))
+    }
+}
+
+impl<T: NpyElement> ArrayCStyle<T> {
+    /// Reads a one-dimensional `.npy` stream.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if reading fails, the header is malformed, or the
+    /// data is not a vector of `T`s.
+    #[track_caller]
+    pub fn read_npy<R: Read>(reader: R) -> Result<Self, Error> {
+        let (header, data) = read(reader)?;
+        match header.shape[..] {
+            [_] => Ok(data),
+            _ => Err(rank_mismatch(1, &header.shape)),
+        }
+    }
+
+    /// Reads the `.npy` file at `path`; see [`ArrayCStyle::read_npy`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be opened or read, or does not
+    /// hold a vector of `T`s.
+    #[track_caller]
+    pub fn load_npy<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        Self::read_npy(BufReader::new(File::open(path)?))
+    }
+}
+
+impl<T: NpyElement, A: RawAllocator> ArrayCStyle<T, A> {
+    /// Writes the elements as a one-dimensional `.npy` stream.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if writing fails.
+    pub fn write_npy<W: Write>(&self, writer: W) -> Result<(), Error> {
+        write(writer, false, &[self.len()], self.as_slice())
+    }
+
+    /// Writes the elements to a `.npy` file at `path`, creating or
+    /// truncating it; see [`ArrayCStyle::write_npy`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be created or written.
+    pub fn save_npy<P: AsRef<Path>>(&self, path: P) -> Result<(), Error> {
+        save(path, |writer| self.write_npy(writer))
+    }
+}
+
+#[cfg(feature = "npz")]
+pub use npz::{NpzReader, NpzWriter};
+
+#[cfg(feature = "npz")]
+mod npz {
+    use std::{
+        fs::File,
+        io::{BufReader, BufWriter, Read, Seek, Write},
+        path::Path,
+    };
+
+    use zip::{
+        result::ZipError, write::SimpleFileOptions, CompressionMethod,
+        ZipArchive, ZipWriter,
+    };
+
+    use super::NpyElement;
+    use crate::{error::Error, matrix::ArrayND};
+
+    fn zip_error(error: ZipError) -> Error {
+        match error {
+            ZipError::Io(error) => error.into(),
+            error => Error::Other(format!("Invalid NPZ archive: {error}")),
+        }
+    }
+
+    /// Reads named arrays from a `.npz` archive, as written by `np.savez`
+    /// and `np.savez_compressed`.
+    ///
+    /// # Example
+    ///
+    /// ```rust ignore
+    /// let mut archive = NpzReader::open("model.npz")?;
+    /// let weights = archive.read::<f32>("weights")?;
+    /// let bias = archive.read::<f32>("bias")?;
+    /// ```
+    pub struct NpzReader<R: Read + Seek> {
+        archive: ZipArchive<R>,
+    }
+
+    impl NpzReader<BufReader<File>> {
+        /// Opens the `.npz` file at `path`.
+        ///
+        /// # Errors
+        ///
+        /// Returns an error if the file cannot be opened or is not a zip
+        /// archive.
+        pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+            Self::new(BufReader::new(File::open(path)?))
+        }
+    }
+
+    impl<R: Read + Seek> NpzReader<R> {
+        /// Reads the directory of a `.npz` archive.
+        ///
+        /// # Errors
+        ///
+        /// Returns an error if reading fails or the data is not a zip
+        /// archive.
+        pub fn new(reader: R) -> Result<Self, Error> {
+            let archive = ZipArchive::new(reader).map_err(zip_error)?;
+            Ok(Self { archive })
+        }
+
+        /// Returns the names of the arrays in the archive.
+        pub fn names(&self) -> Vec<String> {
+            self.archive
+                .file_names()
+                .map(|name| name.strip_suffix(".npy").unwrap_or(name).into())
+                .collect()
+        }
+
+        /// Reads the array called `name`; see [`ArrayND::read_npy`].
+        ///
+        /// # Errors
+        ///
+        /// Returns an error if there is no such array, reading fails, or the
+        /// array does not hold `T`s.
+        #[track_caller]
+        pub fn read<T: NpyElement>(
+            &mut self,
+            name: &str,
+        ) -> Result<ArrayND<T>, Error> {
+            let file = self
+                .archive
+                .by_name(&format!("{name}.npy"))
+                .map_err(zip_error)?;
+            ArrayND::read_npy(file)
+        }
+    }
+
+    /// Writes named arrays into a `.npz` archive that `np.load` can open.
+    ///
+    /// The archive is only complete once [`NpzWriter::finish`] has run.
+    ///
+    /// # Example
+    ///
+    /// ```rust ignore
+    /// let mut archive = NpzWriter::create("model.npz", true)?;
+    /// archive.write("weights", &weights)?;
+    /// archive.write("bias", &bias)?;
+    /// archive.finish()?;
+    /// ```
+    pub struct NpzWriter<W: Write + Seek> {
+        archive: ZipWriter<W>,
+        compression: CompressionMethod,
+    }
+
+    impl NpzWriter<BufWriter<File>> {
+        /// Creates or truncates the `.npz` file at `path`; see
+        /// [`NpzWriter::new`].
+        ///
+        /// # Errors
+        ///
+        /// Returns an error if the file cannot be created.
+        pub fn create<P: AsRef<Path>>(
+            path: P,
+            compressed: bool,
+        ) -> Result<Self, Error> {
+            Ok(Self::new(BufWriter::new(File::create(path)?), compressed))
+        }
+    }
+
+    impl<W: Write + Seek> NpzWriter<W> {
+        /// Starts an archive in `writer`, deflating its arrays as
+        /// `np.savez_compressed` does if `compressed`, or storing them as
+        /// `np.savez` does otherwise.
+        pub fn new(writer: W, compressed: bool) -> Self {
+            let compression = if compressed {
+                CompressionMethod::Deflated
+            } else {
+                CompressionMethod::Stored
+            };
+            Self {
+                archive: ZipWriter::new(writer),
+                compression,
+            }
+        }
+
+        /// Adds `array` to the archive as `name`; see
+        /// [`ArrayND::write_npy`].
+        ///
+        /// # Errors
+        ///
+        /// Returns an error if writing fails.
+        pub fn write<T: NpyElement>(
+            &mut self,
+            name: &str,
+            array: &ArrayND<T>,
+        ) -> Result<(), Error> {
+            let size = array.len().saturating_mul(T::SIZE);
+            let options = SimpleFileOptions::default()
+                .compression_method(self.compression)
+                .large_file(size >= u32::MAX as usize);
+            self.archive
+                .start_file(format!("{name}.npy"), options)
+                .map_err(zip_error)?;
+            array.write_npy(&mut self.archive)
+        }
+
+        /// Writes the archive's directory, completing it, and returns the
+        /// writer.
+        ///
+        /// # Errors
+        ///
+        /// Returns an error if writing fails.
+        pub fn finish(self) -> Result<W, Error> {
+            let mut writer = self.archive.finish().map_err(zip_error)?;
+            writer.flush()?;
+            Ok(writer)
+        }
+    }
+}
@@ -0,0 +1,180 @@
+use std::{alloc::Layout, ptr, ptr::NonNull};
+
+use crate::{
+    allocator::RawAllocator,
+    error::Error,
+    os::{
+        check_page_alignment, map_anonymous, os_error, page_size, round_up,
+        unmap,
+    },
+    runtime_array::ArrayCStyle,
+};
+
+const MPOL_BIND: libc::c_int = 2;
+const MPOL_INTERLEAVE: libc::c_int = 3;
+const MPOL_F_NODE: libc::c_ulong = 1;
+const MPOL_F_ADDR: libc::c_ulong = 2;
+
+const MASK_WORD_BITS: usize = libc::c_ulong::BITS as usize;
+
+/// Where the pages of a NUMA allocation are placed.
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub enum NumaPolicy {
+    /// Place every page on a single node.
+    Bind(usize),
+    /// Spread pages round-robin across the given nodes.
+    Interleave(Vec<usize>),
+}
+
+impl NumaPolicy {
+    fn mode(&self) -> libc::c_int {
+        match self {
+            NumaPolicy::Bind(_) => MPOL_BIND,
+            NumaPolicy::Interleave(_) => MPOL_INTERLEAVE,
+        }
+    }
+
+    fn node_mask(&self) -> Result<Vec<libc::c_ulong>, Error> {
+        let nodes = match self {
+            NumaPolicy::Bind(node) => std::slice::from_ref(node),
+            NumaPolicy::Interleave(nodes) => nodes.as_slice(),
+        };
+        let highest = nodes.iter().copied().max().ok_or_else(|| {
+            Error::Other("NUMA policy names no nodes".to_string())
+        })?;
+
+        let mut mask = vec![0; highest / MASK_WORD_BITS + 1];
+        for node in nodes {
+            mask[node / MASK_WORD_BITS] |= 1 << (node % MASK_WORD_BITS);
+        }
+        Ok(mask)
+    }
+}
+
+/// Allocates anonymous memory whose pages are placed according to a
+/// [`NumaPolicy`].
+///
+/// Pages are faulted in lazily, so they land on the requested node(s) when
+/// first touched rather than on the node of the allocating thread.
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct NumaAlloc {
+    policy: NumaPolicy,
+}
+
+impl NumaAlloc {
+    /// Creates an allocator applying `policy` to every allocation.
+    pub const fn new(policy: NumaPolicy) -> Self {
+        Self { policy }
+    }
+
+    /// Returns the placement policy of this allocator.
+    pub const fn policy(&self) -> &NumaPolicy {
+        &self.policy
+    }
+}
+
+unsafe impl RawAllocator for NumaAlloc {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<u8>, Error> {
+        check_page_alignment(layout)?;
+        let len = round_up(layout.size(), page_size());
+        let mask = self.policy.node_mask()?;
+        let ptr = map_anonymous(len, 0)?;
+
+        let result = unsafe {
+            libc::syscall(
+                libc::SYS_mbind,
+                ptr.as_ptr(),
+                len,
+                self.policy.mode(),
+                mask.as_ptr(),
+                // the kernel expects one more than the number of mask bits
+                mask.len() * MASK_WORD_BITS + 1,
+                0,
+            )
+        };
+        if result != 0 {
+            let error = os_error("mbind");
+            unsafe { unmap(ptr, len) };
+            return Err(error);
+        }
+        Ok(ptr)
+    }
+
+    fn allocate_zeroed(&self, layout: Layout) -> Result<NonNull<u8>, Error> {
+        // anonymous mappings are always zero-filled by the kernel
+        self.allocate(layout)
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        unsafe { unmap(ptr, round_up(layout.size(), page_size())) }
+    }
+}
+
+impl<T> ArrayCStyle<T, NumaAlloc> {
+    /// Creates a new zeroed `Array` whose pages are all placed on `node`.
+    ///
+    /// # Example
+    ///
+    /// ```rust ignore
+    /// use runnarr::runtime_array::ArrayCStyle;
+    ///
+    /// let shard = ArrayCStyle::<u64, _>::new_on_node(1 << 24, 1).unwrap();
+    /// ```
+    #[track_caller]
+    pub fn new_on_node(size: usize, node: usize) -> Result<Self, Error> {
+        Self::zeroed_in(size, NumaAlloc::new(NumaPolicy::Bind(node)))
+    }
+
+    /// Creates a new zeroed `Array` whose pages are interleaved across
+    /// `nodes`.
+    #[track_caller]
+    pub fn new_interleaved(
+        size: usize,
+        nodes: &[usize],
+    ) -> Result<Self, Error> {
+        Self::zeroed_in(
+            size,
+            NumaAlloc::new(NumaPolicy::Interleave(nodes.to_vec())),
+        )
+    }
+}
+
+impl<T, A: RawAllocator> ArrayCStyle<T, A> {
+    /// Returns the NUMA node holding the page of the element at `index`.
+    ///
+    /// The page is faulted in if it has not been touched yet.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `index` is out of bounds or the kernel does not
+    /// support NUMA policies.
+    pub fn numa_node_of(&self, index: usize) -> Result<usize, Error> {
+        if index >= self.len() || std::mem::size_of::<T>() == 0 {
+            return Err(Error::Other(format!(
+                "Index {index} does not refer to an allocated element"
+            )));
+        }
+        let mut node: libc::c_int = -1;
+        let result = unsafe {
+            libc::syscall(
+                libc::SYS_get_mempolicy,
+                &mut node as *mut libc::c_int,
+                ptr::null_mut::<libc::c_ulong>(),
+                0,
+                self.ptr().add(index),
+                MPOL_F_NODE | MPOL_F_ADDR,
+            )
+        };
+        if result != 0 {
+            return Err(os_error("get_mempolicy"));
+        }
+        Ok(node as usize)
+    }
+
+    /// Returns the NUMA node holding the start of the buffer.
+    ///
+    /// See [`ArrayCStyle::numa_node_of`].
+    pub fn numa_node(&self) -> Result<usize, Error> {
+        self.numa_node_of(0)
+    }
+}
@@ -0,0 +1,845 @@
+use std::{alloc::Layout, io, mem, ptr, ptr::NonNull};
+
+use crate::{
+    allocator::RawAllocator, error::Error, runtime_array::ArrayCStyle,
+};
+
+/// Returns the size of a regular virtual memory page in bytes.
+pub fn page_size() -> usize {
+    unsafe { libc::sysconf(libc::_SC_PAGESIZE) as usize }
+}
+
+/// Rounds `size` up to the next multiple of `granule` (a power of two).
+#[inline(always)]
+pub(crate) const fn round_up(size: usize, granule: usize) -> usize {
+    (size + granule - 1) & !(granule - 1)
+}
+
+pub(crate) fn os_error(operation: &str) -> Error {
+    let error = io::Error::last_os_error();
+    Error::Io(io::Error::new(
+        error.kind(),
+        format!("{operation} failed: {error}"),
+    ))
+}
+
+/// Maps `len` bytes of private, zero-filled anonymous memory.
+pub(crate) fn map_anonymous(
+    len: usize,
+    extra_flags: libc::c_int,
+) -> Result<NonNull<u8>, Error> {
+    let ptr = unsafe {
+        libc::mmap(
+            ptr::null_mut(),
+            len,
+            libc::PROT_READ | libc::PROT_WRITE,
+            libc::MAP_PRIVATE | libc::MAP_ANONYMOUS | extra_flags,
+            -1,
+            0,
+        )
+    };
+    if ptr == libc::MAP_FAILED {
+        return Err(os_error("mmap"));
+    }
+    Ok(unsafe { NonNull::new_unchecked(ptr as *mut u8) })
+}
+
+/// Maps `len` bytes of the file behind `fd` as shared, writable memory.
+pub(crate) fn map_shared(
+    fd: libc::c_int,
+    len: usize,
+) -> Result<NonNull<u8>, Error> {
+    let ptr = unsafe {
+        libc::mmap(
+            ptr::null_mut(),
+            len,
+            libc::PROT_READ | libc::PROT_WRITE,
+            libc::MAP_SHARED,
+            fd,
+            0,
+        )
+    };
+    if ptr == libc::MAP_FAILED {
+        return Err(os_error("mmap"));
+    }
+    Ok(unsafe { NonNull::new_unchecked(ptr as *mut u8) })
+}
+
+/// Unmaps a region previously returned by [`map_anonymous`] or
+/// [`map_shared`].
+///
+/// # Safety
+///
+/// `ptr` and `len` must describe a live mapping that is not used afterwards.
+pub(crate) unsafe fn unmap(ptr: NonNull<u8>, len: usize) {
+    unsafe {
+        libc::munmap(ptr.as_ptr() as *mut libc::c_void, len);
+    }
+}
+
+pub(crate) fn check_page_alignment(layout: Layout) -> Result<(), Error> {
+    if layout.align() > page_size() {
+        return Err(Error::Other(format!(
+            "Alignment of {} bytes exceeds the page size",
+            layout.align()
+        )));
+    }
+    Ok(())
+}
+
+/// Allocates arrays directly from anonymous memory mappings.
+///
+/// Multi-gigabyte buffers that are only sparsely touched are better served
+/// by the kernel than by the global allocator: pages are mapped lazily as
+/// zero pages on first access, and the whole region is returned to the
+/// system on drop. Allocations are rounded up to whole pages.
+#[derive(Debug, Clone, Copy, Default, Hash, PartialEq, Eq)]
+pub struct Mmap;
+
+impl Mmap {
+    fn mapping_len(layout: Layout) -> usize {
+        round_up(layout.size(), page_size())
+    }
+}
+
+unsafe impl RawAllocator for Mmap {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<u8>, Error> {
+        check_page_alignment(layout)?;
+        // Sparse buffers should not be charged against the commit limit up
+        // front; pages are accounted for as they are touched.
+        #[cfg(target_os = "linux")]
+        let flags = libc::MAP_NORESERVE;
+        #[cfg(not(target_os = "linux"))]
+        let flags = 0;
+        map_anonymous(Self::mapping_len(layout), flags)
+    }
+
+    fn allocate_zeroed(&self, layout: Layout) -> Result<NonNull<u8>, Error> {
+        // anonymous mappings are always zero-filled by the kernel
+        self.allocate(layout)
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        unsafe { unmap(ptr, Self::mapping_len(layout)) }
+    }
+}
+
+/// Paging hints that can be passed to [`ArrayCStyle::advise`].
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub enum Advice {
+    /// The pages will be accessed soon; the kernel may start reading them in.
+    WillNeed,
+    /// The pages are not needed any more. Their memory is released; the next
+    /// access observes zero-filled pages again for anonymous memory, or
+    /// re-reads the pages from the file for file mappings.
+    DontNeed,
+    /// The pages will be accessed in order, so the kernel may read ahead
+    /// aggressively and drop pages soon after they were used.
+    Sequential,
+    /// The pages will be accessed in random order, so read-ahead is wasted.
+    Random,
+}
+
+impl Advice {
+    fn as_raw(self) -> libc::c_int {
+        match self {
+            Advice::WillNeed => libc::MADV_WILLNEED,
+            Advice::DontNeed => libc::MADV_DONTNEED,
+            Advice::Sequential => libc::MADV_SEQUENTIAL,
+            Advice::Random => libc::MADV_RANDOM,
+        }
+    }
+}
+
+/// Passes `advice` for the pages covering `len` bytes at `ptr`.
+///
+/// Pure hints are applied to every page the range touches. `DontNeed`
+/// discards memory, so it is only applied to pages that lie entirely inside
+/// the range; a partial trailing page may be shared with other data.
+pub(crate) fn advise_range(
+    ptr: *const u8,
+    len: usize,
+    advice: Advice,
+) -> Result<(), Error> {
+    let page = page_size();
+    if !(ptr as usize).is_multiple_of(page) {
+        return Err(Error::Other(
+            "madvise needs a page-aligned buffer".to_string(),
+        ));
+    }
+    let len = match advice {
+        Advice::DontNeed => len & !(page - 1),
+        _ => round_up(len, page),
+    };
+    if len == 0 {
+        return Ok(());
+    }
+    let result = unsafe {
+        libc::madvise(ptr as *mut libc::c_void, len, advice.as_raw())
+    };
+    if result != 0 {
+        return Err(os_error("madvise"));
+    }
+    Ok(())
+}
+
+impl<T, A: RawAllocator> ArrayCStyle<T, A> {
+    /// Passes a paging hint for the whole buffer to the kernel.
+    ///
+    /// Works on any page-aligned array, such as the ones created by
+    /// [`ArrayCStyle::new_mmap`] or `new_aligned(size, page_size())`, and is
+    /// meant for switching multi-gigabyte buffers between access phases.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the buffer does not start on a page boundary or
+    /// the kernel rejects the hint.
+    ///
+    /// # Example
+    ///
+    /// ```rust ignore
+    /// use runnarr::{os::Advice, runtime_array::ArrayCStyle};
+    ///
+    /// let mut samples = ArrayCStyle::<f32, _>::new_mmap(1 << 30).unwrap();
+    /// samples.advise(Advice::Sequential).unwrap();
+    /// let total: f32 = samples.as_slice().iter().sum();
+    /// samples.advise(Advice::DontNeed).unwrap();
+    /// ```
+    pub fn advise(&mut self, advice: Advice) -> Result<(), Error> {
+        advise_range(
+            self.ptr() as *const u8,
+            self.len() * mem::size_of::<T>(),
+            advice,
+        )
+    }
+}
+
+impl<T> ArrayCStyle<T, Mmap> {
+    /// Creates a new zeroed `Array` backed by its own anonymous mapping.
+    ///
+    /// See [`Mmap`].
+    ///
+    /// # Example
+    ///
+    /// ```rust ignore
+    /// use runnarr::{os::Advice, runtime_array::ArrayCStyle};
+    ///
+    /// let mut table = ArrayCStyle::<u64, _>::new_mmap(1 << 30).unwrap();
+    /// table[12345] = 1;
+    /// table.advise(Advice::DontNeed).unwrap();
+    /// assert_eq!(table[12345], 0);
+    /// ```
+    #[track_caller]
+    pub fn new_mmap(size: usize) -> Result<Self, Error> {
+        Self::zeroed_in(size, Mmap)
+    }
+}
+
+/// Changes the protection of the whole mapping behind `array`.
+#[cfg(feature = "freeze")]
+fn protect<T>(
+    array: &ArrayCStyle<T, Mmap>,
+    prot: libc::c_int,
+) -> Result<(), Error> {
+    let len = Mmap::mapping_len(array.layout());
+    if len == 0 {
+        return Ok(());
+    }
+    let result =
+        unsafe { libc::mprotect(array.ptr() as *mut libc::c_void, len, prot) };
+    if result != 0 {
+        return Err(os_error("mprotect"));
+    }
+    Ok(())
+}
+
+#[cfg(feature = "freeze")]
+impl<T> ArrayCStyle<T, Mmap> {
+    /// Marks the whole buffer read-only at the page level and seals it in a
+    /// [`Frozen`] wrapper.
+    ///
+    /// Any write to the buffer afterwards, including through stale raw
+    /// pointers or from C code, faults with `SIGSEGV` instead of silently
+    /// corrupting a lookup table.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `mprotect` fails; the array is dropped in that
+    /// case.
+    ///
+    /// # Example
+    ///
+    /// ```rust ignore
+    /// use runnarr::runtime_array::ArrayCStyle;
+    ///
+    /// let mut table = ArrayCStyle::<u16, _>::new_mmap(65536).unwrap();
+    /// build_crc_table(table.as_mut_slice());
+    /// let table = table.freeze().unwrap();
+    /// let crc = table[0x41];
+    /// ```
+    pub fn freeze(self) -> Result<Frozen<T>, Error> {
+        protect(&self, libc::PROT_READ)?;
+        Ok(Frozen { array: self })
+    }
+}
+
+/// A read-only, page-protected array produced by [`ArrayCStyle::freeze`].
+#[cfg(feature = "freeze")]
+#[derive(Debug)]
+pub struct Frozen<T> {
+    array: ArrayCStyle<T, Mmap>,
+}
+
+#[cfg(feature = "freeze")]
+impl<T> Frozen<T> {
+    /// Returns the length of the array.
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.array.len()
+    }
+
+    /// Returns `true` if the array holds no elements.
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.array.is_empty()
+    }
+
+    /// Returns a read-only raw pointer to the elements.
+    #[inline(always)]
+    pub fn ptr(&self) -> *const T {
+        self.array.ptr()
+    }
+
+    /// Gets a reference to the element at `index`, or `None` if it is out of
+    /// bounds.
+    #[inline(always)]
+    pub fn get(&self, index: usize) -> Option<&T> {
+        self.array.get(index)
+    }
+
+    /// Returns the elements as a slice.
+    #[inline(always)]
+    pub fn as_slice(&self) -> &[T] {
+        self.array.as_slice()
+    }
+
+    /// Makes the buffer writable again and returns the array.
+    pub fn thaw(self) -> Result<ArrayCStyle<T, Mmap>, Error> {
+        protect(&self.array, libc::PROT_READ | libc::PROT_WRITE)?;
+        let this = mem::ManuallyDrop::new(self);
+        Ok(unsafe { ptr::read(&this.array) })
+    }
+}
+
+#[cfg(feature = "freeze")]
+impl<T> Drop for Frozen<T> {
+    fn drop(&mut self) {
+        // the array may still write to its buffer on drop (`poison`)
+        let _ = protect(&self.array, libc::PROT_READ | libc::PROT_WRITE);
+    }
+}
+
+#[cfg(feature = "freeze")]
+impl<T> std::ops::Deref for Frozen<T> {
+    type Target = [T];
+    fn deref(&self) -> &Self::Target {
+        self.as_slice()
+    }
+}
+
+#[cfg(feature = "freeze")]
+#[cfg(not(feature = "no-panic"))]
+impl<T> std::ops::Index<usize> for Frozen<T> {
+    type Output = T;
+    fn index(&self, index: usize) -> &Self::Output {
+        self.get(index).expect("Index out of bounds")
+    }
+}
+
+/// Which end of a guarded buffer sits directly against its guard page.
+#[derive(Debug, Clone, Copy, Default, Hash, PartialEq, Eq)]
+pub enum GuardSide {
+    /// The buffer ends at the trailing guard page, so reads and writes past
+    /// the last element fault immediately.
+    #[default]
+    End,
+    /// The buffer starts at the leading guard page, so reads and writes
+    /// before the first element fault immediately.
+    Start,
+}
+
+/// A debugging allocator that surrounds every buffer with inaccessible guard
+/// pages.
+///
+/// Each allocation gets its own mapping laid out as
+/// `[guard page][data pages][guard page]`, where both guard pages are mapped
+/// `PROT_NONE`. An out-of-bounds access through a raw pointer then faults on
+/// the spot instead of silently corrupting neighbouring heap data.
+///
+/// Since the data pages rarely match the buffer size exactly, only one end of
+/// the buffer can sit flush against a guard page; [`GuardSide`] picks which
+/// kind of overrun is caught immediately. An overrun at the other end is
+/// caught once it crosses the unused slack of the outermost data page.
+///
+/// Every allocation costs at least three pages, so this is meant for tests
+/// and debug builds only.
+#[derive(Debug, Clone, Copy, Default, Hash, PartialEq, Eq)]
+pub struct GuardPages {
+    side: GuardSide,
+}
+
+impl GuardPages {
+    /// Creates a guard page allocator placing buffers flush against `side`.
+    pub const fn new(side: GuardSide) -> Self {
+        Self { side }
+    }
+
+    /// Returns the end of the buffer that touches a guard page.
+    pub const fn side(&self) -> GuardSide {
+        self.side
+    }
+
+    fn data_len(layout: Layout) -> usize {
+        round_up(layout.size(), page_size())
+    }
+}
+
+unsafe impl RawAllocator for GuardPages {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<u8>, Error> {
+        check_page_alignment(layout)?;
+        let page = page_size();
+        let data_len = Self::data_len(layout);
+        let base = map_anonymous(data_len + 2 * page, 0)?;
+
+        unsafe {
+            let leading = base.as_ptr();
+            let trailing = leading.add(page + data_len);
+            for guard in [leading, trailing] {
+                if libc::mprotect(
+                    guard as *mut libc::c_void,
+                    page,
+                    libc::PROT_NONE,
+                ) != 0
+                {
+                    let error = os_error("mprotect");
+                    unmap(base, data_len + 2 * page);
+                    return Err(error);
+                }
+            }
+
+            let data = leading.add(page);
+            let offset = match self.side {
+                GuardSide::Start => 0,
+                // push the buffer as close to the trailing guard page as
+                // its alignment allows
+                GuardSide::End => {
+                    (data_len - layout.size()) & !(layout.align() - 1)
+                }
+            };
+            Ok(NonNull::new_unchecked(data.add(offset)))
+        }
+    }
+
+    fn allocate_zeroed(&self, layout: Layout) -> Result<NonNull<u8>, Error> {
+        // anonymous mappings are always zero-filled by the kernel
+        self.allocate(layout)
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        let page = page_size();
+        let data = ptr.as_ptr() as usize & !(page - 1);
+        unsafe {
+            unmap(
+                NonNull::new_unchecked((data - page) as *mut u8),
+                Self::data_len(layout) + 2 * page,
+            )
+        }
+    }
+}
+
+impl<T> ArrayCStyle<T, GuardPages> {
+    /// Creates a new zeroed `Array` surrounded by guard pages, with the end
+    /// of the buffer flush against the trailing guard page.
+    ///
+    /// See [`GuardPages`].
+    ///
+    /// # Example
+    ///
+    /// ```rust ignore
+    /// use runnarr::runtime_array::ArrayCStyle;
+    ///
+    /// let array = ArrayCStyle::<u32, _>::new_guarded(10).unwrap();
+    /// // Faults instead of reading past the buffer:
+    /// // unsafe { array.ptr().add(10).read() };
+    /// ```
+    #[track_caller]
+    pub fn new_guarded(size: usize) -> Result<Self, Error> {
+        Self::zeroed_in(size, GuardPages::default())
+    }
+
+    /// Creates a new zeroed `Array` surrounded by guard pages, with the
+    /// given end of the buffer flush against its guard page.
+    #[track_caller]
+    pub fn new_guarded_at(size: usize, side: GuardSide) -> Result<Self, Error> {
+        Self::zeroed_in(size, GuardPages::new(side))
+    }
+}
+
+/// Allocates page-locked memory that is never swapped out.
+///
+/// Every allocation gets its own anonymous mapping which is `mlock`ed before
+/// it is handed out and `munlock`ed when it is freed. This keeps sensitive
+/// data off the swap device and keeps latency-critical buffers from page
+/// faulting. Allocations are rounded up to whole pages.
+///
+/// Locking memory is a privileged operation: without `CAP_IPC_LOCK` the
+/// total amount is capped by `RLIMIT_MEMLOCK`, which is often only a few
+/// megabytes. Exceeding it makes the allocation fail with an error naming
+/// the limit instead of silently returning unlocked memory.
+#[derive(Debug, Clone, Copy, Default, Hash, PartialEq, Eq)]
+pub struct Locked;
+
+impl Locked {
+    fn mapping_len(layout: Layout) -> usize {
+        round_up(layout.size(), page_size())
+    }
+}
+
+unsafe impl RawAllocator for Locked {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<u8>, Error> {
+        check_page_alignment(layout)?;
+        let len = Self::mapping_len(layout);
+        let ptr = map_anonymous(len, 0)?;
+
+        if unsafe { libc::mlock(ptr.as_ptr() as *const libc::c_void, len) } != 0
+        {
+            let error = io::Error::last_os_error();
+            unsafe { unmap(ptr, len) };
+            let reason = match error.raw_os_error() {
+                Some(libc::EPERM) => {
+                    "the process lacks the privilege to lock memory"
+                }
+                Some(libc::ENOMEM) | Some(libc::EAGAIN) => {
+                    "the RLIMIT_MEMLOCK limit would be exceeded"
+                }
+                _ => "the kernel refused the request",
+            };
+            return Err(Error::Io(io::Error::new(
+                error.kind(),
+                format!("mlock of {len} bytes failed, {reason}: {error}"),
+            )));
+        }
+        Ok(ptr)
+    }
+
+    fn allocate_zeroed(&self, layout: Layout) -> Result<NonNull<u8>, Error> {
+        // anonymous mappings are always zero-filled by the kernel
+        self.allocate(layout)
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        let len = Self::mapping_len(layout);
+        unsafe {
+            libc::munlock(ptr.as_ptr() as *const libc::c_void, len);
+            unmap(ptr, len);
+        }
+    }
+}
+
+impl<T> ArrayCStyle<T, Locked> {
+    /// Creates a new zeroed `Array` whose pages are locked in memory.
+    ///
+    /// See [`Locked`].
+    ///
+    /// # Example
+    ///
+    /// ```rust ignore
+    /// use runnarr::runtime_array::ArrayCStyle;
+    ///
+    /// let key = ArrayCStyle::<u8, _>::new_locked(32).unwrap();
+    /// ```
+    #[track_caller]
+    pub fn new_locked(size: usize) -> Result<Self, Error> {
+        Self::zeroed_in(size, Locked)
+    }
+}
+
+/// Row pitches of [`ArrayCStyle::new_pinned_pitched`] buffers are multiples
+/// of this many bytes, matching the pitch alignment GPU copy engines expect.
+pub const PITCH_ALIGNMENT: usize = 512;
+
+/// Allocates page-locked host memory for asynchronous DMA and GPU transfers.
+///
+/// Buffers are page-aligned, rounded up to whole pages and locked like
+/// [`Locked`] ones, so they can be handed to `cudaHostRegister` (or a
+/// driver's DMA API) as-is and used as staging buffers without copying
+/// through a separate pinned region. On Linux the pages are additionally
+/// excluded from `fork`, so a child process can never trigger a
+/// copy-on-write that moves a page under an in-flight transfer.
+///
+/// The allocator remembers the row pitch the buffer was laid out with; see
+/// [`ArrayCStyle::pitch`].
+#[derive(Debug, Clone, Copy, Default, Hash, PartialEq, Eq)]
+pub struct Pinned {
+    pitch: usize,
+}
+
+impl Pinned {
+    /// Creates the allocator for buffers laid out with rows of `pitch` bytes.
+    pub const fn with_pitch(pitch: usize) -> Self {
+        Self { pitch }
+    }
+}
+
+unsafe impl RawAllocator for Pinned {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<u8>, Error> {
+        let ptr = Locked.allocate(layout)?;
+        #[cfg(target_os = "linux")]
+        {
+            let len = Locked::mapping_len(layout);
+            let result = unsafe {
+                libc::madvise(
+                    ptr.as_ptr() as *mut libc::c_void,
+                    len,
+                    libc::MADV_DONTFORK,
+                )
+            };
+            if result != 0 {
+                let error = os_error("madvise(MADV_DONTFORK)");
+                unsafe { Locked.deallocate(ptr, layout) };
+                return Err(error);
+            }
+        }
+        Ok(ptr)
+    }
+
+    fn allocate_zeroed(&self, layout: Layout) -> Result<NonNull<u8>, Error> {
+        // anonymous mappings are always zero-filled by the kernel
+        self.allocate(layout)
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        unsafe { Locked.deallocate(ptr, layout) }
+    }
+}
+
+impl<T> ArrayCStyle<T, Pinned> {
+    /// Creates a new zeroed `Array` in page-locked memory, laid out as a
+    /// single row.
+    ///
+    /// See [`Pinned`].
+    ///
+    /// # Example
+    ///
+    /// ```rust ignore
+    /// use runnarr::runtime_array::ArrayCStyle;
+    ///
+    /// let staging = ArrayCStyle::<f32, _>::new_pinned(1 << 20).unwrap();
+    /// cuda_host_register(staging.dma_ptr(), staging.dma_len());
+    /// ```
+    #[track_caller]
+    pub fn new_pinned(size: usize) -> Result<Self, Error> {
+        let pitch = size.checked_mul(mem::size_of::<T>()).ok_or_else(|| {
+            Error::Other(format!(
+                "Pinned array of {size} elements overflows usize"
+            ))
+        })?;
+        Self::zeroed_in(size, Pinned::with_pitch(pitch))
+    }
+
+    /// Creates a new zeroed `Array` in page-locked memory holding `height`
+    /// rows of `width` elements, each row starting [`PITCH_ALIGNMENT`]
+    /// aligned.
+    ///
+    /// Row `y` starts at element `y * pitch() / size_of::<T>()`; the padding
+    /// at the end of each row is part of the array.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the padded rows overflow `usize`, or if the
+    /// memory cannot be allocated or locked.
+    ///
+    /// # Example
+    ///
+    /// ```rust ignore
+    /// use runnarr::runtime_array::ArrayCStyle;
+    ///
+    /// let frame = ArrayCStyle::<u8, _>::new_pinned_pitched(1920 * 4, 1080)
+    ///     .unwrap();
+    /// cuda_memcpy_2d_async(device, device_pitch, frame.dma_ptr(), frame.pitch(), ..);
+    /// ```
+    #[track_caller]
+    pub fn new_pinned_pitched(
+        width: usize,
+        height: usize,
+    ) -> Result<Self, Error> {
+        let overflow = || {
+            Error::Other(format!(
+                "Pinned array of {height} rows of {width} overflows usize"
+            ))
+        };
+        let elem_size = mem::size_of::<T>().max(1);
+        // the pitch must hold whole elements as well as being aligned
+        let granule = (PITCH_ALIGNMENT / gcd(PITCH_ALIGNMENT, elem_size))
+            .checked_mul(elem_size)
+            .ok_or_else(overflow)?;
+        let pitch = width
+            .checked_mul(elem_size)
+            .and_then(|row| row.checked_next_multiple_of(granule))
+            .ok_or_else(overflow)?;
+        let len = (pitch / elem_size)
+            .checked_mul(height)
+            .ok_or_else(overflow)?;
+        Self::zeroed_in(len, Pinned::with_pitch(pitch))
+    }
+
+    /// Returns the distance between the starts of two rows in bytes.
+    #[inline(always)]
+    pub fn pitch(&self) -> usize {
+        self.allocator().pitch
+    }
+
+    /// Returns the start of the buffer, ready to be registered with a DMA or
+    /// GPU API.
+    #[inline(always)]
+    pub fn dma_ptr(&self) -> *mut libc::c_void {
+        self.ptr() as *mut libc::c_void
+    }
+
+    /// Returns the number of locked bytes behind [`ArrayCStyle::dma_ptr`],
+    /// which is always a whole number of pages.
+    #[inline(always)]
+    pub fn dma_len(&self) -> usize {
+        Locked::mapping_len(self.layout())
+    }
+}
+
+const fn gcd(mut a: usize, mut b: usize) -> usize {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// The huge page sizes that can be requested from the kernel.
+#[cfg(target_os = "linux")]
+#[derive(Debug, Clone, Copy, Default, Hash, PartialEq, Eq)]
+pub enum HugePageSize {
+    /// 2 MiB pages, available on most x86_64 and aarch64 systems.
+    #[default]
+    Size2MiB,
+    /// 1 GiB pages, which usually have to be reserved at boot.
+    Size1GiB,
+}
+
+#[cfg(target_os = "linux")]
+impl HugePageSize {
+    /// Returns the page size in bytes.
+    pub const fn bytes(self) -> usize {
+        1 << self.shift()
+    }
+
+    const fn shift(self) -> usize {
+        match self {
+            HugePageSize::Size2MiB => 21,
+            HugePageSize::Size1GiB => 30,
+        }
+    }
+
+    // The kernel encodes the requested size as log2(size) << MAP_HUGE_SHIFT.
+    const fn map_flag(self) -> libc::c_int {
+        const MAP_HUGE_SHIFT: usize = 26;
+        libc::MAP_HUGETLB | (self.shift() << MAP_HUGE_SHIFT) as libc::c_int
+    }
+}
+
+/// Allocates from huge pages to cut TLB misses on very large arrays.
+///
+/// Each allocation first requests explicit huge pages (`MAP_HUGETLB`). If the
+/// kernel has none reserved, it falls back to a regular anonymous mapping
+/// marked with `MADV_HUGEPAGE`, so transparent huge pages can still back it
+/// when they are enabled. Allocations are rounded up to whole huge pages.
+#[cfg(target_os = "linux")]
+#[derive(Debug, Clone, Copy, Default, Hash, PartialEq, Eq)]
+pub struct HugePages {
+    page_size: HugePageSize,
+}
+
+#[cfg(target_os = "linux")]
+impl HugePages {
+    /// Creates a huge page allocator requesting pages of `page_size`.
+    pub const fn new(page_size: HugePageSize) -> Self {
+        Self { page_size }
+    }
+
+    /// Returns the huge page size requested by this allocator.
+    pub const fn page_size(&self) -> HugePageSize {
+        self.page_size
+    }
+
+    fn mapping_len(&self, layout: Layout) -> usize {
+        round_up(layout.size(), self.page_size.bytes())
+    }
+}
+
+#[cfg(target_os = "linux")]
+unsafe impl RawAllocator for HugePages {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<u8>, Error> {
+        check_page_alignment(layout)?;
+        let len = self.mapping_len(layout);
+
+        if let Ok(ptr) = map_anonymous(len, self.page_size.map_flag()) {
+            return Ok(ptr);
+        }
+
+        let ptr = map_anonymous(len, 0)?;
+        unsafe {
+            // Only a hint: failing to enable transparent huge pages still
+            // leaves a perfectly usable mapping.
+            libc::madvise(
+                ptr.as_ptr() as *mut libc::c_void,
+                len,
+                libc::MADV_HUGEPAGE,
+            );
+        }
+        Ok(ptr)
+    }
+
+    fn allocate_zeroed(&self, layout: Layout) -> Result<NonNull<u8>, Error> {
+        // anonymous mappings are always zero-filled by the kernel
+        self.allocate(layout)
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        unsafe { unmap(ptr, self.mapping_len(layout)) }
+    }
+}
+
+#[cfg(target_os = "linux")]
+impl<T> ArrayCStyle<T, HugePages> {
+    /// Creates a new zeroed `Array` backed by 2 MiB huge pages.
+    ///
+    /// Falls back to regular pages when no huge pages are available, see
+    /// [`HugePages`].
+    ///
+    /// # Example
+    ///
+    /// ```rust ignore
+    /// use runnarr::runtime_array::ArrayCStyle;
+    ///
+    /// let samples = ArrayCStyle::<f64, _>::new_huge(1 << 28).unwrap();
+    /// ```
+    #[track_caller]
+    pub fn new_huge(size: usize) -> Result<Self, Error> {
+        Self::new_huge_with(size, HugePageSize::default())
+    }
+
+    /// Creates a new zeroed `Array` backed by huge pages of `page_size`.
+    #[track_caller]
+    pub fn new_huge_with(
+        size: usize,
+        page_size: HugePageSize,
+    ) -> Result<Self, Error> {
+        Self::zeroed_in(size, HugePages::new(page_size))
+    }
+}
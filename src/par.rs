@@ -0,0 +1,321 @@
+use std::{marker::PhantomData, ops::Add, ptr};
+
+use rayon::{
+    iter::{
+        plumbing::{
+            bridge, Consumer, Producer, ProducerCallback, UnindexedConsumer,
+        },
+        IndexedParallelIterator, IntoParallelIterator, ParallelIterator,
+    },
+    slice::{ParallelSlice, ParallelSliceMut},
+};
+
+use crate::{
+    allocator::{Global, RawAllocator},
+    runtime_array::ArrayCStyle,
+};
+
+/// Chunks handed to the pool are never smaller than this many elements, so
+/// small arrays are not split into tasks that cost more than they do.
+const MIN_CHUNK_LEN: usize = 4096;
+
+/// Splits `len` elements into a few chunks per worker thread.
+pub(crate) fn chunk_len(len: usize) -> usize {
+    len.div_ceil(rayon::current_num_threads() * 4)
+        .max(MIN_CHUNK_LEN)
+}
+
+impl<T: Send, A: RawAllocator> ArrayCStyle<T, A> {
+    /// Writes a clone of `value` into every slot, filling disjoint chunks on
+    /// the rayon thread pool.
+    ///
+    /// The slots are overwritten without dropping what they held, so this is
+    /// the way to initialize a buffer from [`ArrayCStyle::new`].
+    ///
+    /// # Example
+    ///
+    /// ```rust ignore
+    /// let mut distances = ArrayCStyle::<f32>::new(1 << 30).unwrap();
+    /// distances.par_fill(f32::INFINITY);
+    /// ```
+    pub fn par_fill(&mut self, value: T)
+    where
+        T: Clone + Sync,
+    {
+        let chunk_len = chunk_len(self.len());
+        self.as_mut_slice()
+            .par_chunks_mut(chunk_len)
+            .for_each(|chunk| {
+                for slot in chunk {
+                    unsafe { ptr::write(slot, value.clone()) };
+                }
+            });
+    }
+
+    /// Replaces every element with `f(&element)`, mapping disjoint chunks on
+    /// the rayon thread pool.
+    ///
+    /// The elements must be initialized; the old values are dropped as they
+    /// are replaced.
+    pub fn par_map_in_place<F>(&mut self, f: F)
+    where
+        F: Fn(&T) -> T + Sync,
+    {
+        let chunk_len = chunk_len(self.len());
+        self.as_mut_slice()
+            .par_chunks_mut(chunk_len)
+            .for_each(|chunk| {
+                for slot in chunk {
+                    *slot = f(slot);
+                }
+            });
+    }
+
+    /// Replaces every element with the sum of itself and all the elements
+    /// before it (an inclusive prefix sum), on the rayon thread pool.
+    ///
+    /// Uses the two-pass blocked algorithm: the first pass sums disjoint
+    /// chunks in parallel, a short sequential scan over the chunk sums gives
+    /// each chunk its starting offset, and the second pass scans the chunks
+    /// in parallel from those offsets. Every element is read twice and
+    /// written once.
+    ///
+    /// `T::default()` must be the additive identity, as it is for the
+    /// primitive numbers. Additions overflow exactly as `+` does.
+    ///
+    /// # Example
+    ///
+    /// ```rust ignore
+    /// let mut counts = ArrayCStyle::<u64>::try_from_slice(&[3, 1, 4, 1])?;
+    /// counts.par_scan();
+    /// assert_eq!(counts.as_slice(), [3, 4, 8, 9]);
+    /// ```
+    pub fn par_scan(&mut self)
+    where
+        T: Copy + Default + Add<Output = T> + Sync,
+    {
+        scan_blocked(self.as_mut_slice(), false);
+    }
+
+    /// Replaces every element with the sum of all the elements before it (an
+    /// exclusive prefix sum), on the rayon thread pool; see
+    /// [`ArrayCStyle::par_scan`].
+    ///
+    /// This turns per-bucket counts into bucket start offsets, as in the
+    /// scatter step of a radix partition.
+    ///
+    /// # Returns
+    ///
+    /// The sum of all the elements.
+    pub fn par_scan_exclusive(&mut self) -> T
+    where
+        T: Copy + Default + Add<Output = T> + Sync,
+    {
+        scan_blocked(self.as_mut_slice(), true)
+    }
+}
+
+/// Prefix-sums `slice` in place in two parallel passes and returns the total.
+fn scan_blocked<T>(slice: &mut [T], exclusive: bool) -> T
+where
+    T: Copy + Default + Add<Output = T> + Send + Sync,
+{
+    let chunk_len = chunk_len(slice.len());
+    let sums: Vec<T> = slice
+        .par_chunks(chunk_len)
+        .map(|chunk| chunk.iter().fold(T::default(), |sum, &value| sum + value))
+        .collect();
+
+    let mut total = T::default();
+    let offsets: Vec<T> = sums
+        .into_iter()
+        .map(|sum| {
+            let offset = total;
+            total = total + sum;
+            offset
+        })
+        .collect();
+
+    slice.par_chunks_mut(chunk_len).zip(offsets).for_each(
+        |(chunk, mut running)| {
+            for slot in chunk {
+                let value = *slot;
+                if exclusive {
+                    *slot = running;
+                    running = running + value;
+                } else {
+                    running = running + value;
+                    *slot = running;
+                }
+            }
+        },
+    );
+    total
+}
+
+impl<'a, T: Sync + 'a, A: RawAllocator> IntoParallelIterator
+    for &'a ArrayCStyle<T, A>
+{
+    type Item = &'a T;
+    type Iter = rayon::slice::Iter<'a, T>;
+
+    fn into_par_iter(self) -> Self::Iter {
+        self.as_slice().into_par_iter()
+    }
+}
+
+impl<'a, T: Send + 'a, A: RawAllocator> IntoParallelIterator
+    for &'a mut ArrayCStyle<T, A>
+{
+    type Item = &'a mut T;
+    type Iter = rayon::slice::IterMut<'a, T>;
+
+    fn into_par_iter(self) -> Self::Iter {
+        self.as_mut_slice().into_par_iter()
+    }
+}
+
+impl<T: Send, A: RawAllocator + Send> IntoParallelIterator
+    for ArrayCStyle<T, A>
+{
+    type Item = T;
+    type Iter = ArrayParIntoIter<T, A>;
+
+    fn into_par_iter(self) -> Self::Iter {
+        ArrayParIntoIter { array: self }
+    }
+}
+
+/// A parallel iterator moving the elements out of an [`ArrayCStyle`].
+///
+/// Like every array in the crate, elements that are not consumed (e.g. after
+/// a `find_any` short-circuits) are not dropped. The buffer is released once
+/// the iterator completes.
+#[derive(Debug)]
+pub struct ArrayParIntoIter<T, A: RawAllocator = Global> {
+    array: ArrayCStyle<T, A>,
+}
+
+impl<T: Send, A: RawAllocator + Send> ParallelIterator
+    for ArrayParIntoIter<T, A>
+{
+    type Item = T;
+
+    fn drive_unindexed<C>(self, consumer: C) -> C::Result
+    where
+        C: UnindexedConsumer<Self::Item>,
+    {
+        bridge(self, consumer)
+    }
+
+    fn opt_len(&self) -> Option<usize> {
+        Some(self.array.len())
+    }
+}
+
+impl<T: Send, A: RawAllocator + Send> IndexedParallelIterator
+    for ArrayParIntoIter<T, A>
+{
+    fn len(&self) -> usize {
+        self.array.len()
+    }
+
+    fn drive<C: Consumer<Self::Item>>(self, consumer: C) -> C::Result {
+        bridge(self, consumer)
+    }
+
+    fn with_producer<CB: ProducerCallback<Self::Item>>(
+        mut self,
+        callback: CB,
+    ) -> CB::Output {
+        let producer = MoveProducer {
+            ptr: self.array.as_mut_slice().as_mut_ptr(),
+            len: self.array.len(),
+            _marker: PhantomData,
+        };
+        // `self.array` outlives the producer and only frees the buffer
+        callback.callback(producer)
+    }
+}
+
+/// Hands out disjoint ranges of the buffer, moving each element out exactly
+/// once.
+struct MoveProducer<'a, T> {
+    ptr: *mut T,
+    len: usize,
+    _marker: PhantomData<&'a mut [T]>,
+}
+
+// the producer owns the elements in its range, so moving it to another
+// thread moves those elements
+unsafe impl<T: Send> Send for MoveProducer<'_, T> {}
+
+impl<'a, T: Send> Producer for MoveProducer<'a, T> {
+    type Item = T;
+    type IntoIter = MoveIter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        MoveIter {
+            ptr: self.ptr,
+            front: 0,
+            back: self.len,
+            _marker: PhantomData,
+        }
+    }
+
+    fn split_at(self, index: usize) -> (Self, Self) {
+        let right = MoveProducer {
+            ptr: unsafe { self.ptr.add(index) },
+            len: self.len - index,
+            _marker: PhantomData,
+        };
+        let left = MoveProducer {
+            ptr: self.ptr,
+            len: index,
+            _marker: PhantomData,
+        };
+        (left, right)
+    }
+}
+
+/// The sequential tail of [`MoveProducer`].
+///
+/// Walks indices rather than pointers so zero-sized elements are counted.
+struct MoveIter<'a, T> {
+    ptr: *mut T,
+    front: usize,
+    back: usize,
+    _marker: PhantomData<&'a mut [T]>,
+}
+
+impl<T> Iterator for MoveIter<'_, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        if self.front == self.back {
+            return None;
+        }
+        // each slot is read once; `front` and `back` only move towards
+        // each other
+        let value = unsafe { ptr::read(self.ptr.add(self.front)) };
+        self.front += 1;
+        Some(value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.back - self.front;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<T> DoubleEndedIterator for MoveIter<'_, T> {
+    fn next_back(&mut self) -> Option<T> {
+        if self.front == self.back {
+            return None;
+        }
+        self.back -= 1;
+        Some(unsafe { ptr::read(self.ptr.add(self.back)) })
+    }
+}
+
+impl<T> ExactSizeIterator for MoveIter<'_, T> {}
@@ -0,0 +1,195 @@
+use std::{
+    cell::RefCell,
+    fmt,
+    mem::ManuallyDrop,
+    ops::{Deref, DerefMut},
+};
+
+use crate::{error::Error, runtime_array::ArrayCStyle, usage::MemoryUsage};
+
+/// The default number of buffers kept per size class.
+pub const DEFAULT_MAX_PER_CLASS: usize = 16;
+
+/// A recycler caching freed array buffers for reuse.
+///
+/// Buffers are grouped in power-of-two size classes. [`ArrayPool::acquire`]
+/// hands out a cached buffer of a large enough class when one is available
+/// and only falls back to a fresh allocation otherwise, which cuts allocator
+/// churn for workloads that keep allocating similarly sized arrays.
+///
+/// Recycled buffers keep whatever contents they had; like
+/// [`ArrayCStyle::new`], acquired arrays must be initialized before use.
+///
+/// # Example
+///
+/// ```rust ignore
+/// use runnarr::pool::ArrayPool;
+///
+/// let pool = ArrayPool::<f32>::new();
+/// for packet in stream {
+///     let mut samples = pool.acquire_pooled(packet.len()).unwrap();
+///     decode(packet, &mut samples);
+///     // the buffer goes back to the pool here
+/// }
+/// ```
+pub struct ArrayPool<T> {
+    classes: RefCell<Vec<Vec<ArrayCStyle<T>>>>,
+    max_per_class: usize,
+}
+
+impl<T> ArrayPool<T> {
+    /// Creates an empty pool keeping up to [`DEFAULT_MAX_PER_CLASS`] buffers
+    /// per size class.
+    pub fn new() -> Self {
+        Self::with_max_per_class(DEFAULT_MAX_PER_CLASS)
+    }
+
+    /// Creates an empty pool keeping up to `max_per_class` buffers per size
+    /// class. Buffers released into a full class are freed.
+    pub fn with_max_per_class(max_per_class: usize) -> Self {
+        Self {
+            classes: RefCell::new(Vec::new()),
+            max_per_class,
+        }
+    }
+
+    /// Takes an array with room for at least `len` elements from the pool,
+    /// allocating a new one if no cached buffer is large enough.
+    ///
+    /// The returned array's `len()` is `len` rounded up to its size class,
+    /// i.e. the next power of two.
+    pub fn acquire(&self, len: usize) -> Result<ArrayCStyle<T>, Error> {
+        let capacity = len.max(1).next_power_of_two();
+        let class = capacity.trailing_zeros() as usize;
+
+        let cached = self
+            .classes
+            .borrow_mut()
+            .get_mut(class)
+            .and_then(|buffers| buffers.pop());
+        match cached {
+            Some(array) => Ok(array),
+            None => ArrayCStyle::new(capacity),
+        }
+    }
+
+    /// Like [`ArrayPool::acquire`], but wraps the array in a handle that
+    /// exposes exactly `len` elements and returns the buffer to the pool when
+    /// dropped.
+    pub fn acquire_pooled(&self, len: usize) -> Result<Pooled<'_, T>, Error> {
+        Ok(Pooled {
+            array: ManuallyDrop::new(self.acquire(len)?),
+            len,
+            pool: self,
+        })
+    }
+
+    /// Hands an array back to the pool for later reuse.
+    ///
+    /// Arrays of any length are accepted; they are filed under the largest
+    /// size class they can serve. Empty arrays and arrays released into a
+    /// full class are simply freed.
+    pub fn release(&self, array: ArrayCStyle<T>) {
+        if array.is_empty() {
+            return;
+        }
+        let class = (usize::BITS - 1 - array.len().leading_zeros()) as usize;
+
+        let mut classes = self.classes.borrow_mut();
+        if classes.len() <= class {
+            classes.resize_with(class + 1, Vec::new);
+        }
+        if classes[class].len() < self.max_per_class {
+            classes[class].push(array);
+        }
+    }
+
+    /// Returns the number of buffers currently cached.
+    pub fn cached(&self) -> usize {
+        self.classes.borrow().iter().map(Vec::len).sum()
+    }
+
+    /// Returns the memory held by cached buffers, all of which is slack
+    /// until the buffers are acquired again.
+    pub fn memory_usage(&self) -> MemoryUsage {
+        let allocated_bytes = self
+            .classes
+            .borrow()
+            .iter()
+            .flatten()
+            .map(ArrayCStyle::allocated_bytes)
+            .sum();
+        MemoryUsage {
+            allocated_bytes,
+            used_bytes: 0,
+        }
+    }
+
+    /// Frees every cached buffer.
+    pub fn clear(&mut self) {
+        self.classes.get_mut().clear();
+    }
+}
+
+impl<T> Default for ArrayPool<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> fmt::Debug for ArrayPool<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ArrayPool")
+            .field("cached", &self.cached())
+            .field("max_per_class", &self.max_per_class)
+            .finish()
+    }
+}
+
+/// An array borrowed from an [`ArrayPool`] that returns to it on drop.
+///
+/// Dereferences to a slice of exactly the requested length.
+pub struct Pooled<'a, T> {
+    array: ManuallyDrop<ArrayCStyle<T>>,
+    len: usize,
+    pool: &'a ArrayPool<T>,
+}
+
+impl<T> Pooled<'_, T> {
+    /// Detaches the array from the pool so it is not returned on drop.
+    ///
+    /// The array has the full length of its size class.
+    pub fn detach(self) -> ArrayCStyle<T> {
+        let mut this = ManuallyDrop::new(self);
+        unsafe { ManuallyDrop::take(&mut this.array) }
+    }
+}
+
+impl<T> Deref for Pooled<'_, T> {
+    type Target = [T];
+    fn deref(&self) -> &Self::Target {
+        &self.array.as_slice()[..self.len]
+    }
+}
+
+impl<T> DerefMut for Pooled<'_, T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.array.as_mut_slice()[..self.len]
+    }
+}
+
+impl<T> Drop for Pooled<'_, T> {
+    fn drop(&mut self) {
+        let array = unsafe { ManuallyDrop::take(&mut self.array) };
+        self.pool.release(array);
+    }
+}
+
+impl<T> fmt::Debug for Pooled<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Pooled")
+            .field("len", &self.len)
+            .field("capacity", &self.array.len())
+            .finish()
+    }
+}
@@ -0,0 +1,2 @@
+pub mod mpmc;
+pub mod spsc;
@@ -0,0 +1,211 @@
+use std::{
+    cell::UnsafeCell,
+    fmt,
+    mem::MaybeUninit,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use crate::{aligned::CacheAligned, error::Error, runtime_array::ArrayCStyle};
+
+/// A queue slot and the position it is ready for.
+///
+/// For the push at position `pos` the slot's sequence is `pos` while it is
+/// free, `pos + 1` once the value is written, and `pos + capacity` once the
+/// value has been popped again, which frees it for the next lap.
+struct Slot<T> {
+    seq: AtomicUsize,
+    value: UnsafeCell<MaybeUninit<T>>,
+}
+
+/// A bounded multi-producer multi-consumer queue.
+///
+/// This is Dmitry Vyukov's array queue: every slot carries a sequence number
+/// that tells producers and consumers whether it is their turn, so a push or
+/// pop is one compare-and-swap on the shared position plus one store on the
+/// slot, and threads working on different slots never touch the same cache
+/// line. Neither operation blocks; they report a full or empty queue instead.
+///
+/// Share it between threads by reference (e.g. with `std::thread::scope`) or
+/// behind an `Arc`.
+///
+/// # Example
+///
+/// ```rust ignore
+/// use runnarr::queues::mpmc::Queue;
+///
+/// let jobs = Queue::<Job>::new(1024).unwrap();
+/// std::thread::scope(|scope| {
+///     for _ in 0..workers {
+///         scope.spawn(|| loop {
+///             match jobs.pop() {
+///                 Some(job) => job.run(),
+///                 None if done() => break,
+///                 None => std::thread::yield_now(),
+///             }
+///         });
+///     }
+///     for job in incoming {
+///         jobs.push(job).unwrap_or_else(|job| run_inline(job));
+///     }
+/// });
+/// ```
+pub struct Queue<T> {
+    slots: ArrayCStyle<Slot<T>>,
+    /// Position of the next pop.
+    head: CacheAligned<AtomicUsize>,
+    /// Position of the next push.
+    tail: CacheAligned<AtomicUsize>,
+}
+
+// A value is written by the one producer that claimed its position and read
+// by the one consumer that claimed it, with the slot's sequence number
+// handing it over, so values only ever move between threads.
+unsafe impl<T: Send> Send for Queue<T> {}
+unsafe impl<T: Send> Sync for Queue<T> {}
+
+impl<T> Queue<T> {
+    /// Creates an empty queue holding up to `capacity` values.
+    ///
+    /// A queue with a capacity of zero rejects every push.
+    #[track_caller]
+    pub fn new(capacity: usize) -> Result<Self, Error> {
+        let slots = ArrayCStyle::<Slot<T>>::new(capacity)?;
+        for index in 0..capacity {
+            unsafe {
+                slots.ptr_mut().add(index).write(Slot {
+                    seq: AtomicUsize::new(index),
+                    value: UnsafeCell::new(MaybeUninit::uninit()),
+                })
+            };
+        }
+        Ok(Self {
+            slots,
+            head: CacheAligned::new(AtomicUsize::new(0)),
+            tail: CacheAligned::new(AtomicUsize::new(0)),
+        })
+    }
+
+    #[inline(always)]
+    fn slot(&self, pos: usize) -> &Slot<T> {
+        let slots = self.slots.as_slice();
+        &slots[pos % slots.len()]
+    }
+
+    /// Appends `value` to the queue.
+    ///
+    /// # Errors
+    ///
+    /// Gives `value` back if the queue is full.
+    pub fn push(&self, value: T) -> Result<(), T> {
+        if self.slots.is_empty() {
+            return Err(value);
+        }
+        let mut pos = self.tail.load(Ordering::Relaxed);
+        loop {
+            let slot = self.slot(pos);
+            let seq = slot.seq.load(Ordering::Acquire);
+            let lag = seq.wrapping_sub(pos) as isize;
+            if lag == 0 {
+                match self.tail.compare_exchange_weak(
+                    pos,
+                    pos.wrapping_add(1),
+                    Ordering::Relaxed,
+                    Ordering::Relaxed,
+                ) {
+                    Ok(_) => {
+                        unsafe { (*slot.value.get()).write(value) };
+                        slot.seq.store(pos.wrapping_add(1), Ordering::Release);
+                        return Ok(());
+                    }
+                    Err(current) => pos = current,
+                }
+            } else if lag < 0 {
+                // the slot still holds the value from the previous lap
+                return Err(value);
+            } else {
+                // another producer claimed `pos` first
+                pos = self.tail.load(Ordering::Relaxed);
+            }
+        }
+    }
+
+    /// Removes the oldest value from the queue, or returns `None` if it is
+    /// empty.
+    pub fn pop(&self) -> Option<T> {
+        if self.slots.is_empty() {
+            return None;
+        }
+        let mut pos = self.head.load(Ordering::Relaxed);
+        loop {
+            let slot = self.slot(pos);
+            let seq = slot.seq.load(Ordering::Acquire);
+            let lag = seq.wrapping_sub(pos.wrapping_add(1)) as isize;
+            if lag == 0 {
+                match self.head.compare_exchange_weak(
+                    pos,
+                    pos.wrapping_add(1),
+                    Ordering::Relaxed,
+                    Ordering::Relaxed,
+                ) {
+                    Ok(_) => {
+                        let value =
+                            unsafe { (*slot.value.get()).assume_init_read() };
+                        slot.seq.store(
+                            pos.wrapping_add(self.capacity()),
+                            Ordering::Release,
+                        );
+                        return Some(value);
+                    }
+                    Err(current) => pos = current,
+                }
+            } else if lag < 0 {
+                // nothing has been pushed at `pos` yet
+                return None;
+            } else {
+                // another consumer claimed `pos` first
+                pos = self.head.load(Ordering::Relaxed);
+            }
+        }
+    }
+
+    /// Returns the number of values the queue can hold.
+    #[inline(always)]
+    pub fn capacity(&self) -> usize {
+        self.slots.len()
+    }
+
+    /// Returns the number of values currently queued.
+    ///
+    /// Other threads may push or pop concurrently, so the result is only a
+    /// snapshot.
+    pub fn len(&self) -> usize {
+        let head = self.head.load(Ordering::Acquire);
+        let tail = self.tail.load(Ordering::Acquire);
+        tail.wrapping_sub(head).min(self.capacity())
+    }
+
+    /// Returns `true` if no values are queued.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns `true` if the queue cannot take another value.
+    pub fn is_full(&self) -> bool {
+        self.len() == self.capacity()
+    }
+}
+
+impl<T> Drop for Queue<T> {
+    fn drop(&mut self) {
+        while self.pop().is_some() {}
+    }
+}
+
+impl<T> fmt::Debug for Queue<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Queue")
+            .field("len", &self.len())
+            .field("capacity", &self.capacity())
+            .finish()
+    }
+}
@@ -0,0 +1,229 @@
+use std::{
+    fmt, ptr,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+};
+
+use crate::{aligned::CacheAligned, error::Error, runtime_array::ArrayCStyle};
+
+/// State shared by the two halves of a channel.
+///
+/// `head` and `tail` count pops and pushes since creation; a value's slot is
+/// its count modulo the capacity.
+struct Shared<T> {
+    buffer: ArrayCStyle<T>,
+    /// `buffer.ptr_mut()`, taken once.
+    slots: *mut T,
+    /// Written by the consumer only.
+    head: CacheAligned<AtomicUsize>,
+    /// Written by the producer only.
+    tail: CacheAligned<AtomicUsize>,
+}
+
+// A slot is touched by exactly one side at a time, handed over through the
+// release/acquire pair on `head`/`tail`, so values only ever move between
+// threads.
+unsafe impl<T: Send> Send for Shared<T> {}
+unsafe impl<T: Send> Sync for Shared<T> {}
+
+impl<T> Shared<T> {
+    #[inline(always)]
+    fn slot(&self, count: usize) -> *mut T {
+        unsafe { self.slots.add(count % self.buffer.len()) }
+    }
+}
+
+impl<T> Drop for Shared<T> {
+    fn drop(&mut self) {
+        // both halves are gone, so the values still queued are owned here
+        let head = self.head.load(Ordering::Relaxed);
+        let tail = self.tail.load(Ordering::Relaxed);
+        for count in head..tail {
+            unsafe { ptr::drop_in_place(self.slot(count)) };
+        }
+    }
+}
+
+/// Creates a bounded single-producer single-consumer channel holding up to
+/// `capacity` values.
+///
+/// Both ends are wait-free: a push or pop is a couple of loads and one
+/// store, and each side caches the other side's index so the shared cache
+/// line is only touched when the queue looks full (or empty). That makes it
+/// suitable for real-time threads that must never block.
+///
+/// # Example
+///
+/// ```rust ignore
+/// use runnarr::queues::spsc;
+///
+/// let (mut tx, mut rx) = spsc::channel::<[f32; 256]>(8).unwrap();
+///
+/// // decoder thread
+/// while tx.push(decode_block()).is_err() {
+///     std::thread::yield_now();
+/// }
+///
+/// // audio callback
+/// if let Some(block) = rx.pop() {
+///     output.copy_from_slice(&block);
+/// }
+/// ```
+#[track_caller]
+pub fn channel<T>(
+    capacity: usize,
+) -> Result<(Producer<T>, Consumer<T>), Error> {
+    let buffer = ArrayCStyle::new(capacity)?;
+    let shared = Arc::new(Shared {
+        slots: buffer.ptr_mut(),
+        buffer,
+        head: CacheAligned::new(AtomicUsize::new(0)),
+        tail: CacheAligned::new(AtomicUsize::new(0)),
+    });
+    Ok((
+        Producer {
+            shared: Arc::clone(&shared),
+            tail: 0,
+            cached_head: 0,
+        },
+        Consumer {
+            shared,
+            head: 0,
+            cached_tail: 0,
+        },
+    ))
+}
+
+/// The sending half of an [`spsc::channel`](channel).
+pub struct Producer<T> {
+    shared: Arc<Shared<T>>,
+    /// Our own copy of `shared.tail`.
+    tail: usize,
+    /// The consumer's `head` as last seen.
+    cached_head: usize,
+}
+
+impl<T> Producer<T> {
+    /// Appends `value` to the queue.
+    ///
+    /// # Errors
+    ///
+    /// Gives `value` back if the queue is full.
+    pub fn push(&mut self, value: T) -> Result<(), T> {
+        let capacity = self.capacity();
+        if self.tail.wrapping_sub(self.cached_head) == capacity {
+            self.cached_head = self.shared.head.load(Ordering::Acquire);
+            if self.tail.wrapping_sub(self.cached_head) == capacity {
+                return Err(value);
+            }
+        }
+        unsafe { self.shared.slot(self.tail).write(value) };
+        self.tail = self.tail.wrapping_add(1);
+        self.shared.tail.store(self.tail, Ordering::Release);
+        Ok(())
+    }
+
+    /// Returns the number of values that can be queued at once.
+    #[inline(always)]
+    pub fn capacity(&self) -> usize {
+        self.shared.buffer.len()
+    }
+
+    /// Returns the number of values currently queued.
+    pub fn len(&self) -> usize {
+        self.tail
+            .wrapping_sub(self.shared.head.load(Ordering::Acquire))
+    }
+
+    /// Returns `true` if no values are queued.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns `true` if the consumer has been dropped.
+    pub fn is_abandoned(&self) -> bool {
+        Arc::strong_count(&self.shared) == 1
+    }
+}
+
+/// The receiving half of an [`spsc::channel`](channel).
+pub struct Consumer<T> {
+    shared: Arc<Shared<T>>,
+    /// Our own copy of `shared.head`.
+    head: usize,
+    /// The producer's `tail` as last seen.
+    cached_tail: usize,
+}
+
+impl<T> Consumer<T> {
+    /// Removes the oldest value from the queue, or returns `None` if it is
+    /// empty.
+    pub fn pop(&mut self) -> Option<T> {
+        if self.head == self.cached_tail {
+            self.cached_tail = self.shared.tail.load(Ordering::Acquire);
+            if self.head == self.cached_tail {
+                return None;
+            }
+        }
+        let value = unsafe { self.shared.slot(self.head).read() };
+        self.head = self.head.wrapping_add(1);
+        self.shared.head.store(self.head, Ordering::Release);
+        Some(value)
+    }
+
+    /// Returns a reference to the oldest value without removing it.
+    pub fn peek(&mut self) -> Option<&T> {
+        if self.head == self.cached_tail {
+            self.cached_tail = self.shared.tail.load(Ordering::Acquire);
+            if self.head == self.cached_tail {
+                return None;
+            }
+        }
+        Some(unsafe { &*self.shared.slot(self.head) })
+    }
+
+    /// Returns the number of values that can be queued at once.
+    #[inline(always)]
+    pub fn capacity(&self) -> usize {
+        self.shared.buffer.len()
+    }
+
+    /// Returns the number of values currently queued.
+    pub fn len(&self) -> usize {
+        self.shared
+            .tail
+            .load(Ordering::Acquire)
+            .wrapping_sub(self.head)
+    }
+
+    /// Returns `true` if no values are queued.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns `true` if the producer has been dropped; values queued before
+    /// that can still be popped.
+    pub fn is_abandoned(&self) -> bool {
+        Arc::strong_count(&self.shared) == 1
+    }
+}
+
+impl<T> fmt::Debug for Producer<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Producer")
+            .field("len", &self.len())
+            .field("capacity", &self.capacity())
+            .finish()
+    }
+}
+
+impl<T> fmt::Debug for Consumer<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Consumer")
+            .field("len", &self.len())
+            .field("capacity", &self.capacity())
+            .finish()
+    }
+}
@@ -0,0 +1,79 @@
+use std::ops::Add;
+
+#[cfg(feature = "rayon")]
+use rayon::{iter::ParallelIterator, slice::ParallelSlice};
+
+use crate::{allocator::RawAllocator, runtime_array::ArrayCStyle};
+
+impl<T: Copy + Send + Sync, A: RawAllocator> ArrayCStyle<T, A> {
+    /// Folds the elements with `op`, starting every partial result from
+    /// `identity`.
+    ///
+    /// With the `rayon` feature, disjoint chunks are folded on the thread
+    /// pool and the partial results are combined pairwise in a tree;
+    /// without it, the elements are folded in order on the calling thread.
+    /// Either way `op` must be associative and `identity` must leave any
+    /// value unchanged under it, since the grouping (and the number of
+    /// times `identity` is used) depends on the split.
+    ///
+    /// The elements must be initialized.
+    ///
+    /// # Example
+    ///
+    /// ```rust ignore
+    /// let peak = samples.par_reduce(f32::NEG_INFINITY, f32::max);
+    /// ```
+    pub fn par_reduce<F>(&self, identity: T, op: F) -> T
+    where
+        F: Fn(T, T) -> T + Send + Sync,
+    {
+        #[cfg(feature = "rayon")]
+        {
+            self.as_slice()
+                .par_chunks(crate::par::chunk_len(self.len()))
+                .map(|chunk| chunk.iter().copied().fold(identity, &op))
+                .reduce(|| identity, &op)
+        }
+        #[cfg(not(feature = "rayon"))]
+        {
+            self.as_slice().iter().copied().fold(identity, op)
+        }
+    }
+
+    /// Returns the sum of the elements; see [`ArrayCStyle::par_reduce`].
+    ///
+    /// `T::default()` must be the additive identity, as it is for the
+    /// primitive numbers. Additions overflow exactly as `+` does.
+    pub fn par_sum(&self) -> T
+    where
+        T: Default + Add<Output = T>,
+    {
+        self.par_reduce(T::default(), Add::add)
+    }
+
+    /// Returns the smallest element, or `None` if the array is empty; see
+    /// [`ArrayCStyle::par_reduce`].
+    ///
+    /// For floats, which are not [`Ord`], use `par_reduce` with
+    /// [`f32::min`] or [`f64::min`].
+    pub fn par_min(&self) -> Option<T>
+    where
+        T: Ord,
+    {
+        let first = *self.as_slice().first()?;
+        Some(self.par_reduce(first, Ord::min))
+    }
+
+    /// Returns the largest element, or `None` if the array is empty; see
+    /// [`ArrayCStyle::par_reduce`].
+    ///
+    /// For floats, which are not [`Ord`], use `par_reduce` with
+    /// [`f32::max`] or [`f64::max`].
+    pub fn par_max(&self) -> Option<T>
+    where
+        T: Ord,
+    {
+        let first = *self.as_slice().first()?;
+        Some(self.par_reduce(first, Ord::max))
+    }
+}
@@ -0,0 +1,281 @@
+use std::{
+    cell::Cell,
+    error, fmt,
+    ops::{Deref, DerefMut},
+};
+
+use crate::{cell_array::CellArray, error::Error, runtime_array::ArrayCStyle};
+
+/// Flag value of an element that is mutably borrowed; positive values count
+/// shared borrows.
+const WRITING: isize = -1;
+
+/// Returned when an element of a [`RefCellArray`] cannot be borrowed.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub enum BorrowError {
+    /// `index` is not below the array length `len`.
+    OutOfBounds { index: usize, len: usize },
+    /// The element at `index` is mutably borrowed, or is borrowed at all
+    /// when a mutable borrow was asked for.
+    Conflict { index: usize },
+}
+
+impl fmt::Display for BorrowError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BorrowError::OutOfBounds { index, len } => write!(
+                f,
+                "index {index} is out of bounds for array of length {len}"
+            ),
+            BorrowError::Conflict { index } => {
+                write!(f, "element {index} is already borrowed")
+            }
+        }
+    }
+}
+
+impl error::Error for BorrowError {}
+
+impl From<BorrowError> for Error {
+    fn from(error_value: BorrowError) -> Self {
+        match error_value {
+            BorrowError::OutOfBounds { index, len } => {
+                Error::IndexOutOfBounds { index, len }
+            }
+            conflict => Error::Other(conflict.to_string()),
+        }
+    }
+}
+
+/// A runtime sized array whose elements are borrow-checked one by one at
+/// runtime, like an array of [`RefCell`](std::cell::RefCell)s.
+///
+/// Every element carries its own borrow flag, so any number of disjoint
+/// elements can be mutably borrowed at once through `&self`, and a borrow
+/// that would alias an outstanding one fails instead. This suits register
+/// files of interpreters and VMs, where an instruction such as
+/// `add r1, r1, r2` names its operands at runtime and static disjointness
+/// cannot be proven.
+///
+/// The array cannot be shared between threads. Like every array in the
+/// crate, the elements are not dropped with it.
+///
+/// # Example
+///
+/// ```rust ignore
+/// use runnarr::refcell_array::RefCellArray;
+///
+/// let registers = RefCellArray::new(ArrayCStyle::<Value>::zeroed(256)?)?;
+/// match instruction {
+///     Add { dst, lhs, rhs } => {
+///         let sum = {
+///             let lhs = registers.try_borrow(lhs)?;
+///             lhs.add(&registers.try_borrow(rhs)?)
+///         };
+///         *registers.try_borrow_mut(dst)? = sum;
+///     }
+///     // ...
+/// }
+/// ```
+pub struct RefCellArray<T> {
+    values: ArrayCStyle<T>,
+    /// `values.ptr_mut()`, taken once.
+    slots: *mut T,
+    flags: CellArray<isize>,
+}
+
+// The flags are plain cells, so all borrows stay on one thread; moving the
+// whole array (with no borrows alive) moves the elements.
+unsafe impl<T: Send> Send for RefCellArray<T> {}
+
+impl<T> RefCellArray<T> {
+    /// Wraps an initialized array, with no element borrowed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the borrow flags cannot be allocated.
+    #[track_caller]
+    pub fn new(values: ArrayCStyle<T>) -> Result<Self, Error> {
+        Ok(Self {
+            flags: CellArray::filled(values.len(), 0)?,
+            slots: values.ptr_mut(),
+            values,
+        })
+    }
+
+    /// Returns the number of elements.
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Returns `true` if the array holds no elements.
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    fn flag(&self, index: usize) -> Result<&Cell<isize>, BorrowError> {
+        self.flags.cell(index).ok_or(BorrowError::OutOfBounds {
+            index,
+            len: self.len(),
+        })
+    }
+
+    /// Borrows the element at `index` immutably.
+    ///
+    /// # Errors
+    ///
+    /// Fails if `index` is out of bounds or the element is mutably
+    /// borrowed.
+    pub fn try_borrow(
+        &self,
+        index: usize,
+    ) -> Result<ElementRef<'_, T>, BorrowError> {
+        let flag = self.flag(index)?;
+        match flag.get() {
+            WRITING | isize::MAX => Err(BorrowError::Conflict { index }),
+            readers => {
+                flag.set(readers + 1);
+                Ok(ElementRef {
+                    value: unsafe { &*self.slots.add(index) },
+                    flag,
+                })
+            }
+        }
+    }
+
+    /// Borrows the element at `index` mutably.
+    ///
+    /// # Errors
+    ///
+    /// Fails if `index` is out of bounds or the element is borrowed.
+    pub fn try_borrow_mut(
+        &self,
+        index: usize,
+    ) -> Result<ElementRefMut<'_, T>, BorrowError> {
+        let flag = self.flag(index)?;
+        if flag.get() != 0 {
+            return Err(BorrowError::Conflict { index });
+        }
+        flag.set(WRITING);
+        Ok(ElementRefMut {
+            // the flag keeps every other borrow of this element away
+            value: unsafe { &mut *self.slots.add(index) },
+            flag,
+        })
+    }
+
+    /// Returns `true` if the element at `index` is currently borrowed in
+    /// any way; out-of-bounds indices are never borrowed.
+    pub fn is_borrowed(&self, index: usize) -> bool {
+        self.flags.get(index).is_some_and(|flag| flag != 0)
+    }
+
+    /// Returns the elements as a plain mutable slice; the exclusive borrow
+    /// rules out any element borrow meanwhile.
+    #[inline(always)]
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        self.values.as_mut_slice()
+    }
+
+    /// Unwraps the underlying array.
+    pub fn into_array(self) -> ArrayCStyle<T> {
+        self.values
+    }
+}
+
+#[cfg(not(feature = "no-panic"))]
+impl<T> RefCellArray<T> {
+    /// Borrows the element at `index` immutably.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds or the element is mutably
+    /// borrowed.
+    #[track_caller]
+    pub fn borrow(&self, index: usize) -> ElementRef<'_, T> {
+        self.try_borrow(index)
+            .unwrap_or_else(|error| panic!("{error}"))
+    }
+
+    /// Borrows the element at `index` mutably.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds or the element is borrowed.
+    #[track_caller]
+    pub fn borrow_mut(&self, index: usize) -> ElementRefMut<'_, T> {
+        self.try_borrow_mut(index)
+            .unwrap_or_else(|error| panic!("{error}"))
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for RefCellArray<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut list = f.debug_list();
+        for index in 0..self.len() {
+            match self.try_borrow(index) {
+                Ok(value) => list.entry(&*value),
+                Err(_) => list.entry(&format_args!("<borrowed>")),
+            };
+        }
+        list.finish()
+    }
+}
+
+/// A shared borrow of one element of a [`RefCellArray`].
+pub struct ElementRef<'a, T> {
+    value: &'a T,
+    flag: &'a Cell<isize>,
+}
+
+impl<T> Deref for ElementRef<'_, T> {
+    type Target = T;
+    fn deref(&self) -> &Self::Target {
+        self.value
+    }
+}
+
+impl<T> Drop for ElementRef<'_, T> {
+    fn drop(&mut self) {
+        self.flag.set(self.flag.get() - 1);
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for ElementRef<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.value.fmt(f)
+    }
+}
+
+/// A mutable borrow of one element of a [`RefCellArray`].
+pub struct ElementRefMut<'a, T> {
+    value: &'a mut T,
+    flag: &'a Cell<isize>,
+}
+
+impl<T> Deref for ElementRefMut<'_, T> {
+    type Target = T;
+    fn deref(&self) -> &Self::Target {
+        self.value
+    }
+}
+
+impl<T> DerefMut for ElementRefMut<'_, T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.value
+    }
+}
+
+impl<T> Drop for ElementRefMut<'_, T> {
+    fn drop(&mut self) {
+        self.flag.set(0);
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for ElementRefMut<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.value.fmt(f)
+    }
+}
@@ -1,19 +1,36 @@
-use std::{
-    mem,
-    ops::{Index, IndexMut},
-    ptr,
-};
+use std::{alloc::Layout, mem, ptr, ptr::NonNull, slice};
 
-use crate::error::BaseError;
+use crate::{
+    allocator::{allocate_with_oom_handler, Global, RawAllocator},
+    error::{Error, GrowError},
+};
 
-type X<T: Default> = ArrayCStyle<T>;
+/// The byte pattern written over a buffer before it is freed when the
+/// `poison` feature is enabled.
+#[cfg(feature = "poison")]
+pub const POISON_BYTE: u8 = 0xDE;
 
-#[derive(Debug, Clone, Hash)]
-pub struct ArrayCStyle<T> {
+#[derive(Debug, Hash)]
+pub struct ArrayCStyle<T, A: RawAllocator = Global> {
     len: usize,
     ptr: *mut T,
+    align: usize,
+    alloc: A,
+    #[cfg(feature = "stats-callsite")]
+    site: &'static std::panic::Location<'static>,
+    #[cfg(feature = "shadow")]
+    shadow: crate::shadow::Shadow,
 }
 
+// SAFETY: an `ArrayCStyle` uniquely owns its buffer, exactly like `Vec<T>`.
+// Sending it moves the elements and the allocator that frees them to another
+// thread, so both must be `Send`. A shared `&ArrayCStyle` only hands out `&T`
+// (and `&A`); writing through `ptr_mut` is already `unsafe` and subject to
+// the usual aliasing rules, so sharing needs `T: Sync` and `A: Sync`. The
+// raw pointer field is the only thing keeping the auto traits away.
+unsafe impl<T: Send, A: RawAllocator + Send> Send for ArrayCStyle<T, A> {}
+unsafe impl<T: Sync, A: RawAllocator + Sync> Sync for ArrayCStyle<T, A> {}
+
 impl<T> ArrayCStyle<T> {
     /// Creates a new `Array` with the specified size.
     ///
@@ -52,21 +69,9 @@ impl<T> ArrayCStyle<T> {
     ///     }
     /// }
     /// ```
-    pub fn new(size: usize) -> Result<Self, BaseError> {
-        let ptr: *mut T;
-        let layout = std::alloc::Layout::array::<T>(size)?;
-
-        unsafe {
-            ptr = std::alloc::alloc(layout) as *mut T;
-        }
-
-        if ptr.is_null() {
-            return Err(BaseError(
-                "Layout or memory allocation failed".to_string(),
-            ));
-        }
-
-        Ok(Self { len: size, ptr })
+    #[track_caller]
+    pub fn new(size: usize) -> Result<Self, Error> {
+        Self::new_in(size, Global)
     }
 
     /// Creates a new `Array` with the specified size, initializing all elements to zero.
@@ -97,22 +102,388 @@ impl<T> ArrayCStyle<T> {
     /// // Create a new Array with size 5, initializing all elements to zero.
     /// let array: Array<i32> = Array::zeroed(5);
     /// ```
-    pub fn zeroed(size: usize) -> Result<Self, BaseError> {
-        let ptr: *mut T;
-        let layout = std::alloc::Layout::array::<T>(size)?;
+    #[track_caller]
+    pub fn zeroed(size: usize) -> Result<Self, Error> {
+        Self::zeroed_in(size, Global)
+    }
+
+    /// Creates a new uninitialized `Array` whose buffer is aligned to at least
+    /// `align` bytes.
+    ///
+    /// This is meant for SIMD and DMA APIs that require a specific alignment
+    /// (typically 32 or 64 bytes). The effective alignment is the larger of
+    /// `align` and the natural alignment of `T`.
+    ///
+    /// # Parameters
+    ///
+    /// - `size`: The number of elements in the array.
+    /// - `align`: The requested alignment in bytes. Must be a power of two.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `align` is not a power of two, if the resulting
+    /// layout overflows, or if memory allocation fails.
+    ///
+    /// # Example
+    ///
+    /// ```rust ignore
+    /// use runnarr::runtime_array::ArrayCStyle;
+    ///
+    /// let lanes = ArrayCStyle::<f32>::new_aligned(1024, 64).unwrap();
+    /// assert_eq!(lanes.ptr() as usize % 64, 0);
+    /// ```
+    #[track_caller]
+    pub fn new_aligned(size: usize, align: usize) -> Result<Self, Error> {
+        Self::new_aligned_in(size, align, Global)
+    }
+
+    /// Creates a new `Array` whose buffer is aligned to at least `align`
+    /// bytes, initializing all elements to zero.
+    ///
+    /// See [`ArrayCStyle::new_aligned`].
+    #[track_caller]
+    pub fn zeroed_aligned(size: usize, align: usize) -> Result<Self, Error> {
+        Self::zeroed_aligned_in(size, align, Global)
+    }
+
+    /// Decomposes the array into its raw pointer and length without freeing
+    /// the buffer.
+    ///
+    /// The caller becomes responsible for the memory, which was allocated by
+    /// the global allocator with the layout `Layout::array::<T>(len)` aligned
+    /// to [`ArrayCStyle::align`]. The easiest way to release it is to
+    /// reassemble the array with [`ArrayCStyle::from_raw_parts`] (or
+    /// [`ArrayCStyle::from_raw_parts_aligned`] for over-aligned arrays) and
+    /// drop it.
+    ///
+    /// # Example
+    ///
+    /// ```rust ignore
+    /// use runnarr::runtime_array::ArrayCStyle;
+    ///
+    /// let array = ArrayCStyle::<u8>::zeroed(64).unwrap();
+    /// let (ptr, len) = array.into_raw_parts();
+    /// unsafe { c_library_fill(ptr, len) };
+    /// let array = unsafe { ArrayCStyle::from_raw_parts(ptr, len) };
+    /// ```
+    pub fn into_raw_parts(self) -> (*mut T, usize) {
+        let this = mem::ManuallyDrop::new(self);
+        #[cfg(feature = "stats")]
+        crate::stats::record_free(
+            this.layout().size(),
+            #[cfg(feature = "stats-callsite")]
+            this.site,
+        );
+        (this.ptr, this.len)
+    }
+
+    /// Reassembles an array from a raw pointer and a length.
+    ///
+    /// # Safety
+    ///
+    /// - `ptr` must have been allocated by the global allocator with the
+    ///   layout `Layout::array::<T>(len)`, or be any well-aligned non-null
+    ///   pointer if that layout has size zero. Pointers returned by
+    ///   [`ArrayCStyle::into_raw_parts`] on an array that was not
+    ///   over-aligned satisfy this.
+    /// - The memory must not be owned by anything else; the returned array
+    ///   frees it on drop.
+    /// - As with [`ArrayCStyle::new`], elements that are read must have been
+    ///   initialized.
+    #[track_caller]
+    pub unsafe fn from_raw_parts(ptr: *mut T, len: usize) -> Self {
+        unsafe { Self::from_raw_parts_aligned(ptr, len, mem::align_of::<T>()) }
+    }
 
+    /// Reassembles an over-aligned array from a raw pointer, a length and the
+    /// alignment it was allocated with.
+    ///
+    /// # Safety
+    ///
+    /// Same as [`ArrayCStyle::from_raw_parts`], except that the layout must
+    /// be `Layout::array::<T>(len)` aligned to `align`.
+    #[track_caller]
+    pub unsafe fn from_raw_parts_aligned(
+        ptr: *mut T,
+        len: usize,
+        align: usize,
+    ) -> Self {
+        let align = align.max(mem::align_of::<T>());
+        #[cfg(feature = "stats")]
+        crate::stats::record_alloc(
+            len * mem::size_of::<T>(),
+            #[cfg(feature = "stats-callsite")]
+            std::panic::Location::caller(),
+        );
+        Self {
+            len,
+            ptr,
+            align,
+            alloc: Global,
+            #[cfg(feature = "stats-callsite")]
+            site: std::panic::Location::caller(),
+            // filled by foreign code, so every element counts as written
+            #[cfg(feature = "shadow")]
+            shadow: crate::shadow::Shadow::new(len, true),
+        }
+    }
+}
+
+#[cfg(any(feature = "nalgebra", feature = "ndarray"))]
+impl<T> ArrayCStyle<T> {
+    /// Reuses a vector's buffer for an array, copying only if the vector
+    /// keeps spare capacity it cannot give back.
+    #[track_caller]
+    pub(crate) fn from_vec(vec: Vec<T>) -> Result<Self, Error> {
+        let mut vec = mem::ManuallyDrop::new(vec);
+        vec.shrink_to_fit();
+        if vec.capacity() == vec.len() || mem::size_of::<T>() == 0 {
+            // a `Vec` allocates `Layout::array::<T>(capacity)` from the
+            // global allocator, which is what `from_raw_parts` expects
+            return Ok(unsafe {
+                Self::from_raw_parts(vec.as_mut_ptr(), vec.len())
+            });
+        }
+        let array = Self::new(vec.len())?;
         unsafe {
-            ptr = std::alloc::alloc_zeroed(layout) as *mut T;
+            ptr::copy_nonoverlapping(vec.as_ptr(), array.ptr, vec.len());
+            #[cfg(feature = "shadow")]
+            array.shadow.mark_all();
+            // the elements moved out, so only the buffer is freed
+            vec.set_len(0);
+            mem::ManuallyDrop::drop(&mut vec);
         }
+        Ok(array)
+    }
 
-        if ptr.is_null() {
-            return Err(BaseError(
-                "Layout or memory allocation failed for zeroed array"
-                    .to_string(),
-            ));
+    /// Hands the buffer to a vector, or fails if the array is over-aligned,
+    /// since the vector would free it with the wrong layout.
+    pub(crate) fn into_vec(self) -> Result<Vec<T>, Error> {
+        if self.align != mem::align_of::<T>() {
+            return Err(Error::Other(format!(
+                "Cannot hand an array aligned to {} bytes to a Vec",
+                self.align
+            )));
         }
+        let (ptr, len) = self.into_raw_parts();
+        Ok(unsafe { Vec::from_raw_parts(ptr, len, len) })
+    }
+}
 
-        Ok(Self { len: size, ptr })
+impl<T, A: RawAllocator> ArrayCStyle<T, A> {
+    /// Creates a new uninitialized `Array` with the specified size, allocated
+    /// from `alloc` instead of the global allocator.
+    ///
+    /// See [`ArrayCStyle::new`] for the initialization contract.
+    ///
+    /// # Example
+    ///
+    /// ```rust ignore
+    /// use runnarr::{allocator::Global, runtime_array::ArrayCStyle};
+    ///
+    /// let array = ArrayCStyle::<i32, _>::new_in(5, Global).unwrap();
+    /// ```
+    #[track_caller]
+    pub fn new_in(size: usize, alloc: A) -> Result<Self, Error> {
+        Self::allocate_in(size, mem::align_of::<T>(), alloc, false)
+    }
+
+    /// Creates a new `Array` with all elements initialized to zero, allocated
+    /// from `alloc` instead of the global allocator.
+    #[track_caller]
+    pub fn zeroed_in(size: usize, alloc: A) -> Result<Self, Error> {
+        Self::allocate_in(size, mem::align_of::<T>(), alloc, true)
+    }
+
+    /// Creates a new uninitialized over-aligned `Array` allocated from
+    /// `alloc`.
+    ///
+    /// See [`ArrayCStyle::new_aligned`].
+    #[track_caller]
+    pub fn new_aligned_in(
+        size: usize,
+        align: usize,
+        alloc: A,
+    ) -> Result<Self, Error> {
+        Self::allocate_in(size, align, alloc, false)
+    }
+
+    /// Creates a new zeroed over-aligned `Array` allocated from `alloc`.
+    ///
+    /// See [`ArrayCStyle::new_aligned`].
+    #[track_caller]
+    pub fn zeroed_aligned_in(
+        size: usize,
+        align: usize,
+        alloc: A,
+    ) -> Result<Self, Error> {
+        Self::allocate_in(size, align, alloc, true)
+    }
+
+    #[track_caller]
+    fn allocate_in(
+        size: usize,
+        align: usize,
+        alloc: A,
+        zeroed: bool,
+    ) -> Result<Self, Error> {
+        let layout = Layout::array::<T>(size)?.align_to(align)?;
+
+        let ptr = if layout.size() == 0 {
+            // Empty buffers are never handed to the allocator, but the
+            // pointer must still honour the requested alignment.
+            ptr::without_provenance_mut(layout.align())
+        } else {
+            allocate_with_oom_handler(
+                layout,
+                std::any::type_name::<T>(),
+                || {
+                    if zeroed {
+                        alloc.allocate_zeroed(layout)
+                    } else {
+                        alloc.allocate(layout)
+                    }
+                },
+            )?
+            .as_ptr() as *mut T
+        };
+
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            target: "runnarr::alloc",
+            size = layout.size(),
+            align = layout.align(),
+            len = size,
+            type_name = std::any::type_name::<T>(),
+            ptr = ?ptr,
+            "array allocated"
+        );
+        #[cfg(feature = "stats-callsite")]
+        let site = std::panic::Location::caller();
+        #[cfg(feature = "stats")]
+        crate::stats::record_alloc(
+            layout.size(),
+            #[cfg(feature = "stats-callsite")]
+            site,
+        );
+
+        Ok(Self {
+            len: size,
+            ptr,
+            align: layout.align(),
+            alloc,
+            #[cfg(feature = "stats-callsite")]
+            site,
+            #[cfg(feature = "shadow")]
+            shadow: crate::shadow::Shadow::new(size, zeroed),
+        })
+    }
+
+    /// Attempts to extend the array by `additional` elements, reallocating
+    /// the buffer in place when the allocator allows it.
+    ///
+    /// The existing elements are preserved; the new ones are uninitialized,
+    /// just like the elements of [`ArrayCStyle::new`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`GrowError`] carrying the requested and current lengths if
+    /// the new size overflows or the allocator cannot provide the memory. In
+    /// that case the array, including its contents, is left untouched, so
+    /// memory-constrained callers can degrade gracefully.
+    ///
+    /// # Example
+    ///
+    /// ```rust ignore
+    /// use runnarr::runtime_array::ArrayCStyle;
+    ///
+    /// let mut history = ArrayCStyle::<u64>::zeroed(1024).unwrap();
+    /// if let Err(error) = history.try_grow(1024) {
+    ///     log::warn!("keeping the short history: {error}");
+    /// }
+    /// ```
+    #[track_caller]
+    pub fn try_grow(&mut self, additional: usize) -> Result<(), GrowError> {
+        let requested = self.len.checked_add(additional);
+        let error = GrowError {
+            requested,
+            current: self.len,
+        };
+        let new_len = requested.ok_or(error)?;
+        let new_layout = Layout::array::<T>(new_len)
+            .and_then(|layout| layout.align_to(self.align))
+            .map_err(|_| error)?;
+        let old_layout = self.layout();
+
+        if new_layout.size() != old_layout.size() {
+            let ptr = allocate_with_oom_handler(
+                new_layout,
+                std::any::type_name::<T>(),
+                || {
+                    if old_layout.size() == 0 {
+                        self.alloc.allocate(new_layout)
+                    } else {
+                        unsafe {
+                            self.alloc.reallocate(
+                                NonNull::new_unchecked(self.ptr as *mut u8),
+                                old_layout,
+                                new_layout,
+                            )
+                        }
+                    }
+                },
+            )
+            .map_err(|_| error)?;
+
+            #[cfg(feature = "tracing")]
+            tracing::debug!(
+                target: "runnarr::alloc",
+                old_size = old_layout.size(),
+                size = new_layout.size(),
+                align = new_layout.align(),
+                len = new_len,
+                type_name = std::any::type_name::<T>(),
+                old_ptr = ?self.ptr,
+                ptr = ?ptr,
+                "array reallocated"
+            );
+            #[cfg(feature = "stats")]
+            crate::stats::record_realloc(
+                old_layout.size(),
+                new_layout.size(),
+                #[cfg(feature = "stats-callsite")]
+                self.site,
+            );
+            self.ptr = ptr.as_ptr() as *mut T;
+        }
+
+        #[cfg(feature = "shadow")]
+        self.shadow.grow(self.len, new_len);
+        self.len = new_len;
+        Ok(())
+    }
+
+    /// Returns the alignment of the buffer in bytes.
+    ///
+    /// This is the natural alignment of `T` unless the array was created
+    /// through one of the `*_aligned` constructors.
+    #[inline(always)]
+    pub const fn align(&self) -> usize {
+        self.align
+    }
+
+    /// Returns the layout of the allocation backing this array.
+    #[inline(always)]
+    pub(crate) fn layout(&self) -> Layout {
+        Layout::array::<T>(self.len)
+            .and_then(|layout| layout.align_to(self.align))
+            .expect("Failed to create exit layout")
+    }
+
+    /// Returns a reference to the allocator backing this array.
+    #[inline(always)]
+    pub const fn allocator(&self) -> &A {
+        &self.alloc
     }
 
     /// Returns the length of the array.
@@ -135,6 +506,12 @@ impl<T> ArrayCStyle<T> {
         self.len
     }
 
+    /// Returns `true` if the array holds no elements.
+    #[inline(always)]
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
     /// Returns a raw pointer to the start of the array.
     ///
     /// # Returns
@@ -157,7 +534,9 @@ impl<T> ArrayCStyle<T> {
 
     #[inline(always)]
     pub fn ptr_mut(&self) -> *mut T {
-        self.ptr as *mut T
+        #[cfg(feature = "shadow")]
+        self.shadow.mark_all();
+        self.ptr
     }
     /// Gets a reference to the element at the specified index.
     ///
@@ -191,6 +570,8 @@ impl<T> ArrayCStyle<T> {
         if index >= self.len {
             return None;
         }
+        #[cfg(feature = "shadow")]
+        self.shadow.check(index);
         Some(unsafe { &*self.ptr.add(index) })
     }
 
@@ -226,13 +607,143 @@ impl<T> ArrayCStyle<T> {
         if index >= self.len {
             return None;
         }
+        #[cfg(feature = "shadow")]
+        self.shadow.mark(index);
         Some(unsafe { &mut *self.ptr.add(index) })
     }
 
+    /// Gets a reference to the element at `index`.
+    ///
+    /// Unlike indexing, this never panics, which suits code that indexes
+    /// with untrusted input.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::IndexOutOfBounds`] carrying the index and the length
+    /// if `index` is out of bounds.
+    ///
+    /// # Example
+    ///
+    /// ```rust ignore
+    /// use runnarr::runtime_array::ArrayCStyle;
+    ///
+    /// let table = ArrayCStyle::<u32>::zeroed(16).unwrap();
+    /// let value = table.at(request.slot)?;
+    /// ```
+    #[inline(always)]
+    pub fn at(&self, index: usize) -> Result<&T, Error> {
+        let len = self.len;
+        self.get(index)
+            .ok_or(Error::IndexOutOfBounds { index, len })
+    }
+
+    /// Gets a mutable reference to the element at `index`.
+    ///
+    /// # Errors
+    ///
+    /// See [`ArrayCStyle::at`].
+    #[inline(always)]
+    pub fn at_mut(&mut self, index: usize) -> Result<&mut T, Error> {
+        let len = self.len;
+        self.get_mut(index)
+            .ok_or(Error::IndexOutOfBounds { index, len })
+    }
+
+    /// Returns the elements of the array as a slice.
+    ///
+    /// # Safety
+    ///
+    /// Caution: The space for the type is allocated but the type itself may not be allocated.
+    ///
+    /// # Example
+    ///
+    /// ```rust ignore
+    /// use runnarr::runtime_array::ArrayCStyle;
+    ///
+    /// let array: Array<i32> = Array::zeroed(10).unwrap();
+    /// let total: i32 = array.as_slice().iter().sum();
+    /// ```
+    #[inline(always)]
+    pub fn as_slice(&self) -> &[T] {
+        unsafe { slice::from_raw_parts(self.ptr, self.len) }
+    }
+
+    /// Returns the elements of the array as a mutable slice.
+    ///
+    /// # Safety
+    ///
+    /// Caution: The space for the type is allocated but the type itself may not be allocated.
+    #[inline(always)]
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        #[cfg(feature = "shadow")]
+        self.shadow.mark_all();
+        unsafe { slice::from_raw_parts_mut(self.ptr, self.len) }
+    }
+
+    /// Splits the elements into `n` disjoint mutable chunks.
+    ///
+    /// Chunk lengths differ by at most one, with the longer chunks first;
+    /// when the array has fewer than `n` elements the trailing chunks are
+    /// empty. `n == 0` yields no chunks. Each chunk can be moved into its own
+    /// `std::thread::scope` closure, which parallelizes work over the array
+    /// without any extra dependency.
+    ///
+    /// # Example
+    ///
+    /// ```rust ignore
+    /// use runnarr::runtime_array::ArrayCStyle;
+    ///
+    /// let mut pixels = ArrayCStyle::<u32>::zeroed(1920 * 1080).unwrap();
+    /// std::thread::scope(|scope| {
+    ///     for chunk in pixels.split_chunks_mut(8) {
+    ///         scope.spawn(move || shade(chunk));
+    ///     }
+    /// });
+    /// ```
+    pub fn split_chunks_mut(
+        &mut self,
+        n: usize,
+    ) -> impl Iterator<Item = &mut [T]> + '_ {
+        let mut rest = self.as_mut_slice();
+        let len = rest.len();
+        (0..n).map(move |index| {
+            let chunk_len = len / n + usize::from(index < len % n);
+            let (chunk, tail) = mem::take(&mut rest).split_at_mut(chunk_len);
+            rest = tail;
+            chunk
+        })
+    }
+
+    /// Consumes the array and returns a mutable slice over its elements that
+    /// lives for the rest of the program (or as long as the allocator does).
+    ///
+    /// Analogous to `Vec::leak`. The buffer is never freed, which suits
+    /// one-time allocations such as lookup tables and interned data.
+    ///
+    /// # Example
+    ///
+    /// ```rust ignore
+    /// use runnarr::runtime_array::ArrayCStyle;
+    ///
+    /// let squares: &'static mut [u32] =
+    ///     (0..256u32).map(|value| value * value).collect::<ArrayCStyle<_>>().leak();
+    /// ```
+    pub fn leak<'a>(self) -> &'a mut [T]
+    where
+        A: 'a,
+    {
+        let this = mem::ManuallyDrop::new(self);
+        unsafe { slice::from_raw_parts_mut(this.ptr, this.len) }
+    }
+
     /// Deallocates the memory used by the array.
     ///
     /// This method should be used when the array is no longer needed to prevent memory leaks.
     ///
+    /// With the `poison` feature enabled the buffer is first overwritten with
+    /// [`POISON_BYTE`], so reads through stale copies of `ptr()` show up as
+    /// obviously bogus values instead of silently "working".
+    ///
     /// # Safety
     ///
     /// This method uses unsafe Rust constructs for deallocating memory. It assumes that the
@@ -248,96 +759,265 @@ impl<T> ArrayCStyle<T> {
     /// //array.deallocate();
     /// ```
     fn deallocate(&mut self) {
-        let layout = std::alloc::Layout::array::<T>(self.len)
-            .expect("Failed to create exit layout");
+        let layout = self.layout();
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            target: "runnarr::alloc",
+            size = layout.size(),
+            align = layout.align(),
+            len = self.len,
+            type_name = std::any::type_name::<T>(),
+            ptr = ?self.ptr,
+            "array freed"
+        );
+        #[cfg(feature = "stats")]
+        crate::stats::record_free(
+            layout.size(),
+            #[cfg(feature = "stats-callsite")]
+            self.site,
+        );
+        if layout.size() == 0 {
+            return;
+        }
+        #[cfg(feature = "poison")]
+        unsafe {
+            (self.ptr as *mut u8).write_bytes(POISON_BYTE, layout.size());
+        }
         unsafe {
-            std::alloc::dealloc(self.ptr as *mut u8, layout);
+            self.alloc.deallocate(
+                NonNull::new_unchecked(self.ptr as *mut u8),
+                layout,
+            );
         }
     }
 }
 
-impl<T> Drop for ArrayCStyle<T> {
+impl<T, A: RawAllocator> Drop for ArrayCStyle<T, A> {
     fn drop(&mut self) {
         self.deallocate();
     }
 }
 
-impl<T> Index<usize> for ArrayCStyle<T> {
+impl<T: Clone, A: RawAllocator + Clone> ArrayCStyle<T, A> {
+    /// Creates a deep copy of the array in a new buffer from a clone of its
+    /// allocator, with the same alignment.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the copy cannot be allocated.
+    #[track_caller]
+    pub fn try_clone(&self) -> Result<Self, Error> {
+        let copy =
+            Self::new_aligned_in(self.len, self.align, self.alloc.clone())?;
+        for index in 0..self.len {
+            unsafe {
+                ptr::write(copy.ptr.add(index), (*self.ptr.add(index)).clone());
+            }
+        }
+        #[cfg(feature = "shadow")]
+        copy.shadow.mark_all();
+        Ok(copy)
+    }
+}
+
+#[cfg(not(feature = "no-panic"))]
+impl<T: Clone, A: RawAllocator + Clone> Clone for ArrayCStyle<T, A> {
+    #[track_caller]
+    fn clone(&self) -> Self {
+        self.try_clone().expect("Failed to allocate array clone")
+    }
+}
+
+#[cfg(not(feature = "no-panic"))]
+impl<T, A: RawAllocator> std::ops::Index<usize> for ArrayCStyle<T, A> {
     type Output = T;
     fn index(&self, index: usize) -> &Self::Output {
         self.get(index).expect("Index out of bounds")
     }
 }
 
-impl<T> IndexMut<usize> for ArrayCStyle<T> {
+#[cfg(not(feature = "no-panic"))]
+impl<T, A: RawAllocator> std::ops::IndexMut<usize> for ArrayCStyle<T, A> {
     fn index_mut(&mut self, index: usize) -> &mut Self::Output {
         self.get_mut(index).expect("Index out of bounds")
     }
 }
 
-#[derive(Debug, Clone, Hash)]
-pub struct ArrayIntoIter<T> {
-    start: *mut T,
-    end: *mut T,
+/// An owning iterator over the elements of an [`ArrayCStyle`].
+///
+/// The buffer is released once the iterator is dropped.
+#[derive(Debug, Hash)]
+pub struct ArrayIntoIter<T, A: RawAllocator = Global> {
+    // Dropping the array only releases the buffer, never the elements, so
+    // the values already moved out are not touched again.
+    array: ArrayCStyle<T, A>,
+    index: usize,
 }
 
-impl<T> Iterator for ArrayIntoIter<T> {
+impl<T, A: RawAllocator> Iterator for ArrayIntoIter<T, A> {
     type Item = T;
     fn next(&mut self) -> Option<Self::Item> {
-        if self.start == self.end {
+        if self.index == self.array.len {
             // reached the end of the array
             None
         } else {
             let result: T;
             unsafe {
-                // does not actually modify the original array.
-                // This only modifies and replaces the values of the iterator.
-                result = mem::replace(
-                    &mut *self.start,
-                    mem::MaybeUninit::uninit().assume_init(),
-                );
-                self.start = self.start.add(1);
+                // moves the value out of the buffer; the slot is not read
+                // again since the iterator only ever walks forward.
+                result = ptr::read(self.array.ptr.add(self.index));
             }
+            self.index += 1;
             Some(result)
         }
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.array.len - self.index;
+        (remaining, Some(remaining))
+    }
 }
 
-impl<T> IntoIterator for ArrayCStyle<T> {
+impl<T, A: RawAllocator> ExactSizeIterator for ArrayIntoIter<T, A> {}
+
+impl<T, A: RawAllocator> IntoIterator for ArrayCStyle<T, A> {
     type Item = T;
-    type IntoIter = ArrayIntoIter<T>;
+    type IntoIter = ArrayIntoIter<T, A>;
 
     fn into_iter(self) -> Self::IntoIter {
         Self::IntoIter {
-            start: self.ptr,
-            end: unsafe { self.ptr.add(self.len) },
+            array: self,
+            index: 0,
         }
     }
 }
 
+#[cfg(not(feature = "no-panic"))]
 impl<T> FromIterator<T> for ArrayCStyle<T> {
+    #[track_caller]
     fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Self::from_iter_in(iter, Global)
+    }
+}
+
+impl<T, A: RawAllocator> ArrayCStyle<T, A> {
+    /// Collects an iterator into an array allocated from `alloc`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if allocation fails or if the iterator yields a different
+    /// number of elements than its lower `size_hint` promised.
+    #[cfg(not(feature = "no-panic"))]
+    #[track_caller]
+    pub fn from_iter_in<I: IntoIterator<Item = T>>(iter: I, alloc: A) -> Self {
+        match Self::try_from_iter_in(iter, alloc) {
+            Ok(array) => array,
+            Err(Error::Other(message)) => panic!("{message}"),
+            Err(_) => panic!("Failed to allocate array for iterator"),
+        }
+    }
+
+    /// Collects an iterator into an array allocated from `alloc`, without
+    /// panicking.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if allocation fails or if the iterator yields a
+    /// different number of elements than its lower `size_hint` promised.
+    /// Elements collected before the mismatch was noticed are leaked.
+    #[track_caller]
+    pub fn try_from_iter_in<I: IntoIterator<Item = T>>(
+        iter: I,
+        alloc: A,
+    ) -> Result<Self, Error> {
         let iter = iter.into_iter();
         let size_hint = iter.size_hint().0;
 
-        let mut array = ArrayCStyle::new(size_hint).expect(""); //TODO come up with meaningful error message
+        let array = Self::new_in(size_hint, alloc)?;
 
+        let mut count = 0;
         for (index, item) in iter.enumerate() {
             if index >= size_hint {
-                panic!("Iterator has more elements than the allocated size");
+                return Err(Error::Other(
+                    "Iterator has more elements than the allocated size"
+                        .to_string(),
+                ));
             }
-            array[index] = item;
+            unsafe {
+                ptr::write(array.ptr.add(index), item);
+            }
+            count += 1;
         }
 
-        if size_hint != array.len {
-            panic!("Iterator produced a different number of elements than the allocated size");
+        if size_hint != count {
+            return Err(Error::Other(
+                "Iterator produced a different number of elements than the allocated size"
+                    .to_string(),
+            ));
+        }
+        #[cfg(feature = "shadow")]
+        array.shadow.mark_all();
+
+        Ok(array)
+    }
+}
+
+impl<A: RawAllocator> ArrayCStyle<u8, A> {
+    /// Compares the contents of the array with `other` in constant time.
+    ///
+    /// Every byte is inspected no matter where the first difference is, so
+    /// the running time leaks nothing about the contents. Only the lengths
+    /// are compared with an early exit, as they are not considered secret.
+    /// Use this for MAC and tag comparisons.
+    ///
+    /// # Example
+    ///
+    /// ```rust ignore
+    /// use runnarr::runtime_array::ArrayCStyle;
+    ///
+    /// let tag = ArrayCStyle::from(&computed_tag[..]);
+    /// if !tag.ct_eq(&received_tag) {
+    ///     return Err(AuthError);
+    /// }
+    /// ```
+    pub fn ct_eq(&self, other: &[u8]) -> bool {
+        if self.len != other.len() {
+            return false;
         }
+        let difference = self
+            .as_slice()
+            .iter()
+            .zip(other)
+            .fold(0u8, |acc, (left, right)| {
+                std::hint::black_box(acc | (left ^ right))
+            });
+        std::hint::black_box(difference) == 0
+    }
+}
 
-        array
+impl<T: Copy> ArrayCStyle<T> {
+    /// Copies `slice` into a new array.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the array cannot be allocated.
+    #[track_caller]
+    pub fn try_from_slice(slice: &[T]) -> Result<Self, Error> {
+        let copy = Self::new(slice.len())?;
+        unsafe {
+            ptr::copy_nonoverlapping(
+                slice.as_ptr(),
+                copy.ptr_mut(),
+                slice.len(),
+            );
+        }
+        Ok(copy)
     }
 }
 
+#[cfg(not(feature = "no-panic"))]
 impl<T> From<&[T]> for ArrayCStyle<T> {
+    #[track_caller]
     fn from(slice: &[T]) -> Self {
         let copy_to_array = ArrayCStyle::new(slice.len()).unwrap();
 
@@ -345,7 +1025,7 @@ impl<T> From<&[T]> for ArrayCStyle<T> {
         unsafe {
             ptr::copy_nonoverlapping(
                 slice.as_ptr(),
-                copy_to_array.ptr() as *mut T,
+                copy_to_array.ptr_mut(),
                 slice.len(),
             );
         }
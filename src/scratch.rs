@@ -0,0 +1,80 @@
+use std::{mem, slice};
+
+use crate::{
+    error::Error, pool::ArrayPool, runtime_array::ArrayCStyle,
+    usage::MemoryUsage,
+};
+
+/// The unit scratch buffers are made of; its alignment is the largest
+/// element alignment served from the pool.
+#[derive(Clone, Copy)]
+#[repr(C, align(64))]
+struct ScratchBlock([u8; 64]);
+
+thread_local! {
+    static SCRATCH: ArrayPool<ScratchBlock> = ArrayPool::new();
+}
+
+/// Runs `f` on a temporary array of `len` elements taken from a per-thread
+/// cache.
+///
+/// The buffer goes back to the calling thread's cache when `f` returns and
+/// is reused by the next call of a similar byte size, whatever its element
+/// type, so per-request scratch space in a multithreaded server costs no
+/// allocation once each worker has warmed up. Threads never share buffers,
+/// so there is no locking. Nested calls get distinct buffers.
+///
+/// Like [`ArrayCStyle::new`], the elements hold leftovers from earlier use
+/// and must be written before they are read.
+///
+/// Types aligned to more than 64 bytes get a fresh allocation every time.
+///
+/// # Errors
+///
+/// Returns an error if a buffer has to be allocated and the allocation
+/// fails.
+///
+/// # Example
+///
+/// ```rust ignore
+/// use runnarr::scratch::with_scratch;
+///
+/// fn handle(request: &Request) -> Response {
+///     with_scratch::<u32>(request.tokens(), |ids| {
+///         tokenize(request, ids);
+///         score(ids)
+///     })
+///     .unwrap()
+/// }
+/// ```
+pub fn with_scratch<T: Copy, R>(
+    len: usize,
+    f: impl FnOnce(&mut [T]) -> R,
+) -> Result<R, Error> {
+    if mem::align_of::<T>() > mem::align_of::<ScratchBlock>() {
+        let mut array = ArrayCStyle::<T>::new(len)?;
+        return Ok(f(array.as_mut_slice()));
+    }
+    let bytes = len.checked_mul(mem::size_of::<T>()).ok_or_else(|| {
+        Error::Other("Scratch buffer size overflows usize".to_string())
+    })?;
+    let blocks = bytes.div_ceil(mem::size_of::<ScratchBlock>());
+
+    let buffer = SCRATCH.with(|pool| pool.acquire(blocks))?;
+    // `T` is `Copy` and no more aligned than a block, and the blocks cover
+    // at least `len * size_of::<T>()` bytes
+    let elements =
+        unsafe { slice::from_raw_parts_mut(buffer.ptr_mut() as *mut T, len) };
+    let result = f(elements);
+    // the cache is already gone if this runs during thread teardown, in
+    // which case the buffer is simply freed
+    let _ = SCRATCH.try_with(|pool| pool.release(buffer));
+    Ok(result)
+}
+
+/// Returns the memory cached for [`with_scratch`] on the calling thread.
+pub fn scratch_memory_usage() -> MemoryUsage {
+    SCRATCH
+        .try_with(ArrayPool::memory_usage)
+        .unwrap_or_default()
+}
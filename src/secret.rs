@@ -0,0 +1,141 @@
+use std::{
+    fmt, ptr,
+    sync::atomic::{compiler_fence, Ordering},
+};
+
+use crate::{error::Error, runtime_array::ArrayCStyle};
+
+/// A runtime sized buffer for keys, passwords and other secrets.
+///
+/// The buffer is overwritten with zeros using volatile writes before it is
+/// freed, so the optimizer cannot elide the wipe as a dead store, and the
+/// `Debug` output never includes the contents.
+///
+/// Only the buffer owned by the `SecretArray` is wiped. Copies made through
+/// [`SecretArray::expose`] or by reading elements out are the caller's
+/// responsibility.
+///
+/// # Example
+///
+/// ```rust ignore
+/// use runnarr::secret::SecretArray;
+///
+/// let mut key = SecretArray::<u8>::zeroed(32).unwrap();
+/// key.expose_mut().copy_from_slice(&derive_key());
+/// println!("{key:?}"); // SecretArray([REDACTED; 32])
+/// ```
+pub struct SecretArray<T: Copy> {
+    array: ArrayCStyle<T>,
+}
+
+impl<T: Copy> SecretArray<T> {
+    /// Creates a new `SecretArray` with all elements initialized to zero.
+    pub fn zeroed(size: usize) -> Result<Self, Error> {
+        Ok(Self {
+            array: ArrayCStyle::zeroed(size)?,
+        })
+    }
+
+    /// Copies `secret` into a new `SecretArray`.
+    ///
+    /// The source slice is left untouched; wipe it separately if needed.
+    pub fn from_slice(secret: &[T]) -> Result<Self, Error> {
+        let mut array = ArrayCStyle::new(secret.len())?;
+        array.as_mut_slice().copy_from_slice(secret);
+        Ok(Self { array })
+    }
+
+    /// Returns the length of the array.
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.array.len()
+    }
+
+    /// Returns `true` if the array holds no elements.
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.array.is_empty()
+    }
+
+    /// Gets a reference to the element at `index`, or `None` if it is out of
+    /// bounds.
+    #[inline(always)]
+    pub fn get(&self, index: usize) -> Option<&T> {
+        self.array.get(index)
+    }
+
+    /// Gets a mutable reference to the element at `index`, or `None` if it
+    /// is out of bounds.
+    #[inline(always)]
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        self.array.get_mut(index)
+    }
+
+    /// Returns the secret contents as a slice.
+    #[inline(always)]
+    pub fn expose(&self) -> &[T] {
+        self.array.as_slice()
+    }
+
+    /// Returns the secret contents as a mutable slice.
+    #[inline(always)]
+    pub fn expose_mut(&mut self) -> &mut [T] {
+        self.array.as_mut_slice()
+    }
+
+    /// Overwrites the whole buffer with zeros.
+    ///
+    /// This happens automatically on drop.
+    pub fn zeroize(&mut self) {
+        let bytes = self.array.ptr_mut() as *mut u8;
+        let byte_len = self.array.len() * std::mem::size_of::<T>();
+        for offset in 0..byte_len {
+            unsafe { ptr::write_volatile(bytes.add(offset), 0) };
+        }
+        // keep later code, including the deallocation, from being reordered
+        // in front of the wipe
+        compiler_fence(Ordering::SeqCst);
+    }
+}
+
+impl SecretArray<u8> {
+    /// Compares the secret with `other` in constant time.
+    ///
+    /// See [`ArrayCStyle::ct_eq`].
+    pub fn ct_eq(&self, other: &[u8]) -> bool {
+        self.array.ct_eq(other)
+    }
+}
+
+impl<T: Copy> Drop for SecretArray<T> {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl<T: Copy> From<ArrayCStyle<T>> for SecretArray<T> {
+    fn from(array: ArrayCStyle<T>) -> Self {
+        Self { array }
+    }
+}
+
+#[cfg(not(feature = "no-panic"))]
+impl<T: Copy> std::ops::Index<usize> for SecretArray<T> {
+    type Output = T;
+    fn index(&self, index: usize) -> &Self::Output {
+        self.get(index).expect("Index out of bounds")
+    }
+}
+
+#[cfg(not(feature = "no-panic"))]
+impl<T: Copy> std::ops::IndexMut<usize> for SecretArray<T> {
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        self.get_mut(index).expect("Index out of bounds")
+    }
+}
+
+impl<T: Copy> fmt::Debug for SecretArray<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "SecretArray([REDACTED; {}])", self.len())
+    }
+}
@@ -0,0 +1,188 @@
+use std::{
+    cell::UnsafeCell,
+    fmt,
+    sync::atomic::{fence, AtomicUsize, Ordering},
+};
+
+use crate::{error::Error, runtime_array::ArrayCStyle};
+
+/// One element together with its sequence counter; odd while a write is in
+/// progress.
+#[repr(C)]
+struct SeqSlot<T> {
+    seq: AtomicUsize,
+    value: UnsafeCell<T>,
+}
+
+/// Makes a claimed slot's counter even again.
+struct Release<'a> {
+    seq: &'a AtomicUsize,
+    next: usize,
+}
+
+impl Drop for Release<'_> {
+    fn drop(&mut self) {
+        self.seq.store(self.next, Ordering::Release);
+    }
+}
+
+/// An array of `Copy` values published by a writer and read without locks.
+///
+/// Every slot carries its own sequence counter, bumped before and after each
+/// write. A reader copies the value and retries if the counter changed in
+/// between, so it never observes a half-written (torn) value, and it never
+/// blocks the writer. This suits high-frequency telemetry where one thread
+/// updates counters and gauges and many others sample them.
+///
+/// Writers to the same slot are serialized against each other, but the
+/// design assumes writes are rare compared to reads; a slot that is written
+/// continuously can starve its readers.
+///
+/// # Example
+///
+/// ```rust ignore
+/// use runnarr::seqlock_array::SeqLockArray;
+///
+/// #[derive(Clone, Copy, Default)]
+/// struct Gauge { value: f64, timestamp: u64 }
+///
+/// let gauges = SeqLockArray::<Gauge>::from_slice(&[Gauge::default(); 64]).unwrap();
+///
+/// // publisher thread
+/// gauges.store(CPU, Gauge { value: load, timestamp: now });
+///
+/// // any number of sampling threads
+/// let cpu = gauges.load(CPU).unwrap();
+/// ```
+pub struct SeqLockArray<T: Copy> {
+    slots: ArrayCStyle<SeqSlot<T>>,
+}
+
+// Values are only written while their slot's counter is odd and claimed by
+// one writer; readers copy them out and discard copies that raced a write.
+unsafe impl<T: Copy + Send> Sync for SeqLockArray<T> {}
+
+impl<T: Copy> SeqLockArray<T> {
+    /// Creates an array of `len` slots whose values are all-zero bytes.
+    ///
+    /// All-zero bytes must be a valid `T`.
+    #[track_caller]
+    pub fn zeroed(len: usize) -> Result<Self, Error> {
+        Ok(Self {
+            slots: ArrayCStyle::zeroed(len)?,
+        })
+    }
+
+    /// Creates an array holding a copy of `values`.
+    #[track_caller]
+    pub fn from_slice(values: &[T]) -> Result<Self, Error> {
+        let array = Self {
+            slots: ArrayCStyle::zeroed(values.len())?,
+        };
+        for (slot, &value) in array.slots.as_slice().iter().zip(values) {
+            unsafe { *slot.value.get() = value };
+        }
+        Ok(array)
+    }
+
+    /// Returns the number of slots.
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.slots.len()
+    }
+
+    /// Returns `true` if the array has no slots.
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.slots.is_empty()
+    }
+
+    /// Returns a consistent copy of the value at `index`, or `None` if it is
+    /// out of bounds.
+    ///
+    /// Spins while a write to the slot is in progress.
+    pub fn load(&self, index: usize) -> Option<T> {
+        let slot = self.slots.get(index)?;
+        loop {
+            let before = slot.seq.load(Ordering::Acquire);
+            if before & 1 == 1 {
+                std::hint::spin_loop();
+                continue;
+            }
+            // may race a writer; the copy is discarded below if it did, and
+            // the volatile read keeps the compiler from assuming it cannot
+            let value = unsafe { slot.value.get().read_volatile() };
+            fence(Ordering::Acquire);
+            if slot.seq.load(Ordering::Relaxed) == before {
+                return Some(value);
+            }
+        }
+    }
+
+    /// Replaces the value at `index` with the result of `f` applied to the
+    /// current value.
+    ///
+    /// # Returns
+    ///
+    /// `false` if `index` is out of bounds.
+    pub fn update(&self, index: usize, f: impl FnOnce(T) -> T) -> bool {
+        let Some(slot) = self.slots.get(index) else {
+            return false;
+        };
+        let mut seq = slot.seq.load(Ordering::Relaxed);
+        loop {
+            if seq & 1 == 1 {
+                std::hint::spin_loop();
+                seq = slot.seq.load(Ordering::Relaxed);
+                continue;
+            }
+            // claim the slot by making its counter odd
+            match slot.seq.compare_exchange_weak(
+                seq,
+                seq.wrapping_add(1),
+                Ordering::Acquire,
+                Ordering::Relaxed,
+            ) {
+                Ok(_) => break,
+                Err(current) => seq = current,
+            }
+        }
+        // order the claim before the data write for readers
+        fence(Ordering::Release);
+        // releases the slot even if `f` panics, leaving the old value
+        let _release = Release {
+            seq: &slot.seq,
+            next: seq.wrapping_add(2),
+        };
+        unsafe {
+            let value = slot.value.get().read_volatile();
+            slot.value.get().write_volatile(f(value));
+        }
+        true
+    }
+
+    /// Publishes `value` at `index`.
+    ///
+    /// # Returns
+    ///
+    /// `false` if `index` is out of bounds.
+    #[inline(always)]
+    pub fn store(&self, index: usize, value: T) -> bool {
+        self.update(index, |_| value)
+    }
+
+    /// Returns the number of writes the slot at `index` has seen, or `None`
+    /// if it is out of bounds.
+    pub fn version(&self, index: usize) -> Option<usize> {
+        let slot = self.slots.get(index)?;
+        Some(slot.seq.load(Ordering::Acquire) / 2)
+    }
+}
+
+impl<T: Copy + fmt::Debug> fmt::Debug for SeqLockArray<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries((0..self.len()).filter_map(|index| self.load(index)))
+            .finish()
+    }
+}
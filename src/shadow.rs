@@ -0,0 +1,113 @@
+//! Shadow memory recording which elements of an array have been written.
+//!
+//! With the `shadow` feature enabled, every [`ArrayCStyle`] of a debug build
+//! keeps one bit per element. Writes through `get_mut`, `IndexMut` and the
+//! zeroing constructors set the bit, and reading an element whose bit is
+//! still clear through `get` or `Index` panics with its index, pinpointing
+//! reads of uninitialized memory after [`ArrayCStyle::new`].
+//!
+//! Writes the array cannot see (through `ptr_mut`, `as_mut_slice`, raw parts
+//! or FFI) conservatively mark the whole array as written. In release builds
+//! the shadow is a zero-sized no-op.
+//!
+//! [`ArrayCStyle`]: crate::runtime_array::ArrayCStyle
+//! [`ArrayCStyle::new`]: crate::runtime_array::ArrayCStyle::new
+
+use std::{fmt, hash};
+
+#[cfg(debug_assertions)]
+use std::sync::atomic::{AtomicU64, Ordering};
+
+#[cfg(debug_assertions)]
+const WORD_BITS: usize = u64::BITS as usize;
+
+#[cfg(debug_assertions)]
+pub(crate) struct Shadow {
+    bits: Box<[AtomicU64]>,
+}
+
+#[cfg(not(debug_assertions))]
+pub(crate) struct Shadow;
+
+#[cfg(debug_assertions)]
+impl Shadow {
+    /// Creates the shadow of `len` elements, all written or all unwritten.
+    pub(crate) fn new(len: usize, written: bool) -> Self {
+        let shadow = Self {
+            bits: (0..len.div_ceil(WORD_BITS))
+                .map(|_| AtomicU64::new(0))
+                .collect(),
+        };
+        if written {
+            shadow.mark_all();
+        }
+        shadow
+    }
+
+    #[inline]
+    pub(crate) fn mark(&self, index: usize) {
+        self.bits[index / WORD_BITS]
+            .fetch_or(1 << (index % WORD_BITS), Ordering::Relaxed);
+    }
+
+    pub(crate) fn mark_all(&self) {
+        for word in self.bits.iter() {
+            word.store(u64::MAX, Ordering::Relaxed);
+        }
+    }
+
+    /// Panics if element `index` has never been written.
+    #[inline]
+    #[track_caller]
+    pub(crate) fn check(&self, index: usize) {
+        let word = self.bits[index / WORD_BITS].load(Ordering::Relaxed);
+        if word & (1 << (index % WORD_BITS)) == 0 {
+            panic!("Read of uninitialized element at index {index}");
+        }
+    }
+
+    /// Extends the shadow to `len` elements; the new ones are unwritten.
+    pub(crate) fn grow(&mut self, old_len: usize, len: usize) {
+        let mut bits: Vec<AtomicU64> = std::mem::take(&mut self.bits).into();
+        // clear the tail of the last word, which may have been set by
+        // `mark_all`
+        if let Some(last) = bits.last_mut() {
+            if !old_len.is_multiple_of(WORD_BITS) {
+                *last.get_mut() &= (1 << (old_len % WORD_BITS)) - 1;
+            }
+        }
+        bits.resize_with(len.div_ceil(WORD_BITS), || AtomicU64::new(0));
+        self.bits = bits.into_boxed_slice();
+    }
+}
+
+#[cfg(not(debug_assertions))]
+impl Shadow {
+    #[inline(always)]
+    pub(crate) fn new(_len: usize, _written: bool) -> Self {
+        Self
+    }
+
+    #[inline(always)]
+    pub(crate) fn mark(&self, _index: usize) {}
+
+    #[inline(always)]
+    pub(crate) fn mark_all(&self) {}
+
+    #[inline(always)]
+    pub(crate) fn check(&self, _index: usize) {}
+
+    #[inline(always)]
+    pub(crate) fn grow(&mut self, _old_len: usize, _len: usize) {}
+}
+
+impl fmt::Debug for Shadow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Shadow")
+    }
+}
+
+// The shadow is bookkeeping, not part of the array's identity.
+impl hash::Hash for Shadow {
+    fn hash<H: hash::Hasher>(&self, _state: &mut H) {}
+}
@@ -0,0 +1,134 @@
+use std::{
+    fmt,
+    sync::{Mutex, PoisonError},
+};
+
+use crate::{aligned::CacheAligned, error::Error, runtime_array::ArrayCStyle};
+
+/// A shared array whose index space is striped across several mutexes.
+///
+/// Element `i` is guarded by lock `i % shards`, so threads writing to
+/// different positions rarely contend, and neighbouring elements (which are
+/// often hot together) land on different locks. Each lock sits on its own
+/// cache line.
+///
+/// A closure that panics while holding a lock does not poison the array;
+/// the next caller sees whatever state the element was left in.
+///
+/// # Example
+///
+/// ```rust ignore
+/// use runnarr::sharded_array::ShardedArray;
+///
+/// let balances = ShardedArray::<i64>::zeroed(1_000_000, 64).unwrap();
+/// std::thread::scope(|scope| {
+///     for batch in transfers.chunks(1024) {
+///         let balances = &balances;
+///         scope.spawn(move || {
+///             for transfer in batch {
+///                 balances.with(transfer.from, |b| *b -= transfer.amount);
+///                 balances.with(transfer.to, |b| *b += transfer.amount);
+///             }
+///         });
+///     }
+/// });
+/// ```
+pub struct ShardedArray<T> {
+    array: ArrayCStyle<T>,
+    locks: Box<[CacheAligned<Mutex<()>>]>,
+}
+
+// Elements are only reached through `&mut self` or while holding their
+// stripe's lock, exactly like `Mutex<T>`, so sharing needs only `T: Send`.
+unsafe impl<T: Send> Sync for ShardedArray<T> {}
+
+impl<T> ShardedArray<T> {
+    /// Wraps an initialized array, striping it across `shards` locks.
+    ///
+    /// A `shards` of zero is treated as one.
+    pub fn from_array(array: ArrayCStyle<T>, shards: usize) -> Self {
+        let locks = (0..shards.max(1))
+            .map(|_| CacheAligned::new(Mutex::new(())))
+            .collect();
+        Self { array, locks }
+    }
+
+    /// Creates an array of `len` zeroed elements striped across `shards`
+    /// locks.
+    #[track_caller]
+    pub fn zeroed(len: usize, shards: usize) -> Result<Self, Error> {
+        Ok(Self::from_array(ArrayCStyle::zeroed(len)?, shards))
+    }
+
+    /// Returns the number of elements.
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.array.len()
+    }
+
+    /// Returns `true` if the array holds no elements.
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.array.is_empty()
+    }
+
+    /// Returns the number of locks.
+    #[inline(always)]
+    pub fn shards(&self) -> usize {
+        self.locks.len()
+    }
+
+    /// Returns the lock guarding `index`.
+    #[inline(always)]
+    pub fn shard_of(&self, index: usize) -> usize {
+        index % self.shards()
+    }
+
+    /// Locks the stripe holding `index` and runs `f` on the element.
+    ///
+    /// # Returns
+    ///
+    /// The closure's result, or `None` if `index` is out of bounds.
+    pub fn with<R>(
+        &self,
+        index: usize,
+        f: impl FnOnce(&mut T) -> R,
+    ) -> Option<R> {
+        if index >= self.len() {
+            return None;
+        }
+        let _guard = self.locks[self.shard_of(index)]
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        // the guard gives this thread exclusive access to every element of
+        // the stripe, `index` included
+        Some(f(unsafe { &mut *self.array.ptr_mut().add(index) }))
+    }
+
+    /// Returns a mutable reference to the element at `index` without
+    /// locking, since `&mut self` already rules out other accessors.
+    #[inline(always)]
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        self.array.get_mut(index)
+    }
+
+    /// Returns the elements as a mutable slice without locking.
+    #[inline(always)]
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        self.array.as_mut_slice()
+    }
+
+    /// Unwraps the underlying array.
+    pub fn into_array(self) -> ArrayCStyle<T> {
+        self.array
+    }
+}
+
+impl<T> fmt::Debug for ShardedArray<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ShardedArray")
+            .field("len", &self.len())
+            .field("shards", &self.shards())
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,264 @@
+use std::{ffi::CString, mem, ptr::NonNull, slice};
+
+use crate::{
+    error::Error,
+    os::{map_shared, os_error, unmap},
+};
+
+/// A runtime array living in named POSIX shared memory.
+///
+/// One process [`create`](SharedArray::create)s the array under a name such
+/// as `"/pipeline-frames"`, and any number of other processes
+/// [`attach`](SharedArray::attach) to it by the same name. All of them see
+/// the same buffer. The creator removes the name when it drops its handle;
+/// processes that are still attached keep their mapping until they drop
+/// theirs.
+///
+/// # Synchronization
+///
+/// `SharedArray` performs no synchronization between processes. Writes from
+/// one process become visible to the others like writes from another thread
+/// would, so concurrent access must be coordinated, for example by using
+/// atomic element types (`SharedArray<AtomicU64>`) with appropriate
+/// orderings, or a process-shared lock kept outside the array.
+///
+/// # Element types
+///
+/// The buffer is reinterpreted as `T`s, which is why [`SharedArray::create`]
+/// and [`SharedArray::attach`] are `unsafe`: `T` must be valid for every
+/// bit pattern and must not contain pointers. Plain numeric and atomic
+/// types are the intended use.
+///
+/// # Example
+///
+/// ```rust ignore
+/// use runnarr::shared_array::SharedArray;
+///
+/// // producer process
+/// let mut frames =
+///     unsafe { SharedArray::<u32>::create("/frames", 1024) }.unwrap();
+/// frames[0] = 7;
+///
+/// // consumer process
+/// let frames = unsafe { SharedArray::<u32>::attach("/frames") }.unwrap();
+/// assert_eq!(frames[0], 7);
+/// ```
+#[derive(Debug)]
+pub struct SharedArray<T> {
+    len: usize,
+    ptr: *mut T,
+    name: CString,
+    owner: bool,
+}
+
+impl<T> SharedArray<T> {
+    /// Creates a new shared memory object called `name` holding `len`
+    /// zeroed elements and maps it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if an object with the same name already exists or
+    /// the object cannot be created or mapped.
+    ///
+    /// # Safety
+    ///
+    /// `T` must be valid for every bit pattern, since the memory starts out
+    /// zeroed and may be written by other processes, and must not contain
+    /// pointers, which are meaningless in another address space.
+    pub unsafe fn create(name: &str, len: usize) -> Result<Self, Error> {
+        let name = Self::c_name(name)?;
+        let byte_len =
+            len.checked_mul(mem::size_of::<T>()).ok_or_else(|| {
+                Error::Other("Shared array size overflows usize".to_string())
+            })?;
+
+        let fd = unsafe {
+            libc::shm_open(
+                name.as_ptr(),
+                libc::O_CREAT | libc::O_EXCL | libc::O_RDWR,
+                0o600,
+            )
+        };
+        if fd < 0 {
+            return Err(os_error("shm_open"));
+        }
+
+        let mapped =
+            if unsafe { libc::ftruncate(fd, byte_len as libc::off_t) } != 0 {
+                Err(os_error("ftruncate"))
+            } else {
+                Self::map(fd, len)
+            };
+        unsafe {
+            libc::close(fd);
+            if mapped.is_err() {
+                libc::shm_unlink(name.as_ptr());
+            }
+        }
+
+        Ok(Self {
+            len,
+            ptr: mapped?,
+            name,
+            owner: true,
+        })
+    }
+
+    /// Attaches to the existing shared memory object called `name`.
+    ///
+    /// The length is derived from the size of the object.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no object called `name` exists or it cannot be
+    /// mapped.
+    ///
+    /// # Safety
+    ///
+    /// Every element the object holds must be a valid `T`, and `T` must not
+    /// contain pointers; see [`SharedArray::create`].
+    pub unsafe fn attach(name: &str) -> Result<Self, Error> {
+        let name = Self::c_name(name)?;
+        let fd = unsafe { libc::shm_open(name.as_ptr(), libc::O_RDWR, 0) };
+        if fd < 0 {
+            return Err(os_error("shm_open"));
+        }
+
+        let mut stat: libc::stat = unsafe { mem::zeroed() };
+        let mapped = if unsafe { libc::fstat(fd, &mut stat) } != 0 {
+            Err(os_error("fstat"))
+        } else {
+            let len = stat.st_size as usize / mem::size_of::<T>().max(1);
+            Self::map(fd, len).map(|ptr| (ptr, len))
+        };
+        unsafe {
+            libc::close(fd);
+        }
+        let (ptr, len) = mapped?;
+
+        Ok(Self {
+            len,
+            ptr,
+            name,
+            owner: false,
+        })
+    }
+
+    /// Removes the shared memory object called `name`, for cleaning up after
+    /// a creator that exited without dropping its handle.
+    pub fn unlink(name: &str) -> Result<(), Error> {
+        let name = Self::c_name(name)?;
+        if unsafe { libc::shm_unlink(name.as_ptr()) } != 0 {
+            return Err(os_error("shm_unlink"));
+        }
+        Ok(())
+    }
+
+    fn c_name(name: &str) -> Result<CString, Error> {
+        CString::new(name).map_err(|error| Error::Io(error.into()))
+    }
+
+    fn map(fd: libc::c_int, len: usize) -> Result<*mut T, Error> {
+        let byte_len = len * mem::size_of::<T>();
+        if byte_len == 0 {
+            return Ok(NonNull::dangling().as_ptr());
+        }
+        Ok(map_shared(fd, byte_len)?.as_ptr() as *mut T)
+    }
+
+    /// Returns the name of the shared memory object.
+    pub fn name(&self) -> &str {
+        self.name.to_str().unwrap_or_default()
+    }
+
+    /// Returns `true` if this handle created the shared memory object and
+    /// will remove its name on drop.
+    #[inline(always)]
+    pub const fn is_owner(&self) -> bool {
+        self.owner
+    }
+
+    /// Returns the length of the array.
+    #[inline(always)]
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the array holds no elements.
+    #[inline(always)]
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns a raw pointer to the start of the shared buffer.
+    #[inline(always)]
+    pub const fn ptr(&self) -> *const T {
+        self.ptr
+    }
+
+    #[inline(always)]
+    pub fn ptr_mut(&self) -> *mut T {
+        self.ptr
+    }
+
+    /// Gets a reference to the element at `index`, or `None` if it is out of
+    /// bounds.
+    #[inline(always)]
+    pub fn get(&self, index: usize) -> Option<&T> {
+        if index >= self.len {
+            return None;
+        }
+        Some(unsafe { &*self.ptr.add(index) })
+    }
+
+    /// Gets a mutable reference to the element at `index`, or `None` if it
+    /// is out of bounds.
+    #[inline(always)]
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        if index >= self.len {
+            return None;
+        }
+        Some(unsafe { &mut *self.ptr.add(index) })
+    }
+
+    /// Returns the shared elements as a slice.
+    #[inline(always)]
+    pub fn as_slice(&self) -> &[T] {
+        unsafe { slice::from_raw_parts(self.ptr, self.len) }
+    }
+
+    /// Returns the shared elements as a mutable slice.
+    #[inline(always)]
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        unsafe { slice::from_raw_parts_mut(self.ptr, self.len) }
+    }
+}
+
+impl<T> Drop for SharedArray<T> {
+    fn drop(&mut self) {
+        let byte_len = self.len * mem::size_of::<T>();
+        unsafe {
+            if byte_len != 0 {
+                unmap(NonNull::new_unchecked(self.ptr as *mut u8), byte_len);
+            }
+            if self.owner {
+                libc::shm_unlink(self.name.as_ptr());
+            }
+        }
+    }
+}
+
+#[cfg(not(feature = "no-panic"))]
+impl<T> std::ops::Index<usize> for SharedArray<T> {
+    type Output = T;
+    fn index(&self, index: usize) -> &Self::Output {
+        self.get(index).expect("Index out of bounds")
+    }
+}
+
+#[cfg(not(feature = "no-panic"))]
+impl<T> std::ops::IndexMut<usize> for SharedArray<T> {
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        self.get_mut(index).expect("Index out of bounds")
+    }
+}
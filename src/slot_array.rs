@@ -0,0 +1,251 @@
+use std::{fmt, mem};
+
+use crate::{error::Error, runtime_array::ArrayCStyle, usage::MemoryUsage};
+
+/// Marks the end of the free list.
+const NO_SLOT: usize = usize::MAX;
+
+/// A handle to a value stored in a [`SlotArray`].
+///
+/// Keys stay valid until their value is removed. The slot may then be
+/// reused, but with a new generation, so an old key can never reach the new
+/// value.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Key {
+    index: usize,
+    generation: u32,
+}
+
+impl Key {
+    /// Returns the slot the key points at.
+    #[inline(always)]
+    pub const fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Returns the generation of the slot when the key was handed out.
+    #[inline(always)]
+    pub const fn generation(&self) -> u32 {
+        self.generation
+    }
+}
+
+enum Entry<T> {
+    Occupied(T),
+    Vacant { next_free: usize },
+}
+
+struct Slot<T> {
+    generation: u32,
+    entry: Entry<T>,
+}
+
+/// A generational slot map storing its values in one [`ArrayCStyle`].
+///
+/// [`SlotArray::insert`] returns a [`Key`] that stays cheap to copy and
+/// store, and lookups through a key whose value was removed fail instead of
+/// returning whatever now lives in the slot. Freed slots are reused and the
+/// buffer grows in place when it runs full.
+///
+/// # Example
+///
+/// ```rust ignore
+/// use runnarr::slot_array::SlotArray;
+///
+/// let mut entities = SlotArray::new();
+/// let player = entities.insert(Entity::player()).unwrap();
+/// entities.remove(player);
+/// assert!(entities.get(player).is_none());
+/// ```
+pub struct SlotArray<T> {
+    slots: ArrayCStyle<Slot<T>>,
+    /// Slots below this index have been written at least once.
+    initialized: usize,
+    free_head: usize,
+    len: usize,
+}
+
+impl<T> SlotArray<T> {
+    /// Creates an empty `SlotArray` that allocates on first insert.
+    ///
+    /// Not available with the `no-panic` feature, which keeps the
+    /// unreachable allocation check out; use [`SlotArray::with_capacity`].
+    #[cfg(not(feature = "no-panic"))]
+    pub fn new() -> Self {
+        Self::with_capacity(0).expect("Empty arrays never allocate")
+    }
+
+    /// Creates an empty `SlotArray` with room for `capacity` values.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the allocation fails; a capacity of zero never
+    /// allocates.
+    #[track_caller]
+    pub fn with_capacity(capacity: usize) -> Result<Self, Error> {
+        Ok(Self {
+            slots: ArrayCStyle::new(capacity)?,
+            initialized: 0,
+            free_head: NO_SLOT,
+            len: 0,
+        })
+    }
+
+    /// Returns the number of values stored.
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if no values are stored.
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the number of slots in the buffer.
+    #[inline(always)]
+    pub fn capacity(&self) -> usize {
+        self.slots.len()
+    }
+
+    /// Returns the memory held by the slots; free and never used slots count
+    /// as slack.
+    pub fn memory_usage(&self) -> MemoryUsage {
+        MemoryUsage {
+            allocated_bytes: self.slots.allocated_bytes(),
+            used_bytes: self.len * self.slots.element_size(),
+        }
+    }
+
+    /// Stores `value`, returning the key to reach it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the buffer is full and cannot grow; the value is
+    /// dropped in that case.
+    #[track_caller]
+    pub fn insert(&mut self, value: T) -> Result<Key, Error> {
+        let index = if self.free_head != NO_SLOT {
+            let index = self.free_head;
+            let slot = &mut self.slots.as_mut_slice()[index];
+            if let Entry::Vacant { next_free } = slot.entry {
+                self.free_head = next_free;
+            }
+            slot.entry = Entry::Occupied(value);
+            index
+        } else {
+            if self.initialized == self.slots.len() {
+                let additional = self.slots.len().max(4);
+                self.slots.try_grow(additional)?;
+            }
+            let index = self.initialized;
+            unsafe {
+                self.slots.ptr_mut().add(index).write(Slot {
+                    generation: 0,
+                    entry: Entry::Occupied(value),
+                });
+            }
+            self.initialized += 1;
+            index
+        };
+        self.len += 1;
+        Ok(Key {
+            index,
+            generation: self.slots.as_slice()[index].generation,
+        })
+    }
+
+    fn slot(&self, key: Key) -> Option<&Slot<T>> {
+        if key.index >= self.initialized {
+            return None;
+        }
+        let slot = &self.slots.as_slice()[key.index];
+        (slot.generation == key.generation).then_some(slot)
+    }
+
+    /// Returns `true` if `key` still refers to a stored value.
+    pub fn contains(&self, key: Key) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Gets a reference to the value behind `key`, or `None` if it was
+    /// removed.
+    pub fn get(&self, key: Key) -> Option<&T> {
+        match &self.slot(key)?.entry {
+            Entry::Occupied(value) => Some(value),
+            Entry::Vacant { .. } => None,
+        }
+    }
+
+    /// Gets a mutable reference to the value behind `key`, or `None` if it
+    /// was removed.
+    pub fn get_mut(&mut self, key: Key) -> Option<&mut T> {
+        self.slot(key)?;
+        match &mut self.slots.as_mut_slice()[key.index].entry {
+            Entry::Occupied(value) => Some(value),
+            Entry::Vacant { .. } => None,
+        }
+    }
+
+    /// Removes and returns the value behind `key`, or `None` if it was
+    /// already removed.
+    pub fn remove(&mut self, key: Key) -> Option<T> {
+        if !self.contains(key) {
+            return None;
+        }
+        let slot = &mut self.slots.as_mut_slice()[key.index];
+        let entry = mem::replace(
+            &mut slot.entry,
+            Entry::Vacant {
+                next_free: self.free_head,
+            },
+        );
+        slot.generation = slot.generation.wrapping_add(1);
+        self.free_head = key.index;
+        self.len -= 1;
+        match entry {
+            Entry::Occupied(value) => Some(value),
+            Entry::Vacant { .. } => unreachable!(),
+        }
+    }
+
+    /// Returns an iterator over the keys and values stored.
+    pub fn iter(&self) -> impl Iterator<Item = (Key, &T)> {
+        self.slots.as_slice()[..self.initialized]
+            .iter()
+            .enumerate()
+            .filter_map(|(index, slot)| match &slot.entry {
+                Entry::Occupied(value) => Some((
+                    Key {
+                        index,
+                        generation: slot.generation,
+                    },
+                    value,
+                )),
+                Entry::Vacant { .. } => None,
+            })
+    }
+}
+
+#[cfg(not(feature = "no-panic"))]
+impl<T> Default for SlotArray<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Drop for SlotArray<T> {
+    fn drop(&mut self) {
+        // the backing array never drops its elements
+        for index in 0..self.initialized {
+            unsafe { self.slots.ptr_mut().add(index).drop_in_place() };
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for SlotArray<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
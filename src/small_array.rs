@@ -0,0 +1,305 @@
+use std::{fmt, mem::MaybeUninit, ptr, slice};
+
+use crate::{
+    error::{Error, GrowError},
+    runtime_array::{ArrayCStyle, ArrayIntoIter},
+    usage::MemoryUsage,
+};
+
+enum Storage<T, const N: usize> {
+    Inline {
+        len: usize,
+        data: [MaybeUninit<T>; N],
+    },
+    Heap(ArrayCStyle<T>),
+}
+
+/// A runtime sized array that keeps up to `N` elements inline and only
+/// spills to the heap beyond that.
+///
+/// It offers the same indexing and iteration API as [`ArrayCStyle`], but the
+/// common tiny-array case costs no heap allocation at all. Like
+/// [`ArrayCStyle`], elements are not dropped with the array.
+///
+/// # Example
+///
+/// ```rust ignore
+/// use runnarr::small_array::SmallRuntimeArray;
+///
+/// let mut args = SmallRuntimeArray::<u32, 4>::zeroed(3).unwrap();
+/// assert!(args.is_inline());
+/// args.try_grow(5).unwrap();
+/// assert!(!args.is_inline());
+/// ```
+pub struct SmallRuntimeArray<T, const N: usize> {
+    storage: Storage<T, N>,
+}
+
+impl<T, const N: usize> SmallRuntimeArray<T, N> {
+    /// Creates a new uninitialized array with the specified size, inline if
+    /// it fits into `N` elements.
+    ///
+    /// See [`ArrayCStyle::new`] for the initialization contract.
+    #[track_caller]
+    pub fn new(size: usize) -> Result<Self, Error> {
+        let storage = if size <= N {
+            Storage::Inline {
+                len: size,
+                data: [const { MaybeUninit::uninit() }; N],
+            }
+        } else {
+            Storage::Heap(ArrayCStyle::new(size)?)
+        };
+        Ok(Self { storage })
+    }
+
+    /// Creates a new array with the specified size, initializing all
+    /// elements to zero.
+    #[track_caller]
+    pub fn zeroed(size: usize) -> Result<Self, Error> {
+        let storage = if size <= N {
+            Storage::Inline {
+                len: size,
+                data: unsafe { MaybeUninit::zeroed().assume_init() },
+            }
+        } else {
+            Storage::Heap(ArrayCStyle::zeroed(size)?)
+        };
+        Ok(Self { storage })
+    }
+
+    /// Returns the heap memory held by the array.
+    ///
+    /// Inline storage lives inside the array value itself and is not
+    /// counted.
+    pub fn memory_usage(&self) -> MemoryUsage {
+        match &self.storage {
+            Storage::Inline { .. } => MemoryUsage::default(),
+            Storage::Heap(array) => array.memory_usage(),
+        }
+    }
+
+    /// Returns `true` if the elements are stored inline.
+    #[inline(always)]
+    pub const fn is_inline(&self) -> bool {
+        matches!(self.storage, Storage::Inline { .. })
+    }
+
+    /// Returns the length of the array.
+    #[inline(always)]
+    pub const fn len(&self) -> usize {
+        match &self.storage {
+            Storage::Inline { len, .. } => *len,
+            Storage::Heap(array) => array.len(),
+        }
+    }
+
+    /// Returns `true` if the array holds no elements.
+    #[inline(always)]
+    pub const fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns a raw pointer to the first element.
+    #[inline(always)]
+    pub const fn ptr(&self) -> *const T {
+        match &self.storage {
+            Storage::Inline { data, .. } => data.as_ptr() as *const T,
+            Storage::Heap(array) => array.ptr(),
+        }
+    }
+
+    #[inline(always)]
+    pub fn ptr_mut(&mut self) -> *mut T {
+        match &mut self.storage {
+            Storage::Inline { data, .. } => data.as_mut_ptr() as *mut T,
+            Storage::Heap(array) => array.ptr_mut(),
+        }
+    }
+
+    /// Gets a reference to the element at `index`, or `None` if it is out of
+    /// bounds.
+    #[inline(always)]
+    pub fn get(&self, index: usize) -> Option<&T> {
+        if index >= self.len() {
+            return None;
+        }
+        Some(unsafe { &*self.ptr().add(index) })
+    }
+
+    /// Gets a mutable reference to the element at `index`, or `None` if it
+    /// is out of bounds.
+    #[inline(always)]
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        if index >= self.len() {
+            return None;
+        }
+        Some(unsafe { &mut *self.ptr_mut().add(index) })
+    }
+
+    /// Returns the elements of the array as a slice.
+    #[inline(always)]
+    pub fn as_slice(&self) -> &[T] {
+        unsafe { slice::from_raw_parts(self.ptr(), self.len()) }
+    }
+
+    /// Returns the elements of the array as a mutable slice.
+    #[inline(always)]
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        let len = self.len();
+        unsafe { slice::from_raw_parts_mut(self.ptr_mut(), len) }
+    }
+
+    /// Extends the array by `additional` uninitialized elements, moving the
+    /// contents to the heap once they no longer fit inline.
+    ///
+    /// See [`ArrayCStyle::try_grow`].
+    #[track_caller]
+    pub fn try_grow(&mut self, additional: usize) -> Result<(), GrowError> {
+        let current = self.len();
+        let requested = current.checked_add(additional);
+        let error = GrowError { requested, current };
+        let new_len = requested.ok_or(error)?;
+
+        match &mut self.storage {
+            Storage::Heap(array) => array.try_grow(additional),
+            Storage::Inline { len, .. } if new_len <= N => {
+                *len = new_len;
+                Ok(())
+            }
+            Storage::Inline { len, data } => {
+                let heap = ArrayCStyle::<T>::new(new_len).map_err(|_| error)?;
+                unsafe {
+                    ptr::copy_nonoverlapping(
+                        data.as_ptr() as *const T,
+                        heap.ptr_mut(),
+                        *len,
+                    );
+                }
+                self.storage = Storage::Heap(heap);
+                Ok(())
+            }
+        }
+    }
+}
+
+#[cfg(not(feature = "no-panic"))]
+impl<T, const N: usize> std::ops::Index<usize> for SmallRuntimeArray<T, N> {
+    type Output = T;
+    fn index(&self, index: usize) -> &Self::Output {
+        self.get(index).expect("Index out of bounds")
+    }
+}
+
+#[cfg(not(feature = "no-panic"))]
+impl<T, const N: usize> std::ops::IndexMut<usize> for SmallRuntimeArray<T, N> {
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        self.get_mut(index).expect("Index out of bounds")
+    }
+}
+
+impl<T: fmt::Debug, const N: usize> fmt::Debug for SmallRuntimeArray<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SmallRuntimeArray")
+            .field("inline", &self.is_inline())
+            .field("elements", &self.as_slice())
+            .finish()
+    }
+}
+
+/// An owning iterator over the elements of a [`SmallRuntimeArray`].
+pub enum SmallArrayIntoIter<T, const N: usize> {
+    Inline {
+        data: [MaybeUninit<T>; N],
+        index: usize,
+        len: usize,
+    },
+    Heap(ArrayIntoIter<T>),
+}
+
+impl<T, const N: usize> Iterator for SmallArrayIntoIter<T, N> {
+    type Item = T;
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            SmallArrayIntoIter::Inline { data, index, len } => {
+                if *index == *len {
+                    return None;
+                }
+                // moves the value out; the iterator never revisits a slot
+                let result = unsafe { data[*index].assume_init_read() };
+                *index += 1;
+                Some(result)
+            }
+            SmallArrayIntoIter::Heap(iter) => iter.next(),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self {
+            SmallArrayIntoIter::Inline { index, len, .. } => {
+                (len - index, Some(len - index))
+            }
+            SmallArrayIntoIter::Heap(iter) => iter.size_hint(),
+        }
+    }
+}
+
+impl<T, const N: usize> ExactSizeIterator for SmallArrayIntoIter<T, N> {}
+
+impl<T, const N: usize> IntoIterator for SmallRuntimeArray<T, N> {
+    type Item = T;
+    type IntoIter = SmallArrayIntoIter<T, N>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        match self.storage {
+            Storage::Inline { len, data } => SmallArrayIntoIter::Inline {
+                data,
+                index: 0,
+                len,
+            },
+            Storage::Heap(array) => SmallArrayIntoIter::Heap(array.into_iter()),
+        }
+    }
+}
+
+#[cfg(not(feature = "no-panic"))]
+impl<T, const N: usize> FromIterator<T> for SmallRuntimeArray<T, N> {
+    #[track_caller]
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let iter = iter.into_iter();
+        if iter.size_hint().0 > N {
+            // `ArrayCStyle` needs an exact length up front
+            let all: Vec<T> = iter.collect();
+            return Self {
+                storage: Storage::Heap(all.into_iter().collect()),
+            };
+        }
+
+        let mut data = [const { MaybeUninit::uninit() }; N];
+        let mut len = 0;
+        let mut rest = iter.peekable();
+        while len < N {
+            match rest.next() {
+                Some(item) => {
+                    data[len].write(item);
+                    len += 1;
+                }
+                None => break,
+            }
+        }
+
+        if rest.peek().is_none() {
+            return Self {
+                storage: Storage::Inline { len, data },
+            };
+        }
+        // more elements than fit inline: move everything to the heap
+        let spilled = data[..len]
+            .iter()
+            .map(|item| unsafe { item.assume_init_read() });
+        let all: Vec<T> = spilled.chain(rest).collect();
+        Self {
+            storage: Storage::Heap(all.into_iter().collect()),
+        }
+    }
+}
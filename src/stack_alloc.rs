@@ -0,0 +1,204 @@
+use std::{alloc::Layout, cell::Cell, ptr::NonNull};
+
+use crate::{
+    allocator::RawAllocator, error::Error, runtime_array::ArrayCStyle,
+    usage::MemoryUsage,
+};
+
+/// An array allocated from a [`Frame`] of a [`StackAlloc`].
+pub type FrameArray<'f, T> = ArrayCStyle<T, &'f Frame<'f>>;
+
+/// A LIFO allocator handing out frame-scoped arrays from one buffer.
+///
+/// Allocations are made from a [`Frame`]; dropping the frame releases
+/// everything allocated in it at once. Frames nest: an inner frame opened
+/// with [`Frame::push_frame`] is released before the frame it was opened
+/// from, and the borrow checker makes sure no array outlives its frame.
+///
+/// Like every array in the crate, frame arrays never drop their elements.
+///
+/// # Example
+///
+/// ```rust ignore
+/// use runnarr::stack_alloc::StackAlloc;
+///
+/// let mut stack = StackAlloc::new(1 << 20)?;
+/// loop {
+///     let frame = stack.push_frame();
+///     let visible = frame.alloc_array::<u32>(scene.len())?;
+///     {
+///         let pass = frame.push_frame()?;
+///         let lights = pass.alloc_zeroed::<Light>(64)?;
+///         shade(&visible, &lights);
+///     } // `lights` is released here
+///     submit(&visible);
+/// } // and `visible` here
+/// ```
+#[derive(Debug)]
+pub struct StackAlloc {
+    buffer: ArrayCStyle<u8>,
+    offset: Cell<usize>,
+    depth: Cell<usize>,
+}
+
+impl StackAlloc {
+    /// Creates a stack allocator owning a single buffer of `capacity` bytes.
+    #[track_caller]
+    pub fn new(capacity: usize) -> Result<Self, Error> {
+        Ok(Self {
+            buffer: ArrayCStyle::new(capacity)?,
+            offset: Cell::new(0),
+            depth: Cell::new(0),
+        })
+    }
+
+    /// Opens the outermost frame.
+    pub fn push_frame(&mut self) -> Frame<'_> {
+        Frame::open(self)
+    }
+
+    /// Returns the total size of the buffer in bytes.
+    #[inline(always)]
+    pub fn capacity(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// Returns the number of bytes held by open frames, including alignment
+    /// padding.
+    #[inline(always)]
+    pub fn used(&self) -> usize {
+        self.offset.get()
+    }
+
+    /// Returns the number of bytes still available.
+    #[inline(always)]
+    pub fn remaining(&self) -> usize {
+        self.capacity() - self.used()
+    }
+
+    /// Returns the memory held by the stack; the space above the open frames
+    /// counts as slack.
+    pub fn memory_usage(&self) -> MemoryUsage {
+        MemoryUsage {
+            allocated_bytes: self.buffer.allocated_bytes(),
+            used_bytes: self.used(),
+        }
+    }
+
+    /// Returns the number of open frames.
+    #[inline(always)]
+    pub fn depth(&self) -> usize {
+        self.depth.get()
+    }
+}
+
+/// A scope of a [`StackAlloc`]; everything allocated from it is released
+/// when it is dropped.
+#[derive(Debug)]
+pub struct Frame<'a> {
+    stack: &'a StackAlloc,
+    start: usize,
+    depth: usize,
+}
+
+impl<'a> Frame<'a> {
+    fn open(stack: &'a StackAlloc) -> Self {
+        let depth = stack.depth.get() + 1;
+        stack.depth.set(depth);
+        Self {
+            stack,
+            start: stack.offset.get(),
+            depth,
+        }
+    }
+
+    #[inline(always)]
+    fn is_innermost(&self) -> bool {
+        self.stack.depth.get() == self.depth
+    }
+
+    /// Opens a frame nested in this one.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if this frame already has an open nested frame.
+    pub fn push_frame(&self) -> Result<Frame<'_>, Error> {
+        if !self.is_innermost() {
+            return Err(Error::Other(
+                "Frame already has an open nested frame".to_string(),
+            ));
+        }
+        Ok(Frame::open(self.stack))
+    }
+
+    /// Allocates an uninitialized array of `len` elements in this frame.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the buffer does not have enough space left or a
+    /// nested frame is open.
+    pub fn alloc_array<T>(
+        &self,
+        len: usize,
+    ) -> Result<FrameArray<'_, T>, Error> {
+        ArrayCStyle::new_in(len, self)
+    }
+
+    /// Allocates an array of `len` zeroed elements in this frame.
+    pub fn alloc_zeroed<T>(
+        &self,
+        len: usize,
+    ) -> Result<FrameArray<'_, T>, Error> {
+        ArrayCStyle::zeroed_in(len, self)
+    }
+
+    /// Returns the number of bytes allocated in this frame and the frames
+    /// nested in it.
+    #[inline(always)]
+    pub fn used(&self) -> usize {
+        self.stack.offset.get() - self.start
+    }
+}
+
+impl Drop for Frame<'_> {
+    fn drop(&mut self) {
+        self.stack.offset.set(self.start);
+        self.stack.depth.set(self.depth - 1);
+    }
+}
+
+unsafe impl RawAllocator for Frame<'_> {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<u8>, Error> {
+        if !self.is_innermost() {
+            return Err(Error::Other(
+                "Cannot allocate from a frame with an open nested frame"
+                    .to_string(),
+            ));
+        }
+        let stack = self.stack;
+        let base = stack.buffer.ptr() as usize;
+        let start =
+            (base + stack.offset.get()).next_multiple_of(layout.align()) - base;
+        let end = start
+            .checked_add(layout.size())
+            .filter(|&end| end <= stack.capacity())
+            .ok_or_else(|| {
+                Error::Other(format!(
+                    "Stack exhausted: requested {} bytes, {} remaining",
+                    layout.size(),
+                    stack.remaining()
+                ))
+            })?;
+
+        stack.offset.set(end);
+        Ok(
+            unsafe {
+                NonNull::new_unchecked(stack.buffer.ptr_mut().add(start))
+            },
+        )
+    }
+
+    unsafe fn deallocate(&self, _ptr: NonNull<u8>, _layout: Layout) {
+        // memory is reclaimed when the frame is popped
+    }
+}
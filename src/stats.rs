@@ -0,0 +1,167 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+#[cfg(feature = "stats-callsite")]
+use std::{
+    collections::HashMap,
+    panic::Location,
+    sync::{Mutex, OnceLock},
+};
+
+static LIVE_ARRAYS: AtomicUsize = AtomicUsize::new(0);
+static LIVE_BYTES: AtomicUsize = AtomicUsize::new(0);
+static PEAK_BYTES: AtomicUsize = AtomicUsize::new(0);
+static TOTAL_ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+/// A snapshot of the memory held by arrays of this crate.
+#[derive(Debug, Clone, Copy, Default, Hash, PartialEq, Eq)]
+pub struct Stats {
+    /// Number of arrays currently alive.
+    pub live_arrays: usize,
+    /// Bytes currently allocated by live arrays.
+    pub live_bytes: usize,
+    /// The highest value `live_bytes` has reached since start-up or the last
+    /// [`reset_peak`].
+    pub peak_bytes: usize,
+    /// Number of arrays created since start-up.
+    pub total_allocations: usize,
+}
+
+/// Returns the current allocation counters.
+///
+/// The counters are updated independently, so a snapshot taken while other
+/// threads allocate may be slightly inconsistent.
+///
+/// # Example
+///
+/// ```rust ignore
+/// use runnarr::stats;
+///
+/// let before = stats::stats();
+/// let array = ArrayCStyle::<u64>::zeroed(1024).unwrap();
+/// assert!(stats::stats().live_bytes >= before.live_bytes + 8192);
+/// ```
+pub fn stats() -> Stats {
+    Stats {
+        live_arrays: LIVE_ARRAYS.load(Ordering::Relaxed),
+        live_bytes: LIVE_BYTES.load(Ordering::Relaxed),
+        peak_bytes: PEAK_BYTES.load(Ordering::Relaxed),
+        total_allocations: TOTAL_ALLOCATIONS.load(Ordering::Relaxed),
+    }
+}
+
+/// Resets the peak to the number of bytes currently alive, to measure the
+/// peak of a particular phase.
+pub fn reset_peak() {
+    PEAK_BYTES.store(LIVE_BYTES.load(Ordering::Relaxed), Ordering::Relaxed);
+}
+
+/// The allocation counters attributed to a single call site.
+#[cfg(feature = "stats-callsite")]
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub struct CallSiteStats {
+    /// The source location that created the arrays.
+    pub location: &'static Location<'static>,
+    /// Number of arrays from this site currently alive.
+    pub live_arrays: usize,
+    /// Bytes currently allocated by live arrays from this site.
+    pub live_bytes: usize,
+    /// The highest value `live_bytes` has reached for this site.
+    pub peak_bytes: usize,
+    /// Number of arrays created at this site since start-up.
+    pub total_allocations: usize,
+}
+
+#[cfg(feature = "stats-callsite")]
+fn sites() -> &'static Mutex<HashMap<&'static Location<'static>, CallSiteStats>>
+{
+    static SITES: OnceLock<
+        Mutex<HashMap<&'static Location<'static>, CallSiteStats>>,
+    > = OnceLock::new();
+    SITES.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Returns the counters of every call site that created an array, sorted by
+/// live bytes in descending order.
+#[cfg(feature = "stats-callsite")]
+pub fn call_sites() -> Vec<CallSiteStats> {
+    let mut sites: Vec<_> = sites()
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .values()
+        .copied()
+        .collect();
+    sites.sort_by_key(|site| std::cmp::Reverse(site.live_bytes));
+    sites
+}
+
+pub(crate) fn record_alloc(
+    bytes: usize,
+    #[cfg(feature = "stats-callsite")] location: &'static Location<'static>,
+) {
+    LIVE_ARRAYS.fetch_add(1, Ordering::Relaxed);
+    TOTAL_ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+    let live = LIVE_BYTES.fetch_add(bytes, Ordering::Relaxed) + bytes;
+    PEAK_BYTES.fetch_max(live, Ordering::Relaxed);
+
+    #[cfg(feature = "stats-callsite")]
+    {
+        let mut sites = sites()
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let site = sites.entry(location).or_insert(CallSiteStats {
+            location,
+            live_arrays: 0,
+            live_bytes: 0,
+            peak_bytes: 0,
+            total_allocations: 0,
+        });
+        site.live_arrays += 1;
+        site.total_allocations += 1;
+        site.live_bytes += bytes;
+        site.peak_bytes = site.peak_bytes.max(site.live_bytes);
+    }
+}
+
+pub(crate) fn record_free(
+    bytes: usize,
+    #[cfg(feature = "stats-callsite")] location: &'static Location<'static>,
+) {
+    LIVE_ARRAYS.fetch_sub(1, Ordering::Relaxed);
+    LIVE_BYTES.fetch_sub(bytes, Ordering::Relaxed);
+
+    #[cfg(feature = "stats-callsite")]
+    {
+        let mut sites = sites()
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if let Some(site) = sites.get_mut(location) {
+            site.live_arrays -= 1;
+            site.live_bytes -= bytes;
+        }
+    }
+}
+
+pub(crate) fn record_realloc(
+    old_bytes: usize,
+    new_bytes: usize,
+    #[cfg(feature = "stats-callsite")] location: &'static Location<'static>,
+) {
+    let live = if new_bytes >= old_bytes {
+        LIVE_BYTES.fetch_add(new_bytes - old_bytes, Ordering::Relaxed)
+            + (new_bytes - old_bytes)
+    } else {
+        LIVE_BYTES.fetch_sub(old_bytes - new_bytes, Ordering::Relaxed)
+            - (old_bytes - new_bytes)
+    };
+    PEAK_BYTES.fetch_max(live, Ordering::Relaxed);
+
+    #[cfg(feature = "stats-callsite")]
+    {
+        let mut sites = sites()
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if let Some(site) = sites.get_mut(location) {
+            site.live_bytes = site.live_bytes - old_bytes + new_bytes;
+            site.peak_bytes = site.peak_bytes.max(site.live_bytes);
+        }
+    }
+}
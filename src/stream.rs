@@ -0,0 +1,102 @@
+use std::{
+    pin::Pin,
+    ptr,
+    task::{Context, Poll},
+};
+
+use futures_core::{stream::FusedStream, Stream};
+
+use crate::{
+    allocator::{Global, RawAllocator},
+    error::Error,
+    runtime_array::ArrayCStyle,
+};
+
+impl<T, A: RawAllocator> ArrayCStyle<T, A> {
+    /// Turns the array into a [`Stream`] of owned chunks of `chunk_len`
+    /// elements; the last chunk may be shorter. A `chunk_len` of 0 is
+    /// treated as 1.
+    ///
+    /// Each chunk is allocated and filled only when the stream is polled, so
+    /// a slow sink holds back the copying instead of the stream running
+    /// ahead of it, and no single poll does more than one chunk's worth of
+    /// work.
+    ///
+    /// # Example
+    ///
+    /// ```rust ignore
+    /// use futures::{SinkExt, StreamExt};
+    ///
+    /// let mut chunks = frame.chunks_stream(64 * 1024);
+    /// while let Some(chunk) = chunks.next().await {
+    ///     sink.send(chunk?).await?;
+    /// }
+    /// ```
+    pub fn chunks_stream(self, chunk_len: usize) -> ChunksStream<T, A> {
+        ChunksStream {
+            array: self,
+            chunk_len: chunk_len.max(1),
+            index: 0,
+        }
+    }
+}
+
+/// A stream moving the elements of an [`ArrayCStyle`] out in owned chunks;
+/// see [`ArrayCStyle::chunks_stream`].
+///
+/// Like every array in the crate, elements left in the stream when it is
+/// dropped are not dropped. The buffer is released with the stream.
+#[derive(Debug)]
+pub struct ChunksStream<T, A: RawAllocator = Global> {
+    // Dropping the array only releases the buffer, so elements already moved
+    // into chunks are not touched again.
+    array: ArrayCStyle<T, A>,
+    chunk_len: usize,
+    index: usize,
+}
+
+// the elements are never pinned; they are moved out by value
+impl<T, A: RawAllocator> Unpin for ChunksStream<T, A> {}
+
+impl<T, A: RawAllocator> Stream for ChunksStream<T, A> {
+    /// A chunk, or the error from allocating it.
+    type Item = Result<ArrayCStyle<T>, Error>;
+
+    fn poll_next(
+        self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        let remaining = this.array.len() - this.index;
+        if remaining == 0 {
+            return Poll::Ready(None);
+        }
+        let len = remaining.min(this.chunk_len);
+        let chunk = match ArrayCStyle::<T>::new(len) {
+            Ok(chunk) => chunk,
+            // the elements stay put, so polling again retries the chunk
+            Err(error) => return Poll::Ready(Some(Err(error))),
+        };
+        unsafe {
+            // each slot is moved out once; `index` only walks forward
+            ptr::copy_nonoverlapping(
+                this.array.ptr().add(this.index),
+                chunk.ptr_mut(),
+                len,
+            );
+        }
+        this.index += len;
+        Poll::Ready(Some(Ok(chunk)))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let chunks = (self.array.len() - self.index).div_ceil(self.chunk_len);
+        (chunks, Some(chunks))
+    }
+}
+
+impl<T, A: RawAllocator> FusedStream for ChunksStream<T, A> {
+    fn is_terminated(&self) -> bool {
+        self.index == self.array.len()
+    }
+}
@@ -0,0 +1,173 @@
+use std::{
+    fmt,
+    ops::{Deref, DerefMut},
+    sync::{
+        Arc, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard,
+        TryLockError,
+    },
+};
+
+use crate::{error::Error, runtime_array::ArrayCStyle};
+
+/// A big array shared between threads behind a reader-writer lock.
+///
+/// Cloning a `SyncArray` hands out another handle to the same buffer. Any
+/// number of [`SyncArray::read`] guards can view the elements at once, while
+/// a [`SyncArray::write`] guard has them to itself. Both guards deref to a
+/// slice. The length is fixed, so it can be queried without locking.
+///
+/// A thread that panics while writing does not poison the array; later
+/// guards see whatever state the elements were left in.
+///
+/// # Example
+///
+/// ```rust ignore
+/// use runnarr::sync_array::SyncArray;
+///
+/// let grid = SyncArray::<f32>::zeroed(4096 * 4096).unwrap();
+///
+/// let writer = grid.clone();
+/// std::thread::spawn(move || writer.write().fill(1.0));
+///
+/// let total: f32 = grid.read().iter().sum();
+/// ```
+pub struct SyncArray<T> {
+    inner: Arc<RwLock<ArrayCStyle<T>>>,
+    len: usize,
+}
+
+impl<T> SyncArray<T> {
+    /// Shares an initialized array between threads.
+    pub fn new(array: ArrayCStyle<T>) -> Self {
+        Self {
+            len: array.len(),
+            inner: Arc::new(RwLock::new(array)),
+        }
+    }
+
+    /// Creates a shared array of `len` zeroed elements.
+    #[track_caller]
+    pub fn zeroed(len: usize) -> Result<Self, Error> {
+        Ok(Self::new(ArrayCStyle::zeroed(len)?))
+    }
+
+    /// Returns the number of elements.
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the array holds no elements.
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Blocks until the elements can be read, then returns a shared view.
+    pub fn read(&self) -> SyncArrayReadGuard<'_, T> {
+        SyncArrayReadGuard {
+            guard: self.inner.read().unwrap_or_else(PoisonError::into_inner),
+        }
+    }
+
+    /// Blocks until no other guard is alive, then returns an exclusive view.
+    pub fn write(&self) -> SyncArrayWriteGuard<'_, T> {
+        SyncArrayWriteGuard {
+            guard: self.inner.write().unwrap_or_else(PoisonError::into_inner),
+        }
+    }
+
+    /// Returns a shared view, or `None` if a writer holds the lock.
+    pub fn try_read(&self) -> Option<SyncArrayReadGuard<'_, T>> {
+        match self.inner.try_read() {
+            Ok(guard) => Some(SyncArrayReadGuard { guard }),
+            Err(TryLockError::Poisoned(poison)) => Some(SyncArrayReadGuard {
+                guard: poison.into_inner(),
+            }),
+            Err(TryLockError::WouldBlock) => None,
+        }
+    }
+
+    /// Returns an exclusive view, or `None` if any other guard is alive.
+    pub fn try_write(&self) -> Option<SyncArrayWriteGuard<'_, T>> {
+        match self.inner.try_write() {
+            Ok(guard) => Some(SyncArrayWriteGuard { guard }),
+            Err(TryLockError::Poisoned(poison)) => Some(SyncArrayWriteGuard {
+                guard: poison.into_inner(),
+            }),
+            Err(TryLockError::WouldBlock) => None,
+        }
+    }
+
+    /// Returns the number of handles sharing the buffer.
+    #[inline(always)]
+    pub fn handle_count(&self) -> usize {
+        Arc::strong_count(&self.inner)
+    }
+
+    /// Returns the array if this is the only handle, or gives the handle
+    /// back otherwise.
+    pub fn try_unwrap(self) -> Result<ArrayCStyle<T>, Self> {
+        let len = self.len;
+        match Arc::try_unwrap(self.inner) {
+            Ok(lock) => {
+                Ok(lock.into_inner().unwrap_or_else(PoisonError::into_inner))
+            }
+            Err(inner) => Err(Self { inner, len }),
+        }
+    }
+}
+
+impl<T> Clone for SyncArray<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: Arc::clone(&self.inner),
+            len: self.len,
+        }
+    }
+}
+
+impl<T> From<ArrayCStyle<T>> for SyncArray<T> {
+    fn from(array: ArrayCStyle<T>) -> Self {
+        Self::new(array)
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for SyncArray<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.try_read() {
+            Some(guard) => f.debug_tuple("SyncArray").field(&&*guard).finish(),
+            None => f.write_str("SyncArray(<locked>)"),
+        }
+    }
+}
+
+/// Shared access to the elements of a [`SyncArray`].
+pub struct SyncArrayReadGuard<'a, T> {
+    guard: RwLockReadGuard<'a, ArrayCStyle<T>>,
+}
+
+impl<T> Deref for SyncArrayReadGuard<'_, T> {
+    type Target = [T];
+    fn deref(&self) -> &Self::Target {
+        self.guard.as_slice()
+    }
+}
+
+/// Exclusive access to the elements of a [`SyncArray`].
+pub struct SyncArrayWriteGuard<'a, T> {
+    guard: RwLockWriteGuard<'a, ArrayCStyle<T>>,
+}
+
+impl<T> Deref for SyncArrayWriteGuard<'_, T> {
+    type Target = [T];
+    fn deref(&self) -> &Self::Target {
+        self.guard.as_slice()
+    }
+}
+
+impl<T> DerefMut for SyncArrayWriteGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.guard.as_mut_slice()
+    }
+}
@@ -1,17 +1,37 @@
+use std::{alloc::Layout, cell::Cell, ptr::NonNull};
+
+#[cfg(not(feature = "no-panic"))]
+use crate::aligned::{AlignedArray, CacheAligned, CACHE_LINE_SIZE};
+use crate::allocator::{Global, RawAllocator};
+use crate::error::Error;
 use crate::runtime_array::ArrayCStyle;
+#[cfg(not(feature = "no-panic"))]
+use crate::typed_array::TypedArray;
+
+/// Collects into an array without the `FromIterator` impl that `no-panic`
+/// removes.
+fn array<T>(iter: impl IntoIterator<Item = T>) -> ArrayCStyle<T> {
+    ArrayCStyle::try_from_iter_in(iter, Global).unwrap()
+}
 
 #[test]
 fn test_array_new() {
     let runt = ArrayCStyle::<i32>::new(10).unwrap();
-    assert_ne!(true, runt.ptr().is_null());
+    assert!(!runt.ptr().is_null());
 }
 
+#[cfg(not(feature = "no-panic"))]
 #[test]
+#[cfg_attr(
+    all(debug_assertions, feature = "shadow"),
+    should_panic(expected = "Read of uninitialized element at index 0")
+)]
 fn test_array_index() {
     let _runt = ArrayCStyle::<i32>::new(4).unwrap();
     println!("runt[0]: {}", _runt[0]);
 }
 
+#[cfg(not(feature = "no-panic"))]
 #[test]
 fn test_array_index_mut() {
     let mut runt = ArrayCStyle::<i32>::zeroed(9).unwrap();
@@ -30,6 +50,3262 @@ fn test_array_into_iter() {
 #[test]
 fn bs() {
     // TODO
-    type X<T: Default> = ArrayCStyle<T>;
-    let x = X::<i32>::new(0);
+    type X<T> = ArrayCStyle<T>;
+    let _x = X::<i32>::new(0);
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct NodeId(usize);
+
+impl From<usize> for NodeId {
+    fn from(index: usize) -> Self {
+        NodeId(index)
+    }
+}
+
+impl From<NodeId> for usize {
+    fn from(id: NodeId) -> Self {
+        id.0
+    }
+}
+
+#[cfg(not(feature = "no-panic"))]
+#[test]
+fn test_typed_array_index() {
+    let mut weights = TypedArray::<NodeId, u32>::zeroed(4).unwrap();
+    weights[NodeId(2)] = 7;
+    assert_eq!(weights[NodeId(2)], 7);
+    assert!(weights.get(NodeId(4)).is_none());
+    assert_eq!(weights.indices().last(), Some(NodeId(3)));
+}
+
+#[derive(Default)]
+struct CountingAlloc {
+    live: Cell<usize>,
+}
+
+unsafe impl RawAllocator for CountingAlloc {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<u8>, Error> {
+        self.live.set(self.live.get() + 1);
+        Global.allocate(layout)
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        self.live.set(self.live.get() - 1);
+        unsafe { Global.deallocate(ptr, layout) }
+    }
+}
+
+#[test]
+fn test_array_custom_allocator() {
+    let alloc = CountingAlloc::default();
+    {
+        let mut runt = ArrayCStyle::<u16, _>::zeroed_in(8, &alloc).unwrap();
+        runt.as_mut_slice()[7] = 7;
+        assert_eq!(alloc.live.get(), 1);
+        let copy = runt.try_clone().unwrap();
+        assert_eq!(copy.as_slice()[7], 7);
+        assert_eq!(alloc.live.get(), 2);
+    }
+    assert_eq!(alloc.live.get(), 0);
+}
+
+#[cfg(not(feature = "no-panic"))]
+#[test]
+fn test_array_collect_and_into_iter() {
+    let runt: ArrayCStyle<String> =
+        (0..4).map(|value| value.to_string()).collect();
+    let values: Vec<String> = runt.into_iter().collect();
+    assert_eq!(values, ["0", "1", "2", "3"]);
+}
+
+#[cfg(not(feature = "no-panic"))]
+#[test]
+fn test_array_new_aligned() {
+    let mut runt = ArrayCStyle::<f32>::zeroed_aligned(33, 64).unwrap();
+    assert_eq!(runt.ptr() as usize % 64, 0);
+    assert_eq!(runt.align(), 64);
+    runt[32] = 1.0;
+
+    let empty = ArrayCStyle::<u8>::new_aligned(0, 32).unwrap();
+    assert_eq!(empty.ptr() as usize % 32, 0);
+
+    assert!(ArrayCStyle::<u8>::new_aligned(4, 3).is_err());
+}
+
+#[cfg(not(feature = "no-panic"))]
+#[test]
+fn test_cache_aligned() {
+    let runt = ArrayCStyle::<u8>::new_cache_aligned(3).unwrap();
+    assert_eq!(runt.ptr() as usize % CACHE_LINE_SIZE, 0);
+
+    let padded = ArrayCStyle::<CacheAligned<u64>>::zeroed(4).unwrap();
+    let first = &padded[0] as *const _ as usize;
+    let second = &padded[1] as *const _ as usize;
+    assert_eq!(second - first, CACHE_LINE_SIZE);
+    assert_eq!(*padded[1], 0);
+}
+
+#[cfg(not(feature = "no-panic"))]
+#[cfg(target_os = "linux")]
+#[test]
+fn test_array_new_huge() {
+    let mut runt = ArrayCStyle::<u64, _>::new_huge(1024).unwrap();
+    assert_eq!(runt[1023], 0);
+    runt[1023] = 9;
+    assert_eq!(runt[1023], 9);
+}
+
+#[cfg(all(target_os = "linux", feature = "numa"))]
+#[test]
+fn test_array_new_on_node() {
+    let mut runt = ArrayCStyle::<u32, _>::new_on_node(4096, 0).unwrap();
+    runt.as_mut_slice()[0] = 1;
+    assert_eq!(runt.numa_node().unwrap(), 0);
+    assert!(runt.numa_node_of(4096).is_err());
+}
+
+#[cfg(not(feature = "no-panic"))]
+#[cfg(unix)]
+#[test]
+fn test_array_new_mmap() {
+    use crate::os::Advice;
+
+    let mut runt = ArrayCStyle::<u64, _>::new_mmap(1 << 20).unwrap();
+    runt[12345] = 1;
+    runt.advise(Advice::WillNeed).unwrap();
+    assert_eq!(runt[12345], 1);
+    runt.advise(Advice::DontNeed).unwrap();
+    assert_eq!(runt[12345], 0);
+}
+
+#[cfg(not(feature = "no-panic"))]
+#[cfg(unix)]
+#[test]
+fn test_mmap_array_roundtrip() {
+    use crate::mmap_array::MmapArray;
+
+    let path = std::env::temp_dir()
+        .join(format!("runnarr-mmap-{}.bin", std::process::id()));
+    {
+        let mut runt = unsafe { MmapArray::<u32>::create(&path, 16) }.unwrap();
+        runt[3] = 33;
+        runt.sync().unwrap();
+    }
+    let runt = unsafe { MmapArray::<u32>::open(&path) }.unwrap();
+    assert_eq!(runt.len(), 16);
+    assert_eq!(runt.into_iter().nth(3), Some(33));
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[cfg(not(feature = "no-panic"))]
+#[cfg(unix)]
+#[test]
+fn test_shared_array_attach() {
+    use crate::shared_array::SharedArray;
+
+    let name = format!("/runnarr-test-{}", std::process::id());
+    let mut owner = unsafe { SharedArray::<u64>::create(&name, 8) }.unwrap();
+    owner[5] = 55;
+
+    let attached = unsafe { SharedArray::<u64>::attach(&name) }.unwrap();
+    assert!(!attached.is_owner());
+    assert_eq!(attached.len(), 8);
+    assert_eq!(attached[5], 55);
+
+    drop(owner);
+    assert!(unsafe { SharedArray::<u64>::attach(&name) }.is_err());
+}
+
+#[cfg(not(feature = "no-panic"))]
+#[cfg(unix)]
+#[test]
+fn test_array_new_guarded() {
+    use crate::os::{page_size, GuardSide};
+
+    let mut runt = ArrayCStyle::<u32, _>::new_guarded(10).unwrap();
+    runt[9] = 9;
+    let end = unsafe { runt.ptr().add(10) } as usize;
+    assert_eq!(end % page_size(), 0);
+
+    let runt =
+        ArrayCStyle::<u32, _>::new_guarded_at(10, GuardSide::Start).unwrap();
+    assert_eq!(runt.ptr() as usize % page_size(), 0);
+}
+
+#[cfg(feature = "canary")]
+#[test]
+fn test_array_canary_detects_overrun() {
+    use crate::error::CanaryRegion;
+
+    let runt = ArrayCStyle::<u32, _>::zeroed_with_canary(4).unwrap();
+    assert!(runt.check_integrity().is_ok());
+
+    unsafe { (runt.ptr_mut().add(4) as *mut u8).write(0) };
+    let error = runt.check_integrity().unwrap_err();
+    assert_eq!(error.region, CanaryRegion::After);
+    assert_eq!(error.offset, 16);
+
+    // restore the canary so dropping the array does not panic
+    unsafe { (runt.ptr_mut().add(4) as *mut u8).write(error.expected) };
+}
+
+#[cfg(feature = "poison")]
+#[test]
+fn test_array_poison_on_free() {
+    use crate::runtime_array::POISON_BYTE;
+
+    struct InspectingAlloc {
+        poisoned: Cell<bool>,
+    }
+
+    unsafe impl RawAllocator for InspectingAlloc {
+        fn allocate(&self, layout: Layout) -> Result<NonNull<u8>, Error> {
+            Global.allocate(layout)
+        }
+
+        unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+            let bytes = unsafe {
+                std::slice::from_raw_parts(ptr.as_ptr(), layout.size())
+            };
+            self.poisoned
+                .set(bytes.iter().all(|&byte| byte == POISON_BYTE));
+            unsafe { Global.deallocate(ptr, layout) }
+        }
+    }
+
+    let alloc = InspectingAlloc {
+        poisoned: Cell::new(false),
+    };
+    drop(ArrayCStyle::<u64, _>::zeroed_in(4, &alloc).unwrap());
+    assert!(alloc.poisoned.get());
+}
+
+#[test]
+fn test_secret_array_zeroize_and_redact() {
+    use crate::secret::SecretArray;
+
+    let mut key = SecretArray::from_slice(&[0xAAu8; 8]).unwrap();
+    assert_eq!(format!("{key:?}"), "SecretArray([REDACTED; 8])");
+    key.zeroize();
+    assert!(key.expose().iter().all(|&byte| byte == 0));
+}
+
+#[cfg(not(feature = "no-panic"))]
+#[cfg(unix)]
+#[test]
+fn test_array_new_locked() {
+    match ArrayCStyle::<u8, _>::new_locked(64) {
+        Ok(mut runt) => {
+            runt[63] = 1;
+            assert_eq!(runt[63], 1);
+        }
+        // unprivileged environments may not allow locking any memory
+        Err(error) => assert!(error.to_string().contains("mlock")),
+    }
+}
+
+#[cfg(not(feature = "no-panic"))]
+#[test]
+fn test_array_ct_eq() {
+    let tag = ArrayCStyle::from(&[1u8, 2, 3, 4][..]);
+    assert!(tag.ct_eq(&[1, 2, 3, 4]));
+    assert!(!tag.ct_eq(&[1, 2, 3, 5]));
+    assert!(!tag.ct_eq(&[1, 2, 3]));
+}
+
+#[cfg(not(feature = "no-panic"))]
+#[test]
+fn test_array_arena_bump_and_reset() {
+    use crate::arena::ArrayArena;
+
+    let mut arena = ArrayArena::new(256).unwrap();
+    {
+        let mut bytes = arena.alloc_array::<u8>(3).unwrap();
+        let words = arena.alloc_zeroed::<u64>(4).unwrap();
+        bytes[2] = 2;
+        assert_eq!(words.ptr() as usize % 8, 0);
+        assert_eq!(words[3], 0);
+        assert!(arena.alloc_array::<u64>(64).is_err());
+        drop(words);
+        assert!(arena.used() <= 8);
+    }
+    arena.reset();
+    assert_eq!(arena.remaining(), 256);
+}
+
+#[test]
+fn test_array_pool_recycles() {
+    use crate::pool::ArrayPool;
+
+    let pool = ArrayPool::<u32>::new();
+    let first = pool.acquire(5).unwrap();
+    assert_eq!(first.len(), 8);
+    let address = first.ptr();
+    pool.release(first);
+    assert_eq!(pool.cached(), 1);
+
+    {
+        let mut pooled = pool.acquire_pooled(7).unwrap();
+        assert_eq!(pooled.len(), 7);
+        assert_eq!(pooled.as_ptr(), address);
+        pooled[6] = 6;
+        assert_eq!(pool.cached(), 0);
+    }
+    assert_eq!(pool.cached(), 1);
+    assert_eq!(pool.acquire_pooled(9).unwrap().detach().len(), 16);
+}
+
+#[cfg(feature = "stats")]
+#[test]
+fn test_stats_track_live_bytes() {
+    use crate::stats;
+
+    let runt = ArrayCStyle::<u64>::zeroed(1 << 16).unwrap();
+    let during = stats::stats();
+    assert!(during.live_arrays >= 1);
+    assert!(during.peak_bytes >= 8 << 16);
+
+    #[cfg(feature = "stats-callsite")]
+    assert!(stats::call_sites().iter().any(|site| site
+        .location
+        .file()
+        .ends_with("test.rs")
+        && site.live_bytes >= 8 << 16));
+    drop(runt);
+}
+
+#[test]
+fn test_oom_handler_retry() {
+    use crate::allocator::{clear_oom_handler, set_oom_handler, OomAction};
+
+    thread_local! {
+        static ATTEMPTS: Cell<usize> = const { Cell::new(0) };
+    }
+
+    struct FlakyAlloc;
+
+    unsafe impl RawAllocator for FlakyAlloc {
+        fn allocate(&self, layout: Layout) -> Result<NonNull<u8>, Error> {
+            match ATTEMPTS.with(Cell::get) {
+                0 | 1 => Err(Error::Other("out of memory".to_string())),
+                _ => Global.allocate(layout),
+            }
+        }
+
+        unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+            unsafe { Global.deallocate(ptr, layout) }
+        }
+    }
+
+    // other tests may fail allocations concurrently; only act on our thread
+    set_oom_handler(|info| {
+        ATTEMPTS.with(|attempts| attempts.set(info.attempt));
+        if info.type_name == "u128" && info.attempt < 5 {
+            OomAction::Retry
+        } else {
+            OomAction::Fail
+        }
+    });
+    let runt = ArrayCStyle::<u128, _>::new_in(4, FlakyAlloc);
+    clear_oom_handler();
+
+    assert!(runt.is_ok());
+    assert_eq!(ATTEMPTS.with(Cell::get), 2);
+}
+
+#[cfg(not(feature = "no-panic"))]
+#[test]
+fn test_array_try_grow() {
+    let mut runt = ArrayCStyle::<u32>::zeroed(3).unwrap();
+    runt[2] = 2;
+    runt.try_grow(5).unwrap();
+    assert_eq!(runt.len(), 8);
+    assert_eq!(runt[2], 2);
+
+    let error = runt.try_grow(usize::MAX).unwrap_err();
+    assert_eq!(error.requested, None);
+    assert_eq!(error.current, 8);
+    assert_eq!(runt[2], 2);
+
+    let mut empty = ArrayCStyle::<u16>::new(0).unwrap();
+    empty.try_grow(2).unwrap();
+    empty[1] = 1;
+    assert_eq!(empty[1], 1);
+}
+
+#[cfg(not(feature = "no-panic"))]
+#[test]
+fn test_array_raw_parts_roundtrip() {
+    let mut runt = ArrayCStyle::<u32>::zeroed(4).unwrap();
+    runt[1] = 11;
+    let (ptr, len) = runt.into_raw_parts();
+    assert_eq!(len, 4);
+    unsafe { ptr.add(2).write(22) };
+
+    let runt = unsafe { ArrayCStyle::from_raw_parts(ptr, len) };
+    assert_eq!(runt[1], 11);
+    assert_eq!(runt[2], 22);
+
+    let aligned = ArrayCStyle::<u8>::zeroed_aligned(3, 64).unwrap();
+    let (ptr, len) = aligned.into_raw_parts();
+    let aligned = unsafe { ArrayCStyle::from_raw_parts_aligned(ptr, len, 64) };
+    assert_eq!(aligned.align(), 64);
+}
+
+#[cfg(not(feature = "no-panic"))]
+#[test]
+fn test_array_leak() {
+    let table: &'static mut [u32] = (0..4u32)
+        .map(|value| value * value)
+        .collect::<ArrayCStyle<_>>()
+        .leak();
+    table[0] = 100;
+    assert_eq!(table, [100, 1, 4, 9]);
+}
+
+#[cfg(not(feature = "no-panic"))]
+#[test]
+fn test_small_array_spills_to_heap() {
+    use crate::small_array::SmallRuntimeArray;
+
+    let mut runt = SmallRuntimeArray::<u32, 4>::zeroed(3).unwrap();
+    runt[2] = 2;
+    assert!(runt.is_inline());
+    runt.try_grow(3).unwrap();
+    assert!(!runt.is_inline());
+    assert_eq!(runt.len(), 6);
+    assert_eq!(runt[2], 2);
+
+    let small: SmallRuntimeArray<u8, 4> = (1..=3).collect();
+    assert!(small.is_inline());
+    let large: SmallRuntimeArray<u8, 4> = (1..=6).filter(|_| true).collect();
+    assert!(!large.is_inline());
+    assert_eq!(large.into_iter().sum::<u8>(), 21);
+}
+
+#[cfg(not(feature = "no-panic"))]
+#[test]
+fn test_cow_array_clones_on_write() {
+    use crate::cow_array::CowArray;
+
+    let source = [1, 2, 3];
+    let mut cow = CowArray::from(&source[..]);
+    assert!(cow.is_borrowed());
+    assert_eq!(cow[1], 2);
+
+    cow.to_mut()[1] = 20;
+    assert!(cow.is_owned());
+    assert_eq!(&*cow, [1, 20, 3]);
+    assert_eq!(source, [1, 2, 3]);
+}
+
+#[cfg(not(feature = "no-panic"))]
+#[test]
+fn test_arc_array_make_mut_copies_when_shared() {
+    use crate::arc_array::ArcArray;
+
+    let mut first = ArcArray::<u32>::zeroed(4).unwrap();
+    let second = first.clone();
+    assert_eq!(ArcArray::strong_count(&first), 2);
+    assert!(ArcArray::ptr_eq(&first, &second));
+
+    ArcArray::make_mut(&mut first)[0] = 7;
+    assert!(!ArcArray::ptr_eq(&first, &second));
+    assert_eq!((first[0], second[0]), (7, 0));
+
+    let reader = std::thread::spawn(move || second.iter().sum::<u32>());
+    assert_eq!(reader.join().unwrap(), 0);
+    assert_eq!(ArcArray::try_unwrap(first).unwrap()[0], 7);
+}
+
+#[test]
+fn test_atomic_array_cell_publishes_replacements() {
+    use crate::arc_array::{ArcArray, AtomicArrayCell};
+    use std::sync::Arc;
+
+    let cell =
+        Arc::new(AtomicArrayCell::new(ArcArray::<u32>::zeroed(8).unwrap()));
+    let readers: Vec<_> = (0..4)
+        .map(|_| {
+            let cell = Arc::clone(&cell);
+            std::thread::spawn(move || {
+                for _ in 0..1000 {
+                    let snapshot = cell.load();
+                    let first = snapshot[0];
+                    assert!(snapshot.iter().all(|&value| value == first));
+                }
+            })
+        })
+        .collect();
+    for version in 1..100 {
+        let mut next = ArrayCStyle::<u32>::zeroed(8).unwrap();
+        next.as_mut_slice().fill(version);
+        cell.store(ArcArray::new(next));
+    }
+    readers
+        .into_iter()
+        .for_each(|reader| reader.join().unwrap());
+
+    let current = cell.load();
+    assert_eq!(current[0], 99);
+    let stale = ArcArray::<u32>::zeroed(8).unwrap();
+    let replacement = ArcArray::<u32>::zeroed(8).unwrap();
+    let replacement = cell.compare_and_swap(&stale, replacement).unwrap_err();
+    assert!(cell.compare_and_swap(&current, replacement).is_ok());
+    assert_eq!(cell.load()[0], 0);
+}
+
+#[test]
+fn test_atomic_array_cell_writers_progress_under_load() {
+    use crate::arc_array::{ArcArray, AtomicArrayCell};
+    use std::sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc,
+    };
+
+    let cell =
+        Arc::new(AtomicArrayCell::new(ArcArray::<u32>::zeroed(4).unwrap()));
+    let stop = Arc::new(AtomicBool::new(false));
+    let loads = Arc::new(AtomicUsize::new(0));
+    // readers that reload back to back keep some load in flight almost
+    // all the time, which could keep a single shared counter off zero
+    let readers: Vec<_> = (0..8)
+        .map(|_| {
+            let (cell, stop, loads) =
+                (Arc::clone(&cell), Arc::clone(&stop), Arc::clone(&loads));
+            std::thread::spawn(move || {
+                while !stop.load(Ordering::Relaxed) {
+                    let snapshot = cell.load();
+                    assert!(snapshot.iter().all(|&value| value == snapshot[0]));
+                    loads.fetch_add(1, Ordering::Relaxed);
+                }
+            })
+        })
+        .collect();
+    while loads.load(Ordering::Relaxed) == 0 {
+        std::thread::yield_now();
+    }
+    for version in 1..=1000 {
+        let mut next = ArrayCStyle::<u32>::zeroed(4).unwrap();
+        next.as_mut_slice().fill(version);
+        let previous = cell.swap(ArcArray::new(next));
+        assert_eq!(previous[0], version - 1);
+    }
+    stop.store(true, Ordering::Relaxed);
+    readers
+        .into_iter()
+        .for_each(|reader| reader.join().unwrap());
+    assert_eq!(cell.load()[0], 1000);
+}
+
+#[test]
+fn test_atomic_array_cell_defers_release() {
+    use crate::arc_array::{ArcArray, AtomicArrayCell};
+
+    let cell = AtomicArrayCell::new(ArcArray::<u32>::zeroed(4).unwrap());
+    let first = cell.swap(ArcArray::zeroed(4).unwrap());
+    // the cell keeps a count until no load can still be reading it
+    assert_eq!(ArcArray::strong_count(&first), 2);
+    for _ in 0..3 {
+        cell.store(ArcArray::zeroed(4).unwrap());
+    }
+    assert_eq!(ArcArray::strong_count(&first), 1);
+
+    let current = cell.load();
+    let replacement = ArcArray::zeroed(4).unwrap();
+    let second = cell.compare_and_swap(&current, replacement).unwrap();
+    assert!(ArcArray::ptr_eq(&current, &second));
+    assert_eq!(ArcArray::strong_count(&second), 3);
+    let inner = cell.into_inner();
+    assert_eq!(ArcArray::strong_count(&second), 2);
+    assert_eq!(ArcArray::strong_count(&inner), 1);
+
+    // no `'static` bound: borrowed elements are released with the cell
+    let value = 5u32;
+    let cell = AtomicArrayCell::new(ArcArray::new(array([&value])));
+    let borrowed = cell.swap(ArcArray::new(array([&value, &value])));
+    assert_eq!((cell.load().len(), *borrowed.as_slice()[0]), (2, 5));
+    drop(cell);
+    assert_eq!(ArcArray::strong_count(&borrowed), 1);
+}
+
+#[cfg(all(unix, feature = "freeze"))]
+#[test]
+fn test_freeze_seals_and_thaws() {
+    let mut table = ArrayCStyle::<u32, _>::new_mmap(2048).unwrap();
+    table
+        .as_mut_slice()
+        .iter_mut()
+        .enumerate()
+        .for_each(|(i, value)| {
+            *value = i as u32;
+        });
+    let frozen = table.freeze().unwrap();
+    assert_eq!(frozen[2047], 2047);
+    assert_eq!(frozen.iter().take(3).sum::<u32>(), 3);
+
+    let mut table = frozen.thaw().unwrap();
+    table.as_mut_slice()[0] = 42;
+    assert_eq!(table.as_slice()[0], 42);
+    drop(table.freeze().unwrap());
+}
+
+#[cfg(not(feature = "no-panic"))]
+#[cfg(unix)]
+#[test]
+fn test_advise_page_aligned_arrays() {
+    use crate::os::{page_size, Advice};
+
+    let page = page_size();
+    let mut runt =
+        ArrayCStyle::<u8>::zeroed_aligned(page * 2 + 1, page).unwrap();
+    runt.as_mut_slice().fill(7);
+    runt.advise(Advice::Sequential).unwrap();
+    runt.advise(Advice::Random).unwrap();
+    runt.advise(Advice::DontNeed).unwrap();
+    // only whole pages are discarded from a heap buffer
+    assert_eq!((runt[0], runt[page * 2]), (0, 7));
+
+    let mut unaligned = ArrayCStyle::<u8>::new_aligned(page * 2, 1).unwrap();
+    if !(unaligned.ptr() as usize).is_multiple_of(page) {
+        assert!(unaligned.advise(Advice::WillNeed).is_err());
+    }
+}
+
+#[cfg(unix)]
+#[test]
+fn test_array_new_pinned_pitched() {
+    use crate::os::{page_size, PITCH_ALIGNMENT};
+
+    match ArrayCStyle::<[u8; 3], _>::new_pinned_pitched(100, 4) {
+        Ok(runt) => {
+            assert_eq!(runt.pitch() % PITCH_ALIGNMENT, 0);
+            assert_eq!(runt.pitch() % 3, 0);
+            assert_eq!(runt.len(), runt.pitch() / 3 * 4);
+            assert_eq!(runt.dma_ptr() as usize % page_size(), 0);
+            assert_eq!(runt.dma_len() % page_size(), 0);
+        }
+        // unprivileged environments may not allow locking any memory
+        Err(error) => assert!(error.to_string().contains("mlock")),
+    }
+
+    for (width, height) in
+        [(usize::MAX, 1), (usize::MAX / 3, 1), (64, usize::MAX)]
+    {
+        match ArrayCStyle::<[u8; 3], _>::new_pinned_pitched(width, height) {
+            Err(Error::Other(message)) => {
+                assert!(message.contains("overflows"))
+            }
+            other => panic!("unexpected result: {other:?}"),
+        }
+    }
+    assert!(matches!(
+        ArrayCStyle::<u64, _>::new_pinned(usize::MAX / 4),
+        Err(Error::Other(_))
+    ));
+}
+
+#[cfg(not(feature = "no-panic"))]
+#[test]
+fn test_aligned_array_type_level_alignment() {
+    fn takes_aligned(buffer: &AlignedArray<f32, 128>) -> usize {
+        buffer.ptr() as usize % AlignedArray::<f32, 128>::alignment()
+    }
+
+    let mut runt = AlignedArray::<f32, 128>::zeroed(5).unwrap();
+    runt[4] = 1.0;
+    assert_eq!(takes_aligned(&runt), 0);
+    assert_eq!(takes_aligned(&runt.clone()), 0);
+
+    let packed = ArrayCStyle::<f32>::zeroed_aligned(5, 256).unwrap();
+    assert!(AlignedArray::<f32, 128>::from_array(packed).is_ok());
+}
+
+#[test]
+fn test_retry_policy_backoff() {
+    use crate::allocator::{clear_retry_policy, set_retry_policy, RetryPolicy};
+    use std::time::Duration;
+
+    thread_local! {
+        static FAILURES_LEFT: Cell<usize> = const { Cell::new(3) };
+        static CALLBACKS: Cell<usize> = const { Cell::new(0) };
+    }
+
+    struct FlakyAlloc;
+
+    unsafe impl RawAllocator for FlakyAlloc {
+        fn allocate(&self, layout: Layout) -> Result<NonNull<u8>, Error> {
+            let left = FAILURES_LEFT.with(Cell::get);
+            if left > 0 {
+                FAILURES_LEFT.with(|failures| failures.set(left - 1));
+                return Err(Error::Other("out of memory".to_string()));
+            }
+            Global.allocate(layout)
+        }
+
+        unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+            unsafe { Global.deallocate(ptr, layout) }
+        }
+    }
+
+    let policy = RetryPolicy::new(3)
+        .with_backoff(Duration::from_millis(1), Duration::from_millis(2))
+        .on_retry(|_| CALLBACKS.with(|calls| calls.set(calls.get() + 1)));
+    assert_eq!(policy.backoff(1), Duration::from_millis(1));
+    assert_eq!(policy.backoff(3), Duration::from_millis(2));
+
+    set_retry_policy(policy);
+    let runt = ArrayCStyle::<i128, _>::zeroed_in(4, FlakyAlloc);
+    FAILURES_LEFT.with(|failures| failures.set(5));
+    let exhausted = ArrayCStyle::<i128, _>::zeroed_in(4, FlakyAlloc);
+    clear_retry_policy();
+
+    assert!(runt.is_ok());
+    assert!(exhausted.is_err());
+    // other tests may fail allocations concurrently; count only ours
+    assert_eq!(CALLBACKS.with(Cell::get), 6);
+}
+
+#[cfg(not(feature = "no-panic"))]
+#[test]
+fn test_array_vtable_allocator() {
+    use crate::allocator::{AllocVTable, VTableAllocator};
+    use std::ffi::c_void;
+
+    unsafe extern "C" fn alloc(
+        ctx: *mut c_void,
+        size: usize,
+        align: usize,
+    ) -> *mut u8 {
+        unsafe { *(ctx as *mut usize) += 1 };
+        let layout = Layout::from_size_align(size, align).unwrap();
+        unsafe { std::alloc::alloc(layout) }
+    }
+
+    unsafe extern "C" fn dealloc(
+        ctx: *mut c_void,
+        ptr: *mut u8,
+        size: usize,
+        align: usize,
+    ) {
+        unsafe { *(ctx as *mut usize) -= 1 };
+        let layout = Layout::from_size_align(size, align).unwrap();
+        unsafe { std::alloc::dealloc(ptr, layout) }
+    }
+
+    let vtable = AllocVTable {
+        alloc,
+        dealloc,
+        realloc: None,
+    };
+    let mut live = 0usize;
+    {
+        let ctx = &mut live as *mut usize as *mut c_void;
+        let mut runt = unsafe {
+            ArrayCStyle::<u64, VTableAllocator>::new_with_vtable(4, vtable, ctx)
+        }
+        .unwrap();
+        runt[3] = 3;
+        runt.try_grow(4).unwrap();
+        assert_eq!(runt[3], 3);
+        assert_eq!(unsafe { *(runt.allocator().ctx() as *const usize) }, 1);
+    }
+    assert_eq!(live, 0);
+}
+
+#[test]
+fn test_slot_array_stale_keys() {
+    use crate::slot_array::SlotArray;
+
+    let mut slots = SlotArray::with_capacity(0).unwrap();
+    let keys: Vec<_> = (0..10)
+        .map(|i| slots.insert(i.to_string()).unwrap())
+        .collect();
+    assert_eq!(slots.len(), 10);
+    assert_eq!(slots.get(keys[7]).map(String::as_str), Some("7"));
+
+    assert_eq!(slots.remove(keys[3]).as_deref(), Some("3"));
+    assert!(slots.remove(keys[3]).is_none());
+    let reused = slots.insert("new".to_string()).unwrap();
+    assert_eq!(reused.index(), keys[3].index());
+    assert!(slots.get(keys[3]).is_none());
+    assert_eq!(slots.get(reused).map(String::as_str), Some("new"));
+    assert_eq!(slots.iter().count(), 10);
+}
+
+#[cfg(not(feature = "no-panic"))]
+#[test]
+fn test_free_list_array_reuses_slots() {
+    use crate::free_list::FreeListArray;
+
+    let mut pool = FreeListArray::with_capacity(3).unwrap();
+    let a = pool.alloc_slot(vec![1]).unwrap();
+    let b = pool.alloc_slot(vec![2]).unwrap();
+    let c = pool.alloc_slot(vec![3]).unwrap();
+    assert_eq!(pool.alloc_slot(vec![4]), Err(vec![4]));
+
+    assert_eq!(pool.free_slot(b), Some(vec![2]));
+    assert_eq!(pool.free_slot(b), None);
+    assert_eq!(pool.alloc_slot(vec![5]), Ok(b));
+    pool[c].push(30);
+    assert_eq!(pool.get(c), Some(&vec![3, 30]));
+    assert_eq!(
+        pool.iter().map(|(index, _)| index).collect::<Vec<_>>(),
+        [a, b, c]
+    );
+}
+
+#[test]
+fn test_stack_alloc_nested_frames() {
+    use crate::stack_alloc::StackAlloc;
+
+    let mut stack = StackAlloc::new(256).unwrap();
+    {
+        let frame = stack.push_frame();
+        let mut outer = frame.alloc_zeroed::<u32>(8).unwrap();
+        let used = frame.used();
+        {
+            let nested = frame.push_frame().unwrap();
+            assert!(frame.alloc_array::<u8>(1).is_err());
+            assert!(frame.push_frame().is_err());
+            drop(nested.push_frame().unwrap());
+            let inner = nested.alloc_zeroed::<u64>(4).unwrap();
+            assert_eq!(inner.get(3), Some(&0));
+            assert!(nested.alloc_array::<u8>(1024).is_err());
+        }
+        assert_eq!(frame.used(), used);
+        *outer.get_mut(7).unwrap() = 7;
+        assert_eq!(outer.get(7), Some(&7));
+    }
+    assert_eq!((stack.used(), stack.depth()), (0, 0));
+}
+
+#[test]
+fn test_memory_usage_reports_slack() {
+    use crate::slot_array::SlotArray;
+    use crate::usage::MemoryUsage;
+
+    let runt = ArrayCStyle::<u32>::new_aligned(3, 16).unwrap();
+    assert_eq!(runt.element_size(), 4);
+    assert_eq!(runt.allocated_bytes(), 12);
+    assert_eq!(runt.memory_usage().slack_bytes(), 0);
+
+    let mut slots = SlotArray::<u64>::with_capacity(8).unwrap();
+    slots.insert(1).unwrap();
+    let usage = slots.memory_usage();
+    assert!(usage.slack_bytes() > 0);
+
+    let total: MemoryUsage = [runt.memory_usage(), usage].into_iter().sum();
+    assert_eq!(total.allocated_bytes, 12 + usage.allocated_bytes);
+}
+
+#[cfg(all(debug_assertions, feature = "shadow"))]
+#[test]
+fn test_shadow_detects_uninitialized_read() {
+    let mut runt = ArrayCStyle::<u32>::new(100).unwrap();
+    *runt.get_mut(70).unwrap() = 7;
+    assert_eq!(runt.get(70).copied().unwrap(), 7);
+    let result = std::panic::catch_unwind(|| runt.get(3).copied());
+    let message = result.unwrap_err();
+    assert_eq!(
+        message.downcast_ref::<String>().map(String::as_str),
+        Some("Read of uninitialized element at index 3")
+    );
+
+    runt.try_grow(30).unwrap();
+    *runt.get_mut(129).unwrap() = 1;
+    assert!(std::panic::catch_unwind(|| runt.get(128).copied()).is_err());
+    assert_eq!(ArrayCStyle::<u8>::zeroed(4).unwrap().get(3), Some(&0));
+}
+
+#[test]
+fn test_error_display_and_source() {
+    use std::error::Error as _;
+
+    fn allocate() -> Result<ArrayCStyle<u64>, Box<dyn std::error::Error>> {
+        Ok(ArrayCStyle::new(usize::MAX)?)
+    }
+
+    let error = allocate().unwrap_err();
+    assert!(error.to_string().starts_with("invalid array layout"));
+    assert!(error.source().is_some());
+
+    let grow = ArrayCStyle::<u8>::new(1).unwrap().try_grow(usize::MAX);
+    let error = Error::from(grow.unwrap_err());
+    assert_eq!(error.source().unwrap().to_string(), error.to_string());
+}
+
+#[test]
+fn test_alloc_error_is_copy() {
+    use crate::error::AllocError;
+
+    #[derive(Debug)]
+    struct ExhaustedAlloc;
+
+    unsafe impl RawAllocator for ExhaustedAlloc {
+        fn allocate(&self, layout: Layout) -> Result<NonNull<u8>, Error> {
+            Err(AllocError::new(layout).into())
+        }
+
+        unsafe fn deallocate(&self, _ptr: NonNull<u8>, _layout: Layout) {}
+    }
+
+    match ArrayCStyle::<u32, _>::new_aligned_in(8, 16, ExhaustedAlloc) {
+        Err(Error::Alloc(error)) => {
+            let copy = error;
+            assert_eq!((copy.size, copy.align), (32, 16));
+            assert_eq!(
+                error.to_string(),
+                "memory allocation of 32 bytes aligned to 16 failed"
+            );
+        }
+        other => panic!("unexpected result: {other:?}"),
+    }
+}
+
+#[test]
+fn test_array_at_reports_bounds() {
+    let mut runt = ArrayCStyle::<u16>::zeroed(3).unwrap();
+    *runt.at_mut(2).unwrap() = 5;
+    assert_eq!(*runt.at(2).unwrap(), 5);
+    match runt.at(3) {
+        Err(Error::IndexOutOfBounds { index, len }) => {
+            assert_eq!((index, len), (3, 3))
+        }
+        other => panic!("unexpected result: {other:?}"),
+    }
+}
+
+#[test]
+fn test_error_codes_and_last_error() {
+    use crate::error::{
+        clear_last_error, last_error_code, last_error_message, set_last_error,
+        CODE_INDEX_OUT_OF_BOUNDS, CODE_OK,
+    };
+
+    let runt = ArrayCStyle::<u8>::zeroed(1).unwrap();
+    let error = runt.at(4).unwrap_err();
+    assert_eq!(set_last_error(&error), CODE_INDEX_OUT_OF_BOUNDS);
+    assert_eq!(last_error_code(), CODE_INDEX_OUT_OF_BOUNDS);
+    assert_eq!(
+        last_error_message().unwrap().to_str().unwrap(),
+        "index 4 is out of bounds for an array of length 1"
+    );
+    // the record is per thread
+    std::thread::spawn(|| assert_eq!(last_error_code(), CODE_OK))
+        .join()
+        .unwrap();
+
+    clear_last_error();
+    assert_eq!(last_error_code(), CODE_OK);
+    assert!(last_error_message().is_none());
+}
+
+#[cfg(not(feature = "no-panic"))]
+#[test]
+fn test_fallible_constructors() {
+    use crate::arc_array::ArcArray;
+    use crate::cow_array::CowArray;
+
+    let runt = ArrayCStyle::try_from_slice(&[1u8, 2, 3]).unwrap();
+    assert_eq!(runt.try_clone().unwrap().as_slice(), [1, 2, 3]);
+
+    let collected = ArrayCStyle::try_from_iter_in(
+        (0..4).filter(|value| value % 2 == 0),
+        Global,
+    );
+    assert!(collected.is_err());
+
+    let mut shared = ArcArray::new(runt);
+    let _other = shared.clone();
+    ArcArray::try_make_mut(&mut shared).unwrap()[0] = 9;
+    assert_eq!(shared[0], 9);
+
+    let mut cow = CowArray::from(&[4u16, 5][..]);
+    cow.try_to_mut().unwrap()[1] = 6;
+    assert_eq!(cow.try_into_owned().unwrap().as_slice(), [4, 6]);
+}
+
+#[test]
+fn test_error_io_interop() {
+    use std::io;
+
+    fn read_header() -> io::Result<u8> {
+        let runt = ArrayCStyle::<u8>::zeroed(2).unwrap();
+        Ok(*runt.at(5)?)
+    }
+    let error = read_header().unwrap_err();
+    assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+    assert!(error.to_string().contains("index 5 is out of bounds"));
+
+    let error = Error::from(io::Error::from(io::ErrorKind::NotFound));
+    assert_eq!(error.code(), crate::error::CODE_IO);
+    assert_eq!(io::Error::from(error).kind(), io::ErrorKind::NotFound);
+
+    #[cfg(unix)]
+    {
+        let missing = unsafe {
+            crate::mmap_array::MmapArray::<u8>::open("/nonexistent/x")
+        };
+        match missing {
+            Err(Error::Io(error)) => {
+                assert_eq!(error.kind(), io::ErrorKind::NotFound)
+            }
+            other => panic!("unexpected result: {other:?}"),
+        }
+    }
+}
+
+#[test]
+fn test_error_categories() {
+    use crate::error::{AllocationError, AllocationResult, ErrorCategory};
+
+    fn reserve(len: usize) -> AllocationResult<ArrayCStyle<u64>> {
+        ArrayCStyle::new(len).map_err(|error| {
+            AllocationError::try_from(error).expect("allocation failure")
+        })
+    }
+
+    let error = reserve(usize::MAX).unwrap_err();
+    assert!(matches!(error, AllocationError::Layout(_)));
+    let error = Error::from(error);
+    assert_eq!(error.category(), ErrorCategory::Allocation);
+
+    let error = ArrayCStyle::<u8>::zeroed(1).unwrap().at(1).unwrap_err();
+    assert_eq!(error.category(), ErrorCategory::Access);
+    assert!(AllocationError::try_from(error).is_err());
+}
+
+#[test]
+fn test_subsystem_errors() {
+    use crate::error::{
+        last_error_code, last_error_message, set_last_error, AllocationError,
+        ContainerError, ContainerResult, FfiError, FfiResult, IoError,
+        CODE_INDEX_OUT_OF_BOUNDS, CODE_IO,
+    };
+    use std::io;
+
+    fn element(array: &ArrayCStyle<u8>, index: usize) -> ContainerResult<u8> {
+        array.at(index).copied().map_err(|error| {
+            ContainerError::try_from(error).expect("container failure")
+        })
+    }
+
+    let array = ArrayCStyle::<u8>::zeroed(2).unwrap();
+    assert_eq!(element(&array, 1), Ok(0));
+    let error = element(&array, 2).unwrap_err();
+    assert_eq!(error, ContainerError::IndexOutOfBounds { index: 2, len: 2 });
+    assert_eq!(
+        error.to_string(),
+        "index 2 is out of bounds for an array of length 2"
+    );
+    assert!(matches!(
+        Error::from(error),
+        Error::IndexOutOfBounds { index: 2, len: 2 }
+    ));
+    // allocation failures belong to both the container and I/O subsystems
+    let error = ArrayCStyle::<u64>::new(usize::MAX).unwrap_err();
+    let error = ContainerError::try_from(error).unwrap();
+    assert!(matches!(
+        error,
+        ContainerError::Allocation(AllocationError::Layout(_))
+    ));
+    let error = Error::from(error);
+    assert!(matches!(IoError::try_from(error), Ok(IoError::Layout(_))));
+
+    #[cfg(unix)]
+    {
+        use crate::error::IoResult;
+
+        let opened: IoResult<crate::mmap_array::MmapArray<u8>> =
+            unsafe { crate::mmap_array::MmapArray::open("/nonexistent/x") }
+                .map_err(|error| {
+                    IoError::try_from(error).expect("I/O failure")
+                });
+        match opened {
+            Err(IoError::Io(error)) => {
+                assert_eq!(error.kind(), io::ErrorKind::NotFound);
+                let error = io::Error::from(IoError::Io(error));
+                assert_eq!(error.kind(), io::ErrorKind::NotFound);
+            }
+            other => panic!("unexpected result: {other:?}"),
+        }
+    }
+    let error = Error::Other("no".into());
+    let error = ContainerError::try_from(error).unwrap_err();
+    assert!(IoError::try_from(error).is_err());
+
+    fn entry_point(index: usize) -> FfiResult<u8> {
+        let array = ArrayCStyle::<u8>::zeroed(1)?;
+        Ok(*array.at(index)?)
+    }
+    assert_eq!(entry_point(0), Ok(0));
+    let error = entry_point(3).unwrap_err();
+    assert_eq!(error.code(), CODE_INDEX_OUT_OF_BOUNDS);
+    let recorded = Error::IndexOutOfBounds { index: 3, len: 1 };
+    assert_eq!(set_last_error(&recorded), error.code());
+    assert_eq!(last_error_message().as_deref(), Some(error.message()));
+
+    let error = FfiError::from(Error::Io(io::Error::other("bad\0byte")));
+    assert_eq!(error.code(), CODE_IO);
+    assert_eq!(error.message().to_str().unwrap(), "bad byte");
+    assert_eq!(
+        set_last_error(&Error::Io(io::ErrorKind::Other.into())),
+        CODE_IO
+    );
+    assert_eq!(last_error_code(), CODE_IO);
+}
+
+#[test]
+fn test_array_send_sync() {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<ArrayCStyle<u64>>();
+
+    let mut runt = ArrayCStyle::<u64>::zeroed(4).unwrap();
+    std::thread::scope(|scope| {
+        let (left, right) = runt.as_mut_slice().split_at_mut(2);
+        scope.spawn(|| left.fill(1));
+        scope.spawn(|| right.fill(2));
+    });
+    let runt = std::thread::spawn(move || runt).join().unwrap();
+    assert_eq!(runt.as_slice(), [1, 1, 2, 2]);
+
+    let shared = &runt;
+    std::thread::scope(|scope| {
+        scope.spawn(|| assert_eq!(shared.as_slice().iter().sum::<u64>(), 6));
+    });
+}
+
+#[cfg(not(feature = "no-panic"))]
+#[test]
+fn test_atomic_array_shared_counters() {
+    use crate::atomic_array::AtomicArray;
+    use std::sync::atomic::Ordering;
+
+    let histogram = AtomicArray::<u64>::zeroed(4).unwrap();
+    std::thread::scope(|scope| {
+        for _ in 0..4 {
+            scope.spawn(|| {
+                for bucket in 0..400 {
+                    histogram.fetch_add(bucket % 4, 1, Ordering::Relaxed);
+                }
+            });
+        }
+    });
+    let counts = histogram.load_all(Ordering::Relaxed).unwrap();
+    assert_eq!(counts.as_slice(), [400; 4]);
+
+    let flags = AtomicArray::<i32>::from_slice(&[0, 7]).unwrap();
+    let swapped =
+        flags.compare_exchange(1, 7, -1, Ordering::AcqRel, Ordering::Acquire);
+    assert_eq!(swapped, Ok(7));
+    assert_eq!(flags.load(1, Ordering::Acquire), -1);
+    assert!(flags.get(2).is_none());
+}
+
+#[test]
+#[cfg(feature = "rayon")]
+fn test_array_rayon_iterators() {
+    use rayon::prelude::*;
+
+    let mut runt = ArrayCStyle::<u64>::zeroed(10_000).unwrap();
+    runt.par_iter_mut()
+        .enumerate()
+        .for_each(|(index, value)| *value = index as u64);
+    assert_eq!(runt.par_iter().sum::<u64>(), 49_995_000);
+
+    let doubled: Vec<u64> =
+        runt.into_par_iter().map(|value| value * 2).collect();
+    assert_eq!(doubled.len(), 10_000);
+    assert_eq!(doubled[9_999], 19_998);
+}
+
+#[test]
+#[cfg(feature = "rayon")]
+fn test_array_par_fill_and_map() {
+    let mut runt = ArrayCStyle::<u32>::new(100_003).unwrap();
+    runt.par_fill(3);
+    runt.par_map_in_place(|value| value * 7);
+    assert!(runt.as_slice().iter().all(|&value| value == 21));
+
+    let mut empty = ArrayCStyle::<u32>::new(0).unwrap();
+    empty.par_fill(1);
+    assert!(empty.is_empty());
+}
+
+#[test]
+#[cfg(feature = "rayon")]
+fn test_array_par_scan() {
+    let mut runt = ArrayCStyle::<u64>::new(100_003).unwrap();
+    runt.par_fill(1);
+    runt.par_scan();
+    assert!(runt
+        .as_slice()
+        .iter()
+        .enumerate()
+        .all(|(index, &sum)| sum == index as u64 + 1));
+
+    runt.par_fill(2);
+    assert_eq!(runt.par_scan_exclusive(), 200_006);
+    assert_eq!(runt.as_slice()[0], 0);
+    assert_eq!(runt.as_slice()[100_002], 200_004);
+
+    let mut empty = ArrayCStyle::<u32>::new(0).unwrap();
+    assert_eq!(empty.par_scan_exclusive(), 0);
+}
+
+#[test]
+#[cfg(feature = "async")]
+fn test_array_chunks_stream() {
+    use futures_core::{stream::FusedStream, Stream};
+    use std::{
+        pin::Pin,
+        task::{Context, Poll, Waker},
+    };
+
+    let runt: ArrayCStyle<u16> = array(0..10);
+    let mut chunks = runt.chunks_stream(4);
+    assert_eq!(chunks.size_hint(), (3, Some(3)));
+
+    let mut cx = Context::from_waker(Waker::noop());
+    let mut lens = Vec::new();
+    let mut values = Vec::new();
+    while let Poll::Ready(Some(chunk)) =
+        Pin::new(&mut chunks).poll_next(&mut cx)
+    {
+        let chunk = chunk.unwrap();
+        lens.push(chunk.len());
+        values.extend_from_slice(chunk.as_slice());
+    }
+    assert_eq!(lens, [4, 4, 2]);
+    assert_eq!(values, (0..10).collect::<Vec<u16>>());
+    assert!(chunks.is_terminated());
+}
+
+#[test]
+#[cfg(feature = "tokio")]
+fn test_array_cursor_async_io() {
+    use crate::cursor::ArrayCursor;
+    use std::{
+        pin::Pin,
+        task::{Context, Poll, Waker},
+    };
+    use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+    let mut cx = Context::from_waker(Waker::noop());
+    let mut cursor = ArrayCursor::new(ArrayCStyle::<u8>::zeroed(8).unwrap());
+    let written = Pin::new(&mut cursor).poll_write(&mut cx, b"hello world");
+    assert!(matches!(written, Poll::Ready(Ok(8))));
+    let written = Pin::new(&mut cursor).poll_write(&mut cx, b"!");
+    assert!(matches!(written, Poll::Ready(Ok(0))));
+
+    cursor.set_position(6);
+    let mut storage = [0; 4];
+    let mut buf = ReadBuf::new(&mut storage);
+    let read = Pin::new(&mut cursor).poll_read(&mut cx, &mut buf);
+    assert!(matches!(read, Poll::Ready(Ok(()))));
+    assert_eq!(buf.filled(), b"wo");
+    assert_eq!(cursor.position(), 8);
+    assert_eq!(cursor.into_inner().as_slice(), b"hello wo");
+}
+
+#[test]
+fn test_array_par_reductions() {
+    let runt: ArrayCStyle<u64> = array((0..100_003).map(|n| n ^ 0x55));
+    assert_eq!(runt.par_sum(), runt.as_slice().iter().sum::<u64>());
+    assert_eq!(runt.par_min(), runt.as_slice().iter().copied().min());
+    assert_eq!(runt.par_max(), runt.as_slice().iter().copied().max());
+    let xor = runt.as_slice().iter().fold(0, |a, b| a ^ b);
+    assert_eq!(runt.par_reduce(0, |a, b| a ^ b), xor);
+
+    let empty = ArrayCStyle::<i32>::new(0).unwrap();
+    assert_eq!(empty.par_sum(), 0);
+    assert_eq!(empty.par_min(), None);
+    assert_eq!(empty.par_max(), None);
+}
+
+#[test]
+fn test_array_split_chunks_mut() {
+    let mut runt = ArrayCStyle::<usize>::zeroed(10).unwrap();
+    let lens: Vec<usize> =
+        runt.split_chunks_mut(4).map(|chunk| chunk.len()).collect();
+    assert_eq!(lens, [3, 3, 2, 2]);
+
+    std::thread::scope(|scope| {
+        for (worker, chunk) in runt.split_chunks_mut(3).enumerate() {
+            scope.spawn(move || chunk.fill(worker));
+        }
+    });
+    assert_eq!(runt.as_slice(), [0, 0, 0, 0, 1, 1, 1, 2, 2, 2]);
+    assert_eq!(runt.split_chunks_mut(0).count(), 0);
+    assert_eq!(
+        runt.split_chunks_mut(12).filter(|c| c.is_empty()).count(),
+        2
+    );
+}
+
+#[test]
+fn test_sharded_array_concurrent_writes() {
+    use crate::sharded_array::ShardedArray;
+
+    let counters = ShardedArray::<u64>::zeroed(100, 8).unwrap();
+    assert_eq!((counters.shards(), counters.shard_of(17)), (8, 1));
+    std::thread::scope(|scope| {
+        for _ in 0..4 {
+            scope.spawn(|| {
+                for index in 0..1000 {
+                    counters.with(index % 100, |count| *count += 1).unwrap();
+                }
+            });
+        }
+    });
+    assert!(counters.with(100, |_| ()).is_none());
+
+    let runt = counters.into_array();
+    assert!(runt.as_slice().iter().all(|&count| count == 40));
+}
+
+#[test]
+fn test_sync_array_guards() {
+    use crate::sync_array::SyncArray;
+
+    let shared = SyncArray::<u32>::zeroed(8).unwrap();
+    let writer = shared.clone();
+    std::thread::spawn(move || writer.write().fill(5))
+        .join()
+        .unwrap();
+    assert_eq!(shared.read().iter().sum::<u32>(), 40);
+
+    let reader = shared.read();
+    assert!(shared.try_read().is_some());
+    assert!(shared.try_write().is_none());
+    drop(reader);
+
+    assert_eq!(shared.handle_count(), 1);
+    assert_eq!(shared.try_unwrap().unwrap().as_slice(), [5; 8]);
+}
+
+#[test]
+fn test_seqlock_array_untorn_reads() {
+    use crate::seqlock_array::SeqLockArray;
+
+    let pairs = SeqLockArray::<(u64, u64)>::from_slice(&[(0, 0); 2]).unwrap();
+    std::thread::scope(|scope| {
+        scope.spawn(|| {
+            for value in 1..=10_000 {
+                pairs.store(1, (value, value));
+            }
+        });
+        for _ in 0..2 {
+            scope.spawn(|| {
+                for _ in 0..10_000 {
+                    let (left, right) = pairs.load(1).unwrap();
+                    assert_eq!(left, right);
+                }
+            });
+        }
+    });
+    assert_eq!(pairs.load(1), Some((10_000, 10_000)));
+    assert_eq!(pairs.version(1), Some(10_000));
+    assert!(pairs.update(0, |(left, right)| (left + 1, right)));
+    assert_eq!(pairs.load(0), Some((1, 0)));
+    assert!(!pairs.store(2, (0, 0)));
+}
+
+#[test]
+fn test_spsc_channel() {
+    use crate::queues::spsc;
+    use std::rc::Rc;
+
+    let (mut tx, mut rx) = spsc::channel::<u32>(4).unwrap();
+    let consumer = std::thread::spawn(move || {
+        let mut expected = 0;
+        while expected < 10_000 {
+            match rx.pop() {
+                Some(value) => {
+                    assert_eq!(value, expected);
+                    expected += 1;
+                }
+                None => std::thread::yield_now(),
+            }
+        }
+        rx
+    });
+    for value in 0..10_000 {
+        let mut value = value;
+        while let Err(rejected) = tx.push(value) {
+            value = rejected;
+            std::thread::yield_now();
+        }
+    }
+    let rx = consumer.join().unwrap();
+    assert!(rx.is_empty());
+
+    // values still queued are dropped with the channel
+    let tracked = Rc::new(());
+    let (mut tx, rx) = spsc::channel(2).unwrap();
+    tx.push(Rc::clone(&tracked)).unwrap();
+    tx.push(Rc::clone(&tracked)).unwrap();
+    assert!(tx.push(Rc::clone(&tracked)).is_err());
+    drop((tx, rx));
+    assert_eq!(Rc::strong_count(&tracked), 1);
+}
+
+#[test]
+fn test_mpmc_queue() {
+    use crate::queues::mpmc::Queue;
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    let queue = Queue::<u64>::new(8).unwrap();
+    let total = AtomicU64::new(0);
+    std::thread::scope(|scope| {
+        for producer in 0..2 {
+            let queue = &queue;
+            scope.spawn(move || {
+                for value in 1..=500 {
+                    let mut value = value + producer * 1000;
+                    while let Err(rejected) = queue.push(value) {
+                        value = rejected;
+                        std::thread::yield_now();
+                    }
+                }
+            });
+        }
+        for _ in 0..2 {
+            scope.spawn(|| {
+                for _ in 0..500 {
+                    loop {
+                        if let Some(value) = queue.pop() {
+                            total.fetch_add(value, Ordering::Relaxed);
+                            break;
+                        }
+                        std::thread::yield_now();
+                    }
+                }
+            });
+        }
+    });
+    assert_eq!(total.into_inner(), 2 * 125_250 + 500_000);
+    assert!(queue.is_empty());
+
+    for value in 0..8 {
+        queue.push(value).unwrap();
+    }
+    assert!(queue.is_full());
+    assert_eq!(queue.push(8), Err(8));
+    assert_eq!(queue.pop(), Some(0));
+    assert!(Queue::<u8>::new(0).unwrap().push(1).is_err());
+}
+
+#[cfg(not(feature = "no-panic"))]
+#[test]
+fn test_double_buffer_swap() {
+    use crate::double_buffer::DoubleBuffer;
+
+    let frames = DoubleBuffer::<u32>::zeroed(64).unwrap();
+    std::thread::scope(|scope| {
+        scope.spawn(|| {
+            for frame in 1..=50 {
+                let mut back = frames.back_mut().unwrap();
+                back.fill(frame);
+                back.publish();
+            }
+        });
+        scope.spawn(|| {
+            for _ in 0..200 {
+                let front = frames.front();
+                assert!(front.iter().all(|&value| value == front[0]));
+            }
+        });
+    });
+    assert!(frames.front().iter().all(|&value| value == 50));
+
+    let back = frames.back_mut().unwrap();
+    assert!(frames.back_mut().is_none());
+    assert!(!frames.swap());
+    drop(back);
+    assert!(frames.swap());
+    let (front, _) = frames.into_arrays();
+    assert_eq!(front[0], 49);
+
+    let halves = (ArrayCStyle::<u8>::zeroed(1), ArrayCStyle::zeroed(2));
+    assert!(DoubleBuffer::new(halves.0.unwrap(), halves.1.unwrap()).is_err());
+}
+
+#[test]
+fn test_versioned_array_snapshots() {
+    use crate::versioned_array::VersionedArray;
+
+    let prices =
+        VersionedArray::new(ArrayCStyle::try_from_slice(&[10u32, 20]).unwrap());
+    let before = prices.snapshot();
+
+    let mut pending = prices.begin().unwrap();
+    pending[0] = 11;
+    assert_eq!(prices.snapshot().as_slice(), [10, 20]);
+    assert_eq!(pending.commit(), 1);
+
+    assert_eq!(prices.update(|values| values[1] = 21).unwrap(), 2);
+    assert_eq!(prices.snapshot().as_slice(), [11, 21]);
+    assert_eq!(before.as_slice(), [10, 20]);
+
+    // dropping an update discards it
+    prices.begin().unwrap()[0] = 0;
+    assert_eq!((prices.version(), prices.snapshot()[0]), (2, 11));
+}
+
+#[test]
+fn test_with_scratch_reuses_buffers() {
+    use crate::scratch::{scratch_memory_usage, with_scratch};
+
+    let first = with_scratch::<u32, _>(100, |ids| {
+        ids.fill(7);
+        (ids.as_ptr() as usize, ids.iter().sum::<u32>())
+    })
+    .unwrap();
+    assert_eq!(first.1, 700);
+    assert!(scratch_memory_usage().allocated_bytes >= 400);
+
+    // same byte size, different element type: the buffer is reused
+    let second =
+        with_scratch::<u64, _>(50, |words| words.as_ptr() as usize).unwrap();
+    assert_eq!(first.0, second);
+
+    let nested = with_scratch::<u8, _>(16, |outer| {
+        with_scratch::<u8, _>(16, |inner| outer.as_ptr() != inner.as_ptr())
+    });
+    assert!(nested.unwrap().unwrap());
+}
+
+#[test]
+fn test_bitmap_allocator() {
+    use crate::bitmap_alloc::BitmapAllocator;
+    use std::sync::Mutex;
+
+    let slots = BitmapAllocator::new(100).unwrap();
+    let taken = Mutex::new(Vec::new());
+    std::thread::scope(|scope| {
+        for _ in 0..4 {
+            scope.spawn(|| {
+                for _ in 0..25 {
+                    let slot = slots.acquire().unwrap();
+                    taken.lock().unwrap().push(slot);
+                }
+            });
+        }
+    });
+    let mut taken = taken.into_inner().unwrap();
+    taken.sort_unstable();
+    assert_eq!(taken, (0..100).collect::<Vec<_>>());
+    assert_eq!(slots.acquire(), None);
+    assert_eq!(slots.acquired(), 100);
+
+    assert!(slots.release(42));
+    assert!(!slots.release(42));
+    assert!(!slots.is_acquired(42));
+    assert_eq!(slots.acquire(), Some(42));
+    assert!(!slots.release(100));
+}
+
+#[test]
+fn test_concurrent_hash_set_dedup() {
+    use crate::concurrent_set::ConcurrentHashSet;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    let seen = ConcurrentHashSet::<String>::with_capacity(100).unwrap();
+    assert_eq!(seen.capacity(), 128);
+    let fresh = AtomicUsize::new(0);
+    std::thread::scope(|scope| {
+        for _ in 0..4 {
+            scope.spawn(|| {
+                for id in 0..50 {
+                    if seen.insert(format!("id-{id}")) == Ok(true) {
+                        fresh.fetch_add(1, Ordering::Relaxed);
+                    }
+                }
+            });
+        }
+    });
+    assert_eq!((fresh.into_inner(), seen.len()), (50, 50));
+    assert!(seen.contains("id-49"));
+    assert!(!seen.contains("id-50"));
+    assert_eq!(seen.iter().count(), 50);
+
+    let tiny = ConcurrentHashSet::<u8>::with_capacity(2).unwrap();
+    assert_eq!((tiny.insert(1), tiny.insert(2)), (Ok(true), Ok(true)));
+    assert_eq!(tiny.insert(3), Err(3));
+}
+
+#[test]
+fn test_epoch_array_grows_under_readers() {
+    use crate::epoch::{Domain, EpochArray};
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+
+    let table =
+        EpochArray::new(ArrayCStyle::try_from_slice(&[0u64, 1, 2]).unwrap());
+    let pinned = table.read();
+    table.grow(2, |index| index as u64).unwrap();
+    // the old buffer is still in use
+    assert_eq!(pinned.len(), 3);
+    assert_eq!(table.domain().pending(), 1);
+    drop(pinned);
+    table.domain().collect();
+    table.domain().collect();
+    assert_eq!(table.domain().pending(), 0);
+
+    std::thread::scope(|scope| {
+        scope.spawn(|| {
+            for _ in 0..20 {
+                table.grow(3, |index| index as u64).unwrap();
+                std::thread::yield_now();
+            }
+        });
+        scope.spawn(|| {
+            for _ in 0..100 {
+                let view = table.read();
+                assert!(view.iter().enumerate().all(|(i, &v)| v == i as u64));
+            }
+        });
+    });
+    assert_eq!(table.into_array().len(), 65);
+
+    let freed = Arc::new(AtomicBool::new(false));
+    let domain = Domain::new();
+    let guard = domain.pin();
+    let flag = Arc::clone(&freed);
+    domain.defer(move || flag.store(true, Ordering::SeqCst));
+    assert_eq!((domain.collect(), domain.collect()), (0, 0));
+    drop(guard);
+    domain.collect();
+    domain.collect();
+    assert!(freed.load(Ordering::SeqCst));
+}
+
+#[test]
+fn test_cell_array() {
+    use crate::cell_array::CellArray;
+
+    // relax every edge of a path graph while iterating over the nodes
+    let distance = CellArray::filled(5, u32::MAX).unwrap();
+    distance.set(0, 0).unwrap();
+    for node in 0..distance.len() - 1 {
+        let next = distance.get(node).unwrap() + 1;
+        if distance.get(node + 1).unwrap() > next {
+            distance.set(node + 1, next).unwrap();
+        }
+    }
+    assert_eq!(distance.into_array().as_slice(), [0, 1, 2, 3, 4]);
+
+    let mut cells = CellArray::from_slice(&[1u8, 2, 3]).unwrap();
+    assert_eq!(cells.replace(1, 9), Some(2));
+    assert_eq!(cells.replace(3, 9), None);
+    assert!(matches!(
+        cells.set(3, 0),
+        Err(Error::IndexOutOfBounds { index: 3, len: 3 })
+    ));
+    cells.as_mut_slice()[0] = 7;
+    assert_eq!(format!("{cells:?}"), "[7, 9, 3]");
+}
+
+#[cfg(not(feature = "no-panic"))]
+#[test]
+fn test_refcell_array_borrows() {
+    use crate::refcell_array::{BorrowError, RefCellArray};
+
+    let registers =
+        RefCellArray::new(ArrayCStyle::<i64>::zeroed(4).unwrap()).unwrap();
+    {
+        let mut r0 = registers.borrow_mut(0);
+        let mut r1 = registers.borrow_mut(1);
+        *r0 = 40;
+        *r1 = 2;
+        assert_eq!(
+            registers.try_borrow(0).err(),
+            Some(BorrowError::Conflict { index: 0 })
+        );
+    }
+    {
+        let lhs = registers.borrow(0);
+        let rhs = registers.borrow(1);
+        let again = registers.borrow(0);
+        assert!(registers.try_borrow_mut(0).is_err());
+        *registers.borrow_mut(2) = *lhs + *rhs + *again - 40;
+        assert_eq!(format!("{registers:?}"), "[40, 2, 42, 0]");
+    }
+    assert!(!registers.is_borrowed(0));
+    assert_eq!(
+        registers.try_borrow_mut(4).err(),
+        Some(BorrowError::OutOfBounds { index: 4, len: 4 })
+    );
+
+    let _held = registers.borrow_mut(3);
+    assert_eq!(format!("{registers:?}"), "[40, 2, 42, <borrowed>]");
+}
+
+#[cfg(not(feature = "no-panic"))]
+#[test]
+fn test_counter_array() {
+    use crate::counter_array::CounterArray;
+
+    let counters = CounterArray::zeroed(4).unwrap();
+    assert_eq!(counters.memory_usage().allocated_bytes, 4 * CACHE_LINE_SIZE);
+    std::thread::scope(|scope| {
+        for shard in 0..4 {
+            let counters = &counters;
+            scope.spawn(move || {
+                for _ in 0..1000 {
+                    counters.inc(shard);
+                }
+                counters.add(shard, shard as u64);
+            });
+        }
+    });
+    assert_eq!(
+        counters.snapshot().unwrap().as_slice(),
+        [1000, 1001, 1002, 1003]
+    );
+    assert_eq!(counters.sum(), 4006);
+    assert!(counters.get(4).is_none());
+}
+
+#[cfg(not(feature = "no-panic"))]
+#[test]
+fn test_array2d_indexing() {
+    use crate::matrix::Array2D;
+
+    let mut grid = Array2D::<u32>::zeroed(3, 4).unwrap();
+    assert_eq!(grid.shape(), (3, 4));
+    grid[(1, 2)] = 7;
+    grid.row_mut(2).unwrap().fill(1);
+    assert_eq!(grid.as_slice()[6], 7);
+    assert_eq!(grid.row(2).unwrap().as_slice(), Some(&[1, 1, 1, 1][..]));
+    assert!(grid.row(3).is_none());
+    assert_eq!(grid.get(0, 4), None);
+    assert!(matches!(
+        grid.at(3, 0),
+        Err(Error::IndexOutOfBounds { index: 3, len: 3 })
+    ));
+    assert!(matches!(
+        grid.at(0, 5),
+        Err(Error::IndexOutOfBounds { index: 5, len: 4 })
+    ));
+    *grid.at_mut(0, 0).unwrap() = 9;
+    assert_eq!(
+        format!("{grid:?}"),
+        "[[9, 0, 0, 0], [0, 0, 7, 0], [1, 1, 1, 1]]"
+    );
+
+    let flat = ArrayCStyle::<u8>::zeroed(6).unwrap();
+    assert!(Array2D::from_array(flat, 4, 2).is_err());
+    assert!(Array2D::<u8>::new(usize::MAX, 2).is_err());
+}
+
+#[cfg(not(feature = "no-panic"))]
+#[test]
+fn test_array3d_planes() {
+    use crate::matrix::Array3D;
+
+    let mut voxels = Array3D::<u16>::zeroed(2, 3, 4).unwrap();
+    for x in 0..2 {
+        for y in 0..3 {
+            for z in 0..4 {
+                voxels[(x, y, z)] = (x * 100 + y * 10 + z) as u16;
+            }
+        }
+    }
+    assert_eq!(voxels.as_slice()[(3 + 2) * 4 + 3], 123);
+    assert_eq!(
+        voxels.plane_x(1).unwrap().as_slice(),
+        Some(&voxels.as_slice()[12..])
+    );
+    let plane_y = voxels.plane_y(2).unwrap();
+    assert_eq!(plane_y.shape(), (2, 4));
+    assert_eq!(plane_y[(1, 3)], 123);
+    assert!(plane_y.as_slice().is_none());
+    let plane_z = voxels.plane_z(1).unwrap();
+    assert_eq!(
+        plane_z.iter().copied().collect::<Vec<_>>(),
+        [1, 11, 21, 101, 111, 121]
+    );
+    assert!(voxels.plane_z(4).is_none());
+    assert!(matches!(
+        voxels.at(0, 0, 4),
+        Err(Error::IndexOutOfBounds { index: 4, len: 4 })
+    ));
+
+    voxels.plane_z_mut(0).unwrap().fill(7);
+    assert_eq!(voxels[(1, 2, 0)], 7);
+    let slab = voxels.slab(1..2).unwrap();
+    assert_eq!(slab.shape(), (1, 3, 4));
+    assert_eq!(slab[(0, 2, 3)], 123);
+    assert!(voxels.slab(1..3).is_none());
+    voxels.slab_mut(0..1).unwrap()[(0, 0, 1)] = 5;
+    assert_eq!(voxels[(0, 0, 1)], 5);
+}
+
+#[cfg(not(feature = "no-panic"))]
+#[test]
+fn test_array_nd_views() {
+    use crate::matrix::ArrayND;
+
+    let mut tensor = ArrayND::<u32>::zeroed(&[2, 3, 4]).unwrap();
+    assert_eq!(tensor.strides(), [12, 4, 1]);
+    for (value, slot) in tensor.iter_mut().enumerate() {
+        *slot = value as u32;
+    }
+    assert_eq!(tensor[[1, 2, 3]], 23);
+    assert_eq!(tensor.get(&[1, 2]), None);
+    assert!(matches!(
+        tensor.at(&[0, 3, 0]),
+        Err(Error::IndexOutOfBounds { index: 3, len: 3 })
+    ));
+    assert!(matches!(tensor.at(&[0, 0]), Err(Error::Other(_))));
+
+    let view = tensor.view();
+    let column = view.index_axis(2, 1).unwrap();
+    assert_eq!(column.shape(), [2, 3]);
+    assert!(!column.is_contiguous());
+    assert_eq!(
+        column.clone().into_iter().copied().collect::<Vec<_>>(),
+        [1, 5, 9, 13, 17, 21]
+    );
+    let window = view.slice_axis(1, 1..3).unwrap().index_axis(0, 1).unwrap();
+    assert_eq!(
+        format!("{window:?}"),
+        "[[16, 17, 18, 19], [20, 21, 22, 23]]"
+    );
+    assert!(view.slice_axis(1, 2..4).is_none());
+
+    let copy = column.to_array_nd().unwrap();
+    assert_eq!(copy.as_slice(), Some(&[1, 5, 9, 13, 17, 21][..]));
+
+    tensor
+        .view_mut()
+        .index_axis(0, 0)
+        .unwrap()
+        .slice_axis(1, 0..2)
+        .unwrap()
+        .fill(0);
+    assert_eq!(tensor[[0, 2, 1]], 0);
+    assert_eq!(tensor[[0, 2, 2]], 10);
+    assert_eq!(tensor[[0, 1, 3]], 7);
+}
+
+#[cfg(not(feature = "no-panic"))]
+#[test]
+fn test_array_reshape() {
+    use crate::matrix::ArrayND;
+
+    let mut flat: ArrayCStyle<u8> = (0..24).collect();
+    let image = flat.reshape(&[2, 4, 3]).unwrap();
+    assert_eq!(image[[1, 2, 0]], 18);
+    assert_eq!(image.reshape(&[6, 4]).unwrap()[[5, 3]], 23);
+    assert!(flat.reshape(&[5, 5]).is_err());
+    flat.reshape_mut(&[4, 6]).unwrap()[[3, 0]] = 99;
+    assert_eq!(flat[18], 99);
+
+    let mut tensor = ArrayND::from_array(flat, &[2, 12]).unwrap();
+    assert_eq!(tensor.reshape(&[3, 8]).unwrap()[[2, 2]], 99);
+    tensor.reshape_mut(&[24]).unwrap()[[0]] = 7;
+    assert_eq!(tensor[[0, 0]], 7);
+    let column = tensor.view().index_axis(1, 0).unwrap();
+    assert!(column.reshape(&[2]).is_err());
+}
+
+#[cfg(not(feature = "no-panic"))]
+#[test]
+fn test_array2d_transpose() {
+    use crate::matrix::{Array2D, ArrayND};
+
+    let flat: ArrayCStyle<u32> = (0..70 * 40).collect();
+    let matrix = Array2D::from_array(flat, 70, 40).unwrap();
+    let view = matrix.t();
+    assert_eq!(view.shape(), (40, 70));
+    assert_eq!(view.strides(), (1, 40));
+    assert_eq!(view[(3, 65)], 65 * 40 + 3);
+
+    let transposed = matrix.transposed().unwrap();
+    assert_eq!(transposed.shape(), (40, 70));
+    assert!(transposed.as_view().iter().eq(view.iter()));
+    assert!(transposed.t().iter().eq(matrix.as_slice().iter()));
+
+    let mut square =
+        Array2D::from_array((0..35 * 35).collect(), 35, 35).unwrap();
+    square.transpose_square().unwrap();
+    assert_eq!(square[(34, 1)], 35 + 34);
+    square.transpose_square().unwrap();
+    assert!(square.as_slice().iter().copied().eq(0..35 * 35));
+    let mut wide = Array2D::<u8>::zeroed(2, 3).unwrap();
+    assert!(wide.transpose_square().is_err());
+
+    let tensor = ArrayND::<u8>::zeroed(&[2, 3, 4]).unwrap();
+    assert_eq!(tensor.t().shape(), [4, 3, 2]);
+    assert_eq!(tensor.t().strides(), [1, 4, 12]);
+}
+
+#[cfg(not(feature = "no-panic"))]
+#[test]
+fn test_array2d_rows_and_cols() {
+    use crate::matrix::Array2D;
+
+    let flat: ArrayCStyle<f64> = (0..12).map(f64::from).collect();
+    let mut matrix = Array2D::from_array(flat, 3, 4).unwrap();
+    assert_eq!(matrix.rows().len(), 3);
+    let last = matrix.rows().next_back().unwrap();
+    assert_eq!(last.as_slice(), Some(&[8.0, 9.0, 10.0, 11.0][..]));
+
+    for mut row in matrix.rows_mut() {
+        let max = row.as_view().iter().copied().fold(f64::MIN, f64::max);
+        row.iter_mut().for_each(|x| *x /= max);
+    }
+    assert_eq!(matrix[(0, 3)], 1.0);
+    assert_eq!(matrix[(2, 0)], 8.0 / 11.0);
+
+    let col = matrix.col(1).unwrap();
+    assert_eq!((col.len(), col.stride()), (3, 4));
+    assert!(col.as_slice().is_none());
+    assert!(matrix.col(4).is_none());
+    let sums: Vec<f64> = matrix.cols().map(|col| col.iter().sum()).collect();
+    assert_eq!(sums.len(), 4);
+    assert_eq!(sums[3], 3.0);
+
+    for (index, mut col) in matrix.cols_mut().enumerate() {
+        col.fill(index as f64);
+    }
+    assert!(matrix
+        .rows()
+        .all(|row| row.iter().eq(&[0.0, 1.0, 2.0, 3.0])));
+    matrix.col_mut(2).unwrap()[1] = -1.0;
+    assert_eq!(matrix.col(2).unwrap().to_array().unwrap()[1], -1.0);
+
+    let empty = Array2D::<u8>::zeroed(3, 0).unwrap();
+    assert!(empty.rows().all(|row| row.is_empty()));
+    assert_eq!(empty.cols().len(), 0);
+}
+
+#[cfg(not(feature = "no-panic"))]
+#[test]
+fn test_array2d_subviews() {
+    use crate::matrix::Array2D;
+
+    let flat: ArrayCStyle<u32> = (0..8 * 10).collect();
+    let mut matrix = Array2D::from_array(flat, 8, 10).unwrap();
+    let window = matrix.view(2..5, 3..7).unwrap();
+    assert_eq!(window.shape(), (3, 4));
+    assert_eq!(window[(0, 0)], 23);
+    assert_eq!(window[(2, 3)], 46);
+    assert!(!window.is_contiguous());
+    assert!(matrix.view(2..4, 0..10).unwrap().as_slice().is_some());
+    assert!(matrix.view(0..9, 0..1).is_none());
+    assert!(matrix.view(0..0, 10..10).unwrap().is_empty());
+
+    let inner = window.view(1..3, 1..2).unwrap();
+    assert!(inner.iter().copied().eq([34, 44]));
+    assert_eq!(window.t().view(0..1, 1..3).unwrap()[(0, 1)], 43);
+
+    let mut region = matrix.view_mut(4..8, 5..10).unwrap();
+    region.view_mut(0..2, 0..2).unwrap().fill(0);
+    region.into_view(3..4, 4..5).unwrap()[(0, 0)] = 1;
+    assert_eq!(matrix[(5, 6)], 0);
+    assert_eq!(matrix[(6, 7)], 67);
+    assert_eq!(matrix[(7, 9)], 1);
+}
+
+#[cfg(not(feature = "no-panic"))]
+#[test]
+fn test_array_nd_broadcasting() {
+    use crate::matrix::{broadcast_shape, ArrayND};
+
+    assert_eq!(broadcast_shape(&[4, 1, 3], &[5, 1]).unwrap(), [4, 5, 3]);
+    assert_eq!(broadcast_shape(&[], &[2, 2]).unwrap(), [2, 2]);
+    assert!(broadcast_shape(&[2, 3], &[2]).is_err());
+
+    let matrix =
+        ArrayND::from_array((0..6).map(f64::from).collect(), &[2, 3]).unwrap();
+    let row = ArrayND::from_array([10.0, 20.0, 30.0][..].into(), &[3]).unwrap();
+    let col = ArrayND::from_array([1.0, 2.0][..].into(), &[2, 1]).unwrap();
+    let two = ArrayND::scalar(2.0).unwrap();
+
+    let shifted = &matrix + &row;
+    assert!(shifted
+        .iter()
+        .copied()
+        .eq([10.0, 21.0, 32.0, 13.0, 24.0, 35.0]));
+    let scaled = &matrix * &two;
+    assert_eq!(scaled[[1, 2]], 10.0);
+    let outer = col.try_sub(&row).unwrap();
+    assert_eq!(outer.shape(), [2, 3]);
+    assert_eq!(outer[[1, 0]], -8.0);
+    assert_eq!(row.try_div(&col).unwrap()[[1, 2]], 15.0);
+    assert!(matrix.try_add(&col.t().to_array_nd().unwrap()).is_err());
+
+    let mut acc = ArrayND::<f64>::zeroed(&[2, 3]).unwrap();
+    acc += &row;
+    acc -= &col;
+    acc.try_mul_assign(&two).unwrap();
+    assert_eq!(acc[[1, 1]], 36.0);
+    assert!(acc.try_div_assign(&matrix.try_add(&row).unwrap()).is_ok());
+    assert!(col.clone().try_add_assign(&row).is_err());
+}
+
+#[cfg(not(feature = "no-panic"))]
+#[test]
+fn test_matmul() {
+    use crate::matrix::{matmul, Array2D};
+
+    let (m, k, n) = (70, 300, 90);
+    let a = Array2D::from_array(
+        (0..m * k).map(|x| (x % 7) as f64 - 3.0).collect(),
+        m,
+        k,
+    )
+    .unwrap();
+    let b =
+        Array2D::from_array((0..k * n).map(|x| (x % 5) as f64).collect(), k, n)
+            .unwrap();
+    let c = matmul(&a, &b).unwrap();
+    assert_eq!(c.shape(), (m, n));
+    for (i, j) in [(0, 0), (13, 77), (69, 89), (64, 3)] {
+        let expected: f64 = (0..k).map(|p| a[(i, p)] * b[(p, j)]).sum();
+        assert_eq!(c[(i, j)], expected);
+    }
+
+    let identity = Array2D::from_array(
+        (0..9)
+            .map(|x| if x % 4 == 0 { 1.0f32 } else { 0.0 })
+            .collect(),
+        3,
+        3,
+    )
+    .unwrap();
+    let x =
+        Array2D::from_array((0..6).map(|x| x as f32).collect(), 2, 3).unwrap();
+    assert_eq!(matmul(&x, &identity).unwrap().as_slice(), x.as_slice());
+    assert!(matmul(&identity, &x).is_err());
+    let empty = Array2D::<f32>::zeroed(2, 0).unwrap();
+    let zeros = matmul(&empty, &Array2D::zeroed(0, 4).unwrap()).unwrap();
+    assert!(zeros.as_slice().iter().all(|&x| x == 0.0));
+}
+
+#[cfg(not(feature = "no-panic"))]
+#[test]
+fn test_matvec_and_vector_ops() {
+    use crate::matrix::{matvec, outer, Array2D};
+
+    let a =
+        Array2D::from_array((1..=6).map(f64::from).collect(), 2, 3).unwrap();
+    let x = ArrayCStyle::from(&[1.0, 0.0, -1.0][..]);
+    let y = matvec(&a, &x).unwrap();
+    assert_eq!(y.as_slice(), [-2.0, -2.0]);
+    assert!(matvec(&a, &y).is_err());
+
+    let product = outer(&y, &x).unwrap();
+    assert_eq!(product.shape(), (2, 3));
+    assert_eq!(
+        product.row(1).unwrap().as_slice().unwrap(),
+        [-2.0, 0.0, 2.0]
+    );
+
+    assert_eq!(x.dot(&x).unwrap(), 2.0);
+    assert!(x.dot(&y).is_err());
+    let v = ArrayCStyle::from(&[3.0f32, 4.0][..]);
+    assert_eq!(v.norm(), 5.0);
+}
+
+#[cfg(feature = "ndarray")]
+#[test]
+fn test_ndarray_interop() {
+    use crate::matrix::{Array2D, ArrayND, ArrayView2D, ArrayViewND};
+
+    let flat: ArrayCStyle<u32> = array(0..6);
+    let ptr = flat.ptr();
+    let vector = flat.into_ndarray().unwrap();
+    assert_eq!(vector.as_ptr(), ptr);
+    let flat = ArrayCStyle::from_ndarray(vector).unwrap();
+    assert_eq!(flat.ptr(), ptr);
+    assert_eq!(flat.as_ndarray_view().sum(), 15);
+    assert!(ArrayCStyle::<u32>::new_aligned(4, 64)
+        .unwrap()
+        .into_ndarray()
+        .is_err());
+
+    let mut matrix = Array2D::from_array(flat, 2, 3).unwrap();
+    matrix.as_ndarray_view_mut()[[1, 2]] = 50;
+    assert_eq!(matrix.t().into_ndarray()[[2, 1]], 50);
+    let nd = matrix.into_ndarray().unwrap();
+    assert_eq!(nd.as_ptr(), ptr);
+    let fortran = Array2D::from_ndarray(nd.clone().reversed_axes()).unwrap();
+    assert_eq!(fortran.layout(), crate::matrix::Layout::ColumnMajor);
+    assert_eq!(fortran.get(2, 1), Some(&50));
+    let matrix = Array2D::from_ndarray(nd).unwrap();
+    assert_eq!(matrix.get(1, 2), Some(&50));
+
+    let tensor = ArrayND::from_array(matrix.into_array(), &[3, 1, 2]).unwrap();
+    let view = tensor.t().into_ndarray();
+    assert_eq!(view.shape(), [2, 1, 3]);
+    assert_eq!(view[[1, 0, 2]], 50);
+    let back = ArrayViewND::from_ndarray(view).unwrap();
+    assert_eq!(back.get(&[1, 0, 2]), Some(&50));
+    let owned = ArrayND::from_ndarray(tensor.into_ndarray().unwrap()).unwrap();
+    assert_eq!(owned.shape(), [3, 1, 2]);
+
+    let source = ndarray::Array2::<u8>::eye(3);
+    let flipped = source.slice(ndarray::s![.., ..;-1]);
+    assert!(ArrayView2D::from_ndarray(flipped).is_err());
+    let view = ArrayView2D::from_ndarray(source.t()).unwrap();
+    assert_eq!(view.strides(), (1, 3));
+}
+
+#[cfg(feature = "nalgebra")]
+#[test]
+fn test_nalgebra_interop() {
+    use crate::matrix::{Array2D, ArrayView2D};
+
+    let values: ArrayCStyle<f64> = array((0..6).map(f64::from));
+    let ptr = values.ptr();
+    let vector = values.into_dvector().unwrap();
+    assert_eq!(vector.as_ptr(), ptr);
+    assert_eq!(vector.sum(), 15.0);
+    let mut values = ArrayCStyle::from_dvector(vector).unwrap();
+    assert_eq!(values.ptr(), ptr);
+    values.as_dvector_view_mut()[5] = 50.0;
+    assert_eq!(values.as_dvector_view().norm_squared(), 2530.0);
+
+    let mut matrix = Array2D::from_array(values, 2, 3).unwrap();
+    let view = matrix.as_dmatrix_view();
+    assert_eq!(view.shape(), (2, 3));
+    assert_eq!(view[(1, 2)], 50.0);
+    assert_eq!(view.as_ptr(), ptr);
+    let copy = matrix.to_dmatrix();
+    assert_eq!(copy, view);
+    assert_eq!(matrix.t().into_dmatrix_view(), copy.transpose());
+    matrix.as_dmatrix_view_mut()[(0, 1)] = -1.0;
+    assert_eq!(matrix.get(0, 1), Some(&-1.0));
+
+    let product = &copy * copy.transpose();
+    let product = Array2D::from_dmatrix(&product).unwrap();
+    assert_eq!(product.shape(), (2, 2));
+    assert_eq!(product.get(0, 1), Some(&(3.0 * 0.0 + 4.0 + 2.0 * 50.0)));
+    let back = ArrayView2D::from_dmatrix_view(copy.columns(1, 2));
+    assert_eq!(back.shape(), (2, 2));
+    assert_eq!(back.get(1, 1), Some(&50.0));
+}
+
+#[cfg(not(feature = "no-panic"))]
+#[test]
+fn test_image_view() {
+    use crate::matrix::{Array2D, ImageView};
+
+    // 3x2 RGB with rows padded to 12 bytes; the last row is unpadded
+    let mut frame: ArrayCStyle<u8> = (0..12 + 9).collect();
+    let image = frame.as_image(3, 2, 12, 3).unwrap();
+    assert_eq!((image.width(), image.height()), (3, 2));
+    assert_eq!(image.pixel(2, 1).unwrap(), [18, 19, 20]);
+    assert!(image.pixel(3, 0).is_none());
+    assert_eq!(image.row(0).unwrap(), [0, 1, 2, 3, 4, 5, 6, 7, 8]);
+    assert_eq!(image.rows().len(), 2);
+    let packed = image.to_array2d().unwrap();
+    assert_eq!(packed.shape(), (2, 9));
+    assert_eq!(packed[(1, 0)], 12);
+
+    assert!(frame.as_image(4, 1, 12, 3).is_ok());
+    assert!(frame.as_image(5, 2, 12, 3).is_err());
+    assert!(frame.as_image(3, 3, 12, 3).is_err());
+    assert!(frame.as_image(3, 2, 12, 0).is_err());
+
+    let mut image = frame.as_image_mut(3, 2, 12, 3).unwrap();
+    image.pixel_mut(0, 1).unwrap().fill(0);
+    for row in image.rows_mut() {
+        row[8] = 255;
+    }
+    assert_eq!(frame[9], 9);
+    assert_eq!(frame[8], 255);
+    assert_eq!(frame.as_slice()[12..15], [0, 0, 0]);
+    assert_eq!(frame[20], 255);
+
+    let gray = Array2D::from_array(frame, 3, 7).unwrap();
+    assert!(ImageView::from_array2d(&gray, 2).is_err());
+    let gray = ImageView::from_array2d(&gray, 1).unwrap();
+    assert_eq!((gray.width(), gray.pitch()), (7, 7));
+}
+
+#[cfg(not(feature = "no-panic"))]
+#[test]
+fn test_pnm_round_trip() {
+    use crate::matrix::{load_pnm, load_raw, read_pnm, ImageView};
+
+    let bytes: ArrayCStyle<u8> = (0..2 * 8).collect();
+    let image = bytes.as_image(2, 2, 8, 3).unwrap();
+    let mut ppm = Vec::new();
+    image.write_pnm(&mut ppm).unwrap();
+    assert!(ppm.starts_with(b"P6\n2 2\n255\n"));
+    assert_eq!(ppm.len(), 11 + 12);
+
+    let (pixels, channels) = read_pnm(&ppm[..]).unwrap();
+    assert_eq!((pixels.shape(), channels), ((2, 6), 3));
+    assert_eq!(
+        pixels.row(1).unwrap().as_slice().unwrap(),
+        [8, 9, 10, 11, 12, 13]
+    );
+
+    let commented = b"P5 # gray\n# size\n3 1\n# depth\n255\n\x01\x02\x03";
+    let (gray, channels) = read_pnm(&commented[..]).unwrap();
+    assert_eq!(channels, 1);
+    assert_eq!(gray.as_slice(), [1, 2, 3]);
+    assert!(read_pnm(&b"P6\n1 1\n65535\n\0\0\0\0\0\0"[..]).is_err());
+    assert!(read_pnm(&b"P3\n1 1\n255\n0 0 0"[..]).is_err());
+    assert!(read_pnm(&b"P5\n2 2\n255\n\0\0\0"[..]).is_err());
+    assert!(bytes
+        .as_image(4, 2, 8, 2)
+        .unwrap()
+        .write_pnm(Vec::new())
+        .is_err());
+
+    let dir = std::env::temp_dir();
+    let path = dir.join(format!("runnarr-pnm-{}.pgm", std::process::id()));
+    let gray_view = ImageView::from_array2d(&gray, 1).unwrap();
+    gray_view.save_pnm(&path).unwrap();
+    assert_eq!(load_pnm(&path).unwrap().0.as_slice(), [1, 2, 3]);
+    image.save_raw(&path).unwrap();
+    let raw = load_raw(&path, 2, 2, 3).unwrap();
+    assert_eq!(raw.as_slice(), pixels.as_slice());
+    assert!(load_raw(&path, 3, 2, 3).is_err());
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[cfg(not(feature = "no-panic"))]
+#[test]
+fn test_convolve2d() {
+    use crate::matrix::{Array2D, BorderMode};
+
+    fn direct(
+        image: &Array2D<f32>,
+        kernel: &Array2D<f32>,
+        border: BorderMode<f32>,
+    ) -> Vec<f32> {
+        let (h, w) = image.shape();
+        let (kh, kw) = kernel.shape();
+        let mut out = Vec::new();
+        for y in 0..h as isize {
+            for x in 0..w as isize {
+                let mut sum = 0.0;
+                for i in 0..kh as isize {
+                    for j in 0..kw as isize {
+                        let sy = y - i + kh as isize / 2;
+                        let sx = x - j + kw as isize / 2;
+                        let value = match (
+                            border.source_index(sy, h),
+                            border.source_index(sx, w),
+                        ) {
+                            (Some(r), Some(c)) => image[(r, c)],
+                            _ => match border {
+                                BorderMode::Constant(v) => v,
+                                _ => unreachable!(),
+                            },
+                        };
+                        sum += kernel[(i as usize, j as usize)] * value;
+                    }
+                }
+                out.push(sum);
+            }
+        }
+        out
+    }
+
+    let image = Array2D::from_array(
+        (0..7 * 9).map(|x| ((x * 37) % 11) as f32).collect(),
+        7,
+        9,
+    )
+    .unwrap();
+    let skewed =
+        Array2D::from_array([1.0, -2.0, 0.5, 3.0, 0.0, 1.0][..].into(), 2, 3)
+            .unwrap();
+    let sobel = Array2D::from_array(
+        [1.0, 0.0, -1.0, 2.0, 0.0, -2.0, 1.0, 0.0, -1.0][..].into(),
+        3,
+        3,
+    )
+    .unwrap();
+    for kernel in [&skewed, &sobel] {
+        for border in [
+            BorderMode::Constant(2.0),
+            BorderMode::Replicate,
+            BorderMode::Reflect,
+        ] {
+            let fast = image.convolve2d(kernel, border).unwrap();
+            let slow = direct(&image, kernel, border);
+            assert!(fast
+                .as_slice()
+                .iter()
+                .zip(&slow)
+                .all(|(a, b)| (a - b).abs() < 1e-4));
+        }
+    }
+
+    let bright = Array2D::<u8>::filled(4, 4, 200).unwrap();
+    let doubled = bright
+        .convolve2d_separable(&[1.0], &[1.0, 1.0], BorderMode::Replicate)
+        .unwrap();
+    assert!(doubled.as_slice().iter().all(|&x| x == 255));
+    let blurred = bright
+        .convolve2d(
+            &Array2D::filled(3, 3, 1.0 / 9.0).unwrap(),
+            BorderMode::Constant(0),
+        )
+        .unwrap();
+    assert_eq!(blurred[(0, 0)], 89);
+    assert_eq!(blurred[(1, 1)], 200);
+    assert!(image
+        .convolve2d(&Array2D::zeroed(0, 3).unwrap(), BorderMode::Reflect)
+        .is_err());
+}
+
+#[cfg(not(feature = "no-panic"))]
+#[test]
+fn test_array2d_pad_and_crop() {
+    use crate::matrix::{Array2D, BorderMode};
+
+    let matrix = Array2D::from_array((1..=6).collect(), 2, 3).unwrap();
+    let constant = matrix.pad((1, 0), (0, 2), BorderMode::Constant(0)).unwrap();
+    assert_eq!(constant.shape(), (3, 5));
+    assert!(constant.rows().map(|row| row.as_slice().unwrap()).eq([
+        &[0, 0, 0, 0, 0][..],
+        &[1, 2, 3, 0, 0],
+        &[4, 5, 6, 0, 0],
+    ]));
+
+    let edge = matrix.pad((0, 2), (2, 1), BorderMode::Replicate).unwrap();
+    assert_eq!(edge.row(0).unwrap().as_slice().unwrap(), [1, 1, 1, 2, 3, 3]);
+    assert_eq!(edge.row(3).unwrap().as_slice().unwrap(), [4, 4, 4, 5, 6, 6]);
+
+    let mirror = matrix.pad((1, 1), (2, 2), BorderMode::Reflect).unwrap();
+    assert_eq!(
+        mirror.row(0).unwrap().as_slice().unwrap(),
+        [6, 5, 4, 5, 6, 5, 4]
+    );
+    assert_eq!(
+        mirror.row(1).unwrap().as_slice().unwrap(),
+        [3, 2, 1, 2, 3, 2, 1]
+    );
+    assert_eq!(
+        mirror.row(3).unwrap().as_slice(),
+        mirror.row(1).unwrap().as_slice()
+    );
+
+    let empty = Array2D::<u8>::zeroed(0, 0).unwrap();
+    assert!(empty.pad((1, 1), (1, 1), BorderMode::Reflect).is_err());
+    let filled = empty.pad((1, 0), (0, 2), BorderMode::Constant(7)).unwrap();
+    assert_eq!(filled.as_slice(), [7, 7]);
+
+    let cropped = mirror.crop(1..3, 2..5).unwrap();
+    assert_eq!(cropped.as_slice(), matrix.as_slice());
+    assert!(mirror.crop(0..5, 0..1).is_err());
+    assert!(mirror.crop(2..2, 7..7).unwrap().is_empty());
+}
+
+#[cfg(not(feature = "no-panic"))]
+#[test]
+fn test_array2d_diag() {
+    use crate::matrix::Array2D;
+
+    let mut matrix = Array2D::from_array((1..=12).collect(), 3, 4).unwrap();
+    assert!(matrix.diag().iter().eq(&[1, 6, 11]));
+    assert!(matrix.diag_offset(1).iter().eq(&[2, 7, 12]));
+    assert!(matrix.diag_offset(3).iter().eq(&[4]));
+    assert!(matrix.diag_offset(-2).iter().eq(&[9]));
+    assert!(matrix.diag_offset(4).is_empty());
+    assert!(matrix.diag_offset(-3).is_empty());
+    assert_eq!(matrix.trace(), 18);
+    assert!(matrix.t().diag_offset(-1).iter().eq(&[2, 7, 12]));
+
+    matrix.diag_mut().fill(0);
+    matrix
+        .diag_offset_mut(-1)
+        .iter_mut()
+        .for_each(|value| *value *= 10);
+    assert_eq!(matrix.trace(), 0);
+    assert!(matrix.rows().map(|row| row.as_slice().unwrap()).eq([
+        &[0, 2, 3, 4][..],
+        &[50, 0, 7, 8],
+        &[9, 100, 0, 12],
+    ]));
+}
+
+#[cfg(not(feature = "no-panic"))]
+#[test]
+fn test_array2d_tiles() {
+    use crate::matrix::Array2D;
+
+    let mut matrix = Array2D::from_array((0..15).collect(), 3, 5).unwrap();
+    let tiles: Vec<_> = matrix.tiles(2, 2).unwrap().collect();
+    assert_eq!(tiles.len(), 6);
+    let origins: Vec<_> = tiles.iter().map(|&(origin, _)| origin).collect();
+    assert_eq!(origins, [(0, 0), (0, 2), (0, 4), (2, 0), (2, 2), (2, 4)]);
+    assert!(tiles[1].1.iter().eq(&[2, 3, 7, 8]));
+    assert_eq!(tiles[2].1.shape(), (2, 1));
+    assert!(tiles[5].1.iter().eq(&[14]));
+    let (origin, last) = matrix.tiles(2, 2).unwrap().next_back().unwrap();
+    assert_eq!((origin, last.shape()), ((2, 4), (1, 1)));
+
+    let transposed = matrix.t();
+    let tiles: Vec<_> = transposed.tiles(4, 2).unwrap().collect();
+    assert_eq!(tiles.len(), 4);
+    assert!(tiles[1].1.iter().eq(&[10, 11, 12, 13]));
+
+    for ((row, col), mut tile) in matrix.tiles_mut(2, 3).unwrap() {
+        tile.fill(row * 10 + col);
+    }
+    assert!(matrix.rows().map(|row| row.as_slice().unwrap()).eq([
+        &[0, 0, 0, 3, 3][..],
+        &[0, 0, 0, 3, 3],
+        &[20, 20, 20, 23, 23],
+    ]));
+    assert!(matrix.tiles(0, 1).is_err());
+    assert_eq!(
+        Array2D::<u8>::zeroed(0, 4)
+            .unwrap()
+            .tiles(1, 1)
+            .unwrap()
+            .len(),
+        0
+    );
+}
+
+#[cfg(not(feature = "no-panic"))]
+#[test]
+fn test_array2d_column_major() {
+    use crate::matrix::{matmul, Array2D, BorderMode, Layout};
+
+    let data = ArrayCStyle::from(&[1.0f32, 4.0, 2.0, 5.0, 3.0, 6.0][..]);
+    let mut matrix =
+        Array2D::from_array_with_layout(data, 2, 3, Layout::ColumnMajor)
+            .unwrap();
+    assert_eq!(matrix.strides(), (1, 2));
+    assert_eq!(matrix[(0, 2)], 3.0);
+    assert_eq!(matrix.get(1, 0), Some(&4.0));
+    let first = matrix.row(0).unwrap();
+    assert_eq!((first.stride(), first.as_slice()), (2, None));
+    assert!(first.iter().eq(&[1.0, 2.0, 3.0]));
+    assert!(matrix.row(2).is_none());
+    assert_eq!(matrix.rows().len(), 2);
+    // rows of Fortran-order data are strided views, so they can be scaled
+    let mut scaled = matrix.try_clone().unwrap();
+    for mut row in scaled.rows_mut() {
+        let norm = row.as_view().iter().map(|x| x * x).sum::<f32>().sqrt();
+        row.iter_mut().for_each(|x| *x /= norm);
+    }
+    assert!(scaled
+        .rows()
+        .map(|row| row.iter().map(|x| x * x).sum::<f32>())
+        .all(|norm| (norm - 1.0).abs() < 1e-6));
+    scaled.row_mut(1).unwrap().fill(0.0);
+    // row 1 occupies every other storage position
+    assert!(scaled
+        .as_slice()
+        .iter()
+        .skip(1)
+        .step_by(2)
+        .all(|&x| x == 0.0));
+    assert!(scaled.as_slice().iter().step_by(2).all(|&x| x > 0.0));
+    assert!(matrix.as_view().row(1).unwrap().iter().eq(&[4.0, 5.0, 6.0]));
+    assert_eq!(matrix.col(1).unwrap().as_slice(), Some(&[2.0, 5.0][..]));
+    assert!(matrix.as_view().iter().eq(&[1.0, 2.0, 3.0, 4.0, 5.0, 6.0]));
+    assert_eq!(format!("{matrix:?}"), "[[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]]");
+
+    let rows = matrix.to_layout(Layout::RowMajor).unwrap();
+    assert_eq!(rows.as_slice(), [1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+    let back = rows.to_layout(Layout::ColumnMajor).unwrap();
+    assert_eq!(back.as_slice(), matrix.as_slice());
+    let transposed = matrix.transposed().unwrap();
+    assert_eq!(transposed.layout(), Layout::ColumnMajor);
+    assert_eq!(transposed.as_slice(), [1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+
+    let product = matmul(&matrix, &transposed).unwrap();
+    assert_eq!(product.as_slice(), [14.0, 32.0, 32.0, 77.0]);
+    assert_eq!(matrix.trace(), 6.0);
+
+    matrix[(1, 1)] = 50.0;
+    matrix.col_mut(0).unwrap().fill(0.0);
+    assert_eq!(matrix.as_slice(), [0.0, 0.0, 2.0, 50.0, 3.0, 6.0]);
+    let cropped = matrix.crop(1..2, 1..3).unwrap();
+    assert_eq!(cropped.layout(), Layout::ColumnMajor);
+    assert_eq!(cropped.as_slice(), [50.0, 6.0]);
+    let padded = cropped.pad((1, 0), (0, 0), BorderMode::Replicate).unwrap();
+    assert_eq!(padded.as_slice(), [50.0, 50.0, 6.0, 6.0]);
+}
+
+#[cfg(not(feature = "no-panic"))]
+#[test]
+fn test_axis_reductions() {
+    use crate::matrix::{Array2D, ArrayND, Axis, Layout};
+
+    let matrix =
+        Array2D::from_array([1.0f64, 5.0, 3.0, 4.0, 2.0, 6.0][..].into(), 2, 3)
+            .unwrap();
+    assert_eq!(
+        matrix.sum_axis(Axis(0)).unwrap().as_slice(),
+        [5.0, 7.0, 9.0]
+    );
+    assert_eq!(matrix.sum_axis(Axis(1)).unwrap().as_slice(), [9.0, 12.0]);
+    assert_eq!(matrix.mean_axis(Axis(1)).unwrap().as_slice(), [3.0, 4.0]);
+    assert_eq!(
+        matrix.min_axis(Axis(0)).unwrap().as_slice(),
+        [1.0, 2.0, 3.0]
+    );
+    assert_eq!(matrix.max_axis(Axis(1)).unwrap().as_slice(), [5.0, 6.0]);
+    assert!(matrix.sum_axis(Axis(2)).is_err());
+
+    let columns = matrix.to_layout(Layout::ColumnMajor).unwrap();
+    assert_eq!(columns.sum_axis(Axis(1)).unwrap().as_slice(), [9.0, 12.0]);
+
+    let empty = Array2D::<u32>::zeroed(0, 3).unwrap();
+    assert_eq!(empty.sum_axis(Axis(0)).unwrap().as_slice(), [0, 0, 0]);
+    assert!(empty.max_axis(Axis(0)).is_err());
+    assert_eq!(empty.max_axis(Axis(1)).unwrap().len(), 0);
+
+    let cube = ArrayND::from_array((0..24).collect(), &[2, 3, 4]).unwrap();
+    let sums = cube.sum_axis(Axis(1)).unwrap();
+    assert_eq!(sums.shape(), [2, 4]);
+    assert_eq!(sums[[1, 2]], 14 + 18 + 22);
+    let maxima = cube.t().max_axis(Axis(0)).unwrap();
+    assert_eq!(maxima.shape(), [3, 2]);
+    assert_eq!(maxima[[2, 0]], 11);
+    let total = sums.sum_axis(Axis(0)).unwrap().sum_axis(Axis(0)).unwrap();
+    assert_eq!(total[[]], (0..24).sum::<i32>());
+}
+
+#[cfg(not(feature = "no-panic"))]
+#[test]
+fn test_concat_and_stack() {
+    use crate::matrix::{concat, stack, Array2D, Axis, Layout};
+
+    let a = Array2D::from_array((1..=4).collect(), 2, 2).unwrap();
+    let b = Array2D::from_array((5..=6).collect(), 1, 2).unwrap();
+    let c = a.to_layout(Layout::ColumnMajor).unwrap();
+
+    let rows = concat(Axis(0), &[&a, &b, &c]).unwrap();
+    assert_eq!(rows.shape(), (5, 2));
+    assert_eq!(rows.as_slice(), [1, 2, 3, 4, 5, 6, 1, 2, 3, 4]);
+    let cols = concat(Axis(1), &[&a, &c.transposed().unwrap()]).unwrap();
+    assert!(cols
+        .rows()
+        .map(|row| row.as_slice().unwrap())
+        .eq([&[1, 2, 1, 3][..], &[3, 4, 2, 4]]));
+    assert!(concat(Axis(1), &[&a, &b]).is_err());
+    assert!(concat::<u8>(Axis(0), &[]).is_err());
+    assert!(concat(Axis(2), &[&a]).is_err());
+
+    let volume = stack(Axis(0), &[&a, &c]).unwrap();
+    assert_eq!(volume.shape(), (2, 2, 2));
+    assert_eq!(volume.as_slice(), [1, 2, 3, 4, 1, 2, 3, 4]);
+    let volume = stack(Axis(2), &[&a, &c]).unwrap();
+    assert_eq!(volume.shape(), (2, 2, 2));
+    assert_eq!(volume.as_slice(), [1, 1, 2, 2, 3, 3, 4, 4]);
+    let volume = stack(Axis(1), &[&a, &a.transposed().unwrap()]).unwrap();
+    assert_eq!(volume.as_slice(), [1, 2, 1, 3, 3, 4, 2, 4]);
+    assert!(stack(Axis(0), &[&a, &b]).is_err());
+}
+
+#[cfg(not(feature = "no-panic"))]
+#[test]
+fn test_flatten() {
+    use crate::matrix::{Array2D, Array3D, ArrayND, Layout};
+
+    let matrix = Array2D::from_array((0..6).collect(), 2, 3).unwrap();
+    let flat = matrix.flatten().unwrap();
+    assert!(flat.is_borrowed());
+    assert_eq!(flat.as_ptr(), matrix.as_slice().as_ptr());
+    let transposed = matrix.t().flatten().unwrap();
+    assert!(transposed.is_owned());
+    assert_eq!(*transposed, [0, 3, 1, 4, 2, 5]);
+    let columns = matrix.to_layout(Layout::ColumnMajor).unwrap();
+    assert_eq!(*columns.flatten().unwrap(), *flat);
+    assert!(matrix
+        .view(1..2, 0..3)
+        .unwrap()
+        .flatten()
+        .unwrap()
+        .is_borrowed());
+
+    let tensor = ArrayND::from_array((0..24).collect(), &[2, 3, 4]).unwrap();
+    assert!(tensor.flatten().unwrap().is_borrowed());
+    let swapped = tensor.t().flatten().unwrap();
+    assert_eq!(swapped.len(), 24);
+    assert_eq!(swapped[..3], [0, 12, 4]);
+    let owned = swapped.try_into_owned().unwrap();
+    assert_eq!(owned.len(), 24);
+
+    let volume = Array3D::<u8>::zeroed(2, 2, 2).unwrap();
+    assert_eq!(volume.flatten().len(), 8);
+}
+
+#[cfg(feature = "npy")]
+#[test]
+fn test_npy_round_trip() {
+    use crate::matrix::{Array2D, ArrayND, Layout};
+
+    let tensor = ArrayND::from_array(array(0..24), &[2, 3, 4]).unwrap();
+    let mut bytes = Vec::new();
+    tensor.write_npy(&mut bytes).unwrap();
+    assert_eq!(&bytes[..8], b"\x93NUMPY\x01\x00");
+    assert_eq!(bytes.len(), 128 + 24 * 4);
+    let header = std::str::from_utf8(&bytes[10..128]).unwrap();
+    assert!(header.ends_with(" \n"));
+    assert!(header.starts_with(
+        "{'descr': '<i4', 'fortran_order': False, 'shape': (2, 3, 4), }"
+    ));
+    let back = ArrayND::<i32>::read_npy(&bytes[..]).unwrap();
+    assert_eq!(
+        (back.shape(), back.as_slice()),
+        (tensor.shape(), tensor.as_slice())
+    );
+    assert!(ArrayND::<f32>::read_npy(&bytes[..]).is_err());
+    assert!(Array2D::<i32>::read_npy(&bytes[..]).is_err());
+
+    // transposed: written in Fortran order without reordering
+    let mut bytes = Vec::new();
+    tensor
+        .try_clone()
+        .unwrap()
+        .reversed_axes()
+        .write_npy(&mut bytes)
+        .unwrap();
+    assert!(std::str::from_utf8(&bytes[10..64])
+        .unwrap()
+        .contains("True"));
+    let back = ArrayND::<i32>::read_npy(&bytes[..]).unwrap();
+    assert_eq!(back.shape(), [4, 3, 2]);
+    assert_eq!(back.get(&[3, 2, 1]), tensor.get(&[1, 2, 3]));
+
+    let matrix = Array2D::from_array(array(0..6u8), 2, 3).unwrap();
+    let columns = matrix.to_layout(Layout::ColumnMajor).unwrap();
+    let mut bytes = Vec::new();
+    columns.write_npy(&mut bytes).unwrap();
+    let back = Array2D::<u8>::read_npy(&bytes[..]).unwrap();
+    assert_eq!(back.layout(), Layout::ColumnMajor);
+    assert_eq!(back.as_slice(), columns.as_slice());
+
+    // big-endian data and a 1-D shape, as other writers produce them
+    let mut bytes = b"\x93NUMPY\x01\x00\x3c\x00".to_vec();
+    let header = "{'descr': '>f8', 'fortran_order': False, 'shape': (2,), }";
+    bytes.extend(format!("{header:<59}\n").bytes());
+    bytes.extend(1.5f64.to_be_bytes());
+    bytes.extend((-2.0f64).to_be_bytes());
+    let vector = ArrayCStyle::<f64>::read_npy(&bytes[..]).unwrap();
+    assert_eq!(vector.as_slice(), [1.5, -2.0]);
+    let mut written = Vec::new();
+    vector.write_npy(&mut written).unwrap();
+    assert_eq!(
+        ArrayCStyle::<f64>::read_npy(&written[..])
+            .unwrap()
+            .as_slice(),
+        [1.5, -2.0]
+    );
+    assert!(ArrayCStyle::<f64>::read_npy(&bytes[..bytes.len() - 1]).is_err());
+}
+
+#[cfg(feature = "npz")]
+#[test]
+fn test_npz_round_trip() {
+    use std::io::Cursor;
+
+    use crate::{
+        matrix::ArrayND,
+        npy::{NpzReader, NpzWriter},
+    };
+
+    let weights =
+        ArrayND::from_array(array((0..12).map(|x| x as f32)), &[3, 4]).unwrap();
+    let bias = ArrayND::from_array(array(0..4i64), &[4]).unwrap();
+    for compressed in [false, true] {
+        let mut archive = NpzWriter::new(Cursor::new(Vec::new()), compressed);
+        archive.write("weights", &weights).unwrap();
+        archive.write("bias", &bias).unwrap();
+        let bytes = archive.finish().unwrap().into_inner();
+
+        let mut archive = NpzReader::new(Cursor::new(bytes)).unwrap();
+        let mut names = archive.names();
+        names.sort();
+        assert_eq!(names, ["bias", "weights"]);
+        let read = archive.read::<f32>("weights").unwrap();
+        assert_eq!(
+            (read.shape(), read.as_slice()),
+            (weights.shape(), weights.as_slice())
+        );
+        assert_eq!(
+            archive.read::<i64>("bias").unwrap().as_slice(),
+            bias.as_slice()
+        );
+        assert!(archive.read::<f32>("bias").is_err());
+        assert!(archive.read::<f32>("missing").is_err());
+    }
+}
+
+#[cfg(not(feature = "no-panic"))]
+#[test]
+fn test_array2d_linear_index() {
+    use crate::matrix::{Array2D, Layout};
+
+    let mut matrix = Array2D::from_array((0..12).collect(), 3, 4).unwrap();
+    assert_eq!(matrix.linear_index(2, 1), 9);
+    assert_eq!(matrix.from_linear(9), (2, 1));
+    assert_eq!(matrix.as_slice()[matrix.linear_index(1, 3)], matrix[(1, 3)]);
+    matrix[(2, 3)] = 99;
+    assert_eq!(matrix.as_slice()[11], 99);
+
+    let columns = matrix.to_layout(Layout::ColumnMajor).unwrap();
+    assert_eq!(columns.linear_index(2, 1), 5);
+    assert_eq!(columns.from_linear(5), (2, 1));
+    for index in 0..columns.len() {
+        let (row, col) = columns.from_linear(index);
+        assert_eq!(columns.linear_index(row, col), index);
+        assert_eq!(columns[(row, col)], matrix[(row, col)]);
+    }
+
+    let panic = std::panic::catch_unwind(|| matrix[(3, 0)]).unwrap_err();
+    let message = panic.downcast_ref::<String>().unwrap();
+    assert_eq!(message, "Index (3, 0) out of bounds for a 3x4 matrix");
+}
+
+#[cfg(not(feature = "no-panic"))]
+#[test]
+fn test_array2d_map_and_zip_with() {
+    use crate::matrix::{Array2D, Layout};
+
+    let mut matrix = Array2D::from_array((0..6).collect(), 2, 3).unwrap();
+    let halves = matrix.map(|&value| value as f32 / 2.0).unwrap();
+    assert_eq!(halves.as_slice(), [0.0, 0.5, 1.0, 1.5, 2.0, 2.5]);
+
+    matrix.map_inplace(|value| *value *= 10);
+    assert_eq!(matrix.as_slice(), [0, 10, 20, 30, 40, 50]);
+
+    let ones = Array2D::filled(2, 3, 1).unwrap();
+    let sums = matrix.zip_with(&ones, |x, y| x + y).unwrap();
+    assert_eq!(sums.as_slice(), [1, 11, 21, 31, 41, 51]);
+
+    let columns = ones.to_layout(Layout::ColumnMajor).unwrap();
+    let columns = columns.zip_with(&matrix, |x, y| x + y).unwrap();
+    assert_eq!(columns.layout(), Layout::ColumnMajor);
+    assert_eq!(columns.as_slice(), [1, 31, 11, 41, 21, 51]);
+    assert_eq!(columns[(0, 2)], 21);
+
+    let wide = Array2D::filled(3, 2, 1).unwrap();
+    assert!(matrix.zip_with(&wide, |x, y| x + y).is_err());
+}
+
+#[cfg(not(feature = "no-panic"))]
+#[test]
+fn test_fixed_vec() {
+    use crate::containers::FixedVec;
+
+    let mut vec = FixedVec::with_capacity(4).unwrap();
+    assert!(vec.is_empty());
+    vec.push(String::from("b"));
+    vec.push(String::from("d"));
+    assert_eq!(vec.insert(0, String::from("a")), Ok(()));
+    assert_eq!(vec.insert(2, String::from("c")), Ok(()));
+    assert_eq!(vec.as_slice(), ["a", "b", "c", "d"]);
+    assert!(vec.is_full());
+    assert_eq!(vec.try_push(String::from("e")), Err(String::from("e")));
+    assert_eq!(vec.insert(0, String::from("e")), Err(String::from("e")));
+
+    assert_eq!(vec.remove(1).as_deref(), Some("b"));
+    assert_eq!(vec.remove(3), None);
+    assert_eq!(vec.pop().as_deref(), Some("d"));
+    assert_eq!(vec.insert(3, String::from("x")), Err(String::from("x")));
+    vec[0].push('!');
+    assert_eq!(format!("{vec:?}"), r#"["a!", "c"]"#);
+    assert_eq!(vec.memory_usage().slack_bytes(), 2 * size_of::<String>());
+
+    vec.clear();
+    assert_eq!(vec.pop(), None);
+    assert_eq!(vec.capacity(), 4);
+}
+
+#[cfg(not(feature = "no-panic"))]
+#[test]
+fn test_ring_buffer() {
+    use crate::containers::{OverflowPolicy, RingBuffer};
+
+    let mut window =
+        RingBuffer::with_capacity(3, OverflowPolicy::Overwrite).unwrap();
+    for value in 1..=3 {
+        assert_eq!(window.push_back(value), Ok(None));
+    }
+    assert_eq!(window.push_back(4), Ok(Some(1)));
+    assert_eq!(window.push_back(5), Ok(Some(2)));
+    assert_eq!(window.as_slices(), (&[3][..], &[4, 5][..]));
+    assert_eq!(window.iter().copied().collect::<Vec<_>>(), [3, 4, 5]);
+    assert_eq!((window[0], window[2]), (3, 5));
+    assert_eq!(window.get(3), None);
+    window[1] = 40;
+    assert_eq!((window.front(), window.back()), (Some(&3), Some(&5)));
+    assert_eq!(window.pop_front(), Some(3));
+    assert_eq!(format!("{window:?}"), "[40, 5]");
+
+    let mut log = RingBuffer::with_capacity(2, OverflowPolicy::Reject).unwrap();
+    log.push_back(String::from("a")).unwrap();
+    log.push_back(String::from("b")).unwrap();
+    assert!(log.is_full());
+    assert_eq!(log.push_back(String::from("c")), Err(String::from("c")));
+    assert_eq!(log.pop_front().as_deref(), Some("a"));
+    log.push_back(String::from("c")).unwrap();
+    assert_eq!(log.iter().rev().collect::<Vec<_>>(), ["c", "b"]);
+    log.clear();
+    assert!(log.is_empty());
+
+    let mut empty =
+        RingBuffer::with_capacity(0, OverflowPolicy::Overwrite).unwrap();
+    assert_eq!(empty.push_back(1), Err(1));
+}
+
+#[cfg(not(feature = "no-panic"))]
+#[test]
+fn test_deque() {
+    use crate::containers::Deque;
+
+    let mut deque = Deque::with_capacity(4).unwrap();
+    assert_eq!(deque.push_back(String::from("c")), Ok(()));
+    assert_eq!(deque.push_front(String::from("b")), Ok(()));
+    assert_eq!(deque.push_front(String::from("a")), Ok(()));
+    assert_eq!(deque.push_back(String::from("d")), Ok(()));
+    assert_eq!(deque.push_front(String::from("x")), Err(String::from("x")));
+    assert_eq!(deque.as_slices().0, ["a", "b"]);
+    assert_eq!(deque.as_slices().1, ["c", "d"]);
+    assert_eq!((deque[0].as_str(), deque[3].as_str()), ("a", "d"));
+    assert_eq!(deque.get(4), None);
+
+    assert_eq!(deque.make_contiguous(), ["a", "b", "c", "d"]);
+    assert_eq!(deque.as_slices().1, [] as [String; 0]);
+    assert_eq!(deque.pop_back().as_deref(), Some("d"));
+    assert_eq!(deque.pop_front().as_deref(), Some("a"));
+    deque.push_back(String::from("e")).unwrap();
+    deque.push_back(String::from("f")).unwrap();
+    assert_eq!(deque.iter().rev().collect::<Vec<_>>(), ["f", "e", "c", "b"]);
+    assert_eq!(deque.make_contiguous(), ["b", "c", "e", "f"]);
+    assert_eq!(format!("{deque:?}"), r#"["b", "c", "e", "f"]"#);
+
+    deque.clear();
+    assert_eq!((deque.pop_front(), deque.pop_back()), (None, None));
+    assert_eq!(deque.front(), None);
+}
+
+#[test]
+fn test_ring_containers_drop_across_wrap() {
+    use crate::containers::{Deque, OverflowPolicy, RingBuffer};
+    use std::rc::Rc;
+
+    let token = Rc::new(());
+    let mut window =
+        RingBuffer::with_capacity(3, OverflowPolicy::Overwrite).unwrap();
+    for _ in 0..7 {
+        // evicted elements are handed back, not leaked or dropped twice
+        drop(window.push_back(Rc::clone(&token)));
+    }
+    assert_eq!(Rc::strong_count(&token), 4);
+    let (first, second) = window.as_slices();
+    assert_eq!((first.len(), second.len()), (2, 1));
+    assert!(window.pop_front().is_some());
+    assert_eq!(Rc::strong_count(&token), 3);
+    drop(window);
+    assert_eq!(Rc::strong_count(&token), 1);
+
+    let mut deque = Deque::with_capacity(3).unwrap();
+    for _ in 0..3 {
+        assert!(deque.push_front(Rc::clone(&token)).is_ok());
+    }
+    assert!(deque.push_back(Rc::clone(&token)).is_err());
+    assert!(deque.pop_back().is_some());
+    assert!(deque.push_front(Rc::clone(&token)).is_ok());
+    assert_eq!(deque.iter().count(), 3);
+    assert_eq!(Rc::strong_count(&token), 4);
+    deque.clear();
+    assert_eq!((Rc::strong_count(&token), deque.capacity()), (1, 3));
+}
+
+#[test]
+fn test_fixed_stack() {
+    use crate::containers::{FixedStack, StackOverflowError};
+
+    let mut stack = FixedStack::with_max_depth(2).unwrap();
+    assert_eq!(stack.peek(), None);
+    stack.push(1).unwrap();
+    stack.push(2).unwrap();
+    let overflow = stack.push(3).unwrap_err();
+    assert_eq!(
+        overflow,
+        StackOverflowError {
+            value: 3,
+            max_depth: 2
+        }
+    );
+    assert_eq!(
+        overflow.to_string(),
+        "stack overflow: depth limit of 2 reached"
+    );
+    assert_eq!(overflow.into_value(), 3);
+
+    assert_eq!((stack.depth(), stack.max_depth()), (2, 2));
+    *stack.peek_mut().unwrap() *= 10;
+    assert_eq!(stack.as_slice(), [1, 20]);
+    assert_eq!(stack.pop(), Some(20));
+    assert_eq!(stack.peek(), Some(&1));
+    assert_eq!(format!("{stack:?}"), "[1]");
+    stack.clear();
+    assert_eq!(stack.pop(), None);
+}
+
+#[cfg(not(feature = "no-panic"))]
+#[test]
+fn test_fixed_heap() {
+    use std::cmp::Reverse;
+
+    use crate::{
+        containers::{FixedHeap, FixedHeapByKey},
+        runtime_array::ArrayCStyle,
+    };
+
+    let mut heap = FixedHeap::with_capacity(4).unwrap();
+    assert_eq!(heap.peek(), None);
+    for value in [3, 7, 1, 5] {
+        heap.push(value).unwrap();
+    }
+    assert_eq!(heap.push(9), Err(9));
+    assert_eq!(heap.peek(), Some(&7));
+    assert_eq!(heap.pop(), Some(7));
+    heap.push(4).unwrap();
+    let drained: Vec<_> = std::iter::from_fn(|| heap.pop()).collect();
+    assert_eq!(drained, [5, 4, 3, 1]);
+
+    let array: ArrayCStyle<_> = [4, 9, 2, 8, 2, 6, 0].into_iter().collect();
+    let mut heap = unsafe { FixedHeap::heapify(array) };
+    assert!(heap.is_full());
+    let drained: Vec<_> = std::iter::from_fn(|| heap.pop()).collect();
+    assert_eq!(drained, [9, 8, 6, 4, 2, 2, 0]);
+
+    let mut by_len =
+        FixedHeapByKey::with_capacity(3, |word: &String| Reverse(word.len()))
+            .unwrap();
+    for word in ["three", "a", "to"] {
+        by_len.push(String::from(word)).unwrap();
+    }
+    assert_eq!(by_len.pop().as_deref(), Some("a"));
+    assert_eq!(by_len.peek().map(String::as_str), Some("to"));
+
+    let array: ArrayCStyle<_> =
+        [(1, 'b'), (3, 'a'), (2, 'c')].into_iter().collect();
+    let mut by_char =
+        unsafe { FixedHeapByKey::heapify(array, |&(_, c): &(i32, char)| c) };
+    assert_eq!(by_char.pop(), Some((2, 'c')));
+    assert_eq!(by_char.pop(), Some((1, 'b')));
+}
+
+#[test]
+fn test_flat_hash_map() {
+    use crate::containers::FlatHashMap;
+
+    let mut map = FlatHashMap::with_capacity(100).unwrap();
+    assert_eq!(map.get("missing"), None);
+    for number in 0..100 {
+        assert_eq!(map.insert(number.to_string(), number), Ok(None));
+    }
+    assert!(map.is_full());
+    assert_eq!(
+        map.insert(String::from("100"), 100),
+        Err((String::from("100"), 100))
+    );
+    assert_eq!(map.insert(String::from("7"), 70), Ok(Some(7)));
+    *map.get_mut("8").unwrap() += 72;
+    assert_eq!((map.get("7"), map.get("8")), (Some(&70), Some(&80)));
+
+    for number in (0..100).step_by(2) {
+        assert!(map.remove(number.to_string().as_str()).is_some());
+    }
+    assert_eq!(map.remove("0"), None);
+    assert_eq!(map.len(), 50);
+    for number in (1..100).step_by(2) {
+        assert!(map.contains_key(number.to_string().as_str()));
+        assert!(!map.contains_key((number - 1).to_string().as_str()));
+    }
+    let sum: i32 = map.iter().map(|(_, &value)| value).sum();
+    assert_eq!(sum, 2500 + 63);
+
+    map.insert(String::from("x"), 1).unwrap();
+    assert_eq!(map.remove_entry("x"), Some((String::from("x"), 1)));
+    map.clear();
+    assert!(map.is_empty());
+    assert_eq!(format!("{map:?}"), "{}");
+
+    let mut empty = FlatHashMap::with_capacity(0).unwrap();
+    assert_eq!(empty.get(&1), None::<&u8>);
+    assert_eq!(empty.insert(1, 1), Err((1, 1)));
+}
+
+#[test]
+fn test_flat_hash_set() {
+    use crate::containers::FlatHashSet;
+
+    let mut seen = FlatHashSet::with_capacity(3).unwrap();
+    assert_eq!(seen.insert(String::from("a")), Ok(true));
+    assert_eq!(seen.insert(String::from("b")), Ok(true));
+    assert_eq!(seen.insert(String::from("a")), Ok(false));
+    assert_eq!(seen.insert(String::from("c")), Ok(true));
+    assert_eq!(seen.insert(String::from("d")), Err(String::from("d")));
+    assert_eq!(seen.len(), 3);
+
+    let mut values: Vec<_> = seen.iter().map(String::as_str).collect();
+    values.sort_unstable();
+    assert_eq!(values, ["a", "b", "c"]);
+
+    assert!(seen.contains("b"));
+    assert!(seen.remove("b"));
+    assert!(!seen.remove("b"));
+    assert_eq!(seen.take("c").as_deref(), Some("c"));
+    assert_eq!(format!("{seen:?}"), r#"{"a"}"#);
+    seen.clear();
+    assert!(seen.is_empty() && !seen.contains("a"));
+}
+
+#[cfg(not(feature = "no-panic"))]
+#[test]
+fn test_fixed_vec_edges() {
+    use crate::containers::FixedVec;
+
+    let mut none = FixedVec::with_capacity(0).unwrap();
+    assert!(none.is_full() && none.is_empty());
+    assert_eq!(none.try_push(1), Err(1));
+    assert_eq!(none.insert(0, 1), Err(1));
+    assert_eq!((none.pop(), none.remove(0)), (None, None));
+
+    let mut vec = FixedVec::with_capacity(3).unwrap();
+    assert_eq!(vec.insert(1, 'x'), Err('x'));
+    vec.insert(0, 'b').unwrap();
+    vec.insert(1, 'c').unwrap();
+    vec.insert(0, 'a').unwrap();
+    assert_eq!(vec.remove(2), Some('c'));
+    vec.truncate(5);
+    assert_eq!(vec.as_slice(), ['a', 'b']);
+    vec.truncate(1);
+    assert_eq!(vec.as_slice(), ['a']);
+
+    let array: ArrayCStyle<String> =
+        ["p", "q"].into_iter().map(String::from).collect();
+    let mut full = unsafe { FixedVec::from_array(array) };
+    assert!(full.is_full());
+    assert_eq!(full.pop().as_deref(), Some("q"));
+    full.push(String::from("r"));
+    assert_eq!(full.as_slice(), ["p", "r"]);
+}
+
+#[cfg(all(debug_assertions, feature = "shadow"))]
+#[test]
+#[should_panic(expected = "Read of uninitialized element at index 0")]
+fn test_fixed_vec_from_uninitialized_array() {
+    use crate::containers::FixedVec;
+
+    let array = ArrayCStyle::<String>::new(2).unwrap();
+    let _ = unsafe { FixedVec::from_array(array) };
+}
+
+#[cfg(not(feature = "no-panic"))]
+#[test]
+fn test_fixed_heap_edges() {
+    use crate::containers::{FixedHeap, FixedHeapByKey, FixedVec};
+
+    let mut none = FixedHeap::with_capacity(0).unwrap();
+    assert_eq!(none.push(1), Err(1));
+    assert_eq!((none.pop(), none.peek()), (None, None));
+
+    let mut items = FixedVec::with_capacity(5).unwrap();
+    for value in [2, 2, 5, 1] {
+        items.push(value);
+    }
+    let mut heap = FixedHeap::from_vec(items);
+    assert_eq!((heap.len(), heap.capacity()), (4, 5));
+    heap.push(3).unwrap();
+    assert_eq!(heap.push(0), Err(0));
+    let drained: Vec<_> = std::iter::from_fn(|| heap.pop()).collect();
+    assert_eq!(drained, [5, 3, 2, 2, 1]);
+
+    let mut items = FixedVec::with_capacity(3).unwrap();
+    items.push(-4);
+    items.push(1);
+    items.push(3);
+    let mut by_abs = FixedHeapByKey::from_vec(items, |value: &i32| value.abs());
+    assert_eq!(by_abs.pop(), Some(-4));
+    assert_eq!(by_abs.pop(), Some(3));
+}
+
+#[cfg(not(feature = "no-panic"))]
+#[test]
+fn test_array2d_empty_views() {
+    use crate::matrix::Array2D;
+
+    let mut matrix = Array2D::from_array((0..4).collect(), 2, 2).unwrap();
+    let base = matrix.as_view().as_ptr();
+    for (rows, cols) in [(2..2, 2..2), (2..2, 0..2), (0..2, 2..2), (1..1, 1..2)]
+    {
+        let view = matrix.view(rows.clone(), cols.clone()).unwrap();
+        assert!(view.is_empty());
+        assert_eq!(view.as_ptr(), base);
+        assert_eq!(view.iter().count(), 0);
+        let mut view = matrix.view_mut(rows, cols).unwrap();
+        assert_eq!(view.as_mut_ptr() as *const i32, base);
+    }
+    assert!(matrix.view(3..3, 0..0).is_none());
+    let backwards = std::ops::Range { start: 1, end: 0 };
+    assert!(matrix.view(0..2, backwards).is_none());
+    let inner = matrix.view(1..2, 1..2).unwrap();
+    assert_eq!(inner.view(1..1, 1..1).unwrap().len(), 0);
+}
+
+#[cfg(feature = "npy")]
+#[test]
+fn test_npy_malformed_input() {
+    use crate::matrix::{Array2D, ArrayND};
+
+    fn npy(header: &str) -> Vec<u8> {
+        let mut bytes = b"\x93NUMPY\x01\x00".to_vec();
+        bytes.extend((header.len() as u16).to_le_bytes());
+        bytes.extend(header.as_bytes());
+        bytes
+    }
+
+    let header = "{'descr': 'é4', 'fortran_order': False, 'shape': (2,), }";
+    let error = ArrayND::<i32>::read_npy(&npy(header)[..]).unwrap_err();
+    assert_eq!(error.to_string(), "Invalid NPY header: unsupported 'descr'");
+    let header = "{'descr': '', 'fortran_order': False, 'shape': (2,), }";
+    assert!(ArrayND::<i32>::read_npy(&npy(header)[..]).is_err());
+
+    // a huge shape over a short stream fails at its end, without
+    // allocating the shape
+    let header =
+        "{'descr': '|u1', 'fortran_order': False, 'shape': (1000000000000,), }";
+    let mut bytes = npy(header);
+    bytes.extend([7; 100]);
+    let error = ArrayND::<u8>::read_npy(&bytes[..]).unwrap_err();
+    assert!(matches!(error, Error::Io(_)));
+    let mut bytes = b"\x93NUMPY\x02\x00".to_vec();
+    bytes.extend(u32::MAX.to_le_bytes());
+    bytes.extend(b"{'descr'");
+    assert!(matches!(
+        ArrayND::<u8>::read_npy(&bytes[..]),
+        Err(Error::Io(_))
+    ));
+
+    // every truncation and many random corruptions of a valid file must
+    // come back as errors or arrays, never as panics
+    let tensor = ArrayND::from_array(array(0..24), &[2, 3, 4]).unwrap();
+    let mut valid = Vec::new();
+    tensor.write_npy(&mut valid).unwrap();
+    for len in 0..valid.len() {
+        assert!(ArrayND::<i32>::read_npy(&valid[..len]).is_err());
+    }
+    let noise = [0x00, b'(', b')', b',', b'\'', b'-', b'9', 0xC3, 0xE2, 0xFF];
+    let mut state = 0x2545_F491_4F6C_DD1Du64;
+    let mut next = |bound: usize| {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state as usize % bound
+    };
+    for _ in 0..5000 {
+        let mut bytes = valid.clone();
+        for _ in 0..1 + next(3) {
+            let index = next(128);
+            bytes[index] = match next(2) {
+                0 => noise[next(noise.len())],
+                _ => next(256) as u8,
+            };
+        }
+        bytes.truncate(next(bytes.len() + 1));
+        let _ = ArrayND::<i32>::read_npy(&bytes[..]);
+        let _ = Array2D::<i32>::read_npy(&bytes[..]);
+    }
+}
+
+/// Evaluates to whether `$type` implements `$trait`, by autoref
+/// specialization: the by-value impl only applies if the bound holds.
+macro_rules! implements {
+    ($type:ty: $($trait:tt)+) => {{
+        struct Probe<T>(std::marker::PhantomData<T>);
+        #[allow(dead_code)]
+        trait Yes {
+            fn implemented(&self) -> bool {
+                true
+            }
+        }
+        impl<T: $($trait)+> Yes for Probe<T> {}
+        #[allow(dead_code)]
+        trait No {
+            fn implemented(&self) -> bool {
+                false
+            }
+        }
+        impl<T> No for &Probe<T> {}
+        (&Probe::<$type>(std::marker::PhantomData)).implemented()
+    }};
+}
+
+#[test]
+fn test_no_panic_api_surface() {
+    use crate::{
+        containers::FixedVec,
+        cow_array::CowArray,
+        matrix::{Array2D, ArrayND},
+    };
+
+    // `no-panic` removes exactly the conveniences that can panic
+    let panicking = !cfg!(feature = "no-panic");
+    assert_eq!(
+        implements!(ArrayCStyle<u8>: std::ops::Index<usize>),
+        panicking
+    );
+    assert_eq!(implements!(ArrayCStyle<u8>: Clone), panicking);
+    assert_eq!(implements!(ArrayCStyle<u8>: FromIterator<u8>), panicking);
+    assert_eq!(
+        implements!(Array2D<u8>: std::ops::IndexMut<(usize, usize)>),
+        panicking
+    );
+    assert_eq!(implements!(ArrayND<u8>: Clone), panicking);
+    assert_eq!(
+        implements!(crate::slot_array::SlotArray<u8>: Default),
+        panicking
+    );
+    assert!(implements!(ArrayCStyle<u8>: Send));
+
+    // and the fallible API covers what they did
+    let runt = ArrayCStyle::try_from_slice(&[1u8, 2, 3]).unwrap();
+    assert_eq!(runt.at(2).copied().ok(), Some(3));
+    assert!(matches!(
+        runt.at(3),
+        Err(Error::IndexOutOfBounds { index: 3, len: 3 })
+    ));
+    assert_eq!(runt.try_clone().unwrap().as_slice(), [1, 2, 3]);
+    let collected = array((0..3).map(|x| x * 2));
+    assert_eq!(collected.as_slice(), [0, 2, 4]);
+    // an iterator outrunning its size hint is an error, not a panic
+    let filtered = (0..5).filter(|_| true);
+    assert!(ArrayCStyle::try_from_iter_in(filtered, Global).is_err());
+
+    let mut cow = CowArray::Borrowed(runt.as_slice());
+    cow.try_to_mut().unwrap().as_mut_slice()[0] = 9;
+    assert_eq!(cow.try_into_owned().unwrap().as_slice(), [9, 2, 3]);
+
+    let mut matrix = Array2D::from_array(array(0..6), 2, 3).unwrap();
+    *matrix.get_mut(1, 2).unwrap() = 50;
+    assert_eq!(matrix.at(1, 2).copied().ok(), Some(50));
+    assert!(matrix.at(2, 0).is_err());
+    assert_eq!(matrix.try_clone().unwrap().as_slice(), matrix.as_slice());
+
+    let tensor = ArrayND::from_array(array(0..8), &[2, 2, 2]).unwrap();
+    assert_eq!(tensor.at(&[1, 1, 1]).copied().ok(), Some(7));
+    assert!(tensor.at(&[2, 0, 0]).is_err());
+    assert!(tensor.try_add(&tensor).is_ok());
+
+    let mut vec = FixedVec::with_capacity(1).unwrap();
+    assert_eq!((vec.try_push(1), vec.try_push(2)), (Ok(()), Err(2)));
 }
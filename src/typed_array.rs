@@ -0,0 +1,149 @@
+use std::{fmt, marker::PhantomData};
+
+use crate::{error::Error, runtime_array::ArrayCStyle};
+
+/// A runtime sized array that is indexed by a domain specific index type
+/// instead of a bare `usize`.
+///
+/// Graph and ECS code tends to juggle several kinds of indices at once
+/// (`NodeId`, `EdgeId`, `RowId`, ...). Wrapping the storage in a
+/// `TypedArray<NodeId, T>` makes the compiler reject an `EdgeId` being used
+/// where a `NodeId` was expected.
+///
+/// # Example
+///
+/// ```rust ignore
+/// use runnarr::typed_array::TypedArray;
+///
+/// #[derive(Clone, Copy)]
+/// struct NodeId(usize);
+///
+/// impl From<usize> for NodeId {
+///     fn from(index: usize) -> Self {
+///         NodeId(index)
+///     }
+/// }
+///
+/// impl From<NodeId> for usize {
+///     fn from(id: NodeId) -> Self {
+///         id.0
+///     }
+/// }
+///
+/// let mut weights: TypedArray<NodeId, f32> = TypedArray::zeroed(4).unwrap();
+/// weights[NodeId(2)] = 1.5;
+/// ```
+pub struct TypedArray<I, T> {
+    array: ArrayCStyle<T>,
+    _index: PhantomData<fn(I) -> I>,
+}
+
+impl<I, T> TypedArray<I, T>
+where
+    I: Into<usize> + From<usize>,
+{
+    /// Creates a new uninitialized `TypedArray` with the specified size.
+    ///
+    /// See [`ArrayCStyle::new`] for the initialization contract.
+    pub fn new(size: usize) -> Result<Self, Error> {
+        Ok(Self::from_array(ArrayCStyle::new(size)?))
+    }
+
+    /// Creates a new `TypedArray` with all elements initialized to zero.
+    ///
+    /// See [`ArrayCStyle::zeroed`].
+    pub fn zeroed(size: usize) -> Result<Self, Error> {
+        Ok(Self::from_array(ArrayCStyle::zeroed(size)?))
+    }
+
+    /// Wraps an existing array so it can be indexed with `I`.
+    #[inline(always)]
+    pub fn from_array(array: ArrayCStyle<T>) -> Self {
+        Self {
+            array,
+            _index: PhantomData,
+        }
+    }
+
+    /// Unwraps the underlying `usize` indexed array.
+    #[inline(always)]
+    pub fn into_array(self) -> ArrayCStyle<T> {
+        self.array
+    }
+
+    /// Returns a reference to the underlying `usize` indexed array.
+    #[inline(always)]
+    pub fn as_array(&self) -> &ArrayCStyle<T> {
+        &self.array
+    }
+
+    /// Returns the length of the array.
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.array.len()
+    }
+
+    /// Returns `true` if the array holds no elements.
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.array.is_empty()
+    }
+
+    /// Gets a reference to the element at `index`, or `None` if it is out of
+    /// bounds.
+    #[inline(always)]
+    pub fn get(&self, index: I) -> Option<&T> {
+        self.array.get(index.into())
+    }
+
+    /// Gets a mutable reference to the element at `index`, or `None` if it is
+    /// out of bounds.
+    #[inline(always)]
+    pub fn get_mut(&mut self, index: I) -> Option<&mut T> {
+        self.array.get_mut(index.into())
+    }
+
+    /// Returns an iterator over every valid index of the array, in order.
+    pub fn indices(&self) -> impl Iterator<Item = I> {
+        (0..self.len()).map(I::from)
+    }
+}
+
+#[cfg(not(feature = "no-panic"))]
+impl<I, T> std::ops::Index<I> for TypedArray<I, T>
+where
+    I: Into<usize> + From<usize>,
+{
+    type Output = T;
+    fn index(&self, index: I) -> &Self::Output {
+        self.get(index).expect("Index out of bounds")
+    }
+}
+
+#[cfg(not(feature = "no-panic"))]
+impl<I, T> std::ops::IndexMut<I> for TypedArray<I, T>
+where
+    I: Into<usize> + From<usize>,
+{
+    fn index_mut(&mut self, index: I) -> &mut Self::Output {
+        self.get_mut(index).expect("Index out of bounds")
+    }
+}
+
+impl<I, T> From<ArrayCStyle<T>> for TypedArray<I, T>
+where
+    I: Into<usize> + From<usize>,
+{
+    fn from(array: ArrayCStyle<T>) -> Self {
+        Self::from_array(array)
+    }
+}
+
+impl<I, T: fmt::Debug> fmt::Debug for TypedArray<I, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TypedArray")
+            .field("index", &std::any::type_name::<I>())
+            .field("array", &self.array)
+            .finish()
+    }
+}
@@ -0,0 +1,90 @@
+use std::{
+    iter::Sum,
+    mem,
+    ops::{Add, AddAssign},
+};
+
+use crate::{allocator::RawAllocator, runtime_array::ArrayCStyle};
+
+/// How much memory a container holds and how much of it is in use.
+///
+/// Values add up, so the footprint of a whole subsystem is the sum of its
+/// containers:
+///
+/// ```rust ignore
+/// use runnarr::usage::MemoryUsage;
+///
+/// let total: MemoryUsage = [meshes.memory_usage(), textures.memory_usage()]
+///     .into_iter()
+///     .sum();
+/// dashboard.gauge("renderer.bytes", total.allocated_bytes);
+/// dashboard.gauge("renderer.slack", total.slack_bytes());
+/// ```
+///
+/// With the `stats` feature, `stats::stats()` reports the bytes held by all
+/// arrays of the process instead.
+#[derive(Debug, Clone, Copy, Default, Hash, PartialEq, Eq)]
+pub struct MemoryUsage {
+    /// Bytes requested from the allocator, including capacity that holds no
+    /// value yet.
+    pub allocated_bytes: usize,
+    /// Bytes occupied by live values.
+    pub used_bytes: usize,
+}
+
+impl MemoryUsage {
+    /// Returns the bytes that are allocated but hold no value, e.g. the spare
+    /// capacity of growable containers.
+    #[inline(always)]
+    pub const fn slack_bytes(&self) -> usize {
+        self.allocated_bytes.saturating_sub(self.used_bytes)
+    }
+}
+
+impl Add for MemoryUsage {
+    type Output = Self;
+    fn add(self, other: Self) -> Self::Output {
+        Self {
+            allocated_bytes: self.allocated_bytes + other.allocated_bytes,
+            used_bytes: self.used_bytes + other.used_bytes,
+        }
+    }
+}
+
+impl AddAssign for MemoryUsage {
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+impl Sum for MemoryUsage {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::default(), Add::add)
+    }
+}
+
+impl<T, A: RawAllocator> ArrayCStyle<T, A> {
+    /// Returns the size of one element in bytes.
+    #[inline(always)]
+    pub const fn element_size(&self) -> usize {
+        mem::size_of::<T>()
+    }
+
+    /// Returns the number of bytes requested from the allocator for the
+    /// buffer. Empty arrays and zero-sized element types allocate nothing.
+    #[inline(always)]
+    pub fn allocated_bytes(&self) -> usize {
+        self.layout().size()
+    }
+
+    /// Returns the memory held by the array.
+    ///
+    /// Every element counts as used; an uninitialized array still owns its
+    /// whole buffer.
+    pub fn memory_usage(&self) -> MemoryUsage {
+        MemoryUsage {
+            allocated_bytes: self.allocated_bytes(),
+            used_bytes: self.len() * self.element_size(),
+        }
+    }
+}
@@ -0,0 +1,168 @@
+use std::{
+    fmt,
+    ops::{Deref, DerefMut},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex, MutexGuard, PoisonError,
+    },
+};
+
+use crate::{
+    arc_array::{ArcArray, AtomicArrayCell},
+    error::Error,
+    runtime_array::ArrayCStyle,
+};
+
+/// An array whose readers always see a complete, immutable version while a
+/// writer prepares the next one.
+///
+/// [`VersionedArray::snapshot`] hands out the current version as an
+/// [`ArcArray`] that stays valid (and unchanged) for as long as the reader
+/// keeps it. Writers never touch a published version: [`VersionedArray::begin`]
+/// copies it into a pending array, and [`PendingUpdate::commit`] publishes
+/// that copy atomically and bumps the version counter. Bulk updates
+/// therefore never expose a half-applied state.
+///
+/// Writers are serialized; readers never wait.
+///
+/// # Example
+///
+/// ```rust ignore
+/// use runnarr::versioned_array::VersionedArray;
+///
+/// let prices = VersionedArray::new(load_prices()?);
+///
+/// // readers
+/// let view = prices.snapshot();
+/// quote(&view);
+///
+/// // nightly repricing
+/// let mut pending = prices.begin()?;
+/// for price in pending.iter_mut() {
+///     *price *= 1.02;
+/// }
+/// pending.commit();
+/// ```
+pub struct VersionedArray<T> {
+    current: AtomicArrayCell<T>,
+    version: AtomicU64,
+    writer: Mutex<()>,
+}
+
+impl<T> VersionedArray<T> {
+    /// Publishes `array` as version 0.
+    pub fn new(array: ArrayCStyle<T>) -> Self {
+        Self {
+            current: AtomicArrayCell::new(ArcArray::new(array)),
+            version: AtomicU64::new(0),
+            writer: Mutex::new(()),
+        }
+    }
+
+    /// Returns the current version.
+    pub fn snapshot(&self) -> ArcArray<T> {
+        self.current.load()
+    }
+
+    /// Returns the number of updates committed so far.
+    #[inline(always)]
+    pub fn version(&self) -> u64 {
+        self.version.load(Ordering::Acquire)
+    }
+
+    /// Publishes `array` as the next version, replacing whatever is current.
+    ///
+    /// # Returns
+    ///
+    /// The new version number.
+    pub fn replace(&self, array: ArrayCStyle<T>) -> u64 {
+        let _writer = self.lock_writer();
+        self.publish(array)
+    }
+
+    fn lock_writer(&self) -> MutexGuard<'_, ()> {
+        self.writer.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Must be called with the writer lock held.
+    fn publish(&self, array: ArrayCStyle<T>) -> u64 {
+        self.current.store(ArcArray::new(array));
+        self.version.fetch_add(1, Ordering::AcqRel) + 1
+    }
+}
+
+impl<T: Clone> VersionedArray<T> {
+    /// Starts an update by copying the current version into a pending
+    /// array.
+    ///
+    /// Other writers wait until the returned update is committed or
+    /// dropped; dropping it discards the changes.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the copy cannot be allocated.
+    #[track_caller]
+    pub fn begin(&self) -> Result<PendingUpdate<'_, T>, Error> {
+        let writer = self.lock_writer();
+        let pending = self.snapshot().array().try_clone()?;
+        Ok(PendingUpdate {
+            owner: self,
+            pending,
+            _writer: writer,
+        })
+    }
+
+    /// Applies `f` to a copy of the current version and publishes the
+    /// result.
+    ///
+    /// # Returns
+    ///
+    /// The new version number.
+    #[track_caller]
+    pub fn update(&self, f: impl FnOnce(&mut [T])) -> Result<u64, Error> {
+        let mut pending = self.begin()?;
+        f(&mut pending);
+        Ok(pending.commit())
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for VersionedArray<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("VersionedArray")
+            .field("version", &self.version())
+            .field("current", &self.snapshot())
+            .finish()
+    }
+}
+
+/// A private copy of a [`VersionedArray`] being prepared as its next
+/// version.
+pub struct PendingUpdate<'a, T> {
+    owner: &'a VersionedArray<T>,
+    pending: ArrayCStyle<T>,
+    _writer: MutexGuard<'a, ()>,
+}
+
+impl<T> PendingUpdate<'_, T> {
+    /// Publishes the pending array as the next version.
+    ///
+    /// # Returns
+    ///
+    /// The new version number.
+    pub fn commit(self) -> u64 {
+        self.owner.publish(self.pending)
+    }
+}
+
+impl<T> Deref for PendingUpdate<'_, T> {
+    type Target = [T];
+    fn deref(&self) -> &Self::Target {
+        self.pending.as_slice()
+    }
+}
+
+impl<T> DerefMut for PendingUpdate<'_, T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.pending.as_mut_slice()
+    }
+}